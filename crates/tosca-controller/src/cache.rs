@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use tosca::device::DeviceData;
+
+use reqwest::StatusCode;
+use reqwest::header::{ETAG, IF_NONE_MATCH};
+
+use crate::error::{Error, ErrorKind};
+
+// Default time a cached description is considered fresh.
+const DEFAULT_DESCRIPTION_TTL: Duration = Duration::from_mins(1);
+
+// A device description along with the data needed to revalidate it.
+#[derive(Debug)]
+struct CachedDescription {
+    // Device description.
+    data: DeviceData,
+    // Entity tag of the description, if sent by the device.
+    etag: Option<String>,
+    // Time of the last fetch or revalidation.
+    validated: Instant,
+}
+
+// A cache of device descriptions, indexed by device identifier.
+//
+// A description older than the time-to-live is revalidated through its
+// entity tag, so an unchanged description is not transmitted again.
+#[derive(Debug)]
+pub(crate) struct DescriptionCache {
+    ttl: Duration,
+    entries: HashMap<usize, CachedDescription>,
+}
+
+impl PartialEq for DescriptionCache {
+    fn eq(&self, other: &Self) -> bool {
+        self.ttl == other.ttl
+    }
+}
+
+impl DescriptionCache {
+    pub(crate) fn new() -> Self {
+        Self::with_ttl(DEFAULT_DESCRIPTION_TTL)
+    }
+
+    pub(crate) fn with_ttl(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn invalidate(&mut self, id: usize) {
+        self.entries.remove(&id);
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub(crate) async fn description(
+        &mut self,
+        client: &reqwest::Client,
+        id: usize,
+        address: &str,
+    ) -> Result<&DeviceData, Error> {
+        let etag = match self.entries.get(&id) {
+            Some(entry) if entry.validated.elapsed() < self.ttl => None,
+            Some(entry) => Some(entry.etag.clone()),
+            None => Some(None),
+        };
+
+        if let Some(etag) = etag {
+            let request = client.get(address);
+            let request = match etag {
+                Some(etag) => request.header(IF_NONE_MATCH, etag),
+                None => request,
+            };
+
+            let response = request.send().await?;
+
+            if response.status() == StatusCode::NOT_MODIFIED
+                && let Some(entry) = self.entries.get_mut(&id)
+            {
+                entry.validated = Instant::now();
+            } else if response.status().is_success() {
+                let etag = response
+                    .headers()
+                    .get(ETAG)
+                    .and_then(|value| value.to_str().ok())
+                    .map(str::to_owned);
+
+                let data = response.json::<DeviceData>().await.map_err(|e| {
+                    Error::new(
+                        ErrorKind::JsonResponse,
+                        format!("Invalid device description from {address}: {e}"),
+                    )
+                })?;
+
+                self.entries.insert(
+                    id,
+                    CachedDescription {
+                        data,
+                        etag,
+                        validated: Instant::now(),
+                    },
+                );
+            } else {
+                return Err(Error::new(
+                    ErrorKind::Request,
+                    format!(
+                        "Unable to retrieve the description from {address}: {}",
+                        response.status()
+                    ),
+                ));
+            }
+        }
+
+        self.entries
+            .get(&id)
+            .map(|entry| &entry.data)
+            .ok_or_else(|| Error::new(ErrorKind::Request, "No cached device description"))
+    }
+}
@@ -1,21 +1,52 @@
 use std::borrow::Cow;
+use std::time::Duration;
 
+use tosca::device::{DeviceData, DeviceInfo, DeviceKind};
+#[cfg(feature = "stream")]
+use tosca::events::EVENTS_SSE_ROUTE;
+use tosca::events::Events;
 use tosca::parameters::ParametersValues;
+use tosca::response::InfoResponse;
+
+use futures_util::Stream;
+
+#[cfg(feature = "stream")]
+use reqwest::header::ACCEPT;
+use reqwest::header::{AUTHORIZATION, HeaderMap, HeaderName, HeaderValue};
 
 use tokio::sync::mpsc::{self, Receiver};
 
+use tokio_util::sync::CancellationToken;
+
 use tracing::{error, warn};
 
+use crate::cache::DescriptionCache;
+use crate::credentials::CredentialStore;
 use crate::device::{Device, Devices};
-use crate::discovery::Discovery;
+use crate::discovery::{Discovery, DiscoveryEvent, DiscoveryRecord};
 use crate::error::{Error, ErrorKind};
-use crate::events::{EventPayload, EventsRunner};
+use crate::events::{EventHistory, EventPayload, EventsRunner};
 use crate::policy::Policy;
-use crate::request::Request;
+use crate::request::{ClientConfig, HttpClient, Request};
 use crate::response::Response;
+use crate::scene::{Scene, SceneAction, SceneReport};
+#[cfg(any(test, feature = "test-util"))]
+use crate::session::{Session, SessionPlayer, SessionRecorder};
+use crate::topology;
+use crate::transport::Transport;
 
 // TODO: Use the MAC address as id.
 
+// Delay before polling a device again while waiting for a condition.
+const INITIAL_POLL_DELAY: Duration = Duration::from_millis(100);
+
+// Maximum delay between two polls while waiting for a condition.
+const MAXIMUM_POLL_DELAY: Duration = Duration::from_secs(2);
+
+// Media type of a Server-Sent Events stream.
+#[cfg(feature = "stream")]
+const SSE_MEDIA_TYPE: &str = "text/event-stream";
+
 fn sender_error(error: impl Into<Cow<'static, str>>) -> Error {
     Error::new(ErrorKind::Sender, error)
 }
@@ -24,11 +55,96 @@ fn sender_error(error: impl Into<Cow<'static, str>>) -> Error {
 #[derive(Debug, PartialEq)]
 pub struct RequestSender<'controller> {
     controller: &'controller Controller,
+    device: &'controller Device,
+    id: usize,
     request: &'controller Request,
     skip: bool,
+    idempotency_key: Option<String>,
+    headers: HeaderMap,
 }
 
-impl RequestSender<'_> {
+impl<'controller> RequestSender<'controller> {
+    /// Attaches an idempotency key to the request.
+    ///
+    /// A device receiving the same key more than once returns the
+    /// response produced for the first request, without running the
+    /// operation again. This makes a request safe to be sent again after a
+    /// network failure.
+    ///
+    /// A `tosca-os` device caches the responses of the last 64 requests
+    /// carrying a key, each one for 5 minutes. Once its response has been
+    /// evicted or has expired, the same key runs the operation again. Only
+    /// successful responses up to 64 KiB are cached.
+    ///
+    /// A key should be unique for each operation, i.e. an `UUID`.
+    #[must_use]
+    pub fn with_idempotency_key(mut self, key: impl Into<String>) -> Self {
+        self.idempotency_key = Some(key.into());
+        self
+    }
+
+    /// Adds a custom header to the request.
+    ///
+    /// It allows to reach devices placed behind a reverse proxy which
+    /// requires additional headers, such as an `X-Api-Key` header. A header
+    /// with the same name as a header set by the request, except for the
+    /// idempotency key, replaces it.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned when the header name or value is not valid,
+    /// such as a value containing a line break.
+    pub fn with_header(mut self, name: &str, value: &str) -> Result<Self, Error> {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| sender_error(format!("Invalid header name `{name}`.")))?;
+        // The value is not reported, since it might be a secret.
+        let value = HeaderValue::from_str(value)
+            .map_err(|_| sender_error(format!("Invalid value for the `{name}` header.")))?;
+
+        self.headers.insert(name, value);
+        Ok(self)
+    }
+
+    /// Adds some custom headers to the request.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned when a header name or value is not valid,
+    /// such as a value containing a line break.
+    pub fn with_headers<N, V>(
+        self,
+        headers: impl IntoIterator<Item = (N, V)>,
+    ) -> Result<Self, Error>
+    where
+        N: AsRef<str>,
+        V: AsRef<str>,
+    {
+        headers.into_iter().try_fold(self, |sender, (name, value)| {
+            sender.with_header(name.as_ref(), value.as_ref())
+        })
+    }
+
+    /// Sends the request only if the current [`DeviceInfo`] of the device
+    /// satisfies the given predicate.
+    ///
+    /// The device information is retrieved right before sending the request,
+    /// so an operation is not performed on a device whose state no longer
+    /// satisfies the predicate.
+    ///
+    /// Checking the state and sending the request are two distinct
+    /// exchanges with the device, hence this is not an atomic operation:
+    /// another controller can still change the device state between them.
+    #[must_use]
+    pub fn if_state_matches<P>(self, predicate: P) -> ConditionalRequestSender<'controller, P>
+    where
+        P: Fn(&DeviceInfo) -> bool,
+    {
+        ConditionalRequestSender {
+            sender: self,
+            predicate,
+        }
+    }
+
     /// Sends a request to a device, getting in return a [`Response`].
     ///
     /// # Errors
@@ -38,7 +154,15 @@ impl RequestSender<'_> {
     /// affect the returned response.
     pub async fn send(&self) -> Result<Response, Error> {
         self.request
-            .retrieve_response(self.skip, || async { self.request.plain_send().await })
+            .retrieve_response(self.skip, || async {
+                self.request
+                    .plain_send(
+                        &self.controller.client,
+                        self.idempotency_key.as_deref(),
+                        &self.headers,
+                    )
+                    .await
+            })
             .await
     }
 
@@ -61,12 +185,93 @@ impl RequestSender<'_> {
 
         self.request
             .retrieve_response(self.skip, || async {
-                self.request.create_response(parameters).await
+                self.request
+                    .create_response(
+                        &self.controller.client,
+                        parameters,
+                        self.idempotency_key.as_deref(),
+                        &self.headers,
+                    )
+                    .await
             })
             .await
     }
 }
 
+/// A request sender which checks a precondition on the device state before
+/// sending a request.
+///
+/// The check is not atomic with the request, as described in
+/// [`RequestSender::if_state_matches`].
+///
+/// It is built through [`RequestSender::if_state_matches`].
+#[derive(Debug)]
+pub struct ConditionalRequestSender<'controller, P> {
+    sender: RequestSender<'controller>,
+    predicate: P,
+}
+
+impl<P> ConditionalRequestSender<'_, P>
+where
+    P: Fn(&DeviceInfo) -> bool,
+{
+    /// Sends a request to a device when the precondition holds, getting in
+    /// return a [`Response`].
+    ///
+    /// A request blocked by the privacy policy is skipped without retrieving
+    /// the device information.
+    ///
+    /// # Errors
+    ///
+    /// An error of kind [`ErrorKind::Precondition`] is returned when the
+    /// device state does not satisfy the predicate. An error is also
+    /// returned when the device information cannot be retrieved, and for the
+    /// same failures of [`RequestSender::send`].
+    pub async fn send(&self) -> Result<Response, Error> {
+        self.check_precondition().await?;
+        self.sender.send().await
+    }
+
+    /// Sends a request to a device with the given [`ParametersValues`] when
+    /// the precondition holds, getting in return a [`Response`].
+    ///
+    /// # Errors
+    ///
+    /// An error of kind [`ErrorKind::Precondition`] is returned when the
+    /// device state does not satisfy the predicate. An error is also
+    /// returned when the device information cannot be retrieved, and for the
+    /// same failures of [`RequestSender::send_with_parameters`].
+    pub async fn send_with_parameters(
+        &self,
+        parameters: &ParametersValues<'_>,
+    ) -> Result<Response, Error> {
+        self.check_precondition().await?;
+        self.sender.send_with_parameters(parameters).await
+    }
+
+    async fn check_precondition(&self) -> Result<(), Error> {
+        if self.sender.skip {
+            return Ok(());
+        }
+
+        let device_sender = DeviceSender {
+            controller: self.sender.controller,
+            device: self.sender.device,
+            id: self.sender.id,
+        };
+
+        let info = device_sender.info().await?;
+        if (self.predicate)(info.info()) {
+            Ok(())
+        } else {
+            Err(Error::new(
+                ErrorKind::Precondition,
+                "The device state does not satisfy the precondition.",
+            ))
+        }
+    }
+}
+
 /// A sender for the requests of a determined device.
 #[derive(Debug, PartialEq)]
 pub struct DeviceSender<'controller> {
@@ -88,22 +293,173 @@ impl DeviceSender<'_> {
             "Error in retrieving the request with route `{route}`."
         )))?;
 
-        let skip = if request.hazards.is_empty() {
-            false
-        } else {
-            self.evaluate_privacy_policy(request, route)
-        };
+        if let Some(reason) = request.deprecation() {
+            warn!("The {route} route is deprecated: {reason}");
+        }
+
+        let skip = self.evaluate_privacy_policy(request, route);
+
+        let mut headers = HeaderMap::new();
+        if let Some(authorization) = self
+            .controller
+            .credentials
+            .authorization(&self.device.network_info().name)
+        {
+            headers.insert(AUTHORIZATION, authorization.clone());
+        }
 
         Ok(RequestSender {
             controller: self.controller,
+            device: self.device,
+            id: self.id,
             request,
             skip,
+            idempotency_key: None,
+            headers,
         })
     }
 
+    /// Polls the request with the given route until the predicate holds for
+    /// its [`Response`], or the timeout elapses.
+    ///
+    /// It allows to confirm that a device has reached a desired state, such
+    /// as a light reporting it is on after turning it on. The delay between
+    /// two polls doubles each time, up to two seconds.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned when the given route **does** not exist, when
+    /// the request is blocked by the privacy policy, when a request fails,
+    /// or when the predicate does not hold before the timeout elapses.
+    pub async fn await_condition(
+        &self,
+        route: &str,
+        predicate: impl AsyncFn(Response) -> bool,
+        timeout: Duration,
+    ) -> Result<(), Error> {
+        let request_sender = self.request(route)?;
+        if request_sender.skip {
+            return Err(sender_error(format!(
+                "The `{route}` request is blocked by the privacy policy."
+            )));
+        }
+
+        let poll = async {
+            let mut delay = INITIAL_POLL_DELAY;
+            loop {
+                if predicate(request_sender.send().await?).await {
+                    return Ok(());
+                }
+                tokio::time::sleep(delay).await;
+                delay = (delay * 2).min(MAXIMUM_POLL_DELAY);
+            }
+        };
+
+        tokio::time::timeout(timeout, poll).await.map_err(|_| {
+            Error::new(
+                ErrorKind::Request,
+                format!("The `{route}` condition has not been met within {timeout:?}."),
+            )
+        })?
+    }
+
+    /// Retrieves the icon advertised by the device through its information
+    /// route, either a `URL` or the name of a well-known icon.
+    ///
+    /// The information route is the `GET` route answering with device
+    /// information. When a device has several of them, the first one in path
+    /// order is requested.
+    ///
+    /// `None` is returned when the device does not advertise any icon.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned when the device does not have an information
+    /// route, when the request is blocked by the privacy policy, or when the
+    /// device information cannot be retrieved.
+    pub async fn icon(&self) -> Result<Option<Cow<'static, str>>, Error> {
+        self.info().await.map(|info| info.info().icon.clone())
+    }
+
+    /// Subscribes to the events streamed by the device through Server-Sent
+    /// Events, at the [`EVENTS_SSE_ROUTE`] route.
+    ///
+    /// Unlike [`Device::start_event_receiver`], events are received directly
+    /// from the device, without a broker. The subscription is a request to
+    /// the route described by the device, so it is subject to the privacy
+    /// policy, the credentials, and the transport of the controller, like
+    /// any other request. Each item of the returned stream is the content
+    /// of a device event, in the order it has been sent.
+    ///
+    /// The subscription ends when the returned stream is dropped.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned when the device does not describe an events
+    /// route, when the request is blocked by the privacy policy, or when
+    /// the device cannot be contacted or does not stream events.
+    ///
+    /// Each item of the stream is an error when it does not contain valid
+    /// events, or when the connection to the device is lost.
+    #[cfg(feature = "stream")]
+    pub async fn subscribe_sse(
+        &self,
+    ) -> Result<impl Stream<Item = Result<Events, Error>> + use<>, Error> {
+        let response = self
+            .request(EVENTS_SSE_ROUTE)?
+            .with_header(ACCEPT.as_str(), SSE_MEDIA_TYPE)?
+            .send()
+            .await?;
+
+        match response {
+            Response::StreamBody(stream) if stream.status().is_success() => {
+                Ok(stream.open_sse_stream())
+            }
+            Response::StreamBody(stream) => Err(Error::new(
+                ErrorKind::Events,
+                format!(
+                    "The device does not stream events: status code {}",
+                    stream.status()
+                ),
+            )),
+            Response::Skipped => Err(sender_error(
+                "The events subscription has been blocked by the privacy policy.",
+            )),
+            _ => Err(Error::new(
+                ErrorKind::Events,
+                "The device events route does not answer with a stream.",
+            )),
+        }
+    }
+
+    // Retrieves the device information through its information route.
+    async fn info(&self) -> Result<InfoResponse, Error> {
+        let route = self
+            .device
+            .info_route()
+            .ok_or_else(|| sender_error("The device does not have an information route."))?;
+
+        let Response::InfoBody(parser) = self.request(route)?.send().await? else {
+            return Err(sender_error(
+                "The information request has been blocked by the privacy policy.",
+            ));
+        };
+
+        parser.parse_body().await
+    }
+
     fn evaluate_privacy_policy(&self, request: &Request, route: &str) -> bool {
         let mut skip = false;
 
+        if self
+            .controller
+            .privacy_policy
+            .blocks_unspecified_hazards(&request.hazards)
+        {
+            warn!("The {route} is skipped because its hazards are unspecified");
+            skip = true;
+        }
+
         let global_blocked_hazards = self
             .controller
             .privacy_policy
@@ -140,13 +496,25 @@ impl DeviceSender<'_> {
 ///
 /// - A privacy policy
 ///
+/// Requests sent to a device having a credential in the controller
+/// [`CredentialStore`] are authenticated with its bearer token.
+///
 /// When the controller receives a response from a device, it forwards it
 /// directly to the caller.
+///
+/// All requests are sent through a single `HTTP` client, which reuses the
+/// connections to a device. The client is thread-safe, so requests sent
+/// concurrently from different tasks share the same connection pool.
 #[derive(Debug, PartialEq)]
 pub struct Controller {
     discovery: Discovery,
     devices: Devices,
+    unreachable: Vec<DiscoveryRecord>,
     privacy_policy: Policy,
+    credentials: CredentialStore,
+    client: HttpClient,
+    descriptions: DescriptionCache,
+    event_history: Option<EventHistory>,
 }
 
 impl Controller {
@@ -157,7 +525,12 @@ impl Controller {
         Self {
             discovery,
             devices: Devices::new(),
+            unreachable: Vec::new(),
             privacy_policy: Policy::init(),
+            credentials: CredentialStore::new(),
+            client: HttpClient::new(ClientConfig::new()),
+            descriptions: DescriptionCache::new(),
+            event_history: None,
         }
     }
 
@@ -172,7 +545,12 @@ impl Controller {
         Self {
             discovery,
             devices,
+            unreachable: Vec::new(),
             privacy_policy: Policy::init(),
+            credentials: CredentialStore::new(),
+            client: HttpClient::new(ClientConfig::new()),
+            descriptions: DescriptionCache::new(),
+            event_history: None,
         }
     }
 
@@ -184,34 +562,179 @@ impl Controller {
         self
     }
 
+    /// Sets the [`CredentialStore`] used to authenticate requests.
+    #[must_use]
+    #[inline]
+    pub fn credentials(mut self, credentials: CredentialStore) -> Self {
+        self.credentials = credentials;
+        self
+    }
+
+    /// Sets the [`ClientConfig`] of the `HTTP` client used to send requests.
+    #[must_use]
+    #[inline]
+    pub fn client_config(mut self, client_config: ClientConfig) -> Self {
+        self.client = self.client.configure(client_config);
+        self
+    }
+
+    /// Limits the number of requests in flight at the same time to the given
+    /// maximum, which is at least one.
+    ///
+    /// Requests exceeding the limit, such as those sent concurrently to many
+    /// devices, wait until a previous request receives its response headers.
+    /// The body of a response is read after its request leaves the limit, so
+    /// streamed responses still being read are not counted.
+    ///
+    /// A maximum greater than [`tokio::sync::Semaphore::MAX_PERMITS`] means no
+    /// limit.
+    #[must_use]
+    #[inline]
+    pub fn max_concurrent_requests(mut self, max_concurrent_requests: usize) -> Self {
+        self.client = self
+            .client
+            .with_max_concurrent_requests(max_concurrent_requests);
+        self
+    }
+
+    /// Delivers every request through the given [`Transport`] instead of
+    /// `HTTP`, such as a relay or a Bluetooth bridge.
+    ///
+    /// Requests are still built as `HTTP` requests, so a transport receives
+    /// their method, address, headers, and body.
+    #[must_use]
+    #[inline]
+    pub fn transport(mut self, transport: impl Transport) -> Self {
+        self.client = self.client.with_transport(transport);
+        self
+    }
+
+    /// Records every request sent to a device, along with its response,
+    /// into the given [`SessionRecorder`].
+    ///
+    /// It replaces any session replayed through [`Self::replay_session`].
+    #[cfg(any(test, feature = "test-util"))]
+    #[must_use]
+    #[inline]
+    pub fn record_session(mut self, recorder: SessionRecorder) -> Self {
+        self.client = self.client.with_session(Session::Record(recorder));
+        self
+    }
+
+    /// Answers every request with a response recorded in the given
+    /// [`SessionPlayer`], without contacting any device.
+    ///
+    /// It replaces any session recorded through [`Self::record_session`].
+    #[cfg(any(test, feature = "test-util"))]
+    #[must_use]
+    #[inline]
+    pub fn replay_session(mut self, player: SessionPlayer) -> Self {
+        self.client = self.client.with_session(Session::Replay(player));
+        self
+    }
+
+    /// Sets the time a device description retrieved through
+    /// [`Self::description`] is reused without contacting the device.
+    ///
+    /// By default, a description is reused for `60` seconds.
+    #[must_use]
+    #[inline]
+    pub fn description_ttl(mut self, ttl: Duration) -> Self {
+        self.descriptions = DescriptionCache::with_ttl(ttl);
+        self
+    }
+
+    /// Keeps the last `capacity` events received from each [`Device`] by the
+    /// receivers of [`Self::start_event_receivers`].
+    ///
+    /// The history holds at most `capacity` events for each device, so its
+    /// memory grows with the number of devices, but never with the number of
+    /// received events. Once the history of a device is full, its oldest
+    /// events are discarded.
+    ///
+    /// By default, no event is kept.
+    #[must_use]
+    #[inline]
+    pub fn event_history(mut self, capacity: usize) -> Self {
+        self.event_history = Some(EventHistory::new(capacity));
+        self
+    }
+
     /// Change preset [`Policy`].
     #[inline]
     pub fn change_policy(&mut self, privacy_policy: Policy) {
         self.privacy_policy = privacy_policy;
     }
 
+    /// Returns a mutable reference to the [`CredentialStore`].
+    #[must_use]
+    #[inline]
+    pub const fn credentials_mut(&mut self) -> &mut CredentialStore {
+        &mut self.credentials
+    }
+
     /// Discovers all available [`Devices`] in a network.
     ///
-    /// # Errors
+    /// It collects the events of [`Self::discover_stream`] until no event is
+    /// reported within the discovery timeout. Devices which cannot be
+    /// contacted, or whose description is invalid, are skipped, and their
+    /// records are returned by [`Self::unreachable_records`].
     ///
-    /// ## Discovery Errors
+    /// # Errors
     ///
     /// During a discovery process some of the most common errors are the
     /// impossibility to connect to a network, disable a particular interface,
     /// or close the discovery process itself.
+    #[inline]
+    pub async fn discover(&mut self) -> Result<(), Error> {
+        (self.devices, self.unreachable) = self.discovery.discover().await?;
+        self.descriptions.clear();
+        Ok(())
+    }
+
+    /// Discovers all available [`Devices`] in a network, stopping early when
+    /// the given [`CancellationToken`] is cancelled.
     ///
-    /// ## Sending Requests Errors
+    /// It behaves like [`Self::discover`], but a cancellation, such as a
+    /// user stopping a search, is not an error: the devices found until
+    /// then are kept.
     ///
-    /// While sending a request to a device to obtain the description of its
-    /// structure and all of its routes, some network failures or
-    /// timeouts can prevent the effective sending.
-    /// Moreover, the same issues can also affect the return response.
+    /// # Errors
+    ///
+    /// During a discovery process some of the most common errors are the
+    /// impossibility to connect to a network, disable a particular interface,
+    /// or close the discovery process itself.
     #[inline]
-    pub async fn discover(&mut self) -> Result<(), Error> {
-        self.devices = self.discovery.discover().await?;
+    pub async fn discover_with_cancel(
+        &mut self,
+        cancellation_token: CancellationToken,
+    ) -> Result<(), Error> {
+        (self.devices, self.unreachable) = self
+            .discovery
+            .discover_with_cancel(&cancellation_token)
+            .await?;
+        self.descriptions.clear();
         Ok(())
     }
 
+    /// Discovers [`Device`]s in a network, reporting each of them as soon as
+    /// it is found or lost.
+    ///
+    /// The returned stream never ends on its own, so it is up to the caller
+    /// to stop polling it. Dropping the stream stops the discovery process.
+    ///
+    /// Discovered devices are not added to the [`Controller`], use
+    /// [`Self::discover`] to collect them.
+    ///
+    /// # Errors
+    ///
+    /// Some of the most common errors are the impossibility to connect to a
+    /// network or disable a particular interface.
+    #[inline]
+    pub fn discover_stream(&self) -> Result<impl Stream<Item = DiscoveryEvent> + use<>, Error> {
+        self.discovery.discover_stream()
+    }
+
     /// Starts asynchronous event receiver tasks for all [`Device`]s that
     /// support events.
     ///
@@ -250,7 +773,14 @@ impl Controller {
                 continue;
             };
 
-            EventsRunner::run_global_subscriber(events, id, tx.clone()).await?;
+            let handle = EventsRunner::run_global_subscriber(
+                events,
+                id,
+                tx.clone(),
+                self.event_history.clone(),
+            )
+            .await?;
+            device.event_handle = Some(handle);
 
             started_count += 1;
         }
@@ -265,6 +795,34 @@ impl Controller {
         Ok(rx)
     }
 
+    /// Stops the event receiver of the [`Device`] with the given identifier,
+    /// leaving the receivers of all other devices running.
+    ///
+    /// The receiver task terminates and releases its side of the channel,
+    /// whether started through [`Self::start_event_receivers`] or
+    /// [`Device::start_event_receiver`], so the receiver can be started
+    /// again afterwards.
+    ///
+    /// Nothing happens when the receiver is not running or the identifier
+    /// does not belong to any device.
+    pub async fn stop_event_receiver(&mut self, id: usize) {
+        if let Some(device) = self.devices.0.get_mut(id) {
+            device.stop_event_receiver().await;
+        }
+    }
+
+    /// Returns the last `n` [`Events`] received from the [`Device`] with the
+    /// given identifier, from the oldest to the most recent.
+    ///
+    /// Events are recorded only when an event history is set through
+    /// [`Self::event_history`], otherwise no events are returned.
+    #[must_use]
+    pub fn recent_events(&self, id: usize, n: usize) -> Vec<Events> {
+        self.event_history
+            .as_ref()
+            .map_or_else(Vec::new, |history| history.recent(id, n))
+    }
+
     /// Returns an immutable reference to the internal [`Devices`].
     #[must_use]
     pub const fn devices(&self) -> &Devices {
@@ -277,6 +835,70 @@ impl Controller {
         &mut self.devices
     }
 
+    /// Returns the [`DiscoveryRecord`]s of the services resolved during the
+    /// last discovery whose devices could not be contacted.
+    ///
+    /// It allows diagnosing devices which have been found, but which are not
+    /// reachable.
+    #[must_use]
+    pub fn unreachable_records(&self) -> &[DiscoveryRecord] {
+        &self.unreachable
+    }
+
+    /// Returns an iterator over the [`Device`]s advertising the given
+    /// location, along with their identifiers.
+    ///
+    /// Devices which do not advertise any location are never returned.
+    pub fn devices_in<'a>(
+        &'a self,
+        location: &'a str,
+    ) -> impl Iterator<Item = (usize, &'a Device)> + 'a {
+        self.devices.iter().enumerate().filter(move |(_, device)| {
+            device.network_info().device_properties.location() == Some(location)
+        })
+    }
+
+    /// Returns an iterator over the [`Device`]s of the given [`DeviceKind`],
+    /// along with their identifiers.
+    ///
+    /// Kinds are compared by their names, so a [`DeviceKind::Custom`] kind
+    /// matches the devices describing themselves with the same name.
+    pub fn devices_of_kind<'a>(
+        &'a self,
+        kind: &'a DeviceKind,
+    ) -> impl Iterator<Item = (usize, &'a Device)> + 'a {
+        self.devices
+            .iter()
+            .enumerate()
+            .filter(move |(_, device)| device.description().kind.as_str() == kind.as_str())
+    }
+
+    /// Exports the discovered network topology as a `JSON` array.
+    ///
+    /// Each array element describes a [`Device`] through its identifier,
+    /// kind, address, location, and routes, along with all data needed to
+    /// rebuild it through [`Self::import_topology`].
+    #[must_use]
+    pub fn export_topology(&self) -> serde_json::Value {
+        topology::export(&self.devices)
+    }
+
+    /// Creates a [`Controller`] from a [`Discovery`] configuration and
+    /// a network topology produced by [`Self::export_topology`].
+    ///
+    /// Imported [`Device`]s have no live connections, so this method might
+    /// be useful for tests and simulations.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned when the topology is not valid.
+    pub fn import_topology(
+        discovery: Discovery,
+        topology: serde_json::Value,
+    ) -> Result<Self, Error> {
+        topology::import(topology).map(|devices| Self::from_devices(discovery, devices))
+    }
+
     /// Builds a [`DeviceSender`] for the [`Device`] with the given identifier.
     ///
     /// # Errors
@@ -298,6 +920,128 @@ impl Controller {
         })
     }
 
+    /// Runs a [`Scene`], returning a [`SceneReport`] with the result of each
+    /// of its steps.
+    ///
+    /// Steps are run in order. A step fails when its request cannot be sent,
+    /// when it is blocked by the privacy policy, or when the device answers
+    /// with an error. After a failed step, no further steps are run, and the
+    /// compensating actions of the completed steps are run in reverse order.
+    pub async fn run_scene(&self, scene: &Scene<'_>) -> SceneReport {
+        let mut steps = Vec::with_capacity(scene.steps.len());
+        for step in &scene.steps {
+            let result = self.run_scene_action(&step.action).await;
+            let failed = result.is_err();
+            steps.push(result);
+            if failed {
+                break;
+            }
+        }
+
+        let mut compensations = Vec::new();
+        if steps.last().is_some_and(Result::is_err) {
+            let completed = &scene.steps[..steps.len() - 1];
+            for (index, step) in completed.iter().enumerate().rev() {
+                if let Some(ref compensation) = step.compensation {
+                    compensations.push((index, self.run_scene_action(compensation).await));
+                }
+            }
+        }
+
+        SceneReport {
+            steps,
+            compensations,
+        }
+    }
+
+    async fn run_scene_action(&self, action: &SceneAction<'_>) -> Result<Response, Error> {
+        let device_sender = self.device(action.id)?;
+        let request_sender = device_sender.request(&action.route)?;
+
+        let response = match action.parameters {
+            Some(ref parameters) => request_sender.send_with_parameters(parameters).await?,
+            None => request_sender.send().await?,
+        };
+
+        if matches!(response, Response::Skipped) {
+            return Err(sender_error(format!(
+                "The `{}` request is blocked by the privacy policy.",
+                action.route
+            )));
+        }
+
+        response.ok_or_error().await
+    }
+
+    /// Retrieves the [`DeviceData`] describing the [`Device`] with the
+    /// given identifier.
+    ///
+    /// A description is cached, so it is reused without contacting the
+    /// device until its time-to-live elapses. Afterwards, the device is
+    /// asked to send the description again only when it has changed.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned when the given identifier **does** not exist,
+    /// when the device cannot be contacted, or when its description is
+    /// invalid.
+    pub async fn description(&mut self, id: usize) -> Result<&DeviceData, Error> {
+        let device = self.devices.get(id).ok_or(sender_error(format!(
+            "Error in retrieving the device with identifier {id}."
+        )))?;
+
+        self.descriptions
+            .description(
+                self.client.client(),
+                id,
+                &device.network_info().last_reachable_address,
+            )
+            .await
+    }
+
+    /// Retrieves the uptime of the [`Device`] with the given identifier
+    /// through its information route, chosen as in [`DeviceSender::icon`].
+    ///
+    /// The uptime is then available through [`Device::uptime`]. When the
+    /// retrieved uptime is lower than the previous one, the device has been
+    /// rebooted in the meantime, so a warning is emitted.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned when the given identifier **does** not exist,
+    /// when the device has no information route or its request is blocked
+    /// by the privacy policy, when the device cannot be contacted, or when
+    /// it does not report its uptime.
+    pub async fn refresh_uptime(&mut self, id: usize) -> Result<Duration, Error> {
+        let uptime = self
+            .device(id)?
+            .info()
+            .await?
+            .info()
+            .uptime
+            .map(Duration::from_secs)
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::JsonResponse,
+                    "The device does not report its uptime.",
+                )
+            })?;
+
+        if self.devices.0[id].record_uptime(uptime) {
+            warn!("The device with identifier {id} has been rebooted.");
+        }
+
+        Ok(uptime)
+    }
+
+    /// Invalidates the cached description of the [`Device`] with the given
+    /// identifier, so the next [`Self::description`] call contacts the
+    /// device.
+    #[inline]
+    pub fn invalidate(&mut self, id: usize) {
+        self.descriptions.invalidate(id);
+    }
+
     /// Shuts down the [`Controller`], stopping all asynchronous tasks and
     /// releasing all associated resources.
     ///
@@ -325,29 +1069,49 @@ impl Controller {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::{HashMap, HashSet};
     use std::fmt::Debug;
+    use std::net::Ipv4Addr;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
     use tracing::warn;
 
+    use tosca::device::{DeviceData, DeviceEnvironment, DeviceInfo, DeviceKind};
+    use tosca::events::{BrokerData, Event, Events, EventsDescription, Topic};
     use tosca::hazards::{Hazard, Hazards};
     use tosca::parameters::ParametersValues;
-    use tosca::response::{OkResponse, SerialResponse};
+    use tosca::response::{ErrorKind as DeviceErrorKind, OkResponse, ResponseKind};
+    use tosca::route::{Route, RouteConfig, RouteConfigs};
 
-    use serde::{Serialize, de::DeserializeOwned};
+    use serde::{Deserialize, Serialize, de::DeserializeOwned};
     use serde_json::json;
 
     use serial_test::serial;
 
-    use crate::device::Devices;
-    use crate::error::Error;
+    use crate::credentials::CredentialStore;
+    use crate::device::{Description, Device, Devices, NetworkInformation};
+    use crate::error::{Error, ErrorKind};
+    use crate::events::Events as DeviceEvents;
+    use crate::mock::MockDevice;
     use crate::policy::Policy;
     use crate::response::Response;
+    use crate::scene::{Scene, SceneStep};
+    use crate::transport::Transport;
 
-    use crate::device::tests::{create_light, create_unknown};
+    use crate::device::tests::{create_light, create_unknown, locate};
     use crate::discovery::tests::configure_discovery;
-    use crate::tests::{Brightness, check_function_with_device};
+    use crate::tests::{
+        Brightness, check_function_with_device, local_server, read_request, write_json,
+    };
 
-    use super::{Controller, DeviceSender, RequestSender, sender_error};
+    use super::{
+        AUTHORIZATION, ClientConfig, Controller, DescriptionCache, DeviceSender, HttpClient,
+        RequestSender, sender_error,
+    };
 
     #[test]
     fn empty_controller() {
@@ -358,7 +1122,12 @@ mod tests {
             Controller {
                 discovery: configure_discovery(),
                 devices: Devices::new(),
+                unreachable: Vec::new(),
                 privacy_policy: Policy::init(),
+                credentials: CredentialStore::new(),
+                client: HttpClient::new(ClientConfig::new()),
+                descriptions: DescriptionCache::new(),
+                event_history: None,
             }
         );
 
@@ -377,9 +1146,996 @@ mod tests {
             Controller {
                 discovery: configure_discovery(),
                 devices: Devices::from_devices(vec![create_light(), create_unknown()]),
+                unreachable: Vec::new(),
                 privacy_policy: Policy::init(),
+                credentials: CredentialStore::new(),
+                client: HttpClient::new(ClientConfig::new()),
+                descriptions: DescriptionCache::new(),
+                event_history: None,
+            }
+        );
+    }
+
+    #[test]
+    fn controller_event_history() {
+        let controller = Controller::new(configure_discovery()).event_history(3);
+        let history = controller.event_history.as_ref().unwrap();
+
+        let level = |value| {
+            let mut events = Events::empty().u8_events(vec![Event::u8("level")]);
+            events.update_u8_value(0, value);
+            events
+        };
+
+        for value in 0..5 {
+            history.record(0, &level(value));
+        }
+
+        // Only the latest events are retained, in order.
+        assert_eq!(
+            controller.recent_events(0, 10),
+            vec![level(2), level(3), level(4)]
+        );
+        assert_eq!(controller.recent_events(0, 2), vec![level(3), level(4)]);
+
+        // No events for an unknown device.
+        assert!(controller.recent_events(1, 10).is_empty());
+
+        // No events without a history.
+        assert!(
+            Controller::new(configure_discovery())
+                .recent_events(0, 10)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn controller_devices_in() {
+        let devices = Devices::from_devices(vec![
+            locate(create_light(), "kitchen"),
+            create_unknown(),
+            locate(create_light(), "bedroom"),
+        ]);
+
+        let controller = Controller::from_devices(configure_discovery(), devices);
+
+        let kitchen = controller.devices_in("kitchen").collect::<Vec<_>>();
+        assert_eq!(kitchen.len(), 1);
+        assert_eq!(kitchen[0].0, 0);
+        assert_eq!(
+            kitchen[0].1.network_info().device_properties.location(),
+            Some("kitchen")
+        );
+
+        let bedroom = controller.devices_in("bedroom").collect::<Vec<_>>();
+        assert_eq!(bedroom.len(), 1);
+        assert_eq!(bedroom[0].0, 2);
+
+        // Devices without a location are never returned.
+        assert_eq!(controller.devices_in("garage").count(), 0);
+    }
+
+    #[test]
+    fn controller_devices_of_kind() {
+        let valve = || MockDevice::new(DeviceKind::custom("WaterValve")).device();
+        let devices = Devices::from_devices(vec![valve(), create_light(), valve()]);
+
+        let controller = Controller::from_devices(configure_discovery(), devices);
+
+        let valves = controller
+            .devices_of_kind(&DeviceKind::custom("WaterValve"))
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>();
+        assert_eq!(valves, [0, 2]);
+
+        let lights = controller
+            .devices_of_kind(&DeviceKind::Light)
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>();
+        assert_eq!(lights, [1]);
+
+        // Custom kinds are compared by their names.
+        assert_eq!(
+            controller
+                .devices_of_kind(&DeviceKind::Custom("Light".into()))
+                .count(),
+            1
+        );
+        assert_eq!(
+            controller
+                .devices_of_kind(&DeviceKind::custom("Valve"))
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn controller_topology() {
+        let devices =
+            Devices::from_devices(vec![locate(create_light(), "kitchen"), create_unknown()]);
+
+        let controller = Controller::from_devices(configure_discovery(), devices);
+
+        let topology = controller.export_topology();
+        assert_eq!(topology.as_array().map(Vec::len), Some(2));
+        assert_eq!(topology[0]["id"], 0);
+        assert_eq!(topology[0]["kind"], "Light");
+        assert_eq!(topology[0]["address"], "http://192.168.1.174:5000");
+        assert_eq!(topology[0]["location"], "kitchen");
+        assert_eq!(topology[0]["routes"].as_array().map(Vec::len), Some(3));
+        assert_eq!(topology[1]["location"], serde_json::Value::Null);
+        assert_eq!(topology[1]["routes"].as_array().map(Vec::len), Some(2));
+
+        let imported = Controller::import_topology(configure_discovery(), topology).unwrap();
+        assert_eq!(imported.devices, controller.devices);
+        for (device, imported_device) in controller.devices.iter().zip(imported.devices.iter()) {
+            assert_eq!(device.route_configs(), imported_device.route_configs());
+        }
+
+        // An invalid topology is rejected.
+        assert!(Controller::import_topology(configure_discovery(), json!({})).is_err());
+    }
+
+    // Answers all requests with the description of a device, counting them.
+    //
+    // A request carrying the description entity tag is answered with a
+    // `304 Not Modified` status.
+    async fn description_server(requests: Arc<AtomicUsize>) -> u16 {
+        const ETAG: &str = "\"description\"";
+
+        let body = serde_json::to_string(&DeviceData::new(
+            DeviceKind::Light,
+            DeviceEnvironment::Os,
+            Some([0x02, 0, 0, 0, 0, 0x01]),
+            None,
+            "light/",
+            RouteConfigs::new(),
+            0,
+        ))
+        .unwrap();
+
+        local_server(move |mut stream| {
+            let requests = Arc::clone(&requests);
+            let body = body.clone();
+            async move {
+                let Some(request) = read_request(&mut stream, &mut Vec::new()).await else {
+                    return;
+                };
+                requests.fetch_add(1, Ordering::SeqCst);
+
+                if request.contains(&format!("if-none-match: {ETAG}")) {
+                    let response = format!(
+                        "HTTP/1.1 304 Not Modified\r\netag: {ETAG}\r\nconnection: close\r\n\r\n"
+                    );
+                    _ = stream.write_all(response.as_bytes()).await;
+                } else {
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\netag: {ETAG}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                        body.len()
+                    );
+                    _ = stream.write_all(response.as_bytes()).await;
+                }
+            }
+        })
+        .await
+    }
+
+    fn description_controller(port: u16, ttl: Duration) -> Controller {
+        let network_info = NetworkInformation::new(
+            "light._tosca._tcp.local.".into(),
+            HashSet::from([Ipv4Addr::LOCALHOST.into()]),
+            port,
+            HashMap::new(),
+            format!("http://127.0.0.1:{port}"),
+        );
+        let description =
+            Description::new(DeviceKind::Light, DeviceEnvironment::Os, "light/".into());
+        let device = Device::new(network_info, description, RouteConfigs::new());
+
+        Controller::from_devices(configure_discovery(), Devices::from_devices(vec![device]))
+            .description_ttl(ttl)
+    }
+
+    #[tokio::test]
+    async fn controller_description_cache() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let port = description_server(requests.clone()).await;
+
+        let mut controller = description_controller(port, Duration::from_mins(1));
+
+        // A second read within the time-to-live does not contact the device.
+        let main_route = controller.description(0).await.unwrap().main_route.clone();
+        assert_eq!(main_route, "light/");
+        assert_eq!(
+            controller.description(0).await.unwrap().main_route,
+            main_route
+        );
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        // An invalidated description is retrieved again.
+        controller.invalidate(0);
+        controller.description(0).await.unwrap();
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        // A non-existent device.
+        assert!(controller.description(1).await.is_err());
+
+        // An expired description is revalidated through its entity tag.
+        let mut controller = description_controller(port, Duration::ZERO);
+        controller.description(0).await.unwrap();
+        assert_eq!(
+            controller.description(0).await.unwrap().main_route,
+            "light/"
+        );
+        assert_eq!(requests.load(Ordering::SeqCst), 4);
+    }
+
+    // Answers requests with the given JSON bodies in order, repeating the
+    // last one once all bodies have been sent.
+    async fn json_server(bodies: Vec<serde_json::Value>) -> u16 {
+        let index = AtomicUsize::new(0);
+
+        local_server(move |mut stream| {
+            // Bodies are assigned in the order connections are accepted.
+            let index = index.fetch_add(1, Ordering::SeqCst);
+            let body = bodies[index.min(bodies.len() - 1)].to_string();
+            async move {
+                if read_request(&mut stream, &mut Vec::new()).await.is_some() {
+                    write_json(&mut stream, &body, false).await;
+                }
+            }
+        })
+        .await
+    }
+
+    // Runs a server answering each request after a delay, recording the
+    // maximum number of requests handled at the same time.
+    async fn concurrency_server(max_in_flight: Arc<AtomicUsize>) -> u16 {
+        let in_flight = Arc::new(AtomicUsize::new(0));
+
+        local_server(move |mut stream| {
+            let in_flight = Arc::clone(&in_flight);
+            let max_in_flight = Arc::clone(&max_in_flight);
+            async move {
+                if read_request(&mut stream, &mut Vec::new()).await.is_none() {
+                    return;
+                }
+
+                let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max_in_flight.fetch_max(current, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+
+                let body = json!({ "action_terminated_correctly": true }).to_string();
+                write_json(&mut stream, &body, false).await;
             }
+        })
+        .await
+    }
+
+    // Creates a controller for a single device, answering from the given
+    // port, with the given route.
+    fn json_controller(port: u16, route_config: RouteConfig) -> Controller {
+        let network_info = NetworkInformation::new(
+            "light._tosca._tcp.local.".into(),
+            HashSet::from([Ipv4Addr::LOCALHOST.into()]),
+            port,
+            HashMap::new(),
+            format!("http://127.0.0.1:{port}"),
+        );
+        let description =
+            Description::new(DeviceKind::Light, DeviceEnvironment::Os, "light/".into());
+        let device = Device::new(
+            network_info,
+            description,
+            RouteConfigs::new().insert(route_config),
         );
+
+        Controller::from_devices(configure_discovery(), Devices::from_devices(vec![device]))
+    }
+
+    // Reads an `MQTT` packet, returning its type and its content.
+    async fn read_mqtt_packet(stream: &mut tokio::net::TcpStream) -> Option<(u8, Vec<u8>)> {
+        let packet_type = stream.read_u8().await.ok()? >> 4;
+
+        let mut length = 0;
+        for shift in (0..28).step_by(7) {
+            let byte = stream.read_u8().await.ok()?;
+            length |= usize::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                break;
+            }
+        }
+
+        let mut content = vec![0; length];
+        stream.read_exact(&mut content).await.ok()?;
+        Some((packet_type, content))
+    }
+
+    // Runs a minimal `MQTT` broker which publishes the given events every
+    // few milliseconds to any subscribed client, until it disconnects.
+    async fn mqtt_broker(events: Events) -> u16 {
+        let payload = serde_json::to_vec(&events).unwrap();
+        let topic = b"light/events";
+        let mut publish = vec![0x30, u8::try_from(topic.len() + 3 + payload.len()).unwrap()];
+        publish.extend_from_slice(&u16::try_from(topic.len()).unwrap().to_be_bytes());
+        publish.extend_from_slice(topic);
+        // No properties.
+        publish.push(0);
+        publish.extend_from_slice(&payload);
+
+        local_server(move |mut stream| {
+            let publish = publish.clone();
+            async move {
+                let mut interval = tokio::time::interval(Duration::from_millis(20));
+                let mut subscribed = false;
+                loop {
+                    tokio::select! {
+                        packet = read_mqtt_packet(&mut stream) => {
+                            let reply = match packet {
+                                // Connect.
+                                Some((1, _)) => vec![0x20, 3, 0, 0, 0],
+                                // Subscribe.
+                                Some((8, content)) => {
+                                    subscribed = true;
+                                    vec![0x90, 4, content[0], content[1], 0, 0]
+                                }
+                                // Ping.
+                                Some((12, _)) => vec![0xd0, 0],
+                                Some(_) => continue,
+                                None => break,
+                            };
+                            if stream.write_all(&reply).await.is_err() {
+                                break;
+                            }
+                        }
+                        _ = interval.tick(), if subscribed => {
+                            if stream.write_all(&publish).await.is_err() {
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+        })
+        .await
+    }
+
+    // Creates a device whose events are published by a broker listening
+    // on the given port.
+    fn events_device(port: u16, events: Events) -> Device {
+        let network_info = NetworkInformation::new(
+            "light._tosca._tcp.local.".into(),
+            HashSet::from([Ipv4Addr::LOCALHOST.into()]),
+            port,
+            HashMap::new(),
+            format!("http://127.0.0.1:{port}"),
+        );
+        let description =
+            Description::new(DeviceKind::Light, DeviceEnvironment::Os, "light/".into());
+        let events = DeviceEvents::new(EventsDescription::new(
+            BrokerData::new(Ipv4Addr::LOCALHOST.into(), port),
+            Topic::new("light/events".into()),
+            events,
+        ));
+
+        Device::init(network_info, description, RouteConfigs::new(), Some(events))
+    }
+
+    #[tokio::test]
+    async fn controller_stop_event_receiver() {
+        let events = Events::empty().u8_events(vec![Event::u8("level")]);
+
+        let first_port = mqtt_broker(events.clone()).await;
+        let second_port = mqtt_broker(events.clone()).await;
+        let mut controller = Controller::from_devices(
+            configure_discovery(),
+            Devices::from_devices(vec![
+                events_device(first_port, events.clone()),
+                events_device(second_port, events),
+            ]),
+        );
+
+        let mut receiver = controller.start_event_receivers(16).await.unwrap();
+
+        // Events arrive from both devices.
+        let mut ids = HashSet::new();
+        while ids.len() < 2 {
+            let payload = tokio::time::timeout(Duration::from_secs(5), receiver.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            ids.insert(payload.device_id);
+        }
+
+        // Stopping a receiver twice, or an unknown one, does nothing.
+        controller.stop_event_receiver(0).await;
+        controller.stop_event_receiver(0).await;
+        controller.stop_event_receiver(5).await;
+
+        assert!(!controller.devices.0[0].is_event_receiver_running());
+        assert!(controller.devices.0[1].is_event_receiver_running());
+
+        // Discard the events sent before stopping the receiver.
+        while receiver.try_recv().is_ok() {}
+
+        // Only the second device still sends events.
+        for _ in 0..5 {
+            let payload = tokio::time::timeout(Duration::from_secs(5), receiver.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(payload.device_id, 1);
+        }
+
+        controller.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn controller_uptime() {
+        let port = json_server(vec![
+            json!({ "uptime": 10 }),
+            json!({ "uptime": 15 }),
+            json!({ "uptime": 3 }),
+        ])
+        .await;
+
+        let mut controller = json_controller(
+            port,
+            Route::get("Info", "/info")
+                .description("Retrieve device information.")
+                .serialize_data()
+                .change_response_kind(ResponseKind::Info),
+        );
+
+        // The uptime is unknown until retrieved.
+        assert_eq!(controller.devices.get(0).unwrap().uptime(), None);
+
+        // The uptime increases between two reads.
+        let first = controller.refresh_uptime(0).await.unwrap();
+        assert_eq!(first, Duration::from_secs(10));
+        assert!(controller.devices.get(0).unwrap().uptime().unwrap() >= first);
+
+        let second = controller.refresh_uptime(0).await.unwrap();
+        assert!(second > first);
+        assert!(controller.devices.get(0).unwrap().uptime().unwrap() >= second);
+
+        // A rebooted device reports a lower uptime.
+        let third = controller.refresh_uptime(0).await.unwrap();
+        assert_eq!(third, Duration::from_secs(3));
+        assert!(controller.devices.get(0).unwrap().uptime().unwrap() < second);
+
+        // A device without an information route.
+        let mut controller = Controller::from_devices(
+            configure_discovery(),
+            Devices::from_devices(vec![create_light()]),
+        );
+        assert!(controller.refresh_uptime(0).await.is_err());
+    }
+
+    #[derive(Deserialize)]
+    struct LightState {
+        on: bool,
+    }
+
+    async fn is_on(response: Response) -> bool {
+        response
+            .json::<LightState>()
+            .await
+            .is_ok_and(|state| state.on)
+    }
+
+    #[tokio::test]
+    async fn controller_await_condition() {
+        let state_route = || {
+            Route::get("State", "/state")
+                .description("Retrieve the light state.")
+                .serialize_data()
+                .change_response_kind(ResponseKind::Serial)
+        };
+
+        // The light turns on after the first poll.
+        let port = json_server(vec![json!({ "on": false }), json!({ "on": true })]).await;
+        let controller = json_controller(port, state_route());
+
+        assert!(
+            controller
+                .device(0)
+                .unwrap()
+                .await_condition("/state", is_on, Duration::from_secs(2))
+                .await
+                .is_ok()
+        );
+
+        // The light never turns on.
+        let port = json_server(vec![json!({ "on": false })]).await;
+        let controller = json_controller(port, state_route());
+
+        assert!(
+            controller
+                .device(0)
+                .unwrap()
+                .await_condition("/state", is_on, Duration::from_millis(300))
+                .await
+                .is_err()
+        );
+
+        // A non-existent route.
+        assert!(
+            controller
+                .device(0)
+                .unwrap()
+                .await_condition("/wrong", is_on, Duration::from_millis(300))
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn controller_precondition() {
+        let mut info = DeviceInfo::empty();
+        info.handled_requests = Some(3);
+
+        let mock = MockDevice::new(DeviceKind::Light)
+            .info_route(
+                Route::get("Info", "/info").description("Retrieve device information."),
+                info,
+            )
+            .ok_route(Route::put("On", "/on").description("Turn light on."));
+        let controller = Controller::from_devices(
+            configure_discovery(),
+            Devices::from_devices(vec![mock.device()]),
+        );
+        let device_sender = controller.device(0).unwrap();
+
+        // The precondition fails, so the mutating request is never sent.
+        let Err(error) = device_sender
+            .request("/on")
+            .unwrap()
+            .if_state_matches(|info| info.handled_requests == Some(2))
+            .send()
+            .await
+        else {
+            panic!("The precondition was expected to fail");
+        };
+        assert_eq!(error.kind(), ErrorKind::Precondition);
+        assert_eq!(mock.received(), vec!["/info"]);
+
+        // The precondition holds.
+        assert!(matches!(
+            device_sender
+                .request("/on")
+                .unwrap()
+                .if_state_matches(|info| info.handled_requests == Some(3))
+                .send()
+                .await
+                .unwrap(),
+            Response::OkBody(_)
+        ));
+        assert_eq!(mock.received(), vec!["/info", "/info", "/on"]);
+    }
+
+    #[tokio::test]
+    async fn controller_device_icon() {
+        let mock = MockDevice::new(DeviceKind::Light).info_route(
+            Route::get("Info", "/info").description("Retrieve device information."),
+            DeviceInfo::empty().with_icon("lightbulb"),
+        );
+        let controller = Controller::from_devices(
+            configure_discovery(),
+            Devices::from_devices(vec![
+                mock.device(),
+                MockDevice::new(DeviceKind::Light).device(),
+            ]),
+        );
+
+        assert_eq!(
+            controller
+                .device(0)
+                .unwrap()
+                .icon()
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("lightbulb")
+        );
+
+        // A device without an information route.
+        assert!(controller.device(1).unwrap().icon().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn controller_info_route() {
+        let mock = MockDevice::new(DeviceKind::Light)
+            .info_route(
+                Route::put("Update energy", "/update-energy")
+                    .description("Update energy efficiency."),
+                DeviceInfo::empty(),
+            )
+            .info_route(
+                Route::get("Status", "/status").description("Retrieve device status."),
+                DeviceInfo::empty(),
+            )
+            .info_route(
+                Route::get("Info", "/info").description("Retrieve device information."),
+                DeviceInfo::empty().with_icon("lightbulb"),
+            );
+        let controller = Controller::from_devices(
+            configure_discovery(),
+            Devices::from_devices(vec![mock.device()]),
+        );
+        let device_sender = controller.device(0).unwrap();
+
+        // The mutating route is never requested, and the same read-only
+        // route is always chosen.
+        for _ in 0..3 {
+            assert_eq!(
+                device_sender.icon().await.unwrap().as_deref(),
+                Some("lightbulb")
+            );
+        }
+        assert_eq!(mock.received(), vec!["/info", "/info", "/info"]);
+    }
+
+    #[tokio::test]
+    async fn controller_custom_headers() {
+        let port = json_server(vec![json!({ "action_terminated_correctly": true })]).await;
+        let controller = json_controller(
+            port,
+            Route::put("On", "/on")
+                .description("Turn light on.")
+                .serialize_data(),
+        );
+        let device_sender = controller.device(0).unwrap();
+
+        let request_sender = device_sender
+            .request("/on")
+            .unwrap()
+            .with_headers([("X-Api-Key", "secret"), ("Host", "light.local")])
+            .unwrap();
+        assert!(request_sender.headers.contains_key("x-api-key"));
+        assert!(matches!(
+            request_sender.send().await.unwrap(),
+            Response::OkBody(_)
+        ));
+
+        // Header injection.
+        assert!(
+            device_sender
+                .request("/on")
+                .unwrap()
+                .with_header("X-Api-Key", "secret\r\nX-Admin: true")
+                .is_err()
+        );
+
+        // Invalid header name.
+        assert!(
+            device_sender
+                .request("/on")
+                .unwrap()
+                .with_header("X Api Key", "secret")
+                .is_err()
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn controller_max_concurrent_requests() {
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        let port = concurrency_server(Arc::clone(&max_in_flight)).await;
+        let controller = json_controller(
+            port,
+            Route::put("On", "/on")
+                .description("Turn light on.")
+                .serialize_data(),
+        )
+        .max_concurrent_requests(2);
+
+        let device_sender = controller.device(0).unwrap();
+        let request_sender = device_sender.request("/on").unwrap();
+
+        let responses =
+            futures_util::future::join_all((0..10).map(|_| request_sender.send())).await;
+        for response in responses {
+            assert!(matches!(response.unwrap(), Response::OkBody(_)));
+        }
+
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn controller_unbounded_concurrent_requests() {
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        let port = concurrency_server(Arc::clone(&max_in_flight)).await;
+
+        // A maximum a semaphore cannot represent means no limit.
+        let controller = json_controller(
+            port,
+            Route::put("On", "/on")
+                .description("Turn light on.")
+                .serialize_data(),
+        )
+        .max_concurrent_requests(usize::MAX);
+
+        let device_sender = controller.device(0).unwrap();
+        let response = device_sender.request("/on").unwrap().send().await.unwrap();
+        assert!(matches!(response, Response::OkBody(_)));
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn controller_subscribe_sse() {
+        use futures_util::StreamExt;
+
+        use tokio::net::TcpListener;
+
+        use tosca::events::EVENTS_SSE_ROUTE;
+
+        use tosca_os::device::Device as OsDevice;
+        use tosca_os::server::Server;
+        use tosca_os::sse::EventStream;
+
+        // Bind the device server to a free port.
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let hazards = Hazards::new().insert(Hazard::LogEnergyConsumption);
+
+        let event_stream = EventStream::new();
+        let (close_tx, close_rx) = tokio::sync::oneshot::channel::<()>();
+        let server = tokio::spawn(
+            Server::new(
+                OsDevice::new()
+                    .main_route("/light")
+                    .event_stream(event_stream.clone(), hazards.clone())
+                    .unwrap(),
+            )
+            .listener(listener)
+            .with_graceful_shutdown(async move {
+                _ = close_rx.await;
+            })
+            .run(),
+        );
+
+        let events_route = || {
+            Route::get("Events", EVENTS_SSE_ROUTE)
+                .description("Stream device events through Server-Sent Events.")
+                .with_hazards(hazards.clone())
+                .serialize_data()
+                .change_response_kind(ResponseKind::Stream)
+        };
+        let controller = json_controller(port, events_route());
+        let device_sender = controller.device(0).unwrap();
+
+        // Wait for the server to accept connections.
+        let mut sse_stream = loop {
+            if let Ok(events_stream) = device_sender.subscribe_sse().await {
+                break Box::pin(events_stream);
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        };
+
+        let events =
+            Events::empty().bool_events(vec![Event::bool("on").description("Light status.")]);
+        assert_eq!(event_stream.send(events.clone()), 1);
+
+        assert_eq!(sse_stream.next().await.unwrap().unwrap(), events);
+
+        // A subscription blocked by the privacy policy.
+        let controller = json_controller(port, events_route()).policy(Policy::new(hazards.clone()));
+        assert_eq!(
+            controller
+                .device(0)
+                .unwrap()
+                .subscribe_sse()
+                .await
+                .err()
+                .map(|error| error.kind()),
+            Some(ErrorKind::Sender)
+        );
+
+        // A device without an events route.
+        let controller = json_controller(
+            port,
+            Route::put("On", "/on")
+                .description("Turn light on.")
+                .serialize_data(),
+        );
+        assert!(controller.device(0).unwrap().subscribe_sse().await.is_err());
+
+        drop(sse_stream);
+        _ = close_tx.send(());
+        server.await.unwrap().unwrap();
+    }
+
+    // A transport answering every request in-memory, recording the
+    // addresses of the received requests.
+    #[derive(Default)]
+    struct MemoryTransport(Mutex<Vec<String>>);
+
+    impl Transport for Arc<MemoryTransport> {
+        async fn send(&self, request: reqwest::Request) -> Result<reqwest::Response, Error> {
+            self.0.lock().unwrap().push(request.url().path().into());
+
+            let body = json!({ "action_terminated_correctly": true }).to_string();
+            Ok(http::Response::builder()
+                .header("content-type", "application/json")
+                .body(body)
+                .unwrap()
+                .into())
+        }
+    }
+
+    #[tokio::test]
+    async fn controller_custom_transport() {
+        let transport = Arc::new(MemoryTransport::default());
+
+        // No device listens on the port, so every response comes from the
+        // transport.
+        let controller = json_controller(
+            0,
+            Route::put("On", "/on")
+                .description("Turn light on.")
+                .serialize_data(),
+        )
+        .transport(Arc::clone(&transport));
+
+        let Response::OkBody(parser) = controller
+            .device(0)
+            .unwrap()
+            .request("/on")
+            .unwrap()
+            .send()
+            .await
+            .unwrap()
+        else {
+            panic!("An ok response was expected");
+        };
+        assert_eq!(parser.parse_body().await.unwrap(), OkResponse::ok());
+
+        assert_eq!(*transport.0.lock().unwrap(), vec!["/light/on"]);
+    }
+
+    #[tokio::test]
+    async fn controller_scene() {
+        let light = MockDevice::new(DeviceKind::Light)
+            .ok_route(Route::put("On", "/on").description("Turn light on."))
+            .ok_route(Route::put("Off", "/off").description("Turn light off."));
+        let blinds = MockDevice::new(DeviceKind::Unknown).status_route(
+            Route::put("Close", "/close").description("Close blinds."),
+            500,
+            &json!({
+                "error": "Internal",
+                "description": "Motor jammed",
+            }),
+        );
+        let controller = Controller::from_devices(
+            configure_discovery(),
+            Devices::from_devices(vec![light.device(), blinds.device()]),
+        );
+
+        let scene = Scene::new()
+            .step(SceneStep::new(0, "/on").compensation("/off"))
+            .step(SceneStep::new(1, "/close"))
+            .step(SceneStep::new(0, "/off"));
+
+        let report = controller.run_scene(&scene).await;
+        assert!(!report.is_success());
+        assert_eq!(report.failed_step(), Some(1));
+
+        // The third step is never run.
+        assert_eq!(report.steps.len(), 2);
+        assert!(report.steps[0].is_ok());
+        assert!(matches!(
+            report.steps[1],
+            Err(ref error) if error.kind() == ErrorKind::Device(DeviceErrorKind::Internal)
+        ));
+
+        // The first step is compensated.
+        assert_eq!(report.compensations.len(), 1);
+        assert_eq!(report.compensations[0].0, 0);
+        assert!(report.compensations[0].1.is_ok());
+        assert_eq!(light.received(), vec!["/on", "/off"]);
+        assert_eq!(blinds.received(), vec!["/close"]);
+
+        // A successful scene is never compensated.
+        let report = controller
+            .run_scene(&Scene::new().step(SceneStep::new(0, "/on").compensation("/off")))
+            .await;
+        assert!(report.is_success());
+        assert!(report.compensations.is_empty());
+        assert_eq!(light.received(), vec!["/on", "/off", "/on"]);
+    }
+
+    #[tokio::test]
+    async fn controller_credentials() {
+        let mock = MockDevice::new(DeviceKind::Light)
+            .ok_route(Route::put("On", "/on").description("Turn light on."));
+
+        let credentials = CredentialStore::from_tokens(HashMap::from([(
+            "first._tosca._tcp.local.",
+            "first-token",
+        )]))
+        .unwrap();
+        let mut controller = Controller::from_devices(
+            configure_discovery(),
+            Devices::from_devices(vec![
+                mock.named_device("first._tosca._tcp.local."),
+                mock.named_device("second._tosca._tcp.local."),
+            ]),
+        )
+        .credentials(credentials);
+
+        // A device with stored credentials.
+        let device_sender = controller.device(0).unwrap();
+        let request_sender = device_sender.request("/on").unwrap();
+        assert_eq!(request_sender.headers[AUTHORIZATION], "Bearer first-token");
+        assert!(matches!(
+            request_sender.send().await.unwrap(),
+            Response::OkBody(_)
+        ));
+
+        // An unknown device.
+        let device_sender = controller.device(1).unwrap();
+        let request_sender = device_sender.request("/on").unwrap();
+        assert!(!request_sender.headers.contains_key(AUTHORIZATION));
+
+        // Credentials added later.
+        controller
+            .credentials_mut()
+            .insert("second._tosca._tcp.local.", "second-token")
+            .unwrap();
+        let device_sender = controller.device(1).unwrap();
+        let request_sender = device_sender.request("/on").unwrap();
+        assert_eq!(request_sender.headers[AUTHORIZATION], "Bearer second-token");
+
+        // Header injection.
+        assert_eq!(
+            controller
+                .credentials_mut()
+                .insert("second._tosca._tcp.local.", "token\r\nX-Admin: true")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::Credentials
+        );
+    }
+
+    #[tokio::test]
+    async fn controller_credentials_rediscovery() {
+        let mock = MockDevice::new(DeviceKind::Light)
+            .ok_route(Route::put("On", "/on").description("Turn light on."));
+
+        let credentials = CredentialStore::from_tokens(HashMap::from([
+            ("first._tosca._tcp.local.", "first-token"),
+            ("second._tosca._tcp.local.", "second-token"),
+        ]))
+        .unwrap();
+        let mut controller = Controller::from_devices(
+            configure_discovery(),
+            Devices::from_devices(vec![
+                mock.named_device("first._tosca._tcp.local."),
+                mock.named_device("second._tosca._tcp.local."),
+            ]),
+        )
+        .credentials(credentials);
+
+        let device_sender = controller.device(0).unwrap();
+        let request_sender = device_sender.request("/on").unwrap();
+        assert_eq!(request_sender.headers[AUTHORIZATION], "Bearer first-token");
+
+        // Devices discovered again in a different order, replacing the
+        // previous ones.
+        controller.devices = Devices::from_devices(vec![
+            mock.named_device("second._tosca._tcp.local."),
+            mock.named_device("first._tosca._tcp.local."),
+        ]);
+
+        let device_sender = controller.device(0).unwrap();
+        let request_sender = device_sender.request("/on").unwrap();
+        assert_eq!(request_sender.headers[AUTHORIZATION], "Bearer second-token");
+
+        let device_sender = controller.device(1).unwrap();
+        let request_sender = device_sender.request("/on").unwrap();
+        assert_eq!(request_sender.headers[AUTHORIZATION], "Bearer first-token");
     }
 
     async fn check_ok_response_plain(device_sender: &DeviceSender<'_>, route: &str) {
@@ -468,7 +2224,7 @@ mod tests {
         let response = get_response(request).await.unwrap();
         if let Response::SerialBody(response) = response {
             let serial_response = response.parse_body::<T>().await.unwrap();
-            assert_eq!(serial_response, SerialResponse::new(value));
+            assert_eq!(serial_response.into_inner(), value);
         } else {
             assert!(
                 matches!(response, Response::Skipped),
@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+
+use reqwest::header::HeaderValue;
+
+use crate::error::{Error, ErrorKind};
+
+/// A store of the authentication credentials of each
+/// [`crate::device::Device`].
+///
+/// A credential is a bearer token associated with the complete name of a
+/// device, as announced during discovery. Since a name does not depend on
+/// the order in which devices are discovered, a credential keeps following
+/// its device when devices are discovered again.
+///
+/// When a request is built for a device having a credential, it
+/// automatically carries an `Authorization` header containing its token,
+/// while requests for any other device carry none.
+///
+/// Tokens are never reported, neither by errors nor by the [`Debug`]
+/// representation of the store.
+#[derive(Debug, Default, PartialEq)]
+pub struct CredentialStore {
+    tokens: HashMap<String, HeaderValue>,
+}
+
+impl CredentialStore {
+    /// Creates an empty [`CredentialStore`].
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a [`CredentialStore`] from a map associating the complete
+    /// name of a [`crate::device::Device`] with its bearer token.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned when a token is not a valid header value, such
+    /// as a token containing a line break.
+    pub fn from_tokens<N, T>(tokens: HashMap<N, T>) -> Result<Self, Error>
+    where
+        N: Into<String>,
+        T: AsRef<str>,
+    {
+        tokens
+            .into_iter()
+            .try_fold(Self::new(), |store, (name, token)| {
+                store.bearer_token(name, token.as_ref())
+            })
+    }
+
+    /// Associates a bearer token with the [`crate::device::Device`] having
+    /// the given complete name, replacing any previous token.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned when the token is not a valid header value, such
+    /// as a token containing a line break.
+    pub fn bearer_token(mut self, name: impl Into<String>, token: &str) -> Result<Self, Error> {
+        self.insert(name, token)?;
+        Ok(self)
+    }
+
+    /// Associates a bearer token with the [`crate::device::Device`] having
+    /// the given complete name, replacing any previous token.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned when the token is not a valid header value, such
+    /// as a token containing a line break.
+    pub fn insert(&mut self, name: impl Into<String>, token: &str) -> Result<(), Error> {
+        let name = name.into();
+        // The token is not reported, since it is a secret.
+        let mut value = HeaderValue::from_str(&format!("Bearer {token}")).map_err(|_| {
+            Error::new(
+                ErrorKind::Credentials,
+                format!("Invalid bearer token for the device `{name}`."),
+            )
+        })?;
+        value.set_sensitive(true);
+
+        self.tokens.insert(name, value);
+        Ok(())
+    }
+
+    /// Removes the credential of the [`crate::device::Device`] with the
+    /// given complete name, returning whether it existed.
+    #[inline]
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.tokens.remove(name).is_some()
+    }
+
+    /// Checks whether the [`crate::device::Device`] with the given
+    /// complete name has a credential.
+    #[must_use]
+    #[inline]
+    pub fn contains(&self, name: &str) -> bool {
+        self.tokens.contains_key(name)
+    }
+
+    /// Checks whether the store is empty.
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    // Returns the `Authorization` header value of the device with the
+    // given complete name.
+    pub(crate) fn authorization(&self, name: &str) -> Option<&HeaderValue> {
+        self.tokens.get(name)
+    }
+}
@@ -1,15 +1,20 @@
 use std::collections::{HashMap, HashSet};
 use std::net::IpAddr;
+use std::time::{Duration, Instant};
 
 use serde::Serialize;
 
 use tokio::sync::broadcast::{self, Receiver};
 use tokio::task::JoinHandle;
 
+use tracing::error;
+
 use tosca::device::{DeviceEnvironment, DeviceKind};
 use tosca::events::{Events as ToscaEvents, EventsDescription};
-use tosca::route::RouteConfigs;
+use tosca::response::ResponseKind;
+use tosca::route::{RestKind, RouteConfigs};
 
+use crate::discovery::DiscoveryRecord;
 use crate::error::{Error, ErrorKind, Result};
 use crate::events::{Events, EventsRunner};
 use crate::request::{Request, RequestInfo, create_requests};
@@ -18,6 +23,107 @@ pub(crate) fn build_device_address(scheme: &str, address: &IpAddr, port: u16) ->
     format!("{scheme}://{address}:{port}")
 }
 
+// Default scheme used when a device does not advertise one.
+const DEFAULT_SCHEME: &str = "http";
+
+// Maximum time to wait for a device to answer a ping.
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Device properties.
+///
+/// Typed view of the properties advertised by a device during discovery.
+#[derive(Debug, PartialEq, Clone)]
+pub struct DeviceProperties {
+    // Scheme used to contact a device.
+    scheme: String,
+    // Path of the device well-known endpoint.
+    path: Option<String>,
+    // Device kind.
+    kind: Option<DeviceKind>,
+    // Number of device routes.
+    routes: Option<usize>,
+    // Device location.
+    location: Option<String>,
+    // Properties without a typed representation.
+    extra: HashMap<String, String>,
+}
+
+impl DeviceProperties {
+    /// Creates [`DeviceProperties`] from a map of raw properties.
+    ///
+    /// When no scheme is advertised, `http` is used. A device kind which
+    /// is not predefined is kept as a [`DeviceKind::Custom`] kind, while a
+    /// routes number which cannot be parsed is kept among the extra
+    /// properties.
+    #[must_use]
+    pub fn new(properties: &HashMap<String, String>) -> Self {
+        let mut scheme = None;
+        let mut path = None;
+        let mut kind = None;
+        let mut routes = None;
+        let mut location = None;
+        let mut extra = HashMap::new();
+
+        for (key, value) in properties {
+            match key.as_str() {
+                "scheme" => scheme = Some(value.clone()),
+                "path" => path = Some(value.clone()),
+                "kind" => kind = Some(DeviceKind::from_name_or_custom(value)),
+                "routes" if value.parse::<usize>().is_ok() => routes = value.parse().ok(),
+                "location" => location = Some(value.clone()),
+                _ => {
+                    extra.insert(key.clone(), value.clone());
+                }
+            }
+        }
+
+        Self {
+            scheme: scheme.unwrap_or_else(|| DEFAULT_SCHEME.into()),
+            path,
+            kind,
+            routes,
+            location,
+            extra,
+        }
+    }
+
+    /// Returns the scheme used to contact a device.
+    #[must_use]
+    pub fn scheme(&self) -> &str {
+        &self.scheme
+    }
+
+    /// Returns the path of the device well-known endpoint, if advertised.
+    #[must_use]
+    pub fn path(&self) -> Option<&str> {
+        self.path.as_deref()
+    }
+
+    /// Returns the device kind, if advertised.
+    #[must_use]
+    pub const fn kind(&self) -> Option<&DeviceKind> {
+        self.kind.as_ref()
+    }
+
+    /// Returns the number of device routes, if advertised.
+    #[must_use]
+    pub const fn routes(&self) -> Option<usize> {
+        self.routes
+    }
+
+    /// Returns the device location, if advertised.
+    #[must_use]
+    pub fn location(&self) -> Option<&str> {
+        self.location.as_deref()
+    }
+
+    /// Returns the properties without a typed representation.
+    #[must_use]
+    pub const fn extra(&self) -> &HashMap<String, String> {
+        &self.extra
+    }
+}
+
 /// Device network information.
 ///
 /// All data needed to contact a device in a network.
@@ -31,6 +137,9 @@ pub struct NetworkInformation {
     pub port: u16,
     /// Device properties.
     pub properties: HashMap<String, String>,
+    /// Device properties parsed from the raw ones.
+    #[serde(skip)]
+    pub device_properties: DeviceProperties,
     /// Device last reachable address.
     pub last_reachable_address: String,
 }
@@ -38,18 +147,20 @@ pub struct NetworkInformation {
 impl NetworkInformation {
     /// Creates a [`NetworkInformation`].
     #[must_use]
-    pub const fn new(
+    pub fn new(
         name: String,
         addresses: HashSet<IpAddr>,
         port: u16,
         properties: HashMap<String, String>,
         last_reachable_address: String,
     ) -> Self {
+        let device_properties = DeviceProperties::new(&properties);
         Self {
             name,
             addresses,
             port,
             properties,
+            device_properties,
             last_reachable_address,
         }
     }
@@ -80,6 +191,15 @@ impl Description {
     }
 }
 
+// Uptime reported by a device.
+#[derive(Debug, Clone, Copy)]
+struct ReportedUptime {
+    // Reported uptime.
+    uptime: Duration,
+    // Instant at which the uptime has been received.
+    received: Instant,
+}
+
 /// A compliant device.
 #[derive(Debug, Serialize)]
 pub struct Device {
@@ -89,6 +209,9 @@ pub struct Device {
     description: Description,
     // All device requests.
     requests: HashMap<String, Request>,
+    // All device routes, as advertised by the device.
+    #[serde(skip)]
+    route_configs: RouteConfigs,
     // All device events.
     //
     // If [`None`], the device does not support events.
@@ -97,6 +220,12 @@ pub struct Device {
     // The join handle for the event task.
     #[serde(skip)]
     pub(crate) event_handle: Option<JoinHandle<()>>,
+    // Last uptime reported by the device.
+    #[serde(skip)]
+    uptime: Option<ReportedUptime>,
+    // Device record, as received through discovery.
+    #[serde(skip)]
+    discovery_record: DiscoveryRecord,
 }
 
 impl PartialEq for Device {
@@ -119,24 +248,10 @@ impl Device {
         description: Description,
         route_configs: RouteConfigs,
     ) -> Self {
-        let requests = create_requests(
-            route_configs,
-            &network_info.last_reachable_address,
-            &description.main_route,
-            description.environment,
-        );
-
         // TODO: Check if the last reachable address works or it is better to
         // build a new one. Return a Result here, because we have to evaluate
         // data validity.
-
-        Self {
-            network_info,
-            description,
-            requests,
-            events: None,
-            event_handle: None,
-        }
+        Self::init(network_info, description, route_configs, None)
     }
 
     /// Returns an immutable reference to [`NetworkInformation`].
@@ -145,12 +260,29 @@ impl Device {
         &self.network_info
     }
 
+    /// Returns the [`DiscoveryRecord`] of the device, as received through
+    /// `mDNS-SD`.
+    ///
+    /// For a device which has not been discovered, the record is built from
+    /// its [`NetworkInformation`], hence its hostname is empty.
+    #[must_use]
+    pub const fn raw_discovery_record(&self) -> &DiscoveryRecord {
+        &self.discovery_record
+    }
+
     /// Returns an immutable reference to [`Description`].
     #[must_use]
     pub const fn description(&self) -> &Description {
         &self.description
     }
 
+    /// Returns an immutable reference to the [`RouteConfigs`] advertised
+    /// by a device.
+    #[must_use]
+    pub const fn route_configs(&self) -> &RouteConfigs {
+        &self.route_configs
+    }
+
     /// Returns an immutable reference to [`EventsDescription`].
     ///
     /// If [`None`], the device does not support events.
@@ -177,6 +309,21 @@ impl Device {
         self.requests.len()
     }
 
+    /// Returns the complete `URL` of each request, together with its route
+    /// and [`RestKind`].
+    ///
+    /// The returned vector is sorted by route.
+    #[must_use]
+    pub fn request_urls(&self) -> Vec<(String, String, RestKind)> {
+        let mut urls = self
+            .requests
+            .iter()
+            .map(|(route, request)| (route.clone(), request.route.clone(), request.kind))
+            .collect::<Vec<_>>();
+        urls.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        urls
+    }
+
     /// Returns the [`Request`] associated with the given route.
     ///
     /// If [`None`], the given route **does not** exist.
@@ -186,6 +333,77 @@ impl Device {
         self.requests.get(route)
     }
 
+    /// Returns the time elapsed since the device server started running.
+    ///
+    /// The uptime is estimated from the last value retrieved through
+    /// [`crate::controller::Controller::refresh_uptime`], adding the time
+    /// elapsed since then.
+    ///
+    /// If [`None`], the uptime has never been retrieved.
+    #[must_use]
+    pub fn uptime(&self) -> Option<Duration> {
+        self.uptime
+            .map(|reported| reported.uptime + reported.received.elapsed())
+    }
+
+    // Returns the route of the `GET` device request answering with device
+    // information.
+    //
+    // Requests with other methods might change the device state, so they are
+    // never chosen. When several routes qualify, the first one in path order
+    // is chosen, so the same route is always requested.
+    pub(crate) fn info_route(&self) -> Option<&str> {
+        self.requests
+            .iter()
+            .filter(|(_, request)| {
+                request.kind == RestKind::Get && request.response_kind == ResponseKind::Info
+            })
+            .map(|(route, _)| route.as_str())
+            .min()
+    }
+
+    // Records the uptime reported by a device.
+    //
+    // Returns whether the device has been rebooted since the last report,
+    // hence when the reported uptime is lower than the previous one.
+    pub(crate) fn record_uptime(&mut self, uptime: Duration) -> bool {
+        let rebooted = self.uptime.is_some_and(|reported| uptime < reported.uptime);
+
+        self.uptime = Some(ReportedUptime {
+            uptime,
+            received: Instant::now(),
+        });
+
+        rebooted
+    }
+
+    /// Measures the round-trip time of a request to a [`Device`].
+    ///
+    /// A `HEAD` request is sent to the device root, so no description is
+    /// transmitted, and any answer is considered valid, whatever its status.
+    /// A new connection is opened for each ping, so the connection setup is
+    /// part of the measured time, making the latencies of different devices
+    /// comparable.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned when the device cannot be contacted or does not
+    /// answer within five seconds.
+    pub async fn ping(&self) -> Result<Duration> {
+        let client = reqwest::Client::builder()
+            .pool_max_idle_per_host(0)
+            .timeout(PING_TIMEOUT)
+            .build()?;
+
+        let start = Instant::now();
+        client
+            .head(&self.network_info.last_reachable_address)
+            .send()
+            .await?;
+
+        Ok(start.elapsed())
+    }
+
     /// Checks if a [`Device`] supports events.
     #[must_use]
     pub const fn has_events(&self) -> bool {
@@ -248,20 +466,55 @@ impl Device {
         Ok(tx.subscribe())
     }
 
-    pub(crate) const fn init(
+    // Stops the event receiver task, if running.
+    pub(crate) async fn stop_event_receiver(&mut self) {
+        let Some(event_handle) = self.event_handle.take() else {
+            return;
+        };
+
+        if let Some(ref mut events) = self.events {
+            events.cancel();
+        }
+
+        if let Err(e) = event_handle.await {
+            error!("Failed to await the event task: {e}");
+        }
+    }
+
+    #[cfg(any(test, feature = "test-util"))]
+    pub(crate) fn requests_mut(&mut self) -> impl Iterator<Item = (&String, &mut Request)> {
+        self.requests.iter_mut()
+    }
+
+    pub(crate) fn init(
         network_info: NetworkInformation,
         description: Description,
-        requests: HashMap<String, Request>,
+        route_configs: RouteConfigs,
         events: Option<Events>,
     ) -> Self {
+        let requests = create_requests(
+            route_configs.clone(),
+            &network_info.last_reachable_address,
+            &description.main_route,
+            description.environment,
+        );
+
         Self {
-            network_info,
             description,
             requests,
+            route_configs,
             events,
             event_handle: None,
+            uptime: None,
+            discovery_record: DiscoveryRecord::from_network_info(&network_info),
+            network_info,
         }
     }
+
+    pub(crate) fn with_discovery_record(mut self, discovery_record: DiscoveryRecord) -> Self {
+        self.discovery_record = discovery_record;
+        self
+    }
 }
 
 /// A collection of [`Device`]s.
@@ -360,9 +613,13 @@ pub(crate) mod tests {
     use tosca::device::{DeviceEnvironment, DeviceKind};
     use tosca::hazards::{Hazard, Hazards};
     use tosca::parameters::Parameters;
-    use tosca::route::{Route, RouteConfigs};
+    use tosca::route::{RestKind, Route, RouteConfigs};
+
+    use crate::tests::{local_server, read_request, write_json};
 
-    use super::{Description, Device, Devices, NetworkInformation, build_device_address};
+    use super::{
+        Description, Device, DeviceProperties, Devices, NetworkInformation, build_device_address,
+    };
 
     fn create_network_info(address: &str, port: u16) -> NetworkInformation {
         let ip_address = address.parse().unwrap();
@@ -401,21 +658,23 @@ pub(crate) mod tests {
             .description("Turn light off.")
             .with_hazard(Hazard::LogEnergyConsumption);
 
-        let toggle_route = Route::get("Toggle", "/toggle")
+        let route_configs = RouteConfigs::new()
+            .insert(light_on_route.serialize_data())
+            .insert(light_off_route.serialize_data())
+            .insert(toggle_route().serialize_data());
+
+        Device::new(network_info, description, route_configs)
+    }
+
+    fn toggle_route() -> Route {
+        Route::get("Toggle", "/toggle")
             .description("Toggle a light.")
             .with_hazards(
                 Hazards::new()
                     .insert(Hazard::FireHazard)
                     .insert(Hazard::ElectricEnergyConsumption),
             )
-            .with_parameters(Parameters::new().rangeu64("brightness", (0, 20, 1)));
-
-        let route_configs = RouteConfigs::new()
-            .insert(light_on_route.serialize_data())
-            .insert(light_off_route.serialize_data())
-            .insert(toggle_route.serialize_data());
-
-        Device::new(network_info, description, route_configs)
+            .with_parameters(Parameters::new().rangeu64("brightness", (0, 20, 1)))
     }
 
     pub(crate) fn create_unknown() -> Device {
@@ -447,6 +706,15 @@ pub(crate) mod tests {
         Device::new(network_info, description, route_configs)
     }
 
+    pub(crate) fn locate(mut device: Device, location: &str) -> Device {
+        let network_info = &mut device.network_info;
+        network_info
+            .properties
+            .insert("location".into(), location.into());
+        network_info.device_properties = DeviceProperties::new(&network_info.properties);
+        device
+    }
+
     #[test]
     fn check_devices() {
         let devices_vector = vec![create_light(), create_unknown()];
@@ -476,4 +744,129 @@ pub(crate) mod tests {
         // Get a reference to a device. The order is important.
         assert_eq!(devices.get(1), Some(&create_unknown()));
     }
+
+    #[test]
+    fn check_route_display() {
+        assert_eq!(
+            toggle_route().serialize_data().to_string(),
+            "GET /toggle (hazards: Fire Hazard, Electric Energy Consumption) \
+             [params: brightness:u64] -> Ok"
+        );
+
+        assert_eq!(
+            Route::put("On", "/on").serialize_data().to_string(),
+            "PUT /on -> Ok"
+        );
+    }
+
+    #[test]
+    fn check_request_urls() {
+        let network_info = create_network_info("192.168.1.174", 5000);
+        let description = create_description(DeviceKind::Light, "light/");
+
+        let route_configs = RouteConfigs::new()
+            .insert(Route::put("On", "/on").serialize_data())
+            .insert(Route::put("Off", "/off").serialize_data());
+
+        let device = Device::new(network_info, description, route_configs);
+
+        assert_eq!(
+            device.request_urls(),
+            vec![
+                (
+                    "/off".into(),
+                    "http://192.168.1.174:5000/light/off".into(),
+                    RestKind::Put
+                ),
+                (
+                    "/on".into(),
+                    "http://192.168.1.174:5000/light/on".into(),
+                    RestKind::Put
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn check_device_properties() {
+        let mut properties = HashMap::new();
+        properties.insert("scheme".into(), "http".into());
+        properties.insert("firmware".into(), "1.0.0".into());
+        properties.insert("kind".into(), "Light".into());
+        properties.insert("routes".into(), "3".into());
+        properties.insert("location".into(), "kitchen".into());
+
+        let device_properties = DeviceProperties::new(&properties);
+
+        assert_eq!(device_properties.scheme(), "http");
+        assert_eq!(device_properties.path(), None);
+        assert_eq!(device_properties.kind(), Some(&DeviceKind::Light));
+        assert_eq!(device_properties.routes(), Some(3));
+        assert_eq!(device_properties.location(), Some("kitchen"));
+        assert_eq!(device_properties.extra().len(), 1);
+        assert_eq!(
+            device_properties
+                .extra()
+                .get("firmware")
+                .map(String::as_str),
+            Some("1.0.0")
+        );
+
+        // Without a scheme, `http` is used.
+        let mut properties = HashMap::new();
+        properties.insert("path".into(), "/.well-known/light".into());
+
+        let device_properties = DeviceProperties::new(&properties);
+
+        assert_eq!(device_properties.scheme(), "http");
+        assert_eq!(device_properties.path(), Some("/.well-known/light"));
+        assert_eq!(device_properties.kind(), None);
+        assert_eq!(device_properties.routes(), None);
+        assert_eq!(device_properties.location(), None);
+        assert!(device_properties.extra().is_empty());
+
+        // Device kinds which are not predefined are custom kinds.
+        let mut properties = HashMap::new();
+        properties.insert("kind".into(), "Fan".into());
+
+        let device_properties = DeviceProperties::new(&properties);
+
+        assert_eq!(device_properties.kind(), Some(&DeviceKind::custom("Fan")));
+        assert!(device_properties.extra().is_empty());
+
+        // Raw properties stay accessible.
+        let network_info = create_network_info("192.168.1.174", 5000);
+        assert_eq!(
+            network_info.properties.get("scheme").map(String::as_str),
+            Some(network_info.device_properties.scheme())
+        );
+    }
+
+    #[tokio::test]
+    async fn ping_device() {
+        let port = local_server(|mut stream| async move {
+            if read_request(&mut stream, &mut Vec::new()).await.is_some() {
+                write_json(&mut stream, "", false).await;
+            }
+        })
+        .await;
+
+        let device = Device::new(
+            create_network_info("127.0.0.1", port),
+            create_description(DeviceKind::Light, "light/"),
+            RouteConfigs::new(),
+        );
+
+        let latency = device.ping().await.unwrap();
+        assert!(latency > std::time::Duration::ZERO);
+        assert!(latency < std::time::Duration::from_secs(5));
+
+        // An unreachable device.
+        let device = Device::new(
+            create_network_info("127.0.0.1", 1),
+            create_description(DeviceKind::Light, "light/"),
+            RouteConfigs::new(),
+        );
+        assert!(device.ping().await.is_err());
+    }
 }
@@ -1,27 +1,37 @@
 use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::net::IpAddr;
 use std::time::Duration;
 
 use tosca::device::DeviceData;
 
-use flume::RecvTimeoutError;
+use futures_util::{Stream, StreamExt, stream};
 
-use mdns_sd::{IfKind, Receiver, ResolvedService, ServiceDaemon, ServiceEvent};
+use mdns_sd::{IfKind, ResolvedService, ScopedIp, ServiceDaemon, ServiceEvent};
 
-use tokio::time::sleep;
+use serde::Serialize;
+
+use tokio_util::sync::CancellationToken;
 
 use tracing::{info, warn};
 
-use crate::device::{Description, Device, Devices, NetworkInformation, build_device_address};
-use crate::error::Error;
+use crate::device::{
+    Description, Device, DeviceProperties, Devices, NetworkInformation, build_device_address,
+};
+use crate::error::{Error, ErrorKind};
 use crate::events::Events;
-use crate::request::create_requests;
 
 // Service top-level domain.
 //
 // It defines the default top-level domain for a service.
 const TOP_LEVEL_DOMAIN: &str = "local";
 
+// Port of a device resolved through `DNS`.
+//
+// A `DNS` record does not advertise any port, so the default port of a
+// `tosca-os` device is used.
+const DNS_DEVICE_PORT: u16 = 3000;
+
 /// Service transport protocol.
 #[derive(Debug, PartialEq)]
 pub enum TransportProtocol {
@@ -48,12 +58,114 @@ impl TransportProtocol {
     }
 }
 
+/// A device record, as received through `mDNS-SD`.
+///
+/// It allows diagnosing discovery issues, such as a device which has been
+/// found, but which is not reachable. Unlike the [`DeviceProperties`], its
+/// `TXT` properties are kept as they are, without being parsed.
+///
+/// `A` and `AAAA` records are received as an unordered set, so addresses are
+/// sorted to obtain a stable representation.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DiscoveryRecord {
+    /// Service complete name.
+    pub service_name: String,
+    /// Device hostname.
+    ///
+    /// It is empty when the device has not been discovered, such as a
+    /// device created from data contained in a database.
+    pub hostname: String,
+    /// Device `A` and `AAAA` records, sorted.
+    pub addresses: Vec<IpAddr>,
+    /// Device port.
+    pub port: u16,
+    /// `TXT` record properties, in the received order.
+    pub txt_properties: Vec<(String, String)>,
+}
+
+impl DiscoveryRecord {
+    // Creates a record from the network information of a device, when its
+    // `mDNS-SD` record is not available.
+    pub(crate) fn from_network_info(network_info: &NetworkInformation) -> Self {
+        let mut txt_properties = network_info
+            .properties
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect::<Vec<_>>();
+        txt_properties.sort();
+
+        let mut addresses = network_info.addresses.iter().copied().collect::<Vec<_>>();
+        addresses.sort();
+
+        Self {
+            service_name: network_info.name.clone(),
+            hostname: String::new(),
+            addresses,
+            port: network_info.port,
+            txt_properties,
+        }
+    }
+
+    fn from_service(service: &ResolvedService) -> Self {
+        let mut addresses = service
+            .addresses
+            .iter()
+            .map(ScopedIp::to_ip_addr)
+            .collect::<Vec<_>>();
+        addresses.sort();
+
+        Self {
+            service_name: service.fullname.clone(),
+            hostname: service.host.clone(),
+            addresses,
+            port: service.port,
+            txt_properties: service
+                .txt_properties
+                .iter()
+                .map(|property| (property.key().to_owned(), property.val_str().to_owned()))
+                .collect(),
+        }
+    }
+}
+
+/// An event reported while discovering devices.
+#[derive(Debug, PartialEq)]
+pub enum DiscoveryEvent {
+    /// A [`Device`] has been found and its data has been retrieved.
+    Found(Box<Device>),
+    /// The [`Device`] with the given complete name has left the network.
+    Lost(String),
+    /// A service has been resolved, but its [`Device`] has not been contacted
+    /// at any of its addresses, or it has returned invalid data.
+    Unreachable(DiscoveryRecord),
+}
+
+// A running `mDNS-SD` browser.
+//
+// When dropped, it stops browsing the service type and shuts down its daemon.
+struct Browser {
+    mdns: ServiceDaemon,
+    service_type: String,
+}
+
+impl Drop for Browser {
+    fn drop(&mut self) {
+        if let Err(e) = self.mdns.stop_browse(&self.service_type) {
+            warn!("Impossible to stop browsing {}: {e}", self.service_type);
+        }
+        if let Err(e) = self.mdns.shutdown() {
+            warn!("Impossible to shutdown the discovery daemon: {e}");
+        }
+    }
+}
+
 /// Devices discovery.
 ///
 /// It detects all `tosca`-compliant [`Device`]s in a network.
 #[derive(Debug, PartialEq)]
 pub struct Discovery {
     domain: Cow<'static, str>,
+    subtype: Option<Cow<'static, str>>,
     transport_protocol: TransportProtocol,
     top_level_domain: Cow<'static, str>,
     timeout: Duration,
@@ -69,6 +181,7 @@ impl Discovery {
     pub fn new(domain: impl Into<Cow<'static, str>>) -> Self {
         Self {
             domain: domain.into(),
+            subtype: None,
             transport_protocol: TransportProtocol::TCP,
             top_level_domain: Cow::Borrowed(TOP_LEVEL_DOMAIN),
             timeout: Duration::from_secs(2), // Default timeout of 2s.
@@ -100,6 +213,18 @@ impl Discovery {
         self
     }
 
+    /// Restricts the discovery to the devices advertising the given service
+    /// subtype, such as `light`.
+    ///
+    /// Devices are filtered by the `mDNS-SD` protocol itself, so devices
+    /// which do not advertise the subtype are never contacted.
+    #[must_use]
+    #[inline]
+    pub fn subtype(mut self, subtype: impl Into<Cow<'static, str>>) -> Self {
+        self.subtype = Some(subtype.into());
+        self
+    }
+
     /// Sets the service top-level domain.
     #[must_use]
     #[inline]
@@ -130,14 +255,163 @@ impl Discovery {
         self
     }
 
-    pub(crate) async fn discover(&self) -> Result<Devices, Error> {
-        // Discover devices.
-        let discovery_info = self.discover_devices().await?;
+    /// Resolves the [`Device`] with the given hostname, such as
+    /// `light.local`.
+    ///
+    /// Rather than collecting all devices in a network, it stops as soon as
+    /// a device with the given hostname is found, so it is faster to
+    /// reconnect to a known device. A hostname without a domain is
+    /// completed with the top-level domain.
+    ///
+    /// When no device is found through `mDNS-SD` within the timeout, the
+    /// hostname is resolved through `DNS`, and the device is contacted on
+    /// the default port of a `tosca-os` device.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned when the discovery process cannot be started, or
+    /// when no device with the given hostname can be contacted.
+    pub async fn resolve_hostname(&self, hostname: &str) -> Result<Device, Error> {
+        let hostname = self.complete_hostname(hostname);
+
+        if let Some(device) = self.resolve_mdns_hostname(&hostname).await? {
+            return Ok(device);
+        }
+
+        info!("No device found through mDNS-SD for {hostname}, trying DNS");
+        Self::resolve_dns_hostname(&hostname).await.ok_or_else(|| {
+            Error::new(
+                ErrorKind::Discovery,
+                format!("Unable to resolve the device with hostname `{hostname}`."),
+            )
+        })
+    }
+
+    pub(crate) async fn discover(&self) -> Result<(Devices, Vec<DiscoveryRecord>), Error> {
+        self.discover_with_cancel(&CancellationToken::new()).await
+    }
 
-        Self::obtain_devices_data(discovery_info).await
+    pub(crate) async fn discover_with_cancel(
+        &self,
+        cancellation_token: &CancellationToken,
+    ) -> Result<(Devices, Vec<DiscoveryRecord>), Error> {
+        let events = self.discover_stream()?;
+        Ok(self.collect_devices(events, cancellation_token).await)
     }
 
-    async fn discover_devices(&self) -> Result<Vec<ResolvedService>, Error> {
+    pub(crate) fn discover_stream(
+        &self,
+    ) -> Result<impl Stream<Item = DiscoveryEvent> + use<>, Error> {
+        let browser = self.browse()?;
+        let service_events = browser.mdns.browse(&browser.service_type)?.into_stream();
+
+        Ok(Self::discovery_events(service_events, Some(browser)))
+    }
+
+    // Collects the discovered devices, along with the records of the
+    // unreachable ones, until no event is reported within the timeout, or
+    // until the token is cancelled.
+    async fn collect_devices(
+        &self,
+        events: impl Stream<Item = DiscoveryEvent>,
+        cancellation_token: &CancellationToken,
+    ) -> (Devices, Vec<DiscoveryRecord>) {
+        let mut events = std::pin::pin!(events);
+        let mut devices = Vec::new();
+        let mut unreachable = Vec::new();
+
+        loop {
+            let event = tokio::select! {
+                biased;
+                () = cancellation_token.cancelled() => break,
+                event = tokio::time::timeout(self.timeout, events.next()) => event,
+            };
+
+            let Ok(Some(event)) = event else {
+                break;
+            };
+
+            match event {
+                DiscoveryEvent::Found(device) => {
+                    unreachable.retain(|record: &DiscoveryRecord| {
+                        record.service_name != device.network_info().name
+                    });
+                    devices.push(*device);
+                }
+                DiscoveryEvent::Lost(name) => {
+                    devices.retain(|device: &Device| device.network_info().name != name);
+                }
+                DiscoveryEvent::Unreachable(record) => {
+                    unreachable.retain(|other: &DiscoveryRecord| {
+                        other.service_name != record.service_name
+                    });
+                    unreachable.push(record);
+                }
+            }
+        }
+
+        (Devices::from_devices(devices), unreachable)
+    }
+
+    // Completes a hostname without a domain with the top-level domain,
+    // removing the trailing dot.
+    fn complete_hostname(&self, hostname: &str) -> String {
+        let hostname = hostname.trim_end_matches('.');
+        if hostname.contains('.') {
+            hostname.to_owned()
+        } else {
+            format!("{hostname}.{}", self.top_level_domain)
+        }
+    }
+
+    // Browses the devices until one with the given hostname is found, or
+    // the timeout elapses.
+    async fn resolve_mdns_hostname(&self, hostname: &str) -> Result<Option<Device>, Error> {
+        let browser = self.browse()?;
+        let mut service_events = browser.mdns.browse(&browser.service_type)?.into_stream();
+
+        let resolve = async {
+            while let Some(event) = service_events.next().await {
+                if let ServiceEvent::ServiceResolved(info) = event
+                    && info
+                        .host
+                        .trim_end_matches('.')
+                        .eq_ignore_ascii_case(hostname)
+                    && let Ok(device) = Self::obtain_device_data(&info).await
+                {
+                    return Some(device);
+                }
+            }
+            None
+        };
+
+        Ok(tokio::time::timeout(self.timeout, resolve)
+            .await
+            .ok()
+            .flatten())
+    }
+
+    async fn resolve_dns_hostname(hostname: &str) -> Option<Device> {
+        let addresses = match tokio::net::lookup_host((hostname, DNS_DEVICE_PORT)).await {
+            Ok(addresses) => addresses.map(|address| address.ip()).collect(),
+            Err(e) => {
+                warn!("Impossible to resolve {hostname} through DNS: {e}");
+                return None;
+            }
+        };
+
+        let device =
+            Self::contact_device(hostname.into(), addresses, DNS_DEVICE_PORT, HashMap::new())
+                .await?;
+
+        let discovery_record = DiscoveryRecord {
+            hostname: hostname.into(),
+            ..device.raw_discovery_record().clone()
+        };
+        Some(device.with_discovery_record(discovery_record))
+    }
+
+    fn browse(&self) -> Result<Browser, Error> {
         // Create a mdns daemon
         let mdns = ServiceDaemon::new()?;
 
@@ -164,133 +438,155 @@ impl Discovery {
             self.top_level_domain
         );
 
-        // Detects devices.
-        let receiver = mdns.browse(&service_type)?;
-
-        // Discovery service.
-        let mut discovery_service = Vec::new();
-
-        // Run for n-seconds in search of devices and saves their information
-        // in memory.
-        while let Ok(event) = self.with_timeout(&receiver).await {
-            if let ServiceEvent::ServiceResolved(info) = event {
-                // Check whether there are device addresses.
-                //
-                // If no address has been found, prints a warning and
-                // continue the loop.
-                if info.get_addresses().is_empty() {
-                    warn!("No device address available for {:?}", info);
-                    continue;
-                }
-
-                // If two devices are equal, skip to the next one.
-                if Self::check_device_duplicates(&discovery_service, &info) {
-                    continue;
-                }
-
-                discovery_service.push(*info);
-            }
-        }
+        // Browse only the service subtype, if any.
+        let service_type = match &self.subtype {
+            Some(subtype) => format!("_{}._sub.{service_type}", subtype.trim_start_matches('_')),
+            None => service_type,
+        };
 
-        // Stop detection.
-        mdns.stop_browse(&service_type)?;
+        Ok(Browser { mdns, service_type })
+    }
 
-        Ok(discovery_service)
+    // Converts the events reported by a `mDNS-SD` browser into discovery
+    // events.
+    //
+    // The browser, if any, is kept alive as long as the stream.
+    fn discovery_events<S>(
+        service_events: S,
+        browser: Option<Browser>,
+    ) -> impl Stream<Item = DiscoveryEvent>
+    where
+        S: Stream<Item = ServiceEvent> + Unpin,
+    {
+        stream::unfold(
+            (service_events, Vec::new(), browser),
+            |(mut service_events, mut discovery_service, browser)| async move {
+                loop {
+                    match service_events.next().await? {
+                        ServiceEvent::ServiceResolved(info) => {
+                            // Check whether there are device addresses.
+                            //
+                            // If no address has been found, prints a warning
+                            // and reports the device as unreachable.
+                            if info.get_addresses().is_empty() {
+                                warn!("No device address available for {:?}", info);
+                                return Some((
+                                    DiscoveryEvent::Unreachable(DiscoveryRecord::from_service(
+                                        &info,
+                                    )),
+                                    (service_events, discovery_service, browser),
+                                ));
+                            }
+
+                            // If two devices are equal, skip to the next one.
+                            if Self::check_device_duplicates(&discovery_service, &info) {
+                                continue;
+                            }
+
+                            let event = match Self::obtain_device_data(&info).await {
+                                Ok(device) => {
+                                    discovery_service.push(*info);
+                                    DiscoveryEvent::Found(Box::new(device))
+                                }
+                                Err(record) => DiscoveryEvent::Unreachable(record),
+                            };
+
+                            return Some((event, (service_events, discovery_service, browser)));
+                        }
+                        ServiceEvent::ServiceRemoved(_, fullname) => {
+                            let services_count = discovery_service.len();
+                            discovery_service.retain(|service| service.fullname != fullname);
+
+                            if discovery_service.len() != services_count {
+                                return Some((
+                                    DiscoveryEvent::Lost(fullname),
+                                    (service_events, discovery_service, browser),
+                                ));
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            },
+        )
     }
 
-    #[inline]
-    async fn with_timeout<T>(&self, receiver: &Receiver<T>) -> Result<T, RecvTimeoutError> {
-        let timeout_future = sleep(self.timeout);
-
-        tokio::select! {
-            () = timeout_future => {
-                // This is the same error returned by the `recv_timeout`
-                // function in case of a timeout.
-                Err(RecvTimeoutError::Timeout)
-            }
-            result = receiver.recv_async() => {
-                // The only variant of `RecvError` is mapped to the
-                // corresponding variant in `RecvTimeoutError`.
-                result.map_err(std::convert::Into::into)
-            }
+    // Contacts a resolved device, returning its record when it cannot be
+    // contacted.
+    async fn obtain_device_data(service: &ResolvedService) -> Result<Device, DiscoveryRecord> {
+        let discovery_record = DiscoveryRecord::from_service(service);
+
+        let device = Self::contact_device(
+            service.fullname.clone(),
+            service.addresses.iter().map(ScopedIp::to_ip_addr).collect(),
+            service.port,
+            service.txt_properties.clone().into_property_map_str(),
+        )
+        .await;
+
+        match device {
+            Some(device) => Ok(device.with_discovery_record(discovery_record)),
+            None => Err(discovery_record),
         }
     }
 
-    async fn obtain_devices_data(
-        discovery_service: Vec<ResolvedService>,
-    ) -> Result<Devices, Error> {
-        // Devices collection.
-        let mut devices = Devices::new();
-
-        // Iterate over discovered metadata
-        for service in discovery_service {
-            // Try to contact each available address for a device
-            // to retrieve data.
-            for address in &service.addresses {
-                let complete_address = build_device_address(
-                    service
-                        .txt_properties
-                        .get_property_val_str("scheme")
-                        // If the scheme is not specified as a property,
-                        // fall back to `http` as default.
-                        .unwrap_or("http"),
-                    &address.to_ip_addr(),
-                    service.port,
-                );
-                info!("Complete address: {complete_address}");
-
-                // Contact devices to retrieve their data
-                match reqwest::get(&complete_address).await {
-                    Ok(response) => {
-                        let device_data: DeviceData = response.json().await?;
-
-                        if device_data.wifi_mac.is_none() && device_data.ethernet_mac.is_none() {
-                            warn!(
-                                "Ignoring device {complete_address} because no valid MAC addresses have been found"
-                            );
-                            continue;
-                        }
-
-                        let requests = create_requests(
-                            device_data.route_configs,
-                            &complete_address,
-                            &device_data.main_route,
-                            device_data.environment,
-                        );
-
-                        let description = Description::new(
-                            device_data.kind,
-                            device_data.environment,
-                            device_data.main_route.into_owned(),
-                        );
-
-                        let network_info = NetworkInformation::new(
-                            service.fullname,
-                            service
-                                .addresses
-                                .into_iter()
-                                .map(|address| address.to_ip_addr())
-                                .collect(),
-                            service.port,
-                            service.txt_properties.into_property_map_str(),
-                            complete_address,
-                        );
-
-                        let events = device_data.events_description.map(Events::new);
-
-                        devices.add(Device::init(network_info, description, requests, events));
-
-                        // Only a single address is necessary.
-                        break;
-                    }
+    async fn contact_device(
+        name: String,
+        addresses: HashSet<IpAddr>,
+        port: u16,
+        properties: HashMap<String, String>,
+    ) -> Option<Device> {
+        let device_properties = DeviceProperties::new(&properties);
+
+        // Try to contact each available address for a device
+        // to retrieve data.
+        for address in &addresses {
+            let complete_address = build_device_address(device_properties.scheme(), address, port);
+            info!("Complete address: {complete_address}");
+
+            // Contact devices to retrieve their data
+            let device_data = match reqwest::get(&complete_address).await {
+                Ok(response) => match response.json::<DeviceData>().await {
+                    Ok(device_data) => device_data,
                     Err(e) => {
-                        warn!("Impossible to contact address {complete_address}: {e}");
+                        warn!("Invalid device data from address {complete_address}: {e}");
+                        continue;
                     }
+                },
+                Err(e) => {
+                    warn!("Impossible to contact address {complete_address}: {e}");
+                    continue;
                 }
+            };
+
+            if device_data.wifi_mac.is_none() && device_data.ethernet_mac.is_none() {
+                warn!(
+                    "Ignoring device {complete_address} because no valid MAC addresses have been found"
+                );
+                continue;
             }
+
+            let description = Description::new(
+                device_data.kind,
+                device_data.environment,
+                device_data.main_route.into_owned(),
+            );
+
+            let network_info =
+                NetworkInformation::new(name, addresses, port, properties, complete_address);
+
+            let events = device_data.events_description.map(Events::new);
+
+            // Only a single address is necessary.
+            return Some(Device::init(
+                network_info,
+                description,
+                device_data.route_configs,
+                events,
+            ));
         }
 
-        Ok(devices)
+        None
     }
 
     // A discovered device is equal to another device when:
@@ -332,17 +628,29 @@ impl Discovery {
 
 #[cfg(test)]
 pub(crate) mod tests {
+    use std::collections::HashMap;
+    use std::net::Ipv4Addr;
     use std::time::Duration;
 
+    use tosca::device::{DeviceData, DeviceEnvironment, DeviceKind};
+    use tosca::route::RouteConfigs;
+
+    use futures_util::{StreamExt, stream};
+
+    use mdns_sd::{ServiceEvent, ServiceInfo};
+
+    use tokio_util::sync::CancellationToken;
+
     use tracing::warn;
 
     use serial_test::serial;
 
     use crate::tests::{
-        DOMAIN, check_function_with_device, check_function_with_two_devices, compare_device_data,
+        DOMAIN, SUBTYPE, check_function_with_device, check_function_with_two_devices,
+        compare_device_data, local_server, read_request, write_json,
     };
 
-    use super::Discovery;
+    use super::{Discovery, DiscoveryEvent};
 
     pub(crate) fn configure_discovery() -> Discovery {
         Discovery::new(DOMAIN)
@@ -352,7 +660,11 @@ pub(crate) mod tests {
     }
 
     async fn discovery_comparison(devices_len: usize) {
-        let devices = configure_discovery().discover().await.unwrap();
+        discovery_comparison_with(configure_discovery(), devices_len).await;
+    }
+
+    async fn discovery_comparison_with(discovery: Discovery, devices_len: usize) {
+        let (devices, _) = discovery.discover().await.unwrap();
 
         // Count devices.
         assert_eq!(devices.len(), devices_len);
@@ -402,4 +714,220 @@ pub(crate) mod tests {
         })
         .await;
     }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    #[serial]
+    async fn test_subtype_discovery() {
+        run_discovery_function("discovery_with_subtype", || async {
+            check_function_with_device(|| async {
+                discovery_comparison_with(configure_discovery().subtype(SUBTYPE), 1).await;
+                discovery_comparison_with(configure_discovery().subtype("_fan"), 0).await;
+            })
+            .await;
+        })
+        .await;
+    }
+
+    // Answers all requests with the data of a device.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    #[serial]
+    async fn test_resolve_hostname() {
+        run_discovery_function("resolve_hostname", || async {
+            check_function_with_device(|| async {
+                for hostname in ["tosca", "tosca.local", "tosca.local."] {
+                    let device = configure_discovery()
+                        .resolve_hostname(hostname)
+                        .await
+                        .unwrap();
+                    compare_device_data(&device);
+                }
+            })
+            .await;
+        })
+        .await;
+    }
+
+    #[test]
+    fn test_complete_hostname() {
+        let discovery = configure_discovery();
+
+        assert_eq!(discovery.complete_hostname("light"), "light.local");
+        assert_eq!(discovery.complete_hostname("light.local."), "light.local");
+        assert_eq!(
+            discovery.complete_hostname("light.home.arpa"),
+            "light.home.arpa"
+        );
+        assert_eq!(
+            discovery
+                .top_level_domain("home.arpa")
+                .complete_hostname("light"),
+            "light.home.arpa"
+        );
+    }
+
+    async fn device_data_server(main_route: &'static str) -> u16 {
+        let body = serde_json::to_string(&DeviceData::new(
+            DeviceKind::Light,
+            DeviceEnvironment::Os,
+            Some([0x02, 0, 0, 0, 0, 0x01]),
+            None,
+            main_route,
+            RouteConfigs::new(),
+            0,
+        ))
+        .unwrap();
+
+        local_server(move |mut stream| {
+            let body = body.clone();
+            async move {
+                if read_request(&mut stream, &mut Vec::new()).await.is_some() {
+                    write_json(&mut stream, &body, false).await;
+                }
+            }
+        })
+        .await
+    }
+
+    fn service_resolved(name: &str, port: u16) -> ServiceEvent {
+        let service = ServiceInfo::new(
+            "_tosca._tcp.local.",
+            name,
+            "tosca.local.",
+            "127.0.0.1",
+            port,
+            HashMap::from([("scheme".to_owned(), "http".to_owned())]),
+        )
+        .unwrap()
+        .as_resolved_service();
+
+        ServiceEvent::ServiceResolved(Box::new(service))
+    }
+
+    #[tokio::test]
+    async fn test_discovery_events() {
+        let first_port = device_data_server("/first").await;
+        let second_port = device_data_server("/second").await;
+
+        // A mocked browser which finds two devices, reports the first one
+        // twice, and then loses the first one.
+        let service_events = stream::iter([
+            service_resolved("first", first_port),
+            service_resolved("second", second_port),
+            service_resolved("first", first_port),
+            ServiceEvent::ServiceRemoved(
+                "_tosca._tcp.local.".into(),
+                "first._tosca._tcp.local.".into(),
+            ),
+        ]);
+
+        let events = Discovery::discovery_events(service_events, None)
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(events.len(), 3);
+
+        let DiscoveryEvent::Found(first) = &events[0] else {
+            panic!("Expected a found device, got {:?}", events[0]);
+        };
+        assert_eq!(first.network_info().name, "first._tosca._tcp.local.");
+        assert_eq!(first.network_info().port, first_port);
+        assert_eq!(first.description().main_route, "/first");
+
+        let DiscoveryEvent::Found(second) = &events[1] else {
+            panic!("Expected a found device, got {:?}", events[1]);
+        };
+        assert_eq!(second.network_info().name, "second._tosca._tcp.local.");
+        assert_eq!(second.network_info().port, second_port);
+        assert_eq!(second.description().main_route, "/second");
+
+        // The raw record is kept as received, with sorted addresses.
+        let record = first.raw_discovery_record();
+        assert_eq!(record.service_name, "first._tosca._tcp.local.");
+        assert_eq!(record.hostname, "tosca.local.");
+        assert_eq!(record.addresses, vec![Ipv4Addr::LOCALHOST]);
+        assert_eq!(record.port, first_port);
+        assert_eq!(
+            record.txt_properties,
+            vec![("scheme".to_owned(), "http".to_owned())]
+        );
+
+        assert_eq!(
+            events[2],
+            DiscoveryEvent::Lost("first._tosca._tcp.local.".into())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_discovery() {
+        let port = device_data_server("/first").await;
+
+        // A mocked browser which finds a device and then waits forever.
+        let service_events =
+            stream::iter([service_resolved("first", port)]).chain(stream::pending());
+        let events = Discovery::discovery_events(service_events, None);
+
+        // A timeout which would never end the discovery by itself.
+        let discovery = configure_discovery().timeout(Duration::from_hours(1));
+
+        let cancellation_token = CancellationToken::new();
+        let cancel = cancellation_token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            cancel.cancel();
+        });
+
+        let (devices, _) = discovery.collect_devices(events, &cancellation_token).await;
+
+        assert_eq!(devices.len(), 1);
+        assert_eq!(
+            devices.get(0).unwrap().network_info().name,
+            "first._tosca._tcp.local."
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_device() {
+        let port = device_data_server("/first").await;
+
+        // A server which closes each connection without answering.
+        let unreachable_port = local_server(|stream| async move { drop(stream) }).await;
+
+        // A mocked browser which resolves an unreachable device, and then
+        // a reachable one.
+        let service_events = stream::iter([
+            service_resolved("unreachable", unreachable_port),
+            service_resolved("first", port),
+        ]);
+
+        let events = Discovery::discovery_events(service_events, None)
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(events.len(), 2);
+
+        let DiscoveryEvent::Unreachable(record) = &events[0] else {
+            panic!("Expected an unreachable device, got {:?}", events[0]);
+        };
+        assert_eq!(record.service_name, "unreachable._tosca._tcp.local.");
+        assert_eq!(record.addresses, vec![Ipv4Addr::LOCALHOST]);
+        assert_eq!(record.port, unreachable_port);
+
+        assert!(matches!(events[1], DiscoveryEvent::Found(_)));
+
+        // The record of the unreachable device is collected along with the
+        // reachable devices.
+        let service_events = stream::iter([
+            service_resolved("unreachable", unreachable_port),
+            service_resolved("first", port),
+        ]);
+        let (devices, unreachable) = configure_discovery()
+            .collect_devices(
+                Discovery::discovery_events(service_events, None),
+                &CancellationToken::new(),
+            )
+            .await;
+
+        assert_eq!(devices.len(), 1);
+        assert_eq!(unreachable, vec![record.clone()]);
+    }
 }
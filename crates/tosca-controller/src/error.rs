@@ -1,5 +1,7 @@
 use std::borrow::Cow;
 
+use tosca::response::ErrorKind as DeviceErrorKind;
+
 use tracing::error;
 
 /// All possible error kinds.
@@ -15,10 +17,25 @@ pub enum ErrorKind {
     JsonResponse,
     /// Errors in receiving a bytes stream response.
     StreamResponse,
+    /// Errors caused by a response body whose shape does not match the
+    /// response kind declared by a route.
+    UnexpectedResponseShape,
+    /// Errors caused by a device state which does not satisfy the
+    /// precondition of a request.
+    Precondition,
     /// Errors in building the mechanism to send a request to a device.
     Sender,
     /// Errors related to event management.
     Events,
+    /// Errors in exporting or importing a network topology.
+    Topology,
+    /// Errors in recording or replaying a session.
+    Session,
+    /// Errors caused by invalid authentication credentials.
+    Credentials,
+    /// Errors reported by a device during an operation, along with the
+    /// device error kind.
+    Device(DeviceErrorKind),
 }
 
 impl ErrorKind {
@@ -29,8 +46,14 @@ impl ErrorKind {
             Self::WrongParameter => "Wrong Parameter",
             Self::JsonResponse => "Json Response",
             Self::StreamResponse => "Stream Response",
+            Self::UnexpectedResponseShape => "Unexpected Response Shape",
+            Self::Precondition => "Precondition",
             Self::Sender => "Response Sender",
             Self::Events => "Events",
+            Self::Topology => "Topology",
+            Self::Session => "Session",
+            Self::Credentials => "Credentials",
+            Self::Device(_) => "Device",
         }
     }
 }
@@ -69,6 +92,12 @@ impl Error {
         Self { kind, description }
     }
 
+    /// Returns the [`ErrorKind`].
+    #[must_use]
+    pub const fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
     fn format(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}: {}", self.kind, self.description)
     }
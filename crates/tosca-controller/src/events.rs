@@ -1,3 +1,5 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, PoisonError};
 use std::time::Duration;
 
 use tosca::events::{BrokerData, Events as ToscaEvents, EventsDescription};
@@ -48,6 +50,63 @@ impl EventPayload {
     }
 }
 
+// A bounded history of the events received from each device.
+//
+// At most `capacity` events are kept for each device, so the history never
+// holds more than `capacity` times the number of devices events. Once the
+// history of a device is full, its oldest events are discarded.
+//
+// The history is shared with the event receiver tasks which feed it.
+#[derive(Debug, Clone)]
+pub(crate) struct EventHistory {
+    capacity: usize,
+    devices: Arc<Mutex<HashMap<usize, VecDeque<ToscaEvents>>>>,
+}
+
+impl PartialEq for EventHistory {
+    fn eq(&self, other: &Self) -> bool {
+        self.capacity == other.capacity
+    }
+}
+
+impl EventHistory {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            devices: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub(crate) fn record(&self, id: usize, events: &ToscaEvents) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut devices = self.devices.lock().unwrap_or_else(PoisonError::into_inner);
+        let history = devices
+            .entry(id)
+            .or_insert_with(|| VecDeque::with_capacity(self.capacity));
+
+        if history.len() == self.capacity {
+            history.pop_front();
+        }
+        history.push_back(events.clone());
+    }
+
+    // Returns the last `n` events of a device, from the oldest to the most
+    // recent.
+    pub(crate) fn recent(&self, id: usize, n: usize) -> Vec<ToscaEvents> {
+        let devices = self.devices.lock().unwrap_or_else(PoisonError::into_inner);
+        devices.get(&id).map_or_else(Vec::new, |history| {
+            history
+                .iter()
+                .skip(history.len().saturating_sub(n))
+                .cloned()
+                .collect()
+        })
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct Events {
     // Events description.
@@ -63,6 +122,13 @@ impl Events {
             cancellation_token: CancellationToken::new(),
         }
     }
+
+    // Cancels the event task, renewing the token so that a new task can be
+    // started afterwards.
+    pub(crate) fn cancel(&mut self) {
+        self.cancellation_token.cancel();
+        self.cancellation_token = CancellationToken::new();
+    }
 }
 
 #[inline]
@@ -103,6 +169,7 @@ async fn run_global_event_subscriber(
     id: usize,
     cancellation_token: CancellationToken,
     sender: mpsc::Sender<EventPayload>,
+    history: Option<EventHistory>,
 ) {
     loop {
         tokio::select! {
@@ -114,6 +181,10 @@ async fn run_global_event_subscriber(
                     continue;
                 };
 
+                if let Some(history) = &history {
+                    history.record(id, &tosca_events);
+                }
+
                 if let Err(e) = sender.send(EventPayload::new(id, tosca_events)).await {
                     error!(
                         "Stop sending events to the global receiver: {e}"
@@ -167,6 +238,7 @@ impl EventsRunner {
         events: &Events,
         id: usize,
         sender: mpsc::Sender<EventPayload>,
+        history: Option<EventHistory>,
     ) -> Result<JoinHandle<()>> {
         let (client, eventloop) = Self::init(id, events).await?;
 
@@ -176,6 +248,7 @@ impl EventsRunner {
             id,
             events.cancellation_token.clone(),
             sender,
+            history,
         )))
     }
 
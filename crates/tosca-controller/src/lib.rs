@@ -12,8 +12,20 @@
 //! - Constructing and sending _REST_ requests to `tosca` devices to trigger
 //!   one or more of their operations
 //! - Defining security and privacy policies to allow or block requests
+//! - Authenticating requests with per-device bearer tokens
+//! - Running scenes, which send requests to several devices as a single
+//!   transaction
+//! - Delivering requests through a custom transport, such as a relay, in
+//!   place of `HTTP`
 //! - Intercepting device events by subscribing to the brokers where
-//!   they are published
+//!   they are published, or directly to the devices streaming them
+//!   through Server-Sent Events
+//!
+//! The `test-util` feature provides a `MockDevice`, which answers
+//! requests in-process, to test the logic built on top of a controller
+//! without any network. It also provides a `SessionRecorder` and a
+//! `SessionPlayer`, which record the requests sent by a controller along
+//! with their responses, and replay them later to reproduce a bug.
 //!
 //! To optimize system resource usage, `tosca-controller` leverages `tokio` as
 //! an asynchronous executor. This improves performance by allowing concurrent
@@ -26,6 +38,8 @@
 
 /// A controller for interacting with `tosca` devices.
 pub mod controller;
+/// A store of the authentication credentials of each device.
+pub mod credentials;
 /// A device definition along with its operations.
 pub mod device;
 /// A mechanism for discovering all `tosca` devices in a network.
@@ -34,6 +48,9 @@ pub mod discovery;
 pub mod error;
 /// Events data.
 pub mod events;
+/// An in-memory device to test the logic built on top of a controller.
+#[cfg(any(test, feature = "test-util"))]
+pub mod mock;
 /// A privacy and security policy manager that determines whether `REST`
 /// requests can be sent or blocked.
 pub mod policy;
@@ -41,6 +58,17 @@ pub mod policy;
 pub mod request;
 /// All supported methods and data for device responses.
 pub mod response;
+/// A sequence of requests sent to several devices as a single transaction.
+pub mod scene;
+/// A recorder and a player of the requests sent by a controller, to
+/// reproduce a session without any device.
+#[cfg(any(test, feature = "test-util"))]
+pub mod session;
+/// A pluggable link delivering the requests of a controller to devices.
+pub mod transport;
+
+mod cache;
+mod topology;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,278 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use tosca::device::{DeviceEnvironment, DeviceInfo, DeviceKind};
+use tosca::response::{InfoResponse, OkResponse, ResponseKind, SerialResponse};
+use tosca::route::{Route, RouteConfigs};
+
+use bytes::Bytes;
+
+use reqwest::header::CONTENT_TYPE;
+
+use serde::Serialize;
+
+use crate::device::{Description, Device, NetworkInformation};
+
+// Main route of a mock device.
+const MAIN_ROUTE: &str = "device/";
+
+// Complete name of a mock device.
+const MOCK_NAME: &str = "mock._tosca._tcp.local.";
+
+// Address of a mock device.
+//
+// It is never contacted, since a mock device answers in-process.
+const MOCK_ADDRESS: &str = "http://mock.invalid";
+
+// Routes requested to a mock device, in order.
+type ReceivedRoutes = Arc<Mutex<Vec<String>>>;
+
+// A canned response for a mock device route.
+#[derive(Debug, Clone)]
+pub(crate) struct MockRoute {
+    path: String,
+    status: u16,
+    body: Bytes,
+    received: ReceivedRoutes,
+}
+
+impl PartialEq for MockRoute {
+    fn eq(&self, other: &Self) -> bool {
+        self.path == other.path && self.status == other.status && self.body == other.body
+    }
+}
+
+impl MockRoute {
+    // Records the request, answering with the canned response.
+    pub(crate) fn respond(&self) -> reqwest::Response {
+        self.received
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(self.path.clone());
+
+        http::Response::builder()
+            .status(self.status)
+            .header(CONTENT_TYPE, "application/json")
+            .body(self.body.clone())
+            .expect("A mock response is always valid")
+            .into()
+    }
+}
+
+/// An in-memory [`Device`] answering requests with canned responses.
+///
+/// A mock device exposes the same request and response surface of a real
+/// device, but it is answered in-process, without opening any socket. It
+/// allows testing the logic built on top of a
+/// [`Controller`](crate::controller::Controller), such as its privacy
+/// policy, quickly and deterministically.
+///
+/// Requests blocked by a policy never reach the mock device, so they are
+/// not reported by [`Self::received`].
+#[derive(Debug)]
+pub struct MockDevice {
+    kind: DeviceKind,
+    route_configs: RouteConfigs,
+    responses: HashMap<String, (u16, Bytes)>,
+    received: ReceivedRoutes,
+}
+
+impl MockDevice {
+    /// Creates a [`MockDevice`] of the given [`DeviceKind`] without any
+    /// route.
+    #[must_use]
+    pub fn new(kind: DeviceKind) -> Self {
+        Self {
+            kind,
+            route_configs: RouteConfigs::new(),
+            responses: HashMap::new(),
+            received: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Adds a [`Route`] answering with an [`OkResponse`].
+    #[must_use]
+    pub fn ok_route(self, route: Route) -> Self {
+        self.route(route, ResponseKind::Ok, &OkResponse::ok())
+    }
+
+    /// Adds a [`Route`] answering with a [`SerialResponse`] containing the
+    /// given data.
+    #[must_use]
+    pub fn serial_route<T: Serialize>(self, route: Route, data: T) -> Self {
+        self.route(route, ResponseKind::Serial, &SerialResponse::new(data))
+    }
+
+    /// Adds a [`Route`] answering with an [`InfoResponse`] containing the
+    /// given [`DeviceInfo`].
+    #[must_use]
+    pub fn info_route(self, route: Route, info: DeviceInfo) -> Self {
+        self.route(route, ResponseKind::Info, &InfoResponse::new(info))
+    }
+
+    /// Adds a [`Route`] answering with the given `HTTP` status and json
+    /// body.
+    ///
+    /// It allows testing how device failures are handled.
+    #[must_use]
+    pub fn status_route(self, route: Route, status: u16, body: &serde_json::Value) -> Self {
+        self.insert(
+            route,
+            ResponseKind::Serial,
+            status,
+            body.to_string().into_bytes(),
+        )
+    }
+
+    /// Creates a [`Device`] answered by this [`MockDevice`].
+    ///
+    /// Every created device shares the routes received by this mock device.
+    #[must_use]
+    #[inline]
+    pub fn device(&self) -> Device {
+        self.named_device(MOCK_NAME)
+    }
+
+    /// Creates a [`Device`] answered by this [`MockDevice`] with the given
+    /// complete name.
+    ///
+    /// It allows distinguishing several devices created by the same mock
+    /// device, as discovered devices are.
+    #[must_use]
+    pub fn named_device(&self, name: &str) -> Device {
+        let network_info = NetworkInformation::new(
+            name.into(),
+            HashSet::new(),
+            0,
+            HashMap::new(),
+            MOCK_ADDRESS.into(),
+        );
+        let description =
+            Description::new(self.kind.clone(), DeviceEnvironment::Os, MAIN_ROUTE.into());
+
+        let mut device = Device::init(network_info, description, self.route_configs.clone(), None);
+
+        for (path, request) in device.requests_mut() {
+            request.mock = self.responses.get(path).map(|(status, body)| MockRoute {
+                path: path.clone(),
+                status: *status,
+                body: body.clone(),
+                received: Arc::clone(&self.received),
+            });
+        }
+
+        device
+    }
+
+    /// Returns the routes requested to the mock device so far, in order.
+    #[must_use]
+    pub fn received(&self) -> Vec<String> {
+        self.received
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    fn route<T: Serialize>(self, route: Route, response_kind: ResponseKind, body: &T) -> Self {
+        let body = serde_json::to_vec(body).expect("A tosca response is always serializable");
+        self.insert(route, response_kind, 200, body)
+    }
+
+    fn insert(
+        mut self,
+        route: Route,
+        response_kind: ResponseKind,
+        status: u16,
+        body: Vec<u8>,
+    ) -> Self {
+        let route_config = route.serialize_data().change_response_kind(response_kind);
+        self.responses.insert(
+            route_config.data.path.to_string(),
+            (status, Bytes::from(body)),
+        );
+        self.route_configs = self.route_configs.insert(route_config);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tosca::device::DeviceKind;
+    use tosca::hazards::{Hazard, Hazards};
+    use tosca::route::Route;
+
+    use crate::controller::Controller;
+    use crate::device::Devices;
+    use crate::discovery::tests::configure_discovery;
+    use crate::policy::Policy;
+    use crate::response::Response;
+    use crate::tests::Brightness;
+
+    use super::MockDevice;
+
+    fn mock_light() -> MockDevice {
+        MockDevice::new(DeviceKind::Light)
+            .ok_route(
+                Route::put("On", "/on")
+                    .description("Turn light on.")
+                    .with_hazard(Hazard::ElectricEnergyConsumption),
+            )
+            .ok_route(
+                Route::put("Off", "/off")
+                    .description("Turn light off.")
+                    .with_hazard(Hazard::LogEnergyConsumption),
+            )
+            .serial_route(
+                Route::get("Brightness", "/brightness").description("Retrieve brightness."),
+                Brightness { brightness: 5 },
+            )
+    }
+
+    #[tokio::test]
+    async fn mock_device_policy() {
+        let mock = mock_light();
+
+        let policy = Policy::new(Hazards::new().insert(Hazard::LogEnergyConsumption));
+        let controller = Controller::from_devices(
+            configure_discovery(),
+            Devices::from_devices(vec![mock.device()]),
+        )
+        .policy(policy);
+
+        let device_sender = controller.device(0).unwrap();
+
+        // Allowed by the policy.
+        let Response::OkBody(parser) = device_sender.request("/on").unwrap().send().await.unwrap()
+        else {
+            panic!("An ok response was expected");
+        };
+        assert!(parser.parse_body().await.is_ok());
+
+        // Blocked by the policy.
+        assert!(matches!(
+            device_sender.request("/off").unwrap().send().await.unwrap(),
+            Response::Skipped
+        ));
+
+        let Response::SerialBody(parser) = device_sender
+            .request("/brightness")
+            .unwrap()
+            .send()
+            .await
+            .unwrap()
+        else {
+            panic!("A serial response was expected");
+        };
+        assert_eq!(
+            parser
+                .parse_body::<Brightness>()
+                .await
+                .unwrap()
+                .into_inner(),
+            Brightness { brightness: 5 }
+        );
+
+        // Blocked requests never reach the device.
+        assert_eq!(mock.received(), vec!["/on", "/brightness"]);
+    }
+}
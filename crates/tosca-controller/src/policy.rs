@@ -1,9 +1,37 @@
 use std::collections::HashMap;
 
 use tosca::hazards::Hazards;
+use tosca::route::RouteConfig;
 
 // TODO: Eventually rewrite policy IDs as &'static str.
 
+/// A [`Policy`] rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    /// The rule blocking hazards for all devices.
+    Global,
+    /// The rule blocking hazards for the [`crate::device::Device`] with the
+    /// given identifier.
+    Device(usize),
+    /// The rule blocking routes whose hazards are unspecified, hence neither
+    /// declared nor explicitly marked as known safe.
+    UnspecifiedHazards,
+}
+
+/// The decision of a [`Policy`] about a route.
+///
+/// It explains which rules would block the route and because of which
+/// hazards.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decision {
+    /// Whether the route is allowed.
+    pub allowed: bool,
+    /// Rules which block the route.
+    pub matched_rules: Vec<Rule>,
+    /// Route hazards blocked by the matched rules.
+    pub offending_hazards: Hazards,
+}
+
 /// A privacy policy manager.
 ///
 /// A privacy policy can be applied to all requests sent to devices or just to
@@ -12,6 +40,7 @@ use tosca::hazards::Hazards;
 pub struct Policy {
     block_on_hazards: Hazards,
     block_device_on_hazards: HashMap<usize, Hazards>,
+    unspecified_hazards_blocked: bool,
 }
 
 impl Policy {
@@ -23,6 +52,7 @@ impl Policy {
         Self {
             block_on_hazards,
             block_device_on_hazards: HashMap::new(),
+            unspecified_hazards_blocked: false,
         }
     }
 
@@ -44,13 +74,84 @@ impl Policy {
         self
     }
 
+    /// Adds a [`Policy`] rule to block the sending of requests for all routes
+    /// whose hazards are unspecified.
+    ///
+    /// A route without hazards could still pose unknown risks, unless it has
+    /// been explicitly marked as known safe, so this rule only allows routes
+    /// which declare either their hazards or their safety.
+    #[must_use]
+    #[inline]
+    pub const fn block_unspecified_hazards(mut self) -> Self {
+        self.unspecified_hazards_blocked = true;
+        self
+    }
+
+    /// Explains the decision of the global [`Policy`] rule about a route.
+    ///
+    /// The evaluation has no side effects, so it can be used to check a
+    /// [`Policy`] before applying it to a controller.
+    #[must_use]
+    pub fn explain(&self, route: &RouteConfig) -> Decision {
+        self.decide(None, &route.data.hazards)
+    }
+
+    /// Explains the decision of both the global [`Policy`] rule and the rule
+    /// for the [`crate::device::Device`] with the given identifier about a
+    /// route.
+    ///
+    /// The evaluation has no side effects, so it can be used to check a
+    /// [`Policy`] before applying it to a controller.
+    #[must_use]
+    pub fn explain_for_device(&self, id: usize, route: &RouteConfig) -> Decision {
+        self.decide(Some(id), &route.data.hazards)
+    }
+
+    fn decide(&self, id: Option<usize>, hazards: &Hazards) -> Decision {
+        let mut matched_rules = Vec::new();
+        let mut offending_hazards = Hazards::new();
+
+        if self.blocks_unspecified_hazards(hazards) {
+            matched_rules.push(Rule::UnspecifiedHazards);
+        }
+
+        let global_blocked_hazards = self.global_blocked_hazards(hazards);
+        if !global_blocked_hazards.is_empty() {
+            matched_rules.push(Rule::Global);
+            for hazard in &global_blocked_hazards {
+                offending_hazards.add(*hazard);
+            }
+        }
+
+        if let Some(id) = id {
+            let local_blocked_hazards = self.local_blocked_hazards(id, hazards);
+            if !local_blocked_hazards.is_empty() {
+                matched_rules.push(Rule::Device(id));
+                for hazard in &local_blocked_hazards {
+                    offending_hazards.add(*hazard);
+                }
+            }
+        }
+
+        Decision {
+            allowed: matched_rules.is_empty(),
+            matched_rules,
+            offending_hazards,
+        }
+    }
+
     pub(crate) fn init() -> Self {
         Self {
             block_on_hazards: Hazards::new(),
             block_device_on_hazards: HashMap::new(),
+            unspecified_hazards_blocked: false,
         }
     }
 
+    pub(crate) fn blocks_unspecified_hazards(&self, hazards: &Hazards) -> bool {
+        self.unspecified_hazards_blocked && hazards.is_unspecified()
+    }
+
     pub(crate) fn global_blocked_hazards(&self, hazards: &Hazards) -> Hazards {
         let mut blocked_hazards = Hazards::new();
         for hazard in hazards {
@@ -81,8 +182,9 @@ mod tests {
     use std::collections::HashMap;
 
     use tosca::hazards::{Hazard, Hazards};
+    use tosca::route::{Route, RouteConfig};
 
-    use super::Policy;
+    use super::{Decision, Policy, Rule};
 
     fn create_policy() -> (Hazards, Policy) {
         let hazards = Hazards::new().insert(Hazard::ElectricEnergyConsumption);
@@ -102,6 +204,7 @@ mod tests {
             &Policy {
                 block_on_hazards,
                 block_device_on_hazards: devices_hazards,
+                unspecified_hazards_blocked: false,
             }
         );
     }
@@ -114,7 +217,8 @@ mod tests {
             policy,
             Policy {
                 block_on_hazards: hazards,
-                block_device_on_hazards: HashMap::new()
+                block_device_on_hazards: HashMap::new(),
+                unspecified_hazards_blocked: false,
             }
         );
     }
@@ -141,4 +245,98 @@ mod tests {
 
         check_device_policies(&policy, global_hazards, &local_hazards);
     }
+
+    fn route_config(hazards: Hazards) -> RouteConfig {
+        Route::put("On", "/on")
+            .description("Turn light on.")
+            .with_hazards(hazards)
+            .serialize_data()
+    }
+
+    #[test]
+    fn explain_allowed_route() {
+        let (_, policy) = create_policy();
+        let policy = policy.block_device_on_hazards(1, Hazards::init(Hazard::Explosion));
+
+        let route = route_config(Hazards::init(Hazard::FireHazard));
+
+        let decision = Decision {
+            allowed: true,
+            matched_rules: Vec::new(),
+            offending_hazards: Hazards::new(),
+        };
+        assert_eq!(policy.explain(&route), decision);
+        assert_eq!(policy.explain_for_device(1, &route), decision);
+    }
+
+    #[test]
+    fn explain_blocked_route() {
+        let (global_hazards, policy) = create_policy();
+        let policy = policy.block_device_on_hazards(1, Hazards::init(Hazard::Explosion));
+
+        // Blocked by the global rule.
+        let route = route_config(global_hazards.clone().insert(Hazard::FireHazard));
+        assert_eq!(
+            policy.explain(&route),
+            Decision {
+                allowed: false,
+                matched_rules: vec![Rule::Global],
+                offending_hazards: global_hazards.clone(),
+            }
+        );
+
+        // Blocked by the device rule, only for that device.
+        let route = route_config(Hazards::init(Hazard::Explosion));
+        assert!(policy.explain(&route).allowed);
+        assert!(policy.explain_for_device(2, &route).allowed);
+        assert_eq!(
+            policy.explain_for_device(1, &route),
+            Decision {
+                allowed: false,
+                matched_rules: vec![Rule::Device(1)],
+                offending_hazards: Hazards::init(Hazard::Explosion),
+            }
+        );
+
+        // Blocked by both rules.
+        let route = route_config(global_hazards.insert(Hazard::Explosion));
+        let decision = policy.explain_for_device(1, &route);
+        assert!(!decision.allowed);
+        assert_eq!(decision.matched_rules, vec![Rule::Global, Rule::Device(1)]);
+        assert_eq!(decision.offending_hazards.len(), 2);
+    }
+
+    #[test]
+    fn explain_unspecified_hazards() {
+        let policy = Policy::init().block_unspecified_hazards();
+
+        // Routes declaring their hazards are allowed.
+        assert!(
+            policy
+                .explain(&route_config(Hazards::init(Hazard::FireHazard)))
+                .allowed
+        );
+
+        // Routes explicitly marked as known safe are allowed.
+        let route = Route::put("On", "/on")
+            .description("Turn light on.")
+            .no_hazards_known_safe()
+            .serialize_data();
+        assert!(policy.explain(&route).allowed);
+
+        // Routes with unspecified hazards are blocked.
+        assert_eq!(
+            policy.explain(&route_config(Hazards::new())),
+            Decision {
+                allowed: false,
+                matched_rules: vec![Rule::UnspecifiedHazards],
+                offending_hazards: Hazards::new(),
+            }
+        );
+
+        // Without the rule, both are allowed.
+        let policy = Policy::init();
+        assert!(policy.explain(&route).allowed);
+        assert!(policy.explain(&route_config(Hazards::new())).allowed);
+    }
 }
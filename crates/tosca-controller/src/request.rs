@@ -1,19 +1,224 @@
 use std::collections::HashMap;
 use std::fmt::Write;
 use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::header::HeaderMap;
 
 use serde::Serialize;
 
-use tracing::error;
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+use tracing::{error, warn};
 
 use tosca::device::DeviceEnvironment;
 use tosca::hazards::Hazards;
-use tosca::parameters::{ParameterValue, ParametersData, ParametersValues};
-use tosca::response::{ResponseKind, SERIALIZATION_ERROR};
-use tosca::route::{RestKind, RouteConfig, RouteConfigs};
+use tosca::parameters::{ParameterKind, ParameterValue, ParametersData, ParametersValues};
+use tosca::response::{IDEMPOTENCY_KEY, ResponseKind, SERIALIZATION_ERROR};
+use tosca::route::{ParameterStyle, RestKind, RouteConfig, RouteConfigs};
 
 use crate::error::{Error, ErrorKind};
-use crate::response::{InfoResponseParser, OkResponseParser, Response, SerialResponseParser};
+use crate::response::{
+    EmptyResponse, InfoResponseParser, OkResponseParser, Response, SerialResponseParser,
+};
+use crate::transport::{SharedTransport, Transport};
+
+// Default maximum number of idle connections kept for each device.
+const DEFAULT_MAX_IDLE_CONNECTIONS: usize = 8;
+
+// Default time an idle connection is kept open.
+const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Configuration of the `HTTP` client used to send requests to devices.
+///
+/// The client keeps a pool of connections for each device, so subsequent
+/// requests to the same device skip the connection setup.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClientConfig {
+    // Maximum number of idle connections kept for each device.
+    max_idle_connections: usize,
+    // Time an idle connection is kept open.
+    idle_timeout: Duration,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClientConfig {
+    /// Creates a [`ClientConfig`].
+    ///
+    /// At most `8` idle connections are kept for each device, for at most
+    /// `90` seconds.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            max_idle_connections: DEFAULT_MAX_IDLE_CONNECTIONS,
+            idle_timeout: DEFAULT_IDLE_TIMEOUT,
+        }
+    }
+
+    /// Sets the maximum number of idle connections kept for each device.
+    ///
+    /// With `0`, connections are never reused.
+    #[must_use]
+    pub const fn max_idle_connections(mut self, max_idle_connections: usize) -> Self {
+        self.max_idle_connections = max_idle_connections;
+        self
+    }
+
+    /// Sets the time an idle connection is kept open.
+    #[must_use]
+    pub const fn idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+}
+
+// An `HTTP` client shared by all requests.
+//
+// The underlying client is reference-counted, so cloning it shares the
+// same connection pool.
+#[derive(Debug, Clone)]
+pub(crate) struct HttpClient {
+    config: ClientConfig,
+    client: reqwest::Client,
+    limiter: Option<Arc<Semaphore>>,
+    transport: Option<SharedTransport>,
+    #[cfg(any(test, feature = "test-util"))]
+    session: Option<crate::session::Session>,
+}
+
+impl PartialEq for HttpClient {
+    fn eq(&self, other: &Self) -> bool {
+        self.config == other.config
+    }
+}
+
+impl HttpClient {
+    pub(crate) fn new(config: ClientConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .pool_max_idle_per_host(config.max_idle_connections)
+            .pool_idle_timeout(config.idle_timeout)
+            .build()
+            .unwrap_or_else(|e| {
+                warn!("Unable to configure the HTTP client, using the default one: {e}");
+                reqwest::Client::new()
+            });
+
+        Self {
+            config,
+            client,
+            limiter: None,
+            transport: None,
+            #[cfg(any(test, feature = "test-util"))]
+            session: None,
+        }
+    }
+
+    // Rebuilds the client with a new configuration, keeping its concurrency
+    // limit, its transport, and its session.
+    pub(crate) fn configure(self, config: ClientConfig) -> Self {
+        Self {
+            limiter: self.limiter,
+            transport: self.transport,
+            #[cfg(any(test, feature = "test-util"))]
+            session: self.session,
+            ..Self::new(config)
+        }
+    }
+
+    // Limits the number of requests in flight at the same time.
+    //
+    // A limit a semaphore cannot represent is treated as no limit.
+    pub(crate) fn with_max_concurrent_requests(mut self, max_concurrent_requests: usize) -> Self {
+        self.limiter = (max_concurrent_requests <= Semaphore::MAX_PERMITS)
+            .then(|| Arc::new(Semaphore::new(max_concurrent_requests.max(1))));
+        self
+    }
+
+    // Waits until a request can be sent without exceeding the concurrency
+    // limit, if any.
+    //
+    // The semaphore is never closed, so a permit is always acquired.
+    async fn acquire(&self) -> Option<SemaphorePermit<'_>> {
+        match self.limiter {
+            Some(ref limiter) => limiter.acquire().await.ok(),
+            None => None,
+        }
+    }
+
+    // Delivers requests through a custom transport instead of `HTTP`.
+    pub(crate) fn with_transport(mut self, transport: impl Transport) -> Self {
+        self.transport = Some(SharedTransport::new(transport));
+        self
+    }
+
+    #[cfg(any(test, feature = "test-util"))]
+    pub(crate) fn with_session(mut self, session: crate::session::Session) -> Self {
+        self.session = Some(session);
+        self
+    }
+
+    pub(crate) const fn client(&self) -> &reqwest::Client {
+        &self.client
+    }
+
+    // Sends a request through the custom transport, if any.
+    async fn execute(&self, request: reqwest::Request) -> Result<reqwest::Response, Error> {
+        match self.transport {
+            Some(ref transport) => transport.send(request).await,
+            None => Ok(self.client.execute(request).await?),
+        }
+    }
+
+    // Sends a request, within the session if any.
+    //
+    // A mock device answers in-process.
+    #[cfg(any(test, feature = "test-util"))]
+    async fn send(
+        &self,
+        request: reqwest::RequestBuilder,
+        mock: Option<&crate::mock::MockRoute>,
+    ) -> Result<reqwest::Response, Error> {
+        let send = async |request| match mock {
+            Some(mock) => Ok(mock.respond()),
+            None => self.execute(request).await,
+        };
+
+        let request = request.build()?;
+        let _permit = self.acquire().await;
+        match self.session {
+            Some(ref session) => session.send(request, send).await,
+            None => send(request).await,
+        }
+    }
+
+    #[cfg(not(any(test, feature = "test-util")))]
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response, Error> {
+        let request = request.build()?;
+        let _permit = self.acquire().await;
+        self.execute(request).await
+    }
+}
+
+// Encodes a parameter value to be sent to a device.
+//
+// Secret values are masked when displayed, so they are encoded as they are.
+// Floating point values are encoded with the decimal places declared by
+// their kind, so a computed value such as `0.1 + 0.2` is sent as `0.3`.
+fn wire_value(value: &ParameterValue, parameter_kind: &ParameterKind) -> String {
+    match (value, parameter_kind.decimal_places()) {
+        (ParameterValue::F32(value), Some(places)) => format!("{value:.*}", places as usize),
+        (ParameterValue::F64(value), Some(places)) => format!("{value:.*}", places as usize),
+        _ => value
+            .chars_sequence()
+            .map_or_else(|| value.to_string(), ToString::to_string),
+    }
+}
 
 fn slash_end(s: &str) -> &str {
     if s.len() > 1 && s.ends_with('/') {
@@ -35,6 +240,16 @@ fn slash_start_end(s: &str) -> &str {
     slash_start(slash_end(s))
 }
 
+// Builds the complete address of a device route.
+pub(crate) fn device_route(address: &str, main_route: &str, route: &str) -> String {
+    format!(
+        "{}/{}/{}",
+        slash_end(address),
+        slash_start_end(main_route),
+        slash_start_end(route)
+    )
+}
+
 fn compare_values_with_params_data(
     parameter_values: &ParametersValues,
     parameters_data: &ParametersData,
@@ -106,6 +321,8 @@ pub struct RequestInfo<'device> {
     pub parameters_data: &'device ParametersData,
     /// Response kind.
     pub response_kind: ResponseKind,
+    /// Deprecation reason, if the route is deprecated.
+    pub deprecated: Option<&'device str>,
 }
 
 impl<'device> RequestInfo<'device> {
@@ -117,6 +334,7 @@ impl<'device> RequestInfo<'device> {
             hazards: &request.hazards,
             parameters_data: &request.parameters_data,
             response_kind: request.response_kind,
+            deprecated: request.deprecated.as_deref(),
         }
     }
 }
@@ -135,7 +353,12 @@ pub struct Request {
     pub(crate) description: Option<String>,
     pub(crate) parameters_data: ParametersData,
     pub(crate) response_kind: ResponseKind,
+    pub(crate) parameter_style: ParameterStyle,
     pub(crate) device_environment: DeviceEnvironment,
+    pub(crate) deprecated: Option<String>,
+    #[cfg(any(test, feature = "test-util"))]
+    #[serde(skip)]
+    pub(crate) mock: Option<crate::mock::MockRoute>,
 }
 
 impl Request {
@@ -157,9 +380,34 @@ impl Request {
     /// If [`None`], the request **does not** contain any [`ParametersData`].
     #[must_use]
     pub fn parameters_data(&self) -> Option<&ParametersData> {
-        self.parameters_data
-            .is_empty()
-            .then_some(&self.parameters_data)
+        (!self.parameters_data.is_empty()).then_some(&self.parameters_data)
+    }
+
+    /// Returns an immutable reference to the [`ParametersData`] of a
+    /// request, which is empty when the request has no parameters.
+    #[must_use]
+    pub fn parameters(&self) -> &ParametersData {
+        &self.parameters_data
+    }
+
+    /// Returns the [`ResponseKind`] of a request.
+    #[must_use]
+    pub fn response_kind(&self) -> ResponseKind {
+        self.response_kind
+    }
+
+    /// Returns the complete address of a request, without any parameter.
+    #[must_use]
+    pub fn url(&self) -> &str {
+        &self.route
+    }
+
+    /// Returns the reason why a request is deprecated.
+    ///
+    /// If [`None`], the request is **not** deprecated.
+    #[must_use]
+    pub fn deprecation(&self) -> Option<&str> {
+        self.deprecated.as_deref()
     }
 
     pub(crate) fn new(
@@ -169,15 +417,11 @@ impl Request {
         route_config: RouteConfig,
     ) -> Self {
         let kind = route_config.rest_kind;
-        let route = format!(
-            "{}/{}/{}",
-            slash_end(address),
-            slash_start_end(main_route),
-            slash_start_end(&route_config.data.path)
-        );
+        let route = device_route(address, main_route, &route_config.data.path);
         let hazards = route_config.data.hazards;
         let parameters_data = route_config.data.parameters;
         let response_kind = route_config.response_kind;
+        let parameter_style = route_config.parameter_style;
 
         Self {
             kind,
@@ -186,7 +430,11 @@ impl Request {
             description: route_config.data.description.map(|s| s.to_string()),
             parameters_data,
             response_kind,
+            parameter_style,
             device_environment,
+            deprecated: route_config.deprecated.map(|s| s.to_string()),
+            #[cfg(any(test, feature = "test-util"))]
+            mock: None,
         }
     }
 
@@ -209,43 +457,122 @@ impl Request {
             ResponseKind::Ok => Response::OkBody(OkResponseParser::new(response)),
             ResponseKind::Serial => Response::SerialBody(SerialResponseParser::new(response)),
             ResponseKind::Info => Response::InfoBody(InfoResponseParser::new(response)),
+            ResponseKind::Empty => Response::Empty(EmptyResponse::new(response)),
             #[cfg(feature = "stream")]
             ResponseKind::Stream => {
                 Response::StreamBody(crate::response::StreamResponse::new(response))
             }
+            // A stream route is described whenever another crate enables the
+            // `tosca/stream` feature, but its body can only be retrieved
+            // with the `stream` feature.
+            #[cfg(not(feature = "stream"))]
+            #[allow(unreachable_patterns)]
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::StreamResponse,
+                    "Enable the `stream` feature to retrieve a bytes stream response",
+                ));
+            }
         })
     }
 
-    pub(crate) async fn plain_send(&self) -> Result<reqwest::Response, Error> {
+    pub(crate) async fn plain_send(
+        &self,
+        client: &HttpClient,
+        idempotency_key: Option<&str>,
+        headers: &HeaderMap,
+    ) -> Result<reqwest::Response, Error> {
         let request_data =
             self.request_data(|| self.axum_get_plain(), || self.create_params_plain());
 
-        self.parameters_send(request_data).await
+        self.parameters_send(client, request_data, idempotency_key, headers)
+            .await
     }
 
     pub(crate) async fn create_response(
         &self,
+        client: &HttpClient,
         parameters: &ParametersValues<'_>,
+        idempotency_key: Option<&str>,
+        headers: &HeaderMap,
     ) -> Result<reqwest::Response, Error> {
         let request_data = self.create_request(parameters)?;
-        self.parameters_send(request_data).await
+        self.parameters_send(client, request_data, idempotency_key, headers)
+            .await
     }
 
-    async fn parameters_send(&self, request_data: RequestData) -> Result<reqwest::Response, Error> {
+    // Builds the `HTTP` request for a device.
+    //
+    // Parameters of `DELETE` requests are sent as a body only when their
+    // route explicitly requests it, while `GET` requests never carry one.
+    fn http_request(
+        &self,
+        client: &reqwest::Client,
+        request_data: RequestData,
+    ) -> reqwest::RequestBuilder {
         let RequestData {
             request,
             parameters,
         } = request_data;
 
-        let client = reqwest::Client::new();
+        match self.kind {
+            RestKind::Get => match self.parameter_style {
+                ParameterStyle::Path => client.get(request),
+                ParameterStyle::Query | ParameterStyle::Body => {
+                    client.get(request).query(&parameters)
+                }
+            },
+            RestKind::Post => client.post(request).json(&parameters),
+            RestKind::Put => client.put(request).json(&parameters),
+            RestKind::Delete => match self.parameter_style {
+                ParameterStyle::Path => client.delete(request),
+                ParameterStyle::Query => client.delete(request).query(&parameters),
+                ParameterStyle::Body => client.delete(request).json(&parameters),
+            },
+            RestKind::Patch => client.patch(request).json(&parameters),
+        }
+    }
 
-        let response = match self.kind {
-            RestKind::Get => client.get(request).send(),
-            RestKind::Post => client.post(request).json(&parameters).send(),
-            RestKind::Put => client.put(request).json(&parameters).send(),
-            RestKind::Delete => client.delete(request).json(&parameters).send(),
+    // Builds the `HTTP` request for a device, along with the custom headers
+    // and the idempotency key.
+    //
+    // Custom headers replace the headers with the same name, except for the
+    // idempotency key.
+    fn outgoing_request(
+        &self,
+        client: &reqwest::Client,
+        request_data: RequestData,
+        idempotency_key: Option<&str>,
+        headers: &HeaderMap,
+    ) -> reqwest::RequestBuilder {
+        let mut headers = headers.clone();
+        if idempotency_key.is_some() {
+            headers.remove(IDEMPOTENCY_KEY);
         }
-        .await?;
+
+        let builder = self.http_request(client, request_data).headers(headers);
+
+        match idempotency_key {
+            Some(key) => builder.header(IDEMPOTENCY_KEY, key),
+            None => builder,
+        }
+    }
+
+    async fn parameters_send(
+        &self,
+        client: &HttpClient,
+        request_data: RequestData,
+        idempotency_key: Option<&str>,
+        headers: &HeaderMap,
+    ) -> Result<reqwest::Response, Error> {
+        let request =
+            self.outgoing_request(client.client(), request_data, idempotency_key, headers);
+
+        #[cfg(any(test, feature = "test-util"))]
+        let response = client.send(request, self.mock.as_ref()).await?;
+
+        #[cfg(not(any(test, feature = "test-util")))]
+        let response = client.send(request).await?;
 
         // TODO: Analyze the response status.
         // A 404 status (route not found) might be returned when a
@@ -285,12 +612,14 @@ impl Request {
         A: FnOnce() -> String,
         F: FnOnce() -> HashMap<String, String>,
     {
-        let request =
-            if self.kind == RestKind::Get && self.device_environment == DeviceEnvironment::Os {
-                axum_get()
-            } else {
-                self.route.clone()
-            };
+        let request = if matches!(self.kind, RestKind::Get | RestKind::Delete)
+            && self.parameter_style == ParameterStyle::Path
+            && self.device_environment == DeviceEnvironment::Os
+        {
+            axum_get()
+        } else {
+            self.route.clone()
+        };
 
         let parameters = params();
 
@@ -314,7 +643,10 @@ impl Request {
             if let Err(e) = write!(
                 route,
                 "/{}",
-                ParameterValue::from_parameter_kind(parameter_kind)
+                wire_value(
+                    &ParameterValue::from_parameter_kind(parameter_kind),
+                    parameter_kind
+                )
             ) {
                 error!("Error in adding a path to a route : {e}");
                 break;
@@ -328,7 +660,10 @@ impl Request {
         for (name, parameter_kind) in &self.parameters_data {
             params.insert(
                 name.clone(),
-                format!("{}", ParameterValue::from_parameter_kind(parameter_kind)),
+                wire_value(
+                    &ParameterValue::from_parameter_kind(parameter_kind),
+                    parameter_kind,
+                ),
             );
         }
         params
@@ -340,9 +675,12 @@ impl Request {
         let mut route = String::from(&self.route);
         for (name, parameter_kind) in &self.parameters_data {
             let value = if let Some(value) = parameters.get(name) {
-                format!("{value}")
+                wire_value(value, parameter_kind)
             } else {
-                format!("{}", ParameterValue::from_parameter_kind(parameter_kind))
+                wire_value(
+                    &ParameterValue::from_parameter_kind(parameter_kind),
+                    parameter_kind,
+                )
             };
             // TODO: Consider returning `Option<String>`
             if let Err(e) = write!(route, "/{value}") {
@@ -358,11 +696,14 @@ impl Request {
         let mut params = HashMap::new();
         for (name, parameter_kind) in &self.parameters_data {
             let (name, value) = if let Some(value) = parameters.get(name) {
-                (name, format!("{value}"))
+                (name, wire_value(value, parameter_kind))
             } else {
                 (
                     name,
-                    format!("{}", ParameterValue::from_parameter_kind(parameter_kind)),
+                    wire_value(
+                        &ParameterValue::from_parameter_kind(parameter_kind),
+                        parameter_kind,
+                    ),
                 )
             };
             params.insert(name.clone(), value);
@@ -374,13 +715,26 @@ impl Request {
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use reqwest::header::{HeaderMap, HeaderValue};
+
+    use tokio::net::TcpStream;
 
     use tosca::device::DeviceEnvironment;
     use tosca::hazards::{Hazard, Hazards};
-    use tosca::parameters::{ParameterKind, Parameters, ParametersData, ParametersValues};
-    use tosca::route::{RestKind, Route, RouteConfig};
+    use tosca::parameters::{
+        DecimalPrecision, ParameterKind, Parameters, ParametersData, ParametersValues,
+    };
+    use tosca::route::{ParameterStyle, RestKind, Route, RouteConfig};
+
+    use crate::tests::{local_server, read_request, write_json};
 
-    use super::{Request, RequestData, ResponseKind, parameter_error};
+    use super::{
+        ClientConfig, HttpClient, IDEMPOTENCY_KEY, Request, RequestData, ResponseKind,
+        parameter_error,
+    };
 
     const ADDRESS_ROUTE: &str = "http://tosca.local/";
     const ADDRESS_ROUTE_WITHOUT_SLASH: &str = "http://tosca.local/";
@@ -405,7 +759,10 @@ mod tests {
                 description,
                 parameters_data: ParametersData::new(),
                 response_kind: ResponseKind::Ok,
+                parameter_style: ParameterStyle::Path,
                 device_environment: DeviceEnvironment::Os,
+                deprecated: None,
+                mock: None,
             }
         );
     }
@@ -455,7 +812,10 @@ mod tests {
                 description,
                 parameters_data,
                 response_kind: ResponseKind::Ok,
+                parameter_style: ParameterStyle::Path,
                 device_environment: DeviceEnvironment::Os,
+                deprecated: None,
+                mock: None,
             }
         );
 
@@ -475,13 +835,13 @@ mod tests {
 
         let mut parameters = HashMap::with_capacity(2);
         parameters.insert("rangeu64".into(), "3".into());
-        parameters.insert("rangef64".into(), "0".into());
+        parameters.insert("rangef64".into(), "0.0".into());
 
         assert_eq!(
             request.create_request(ParametersValues::new().u64("rangeu64", 3)),
             Ok(RequestData {
-                request: if kind == RestKind::Get {
-                    format!("{COMPLETE_ROUTE}/3/0")
+                request: if matches!(kind, RestKind::Get | RestKind::Delete) {
+                    format!("{COMPLETE_ROUTE}/3/0.0")
                 } else {
                     COMPLETE_ROUTE.into()
                 },
@@ -505,7 +865,10 @@ mod tests {
                 description: None,
                 parameters_data: ParametersData::new(),
                 response_kind: ResponseKind::Ok,
+                parameter_style: ParameterStyle::Path,
                 device_environment: DeviceEnvironment::Os,
+                deprecated: None,
+                mock: None,
             }
         );
     }
@@ -543,6 +906,12 @@ mod tests {
         plain_request(route, RestKind::Delete, Hazards::new());
     }
 
+    #[test]
+    fn create_plain_patch_request() {
+        let route = Route::patch("Route", "/route").description("A PATCH route.");
+        plain_request(route, RestKind::Patch, Hazards::new());
+    }
+
     #[test]
     fn create_plain_get_request_with_hazards() {
         let hazards = Hazards::new()
@@ -566,6 +935,74 @@ mod tests {
         );
     }
 
+    #[test]
+    fn create_get_request_with_query_parameters() {
+        let route = Route::get("Route", "/route")
+            .description("A GET route.")
+            .with_parameters(
+                Parameters::new()
+                    .rangeu64_with_default("rangeu64", (0, 20, 1), 5)
+                    .rangef64("rangef64", (0., 20., 0.1)),
+            )
+            .with_parameter_style(ParameterStyle::Query)
+            .serialize_data();
+
+        let request = Request::new(ADDRESS_ROUTE, "light/", DeviceEnvironment::Os, route);
+        assert_eq!(request.parameter_style, ParameterStyle::Query);
+
+        let mut parameters = HashMap::with_capacity(2);
+        parameters.insert("rangeu64".into(), "3".into());
+        parameters.insert("rangef64".into(), "0.0".into());
+
+        // Parameters are not appended to the route as path segments.
+        assert_eq!(
+            request.create_request(ParametersValues::new().u64("rangeu64", 3)),
+            Ok(RequestData {
+                request: COMPLETE_ROUTE.into(),
+                parameters,
+            })
+        );
+    }
+
+    #[test]
+    fn create_request_with_fixed_precision() {
+        let route = Route::get("Route", "/route")
+            .description("A GET route.")
+            .with_parameters(
+                Parameters::new()
+                    .rangef64_with_precision(
+                        "brightness",
+                        (0., 1.),
+                        DecimalPrecision::OneDigit,
+                        0.5,
+                    )
+                    .f64("any", 0.5),
+            )
+            .with_parameter_style(ParameterStyle::Query)
+            .serialize_data();
+
+        let request = Request::new(ADDRESS_ROUTE, "light/", DeviceEnvironment::Os, route);
+
+        // A value computed as `0.30000000000000004`.
+        let brightness = 0.1 + 0.2;
+
+        let mut parameters = HashMap::with_capacity(2);
+        parameters.insert("brightness".into(), "0.3".into());
+        parameters.insert("any".into(), brightness.to_string());
+
+        assert_eq!(
+            request.create_request(
+                ParametersValues::new()
+                    .f64("brightness", brightness)
+                    .f64("any", brightness)
+            ),
+            Ok(RequestData {
+                request: COMPLETE_ROUTE.into(),
+                parameters,
+            })
+        );
+    }
+
     #[test]
     fn create_post_request_with_parameters() {
         let route = Route::post("Route", "/route").description("A POST route.");
@@ -584,6 +1021,142 @@ mod tests {
         request_with_parameters(route, RestKind::Delete, &Hazards::new());
     }
 
+    #[test]
+    fn create_get_request_parameters_order() {
+        // Names are deliberately not in alphabetical order.
+        let route = Route::get("Route", "/route")
+            .description("A GET route.")
+            .with_parameters(Parameters::new().u8("red", 1).u8("green", 2).u8("blue", 3))
+            .serialize_data();
+
+        // A description is received as text, while a topology is imported
+        // as a json value.
+        let received = [
+            serde_json::from_str::<RouteConfig>(&serde_json::to_string(&route).unwrap()).unwrap(),
+            serde_json::from_value::<RouteConfig>(serde_json::to_value(&route).unwrap()).unwrap(),
+        ];
+
+        for route in received {
+            let request = Request::new(ADDRESS_ROUTE, "light/", DeviceEnvironment::Os, route);
+            assert_eq!(
+                request.create_request(&ParametersValues::new()).unwrap(),
+                RequestData {
+                    request: format!("{COMPLETE_ROUTE}/1/2/3"),
+                    parameters: HashMap::from([
+                        ("red".into(), "1".into()),
+                        ("green".into(), "2".into()),
+                        ("blue".into(), "3".into()),
+                    ]),
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn create_delete_request_without_body() {
+        let route = Route::delete("Route", "/route")
+            .description("A DELETE route.")
+            .with_parameters(Parameters::new().u64("id", 0))
+            .serialize_data();
+
+        let request = Request::new(ADDRESS_ROUTE, "light/", DeviceEnvironment::Os, route);
+
+        let request_data = request
+            .create_request(ParametersValues::new().u64("id", 7))
+            .unwrap();
+
+        let http_request = request
+            .http_request(&reqwest::Client::new(), request_data)
+            .build()
+            .unwrap();
+
+        assert_eq!(http_request.method(), reqwest::Method::DELETE);
+        assert_eq!(http_request.url().as_str(), format!("{COMPLETE_ROUTE}/7"));
+        assert!(http_request.body().is_none());
+    }
+
+    #[test]
+    fn create_delete_request_with_body() {
+        let route = Route::delete("Route", "/route")
+            .description("A DELETE route.")
+            .with_parameters(Parameters::new().u64("id", 0))
+            .with_parameter_style(ParameterStyle::Body)
+            .serialize_data();
+
+        let request = Request::new(ADDRESS_ROUTE, "light/", DeviceEnvironment::Os, route);
+
+        let request_data = request
+            .create_request(ParametersValues::new().u64("id", 7))
+            .unwrap();
+
+        let http_request = request
+            .http_request(&reqwest::Client::new(), request_data)
+            .build()
+            .unwrap();
+
+        assert_eq!(http_request.method(), reqwest::Method::DELETE);
+        assert_eq!(http_request.url().as_str(), COMPLETE_ROUTE);
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(
+                http_request
+                    .body()
+                    .and_then(reqwest::Body::as_bytes)
+                    .unwrap()
+            )
+            .unwrap(),
+            serde_json::json!({ "id": "7" })
+        );
+    }
+
+    #[test]
+    fn create_patch_request_with_parameters() {
+        let route = Route::patch("Route", "/route").description("A PATCH route.");
+        request_with_parameters(route, RestKind::Patch, &Hazards::new());
+    }
+
+    #[test]
+    fn create_post_request_with_values() {
+        let route = Route::post("Route", "/route")
+            .description("A POST route.")
+            .with_parameters(
+                Parameters::new()
+                    .rangeu64_with_default("rangeu64", (0, 20, 1), 5)
+                    .characters_sequence("name", "light"),
+            )
+            .serialize_data();
+
+        let request = Request::new(ADDRESS_ROUTE, "light/", DeviceEnvironment::Os, route);
+
+        let mut parameters = HashMap::with_capacity(2);
+        parameters.insert("rangeu64".into(), "3".into());
+        parameters.insert("name".into(), "kitchen".into());
+
+        // All parameters are filled from the same values.
+        assert_eq!(
+            request.create_request(
+                ParametersValues::new()
+                    .u64("rangeu64", 3)
+                    .characters_sequence("name", "kitchen".into())
+            ),
+            Ok(RequestData {
+                request: COMPLETE_ROUTE.into(),
+                parameters,
+            })
+        );
+
+        // The first mismatching value stops the request creation.
+        assert_eq!(
+            request.create_request(
+                ParametersValues::new()
+                    .u64("rangeu64", 3)
+                    .bool("name", true)
+            ),
+            Err(parameter_error(
+                "Found type `bool` for `name`, expected type `String`".into()
+            ))
+        );
+    }
+
     #[test]
     fn create_get_request_with_hazards_and_parameters() {
         let hazards = Hazards::new()
@@ -598,4 +1171,123 @@ mod tests {
             &hazards,
         );
     }
+
+    #[test]
+    fn request_accessors() {
+        let route = Route::put("Toggle", "/route")
+            .description("Toggle a light.")
+            .with_hazard(Hazard::FireHazard)
+            .with_parameters(Parameters::new().rangeu64("brightness", (0, 20, 1)))
+            .serialize_data();
+        let request = Request::new(ADDRESS_ROUTE, "light/", DeviceEnvironment::Os, route);
+
+        assert_eq!(request.kind(), RestKind::Put);
+        assert_eq!(
+            request.hazards(),
+            &Hazards::new().insert(Hazard::FireHazard)
+        );
+        assert_eq!(
+            request.parameters(),
+            &ParametersData::new().insert(
+                "brightness".into(),
+                ParameterKind::RangeU64 {
+                    min: 0,
+                    max: 20,
+                    step: 1,
+                    default: 0,
+                },
+            )
+        );
+        assert_eq!(request.parameters_data(), Some(request.parameters()));
+        assert_eq!(request.response_kind(), ResponseKind::Ok);
+        assert_eq!(request.url(), COMPLETE_ROUTE);
+
+        // A request without parameters.
+        let route = Route::get("Route", "/route").serialize_data();
+        let request = Request::new(ADDRESS_ROUTE, "light/", DeviceEnvironment::Os, route);
+
+        assert!(request.parameters().is_empty());
+        assert_eq!(request.parameters_data(), None);
+    }
+
+    #[test]
+    fn create_request_with_headers() {
+        let request = Request::new(
+            ADDRESS_ROUTE,
+            "light",
+            DeviceEnvironment::Os,
+            Route::put("Route", "/route").serialize_data(),
+        );
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-api-key", HeaderValue::from_static("secret"));
+        headers.insert(IDEMPOTENCY_KEY, HeaderValue::from_static("custom"));
+
+        let client = HttpClient::new(ClientConfig::new());
+        let http_request = request
+            .outgoing_request(
+                client.client(),
+                request.create_request(&ParametersValues::new()).unwrap(),
+                Some("key"),
+                &headers,
+            )
+            .build()
+            .unwrap();
+
+        assert_eq!(http_request.headers()["x-api-key"], "secret");
+        // The idempotency key is never replaced by a custom header.
+        assert_eq!(
+            http_request
+                .headers()
+                .get_all(IDEMPOTENCY_KEY)
+                .iter()
+                .count(),
+            1
+        );
+        assert_eq!(http_request.headers()[IDEMPOTENCY_KEY], "key");
+    }
+
+    const OK_BODY: &str = r#"{"action_terminated_correctly":true}"#;
+
+    // Answers all requests sent over a keep-alive connection.
+    async fn answer_requests(mut stream: TcpStream) {
+        let mut buffer = Vec::new();
+        while read_request(&mut stream, &mut buffer).await.is_some() {
+            if !write_json(&mut stream, OK_BODY, true).await {
+                return;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn reuse_client_connections() {
+        let connections = Arc::new(AtomicUsize::new(0));
+        let accepted = connections.clone();
+        let port = local_server(move |stream| {
+            accepted.fetch_add(1, Ordering::SeqCst);
+            answer_requests(stream)
+        })
+        .await;
+
+        let request = Request::new(
+            &format!("http://127.0.0.1:{port}"),
+            "light",
+            DeviceEnvironment::Os,
+            Route::put("On", "/on")
+                .description("Turn light on.")
+                .serialize_data(),
+        );
+
+        let client = HttpClient::new(ClientConfig::new());
+        for _ in 0..2 {
+            let response = request
+                .plain_send(&client, None, &HeaderMap::new())
+                .await
+                .unwrap();
+            assert_eq!(response.text().await.unwrap(), OK_BODY);
+        }
+
+        // Both requests are sent over the same pooled connection.
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+    }
 }
@@ -1,8 +1,13 @@
-use tosca::response::{InfoResponse, OkResponse, SerialResponse};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tosca::economy::Economy;
+use tosca::energy::Energy;
+use tosca::response::{ErrorResponse, InfoResponse, OkResponse, ResponseKind};
+use tosca::time::Timestamp;
 
 use reqwest::Response as ReqwestResponse;
 
-use serde::{Serialize, de::DeserializeOwned};
+use serde::{Deserialize, Deserializer, de::DeserializeOwned};
 
 use crate::error::{Error, ErrorKind, Result};
 
@@ -14,7 +19,7 @@ use crate::error::{Error, ErrorKind, Result};
 
 async fn json_response<T>(response: ReqwestResponse) -> Result<T>
 where
-    T: Serialize + DeserializeOwned,
+    T: DeserializeOwned,
 {
     response
         .json::<T>()
@@ -22,6 +27,96 @@ where
         .map_err(|e| Error::new(ErrorKind::JsonResponse, format!("Json error caused by {e}")))
 }
 
+// Shape of an `Ok` response body.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OkShape {
+    #[serde(rename = "action_terminated_correctly")]
+    _terminated: bool,
+}
+
+// Shape of an `Info` response body.
+//
+// Each field mirrors the type of its counterpart in `InfoResponse`, so a body
+// containing unknown fields or fields of the wrong type is rejected.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct InfoShape {
+    #[serde(default)]
+    energy: Option<Energy>,
+    #[serde(default)]
+    economy: Option<Economy>,
+    #[serde(rename = "handled requests")]
+    #[serde(default)]
+    handled_requests: Option<u32>,
+    #[serde(default)]
+    uptime: Option<u64>,
+    #[serde(default)]
+    icon: Option<String>,
+    #[serde(rename = "generated at")]
+    #[serde(default)]
+    generated_at: Option<Timestamp>,
+}
+
+impl InfoShape {
+    // An `Info` response carrying no information cannot be told apart from
+    // any other empty json object.
+    const fn is_empty(&self) -> bool {
+        self.energy.is_none()
+            && self.economy.is_none()
+            && self.handled_requests.is_none()
+            && self.uptime.is_none()
+            && self.icon.is_none()
+            && self.generated_at.is_none()
+    }
+}
+
+// Checks whether a json body has the shape of the given response kind.
+//
+// Serial data are defined by each device, so any json value is accepted.
+fn has_shape(value: &serde_json::Value, response_kind: ResponseKind) -> bool {
+    match response_kind {
+        ResponseKind::Ok => OkShape::deserialize(value).is_ok(),
+        ResponseKind::Info => InfoShape::deserialize(value).is_ok_and(|shape| !shape.is_empty()),
+        _ => true,
+    }
+}
+
+// Parses a json response body, checking beforehand that its shape matches
+// the response kind declared by the route.
+async fn shaped_json_response<T>(
+    response: ReqwestResponse,
+    response_kind: ResponseKind,
+) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    let value = json_response::<serde_json::Value>(response).await?;
+
+    if !has_shape(&value, response_kind) {
+        return Err(Error::new(
+            ErrorKind::UnexpectedResponseShape,
+            format!("The response body does not have the shape of a `{response_kind}` response"),
+        ));
+    }
+
+    serde_json::from_value(value)
+        .map_err(|e| Error::new(ErrorKind::JsonResponse, format!("Json error caused by {e}")))
+}
+
+/// Checks whether a device response generated at the given [`Timestamp`]
+/// is older than `threshold`.
+///
+/// Device and controller clocks might drift, so the threshold should account
+/// for some tolerance.
+#[must_use]
+pub fn is_stale(generated_at: Timestamp, threshold: Duration) -> bool {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    generated_at.is_stale(Timestamp::from_unix_secs(now), threshold)
+}
+
 /// An [`OkResponse`] body parser.
 pub struct OkResponseParser(ReqwestResponse);
 
@@ -34,8 +129,10 @@ impl OkResponseParser {
     /// The response body does not contain a valid [`OkResponse`].
     /// A parsing error is raised either because the given format is not correct
     /// or because binary data contains some syntactic or semantic errors.
+    /// An error of kind [`ErrorKind::UnexpectedResponseShape`] is raised when
+    /// the body is valid json, but not shaped as an [`OkResponse`].
     pub async fn parse_body(self) -> Result<OkResponse> {
-        json_response::<OkResponse>(self.0).await
+        shaped_json_response::<OkResponse>(self.0, ResponseKind::Ok).await
     }
 
     pub(crate) const fn new(response: ReqwestResponse) -> Self {
@@ -43,6 +140,57 @@ impl OkResponseParser {
     }
 }
 
+/// The data produced during a device operation, sent by a device through a
+/// [`tosca::response::SerialResponse`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SerialResponse<T> {
+    data: T,
+    generated_at: Option<Timestamp>,
+}
+
+impl<T> SerialResponse<T> {
+    /// Returns an immutable reference to the data contained in the
+    /// response.
+    #[must_use]
+    pub const fn data(&self) -> &T {
+        &self.data
+    }
+
+    /// Returns the data contained in the response, consuming it.
+    #[must_use]
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.data
+    }
+
+    /// Returns the [`Timestamp`] at which the response has been generated,
+    /// if any.
+    #[must_use]
+    pub const fn generated_at(&self) -> Option<Timestamp> {
+        self.generated_at
+    }
+}
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for SerialResponse<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        use serde::de::Error;
+
+        let mut value = serde_json::Value::deserialize(deserializer)?;
+
+        // The generation time is sent alongside the data fields.
+        let generated_at = value
+            .as_object_mut()
+            .and_then(|map| map.remove("generated at"))
+            .map(Timestamp::deserialize)
+            .transpose()
+            .map_err(D::Error::custom)?;
+
+        let data = T::deserialize(value).map_err(D::Error::custom)?;
+
+        Ok(Self { data, generated_at })
+    }
+}
+
 /// A [`SerialResponse`] body parser.
 pub struct SerialResponseParser(ReqwestResponse);
 
@@ -55,7 +203,7 @@ impl SerialResponseParser {
     /// The response body does not contain a valid [`SerialResponse`].
     /// A parsing error is raised either because the given format is not correct
     /// or because binary data contains some syntactic or semantic errors.
-    pub async fn parse_body<T: Serialize + DeserializeOwned>(self) -> Result<SerialResponse<T>> {
+    pub async fn parse_body<T: DeserializeOwned>(self) -> Result<SerialResponse<T>> {
         json_response::<SerialResponse<T>>(self.0).await
     }
 
@@ -76,8 +224,11 @@ impl InfoResponseParser {
     /// The response body does not contain a valid [`InfoResponse`].
     /// A parsing error is raised either because the given format is not correct
     /// or because binary data contains some syntactic or semantic errors.
+    /// An error of kind [`ErrorKind::UnexpectedResponseShape`] is raised when
+    /// the body is valid json, but not shaped as an [`InfoResponse`], such as
+    /// a body containing unknown fields or no field at all.
     pub async fn parse_body(self) -> Result<InfoResponse> {
-        json_response::<InfoResponse>(self.0).await
+        shaped_json_response::<InfoResponse>(self.0, ResponseKind::Info).await
     }
 
     pub(crate) const fn new(response: ReqwestResponse) -> Self {
@@ -85,6 +236,17 @@ impl InfoResponseParser {
     }
 }
 
+/// A response without a body.
+///
+/// The device has no data to return, so there is nothing to parse.
+pub struct EmptyResponse(ReqwestResponse);
+
+impl EmptyResponse {
+    pub(crate) const fn new(response: ReqwestResponse) -> Self {
+        Self(response)
+    }
+}
+
 /// A stream response.
 #[cfg(feature = "stream")]
 pub struct StreamResponse(ReqwestResponse);
@@ -107,11 +269,300 @@ impl StreamResponse {
         })
     }
 
+    /// Consumes the internal response body opening a stream of frames.
+    ///
+    /// The response body must be a `multipart/x-mixed-replace` stream, such
+    /// as a `MJPEG` camera feed, and each frame is the body of a multipart
+    /// section.
+    ///
+    /// # Errors
+    ///
+    /// The response is not a multipart stream, or stream data are not
+    /// retrieved correctly because of network failures or data corruption.
+    pub fn open_multipart_stream(
+        self,
+    ) -> Result<impl futures_util::Stream<Item = Result<bytes::Bytes>>> {
+        let boundary = self
+            .0
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .and_then(multipart_boundary)
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::StreamResponse,
+                    "The response is not a multipart stream",
+                )
+            })?;
+
+        Ok(split_multipart(self.open_stream(), &boundary))
+    }
+
+    /// Consumes the internal response body opening a stream of records.
+    ///
+    /// The response body must be a newline-delimited JSON stream, such as a
+    /// sensor log, and each record is deserialized as soon as its line has
+    /// been entirely received.
+    ///
+    /// # Errors
+    ///
+    /// A record is not a valid JSON value of the given type, or stream data
+    /// are not retrieved correctly because of network failures or data
+    /// corruption.
+    pub fn open_ndjson_stream<T: DeserializeOwned>(
+        self,
+    ) -> impl futures_util::Stream<Item = Result<T>> {
+        ndjson_records(self.open_stream())
+    }
+
+    /// Consumes the internal response body opening a stream of records.
+    ///
+    /// The response body must be a Server-Sent Events stream, such as the
+    /// events of a device, and each record is deserialized from the data of
+    /// an event. Events without data, such as keep-alive comments, are
+    /// skipped.
+    ///
+    /// # Errors
+    ///
+    /// A record is not a valid JSON value of the given type, or stream data
+    /// are not retrieved correctly because of network failures or data
+    /// corruption.
+    pub fn open_sse_stream<T: DeserializeOwned>(
+        self,
+    ) -> impl futures_util::Stream<Item = Result<T>> {
+        sse_records(self.open_stream())
+    }
+
+    // Returns the status code of the response.
+    pub(crate) fn status(&self) -> reqwest::StatusCode {
+        self.0.status()
+    }
+
     pub(crate) const fn new(response: ReqwestResponse) -> Self {
         Self(response)
     }
 }
 
+// Retrieves the boundary of a multipart content type.
+#[cfg(feature = "stream")]
+fn multipart_boundary(content_type: &str) -> Option<String> {
+    let mut parameters = content_type.split(';');
+    if !parameters
+        .next()?
+        .trim()
+        .to_ascii_lowercase()
+        .starts_with("multipart/")
+    {
+        return None;
+    }
+
+    parameters.find_map(|parameter| {
+        let (name, value) = parameter.split_once('=')?;
+        name.trim()
+            .eq_ignore_ascii_case("boundary")
+            .then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+// Finds the position of a subsequence of bytes.
+#[cfg(feature = "stream")]
+fn find(bytes: &[u8], subsequence: &[u8]) -> Option<usize> {
+    bytes
+        .windows(subsequence.len())
+        .position(|window| window == subsequence)
+}
+
+// Splits a multipart body into the bodies of its sections.
+#[cfg(feature = "stream")]
+struct MultipartSplitter {
+    // Section delimiter.
+    delimiter: Vec<u8>,
+    // Bytes not yet split.
+    buffer: bytes::BytesMut,
+}
+
+#[cfg(feature = "stream")]
+impl MultipartSplitter {
+    fn new(boundary: &str) -> Self {
+        Self {
+            delimiter: format!("--{boundary}").into_bytes(),
+            buffer: bytes::BytesMut::new(),
+        }
+    }
+
+    fn push(&mut self, chunk: &[u8]) {
+        self.buffer.extend_from_slice(chunk);
+    }
+
+    // Returns the next section body, if it has been entirely received.
+    fn next_frame(&mut self) -> Option<bytes::Bytes> {
+        let headers_start = find(&self.buffer, &self.delimiter)? + self.delimiter.len();
+        let headers_end = headers_start + find(&self.buffer[headers_start..], b"\r\n\r\n")?;
+        let body_start = headers_end + 4;
+
+        // Use the section length when present, otherwise look for the next
+        // delimiter.
+        let headers = &self.buffer[headers_start..headers_end];
+        let body_end = if let Some(length) = content_length(headers) {
+            (self.buffer.len() >= body_start + length).then_some(body_start + length)?
+        } else {
+            let mut next_delimiter = b"\r\n".to_vec();
+            next_delimiter.extend_from_slice(&self.delimiter);
+            body_start + find(&self.buffer[body_start..], &next_delimiter)?
+        };
+
+        let mut section = self.buffer.split_to(body_end);
+        Some(section.split_off(body_start).freeze())
+    }
+}
+
+// Retrieves the `Content-Length` value of multipart section headers.
+#[cfg(feature = "stream")]
+fn content_length(headers: &[u8]) -> Option<usize> {
+    core::str::from_utf8(headers)
+        .ok()?
+        .split("\r\n")
+        .find_map(|header| {
+            let (name, value) = header.split_once(':')?;
+            name.trim()
+                .eq_ignore_ascii_case("content-length")
+                .then(|| value.trim().parse().ok())?
+        })
+}
+
+/// Splits a `multipart/x-mixed-replace` byte stream, delimited by the
+/// given boundary, into a stream of frames.
+///
+/// Each frame is the body of a multipart section, such as a `JPEG` image
+/// of a `MJPEG` camera feed.
+#[cfg(feature = "stream")]
+pub fn split_multipart<S>(
+    stream: S,
+    boundary: &str,
+) -> impl futures_util::Stream<Item = Result<bytes::Bytes>> + use<S>
+where
+    S: futures_util::Stream<Item = Result<bytes::Bytes>>,
+{
+    use futures_util::StreamExt;
+
+    futures_util::stream::unfold(
+        (Box::pin(stream), MultipartSplitter::new(boundary)),
+        |(mut stream, mut splitter)| async move {
+            loop {
+                if let Some(frame) = splitter.next_frame() {
+                    return Some((Ok(frame), (stream, splitter)));
+                }
+
+                match stream.next().await? {
+                    Ok(chunk) => splitter.push(&chunk),
+                    Err(e) => return Some((Err(e), (stream, splitter))),
+                }
+            }
+        },
+    )
+}
+
+// Splits a newline-delimited JSON byte stream into a stream of records.
+//
+// Empty lines are ignored, while the last record might lack its newline.
+#[cfg(feature = "stream")]
+fn ndjson_records<S, T>(stream: S) -> impl futures_util::Stream<Item = Result<T>> + use<S, T>
+where
+    S: futures_util::Stream<Item = Result<bytes::Bytes>>,
+    T: DeserializeOwned,
+{
+    use futures_util::StreamExt;
+
+    let record = |line: &[u8]| {
+        serde_json::from_slice::<T>(line)
+            .map_err(|e| Error::new(ErrorKind::JsonResponse, format!("Json error caused by {e}")))
+    };
+
+    futures_util::stream::unfold(
+        (Box::pin(stream), bytes::BytesMut::new(), false),
+        move |(mut stream, mut buffer, mut ended)| async move {
+            loop {
+                let line = match buffer.iter().position(|byte| *byte == b'\n') {
+                    Some(position) => buffer.split_to(position + 1),
+                    None if ended => buffer.split(),
+                    None => {
+                        match stream.next().await {
+                            Some(Ok(chunk)) => buffer.extend_from_slice(&chunk),
+                            Some(Err(e)) => return Some((Err(e), (stream, buffer, ended))),
+                            None => ended = true,
+                        }
+                        continue;
+                    }
+                };
+
+                if !line.trim_ascii().is_empty() {
+                    return Some((record(&line), (stream, buffer, ended)));
+                }
+
+                if ended && buffer.is_empty() {
+                    return None;
+                }
+            }
+        },
+    )
+}
+
+// Splits a Server-Sent Events byte stream into a stream of records, each
+// one carried by the data of an event.
+//
+// Events without data, such as keep-alive comments, are ignored.
+#[cfg(feature = "stream")]
+fn sse_records<S, T>(stream: S) -> impl futures_util::Stream<Item = Result<T>> + use<S, T>
+where
+    S: futures_util::Stream<Item = Result<bytes::Bytes>>,
+    T: DeserializeOwned,
+{
+    use futures_util::StreamExt;
+
+    // Retrieves the data of an event, joining its data lines.
+    let event_data = |event: &[u8]| {
+        let mut data = Vec::new();
+        for line in event.split(|byte| *byte == b'\n') {
+            if let Some(value) = line.strip_prefix(b"data:") {
+                if !data.is_empty() {
+                    data.push(b'\n');
+                }
+                data.extend_from_slice(value.strip_prefix(b" ").unwrap_or(value));
+            }
+        }
+        data
+    };
+
+    let record = |data: &[u8]| {
+        serde_json::from_slice::<T>(data)
+            .map_err(|e| Error::new(ErrorKind::JsonResponse, format!("Json error caused by {e}")))
+    };
+
+    futures_util::stream::unfold(
+        (Box::pin(stream), bytes::BytesMut::new()),
+        move |(mut stream, mut buffer)| async move {
+            loop {
+                // Events are separated by an empty line.
+                let Some(position) = buffer.windows(2).position(|bytes| bytes == b"\n\n") else {
+                    match stream.next().await? {
+                        // Carriage returns are dropped, so all line endings
+                        // are newlines.
+                        Ok(chunk) => buffer.extend(chunk.iter().filter(|byte| **byte != b'\r')),
+                        Err(e) => return Some((Err(e), (stream, buffer))),
+                    }
+                    continue;
+                };
+
+                let event = buffer.split_to(position + 2);
+                let data = event_data(&event);
+                if !data.is_empty() {
+                    return Some((record(&data), (stream, buffer)));
+                }
+            }
+        },
+    )
+}
+
 /// All supported device response kinds.
 ///
 /// Each response includes a dedicated body parser responsible for
@@ -126,7 +577,486 @@ pub enum Response {
     SerialBody(SerialResponseParser),
     /// An [`InfoResponse`] body.
     InfoBody(InfoResponseParser),
+    /// A response without a body.
+    Empty(EmptyResponse),
     /// A stream response body.
     #[cfg(feature = "stream")]
     StreamBody(StreamResponse),
 }
+
+impl Response {
+    /// Parses the response body as a JSON value of the given type.
+    ///
+    /// # Errors
+    ///
+    /// The response has been skipped, or its body does not contain
+    /// a valid JSON value of the given type.
+    pub async fn json<T: DeserializeOwned>(self) -> Result<T> {
+        json_response::<T>(self.into_inner()?).await
+    }
+
+    /// Checks whether the device has completed its operation successfully.
+    ///
+    /// On success, the response is returned unchanged, while a skipped
+    /// response is always considered successful.
+    ///
+    /// # Errors
+    ///
+    /// The device has answered with an [`ErrorResponse`], which is mapped
+    /// into an [`Error`] of kind [`ErrorKind::Device`] carrying the device
+    /// error kind. An error is also returned when the [`ErrorResponse`]
+    /// cannot be parsed.
+    pub async fn ok_or_error(self) -> Result<Self> {
+        let status = match &self {
+            Self::Skipped => return Ok(self),
+            Self::OkBody(parser) => parser.0.status(),
+            Self::SerialBody(parser) => parser.0.status(),
+            Self::InfoBody(parser) => parser.0.status(),
+            Self::Empty(response) => response.0.status(),
+            #[cfg(feature = "stream")]
+            Self::StreamBody(stream) => stream.0.status(),
+        };
+
+        if status.is_success() {
+            return Ok(self);
+        }
+
+        let error = json_response::<ErrorResponse>(self.into_inner()?).await?;
+
+        Err(Error::new(
+            ErrorKind::Device(error.error),
+            match error.info {
+                Some(info) => format!("{:?}: {} ({info})", error.error, error.description),
+                None => format!("{:?}: {}", error.error, error.description),
+            },
+        ))
+    }
+
+    /// Consumes the response opening a bytes stream of its body.
+    ///
+    /// It allows to relay a device stream, such as a camera feed, without
+    /// buffering it.
+    ///
+    /// # Errors
+    ///
+    /// A skipped response does not have a body.
+    #[cfg(feature = "stream")]
+    pub fn into_byte_stream(
+        self,
+    ) -> Result<impl futures_util::Stream<Item = Result<bytes::Bytes>>> {
+        Ok(StreamResponse::new(self.into_inner()?).open_stream())
+    }
+
+    /// Consumes the response opening a stream of records, sent by a device
+    /// as newline-delimited JSON.
+    ///
+    /// Each record is yielded as soon as it has been entirely received, in
+    /// the order it has been sent.
+    ///
+    /// # Errors
+    ///
+    /// A skipped response does not have a body. Each record is an error when
+    /// it is not a valid JSON value of the given type.
+    #[cfg(feature = "stream")]
+    pub fn into_ndjson_stream<T: DeserializeOwned>(
+        self,
+    ) -> Result<impl futures_util::Stream<Item = Result<T>>> {
+        Ok(StreamResponse::new(self.into_inner()?).open_ndjson_stream())
+    }
+
+    fn into_inner(self) -> Result<ReqwestResponse> {
+        match self {
+            Self::Skipped => Err(Error::new(
+                ErrorKind::JsonResponse,
+                "A skipped response does not have a body",
+            )),
+            Self::OkBody(parser) => Ok(parser.0),
+            Self::SerialBody(parser) => Ok(parser.0),
+            Self::InfoBody(parser) => Ok(parser.0),
+            Self::Empty(response) => Ok(response.0),
+            #[cfg(feature = "stream")]
+            Self::StreamBody(stream) => Ok(stream.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tosca::device::DeviceInfo;
+    use tosca::energy::{Energy, WaterUseEfficiency};
+    use tosca::response::{
+        ErrorKind as DeviceErrorKind, ErrorResponse, InfoResponse, OkResponse, SerialResponse,
+    };
+    use tosca::time::Timestamp;
+
+    use crate::error::{Error, ErrorKind};
+    use crate::tests::Brightness;
+
+    use super::{
+        InfoResponseParser, OkResponseParser, Response, SerialResponse as TypedSerialResponse,
+        SerialResponseParser,
+    };
+
+    fn serial_response(status: u16, body: String) -> Response {
+        Response::SerialBody(SerialResponseParser::new(
+            http::Response::builder()
+                .status(status)
+                .body(body)
+                .unwrap()
+                .into(),
+        ))
+    }
+
+    #[tokio::test]
+    async fn json_response() {
+        let body =
+            serde_json::to_string(&SerialResponse::new(Brightness { brightness: 5 })).unwrap();
+
+        let brightness = serial_response(200, body)
+            .ok_or_error()
+            .await
+            .unwrap()
+            .json::<Brightness>()
+            .await
+            .unwrap();
+
+        assert_eq!(brightness, Brightness { brightness: 5 });
+    }
+
+    #[tokio::test]
+    async fn typed_serial_response() {
+        let generated_at = Timestamp::from_unix_secs(1_700_000_000);
+        let body = serde_json::to_string(
+            &SerialResponse::new(Brightness { brightness: 5 }).with_generated_at(generated_at),
+        )
+        .unwrap();
+
+        let serial_response: TypedSerialResponse<Brightness> =
+            SerialResponseParser::new(http_response(body))
+                .parse_body()
+                .await
+                .unwrap();
+
+        assert_eq!(serial_response.data(), &Brightness { brightness: 5 });
+        assert_eq!(serial_response.generated_at(), Some(generated_at));
+        assert_eq!(serial_response.into_inner(), Brightness { brightness: 5 });
+
+        // A response without its generation time.
+        let body =
+            serde_json::to_string(&SerialResponse::new(Brightness { brightness: 5 })).unwrap();
+
+        let serial_response = SerialResponseParser::new(http_response(body))
+            .parse_body::<Brightness>()
+            .await
+            .unwrap();
+
+        assert_eq!(serial_response.generated_at(), None);
+        assert_eq!(serial_response.into_inner(), Brightness { brightness: 5 });
+    }
+
+    fn http_response(body: String) -> reqwest::Response {
+        http::Response::builder()
+            .status(200)
+            .body(body)
+            .unwrap()
+            .into()
+    }
+
+    #[tokio::test]
+    async fn unexpected_response_shape() {
+        let serial_body =
+            serde_json::to_string(&SerialResponse::new(Brightness { brightness: 5 })).unwrap();
+
+        // A serial body for a route declared as `Ok`.
+        assert_eq!(
+            OkResponseParser::new(http_response(serial_body.clone()))
+                .parse_body()
+                .await
+                .err(),
+            Some(Error::new(
+                ErrorKind::UnexpectedResponseShape,
+                "The response body does not have the shape of a `Ok` response"
+            ))
+        );
+
+        // A serial body for a route declared as `Info`.
+        assert_eq!(
+            InfoResponseParser::new(http_response(serial_body))
+                .parse_body()
+                .await
+                .err()
+                .map(|error| error.kind()),
+            Some(ErrorKind::UnexpectedResponseShape)
+        );
+
+        // Bodies with the declared shape.
+        let ok_body = serde_json::to_string(&OkResponse::ok()).unwrap();
+        assert_eq!(
+            OkResponseParser::new(http_response(ok_body))
+                .parse_body()
+                .await
+                .unwrap(),
+            OkResponse::ok()
+        );
+
+        let info_body = serde_json::to_string(
+            &InfoResponse::new(
+                DeviceInfo::empty()
+                    .add_energy(Energy::init_with_water_use_efficiency(
+                        WaterUseEfficiency::init_with_gpp(42.0),
+                    ))
+                    .add_handled_requests(2)
+                    .add_uptime(3600)
+                    .with_icon("lightbulb"),
+            )
+            .with_generated_at(Timestamp::from_unix_secs(1_700_000_000)),
+        )
+        .unwrap();
+        assert!(
+            InfoResponseParser::new(http_response(info_body))
+                .parse_body()
+                .await
+                .is_ok()
+        );
+
+        // Bodies which are not shaped as the declared response kind.
+        for body in [
+            r#"{"action_terminated_correctly":true,"brightness":5}"#,
+            r#"{"action_terminated_correctly":"yes"}"#,
+            "{}",
+        ] {
+            assert_eq!(
+                OkResponseParser::new(http_response(body.into()))
+                    .parse_body()
+                    .await
+                    .err()
+                    .map(|error| error.kind()),
+                Some(ErrorKind::UnexpectedResponseShape)
+            );
+        }
+
+        for body in [
+            r#"{"uptime":3600,"brightness":5}"#,
+            r#"{"uptime":"1h"}"#,
+            "{}",
+        ] {
+            assert_eq!(
+                InfoResponseParser::new(http_response(body.into()))
+                    .parse_body()
+                    .await
+                    .err()
+                    .map(|error| error.kind()),
+                Some(ErrorKind::UnexpectedResponseShape)
+            );
+        }
+
+        // Invalid json is still a json error.
+        assert_eq!(
+            OkResponseParser::new(http_response("{".into()))
+                .parse_body()
+                .await
+                .err()
+                .map(|error| error.kind()),
+            Some(ErrorKind::JsonResponse)
+        );
+    }
+
+    #[tokio::test]
+    async fn error_response() {
+        let body = serde_json::to_string(&ErrorResponse::invalid_data_with_error(
+            "Invalid brightness",
+            "Value out of range",
+        ))
+        .unwrap();
+
+        assert_eq!(
+            serial_response(500, body).ok_or_error().await.err(),
+            Some(Error::new(
+                ErrorKind::Device(DeviceErrorKind::InvalidData),
+                "InvalidData: Invalid brightness (Value out of range)"
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn error_kinds() {
+        for (status, error) in [
+            (404, ErrorResponse::not_found("Missing brightness")),
+            (401, ErrorResponse::unauthorized("Unknown requester")),
+            (
+                429,
+                ErrorResponse::rate_limited("Too many brightness changes"),
+            ),
+        ] {
+            let kind = error.error;
+            let body = serde_json::to_string(&error).unwrap();
+
+            assert_eq!(
+                serial_response(status, body)
+                    .ok_or_error()
+                    .await
+                    .err()
+                    .map(|error| error.kind()),
+                Some(ErrorKind::Device(kind))
+            );
+        }
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn multipart_stream() {
+        use futures_util::StreamExt;
+
+        let body = concat!(
+            "--frame\r\nContent-Type: image/jpeg\r\nContent-Length: 5\r\n\r\n",
+            "first\r\n",
+            "--frame\r\nContent-Type: image/jpeg\r\n\r\n",
+            "second\r\n",
+            "--frame--\r\n"
+        );
+
+        let stream = super::StreamResponse::new(
+            http::Response::builder()
+                .header("Content-Type", "multipart/x-mixed-replace; boundary=frame")
+                .body(body)
+                .unwrap()
+                .into(),
+        );
+
+        let frames = stream
+            .open_multipart_stream()
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].as_ref().unwrap().as_ref(), b"first");
+        assert_eq!(frames[1].as_ref().unwrap().as_ref(), b"second");
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn split_multipart_chunks() {
+        use futures_util::StreamExt;
+
+        // Sections split across several chunks.
+        let chunks = [
+            "--frame\r\nContent-Le",
+            "ngth: 5\r\n\r\nfir",
+            "st\r\n--frame\r\nContent-Length: 6\r\n\r\nsecond\r\n",
+            "--frame--\r\n",
+        ]
+        .map(|chunk| Ok(bytes::Bytes::from_static(chunk.as_bytes())));
+
+        let frames = super::split_multipart(futures_util::stream::iter(chunks), "frame")
+            .map(|frame| frame.unwrap())
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(frames, ["first", "second"]);
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn ndjson_stream() {
+        use futures_util::StreamExt;
+
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        struct Record {
+            temperature: f64,
+        }
+
+        assert!(Response::Skipped.into_ndjson_stream::<Record>().is_err());
+
+        // Records split across several chunks, the last one without its
+        // newline.
+        let chunks = [
+            "{\"temperature\":20.5}\n{\"tempera",
+            "ture\":21.0}\n",
+            "\n{\"temperature\":21.5}",
+        ]
+        .map(|chunk| Ok::<_, std::io::Error>(bytes::Bytes::from_static(chunk.as_bytes())));
+
+        let response = Response::StreamBody(super::StreamResponse::new(
+            http::Response::builder()
+                .header("Content-Type", "application/x-ndjson")
+                .body(reqwest::Body::wrap_stream(futures_util::stream::iter(
+                    chunks,
+                )))
+                .unwrap()
+                .into(),
+        ));
+
+        let records = response
+            .into_ndjson_stream::<Record>()
+            .unwrap()
+            .map(|record| record.unwrap())
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(
+            records,
+            [
+                Record { temperature: 20.5 },
+                Record { temperature: 21. },
+                Record { temperature: 21.5 },
+            ]
+        );
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn sse_stream() {
+        use futures_util::StreamExt;
+
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        struct Record {
+            temperature: f64,
+        }
+
+        // Events split across several chunks, along with a keep-alive
+        // comment and an event whose data spans two lines.
+        let chunks = [
+            "data: {\"temperature\":20.5}\n\n:\n\ndata: {\"tempera",
+            "ture\":21.0}\r\n\r\n",
+            "data: {\"temperature\":\ndata: 21.5}\n\n",
+        ]
+        .map(|chunk| Ok(bytes::Bytes::from_static(chunk.as_bytes())));
+
+        let records = super::sse_records::<_, Record>(futures_util::stream::iter(chunks))
+            .map(|record| record.unwrap())
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(
+            records,
+            [
+                Record { temperature: 20.5 },
+                Record { temperature: 21. },
+                Record { temperature: 21.5 },
+            ]
+        );
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn byte_stream() {
+        use futures_util::StreamExt;
+
+        assert!(Response::Skipped.into_byte_stream().is_err());
+
+        let chunks = serial_response(200, "bytes".into())
+            .into_byte_stream()
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(chunks[0].as_ref().unwrap().as_ref(), b"bytes");
+    }
+
+    #[tokio::test]
+    async fn skipped_response() {
+        assert!(Response::Skipped.ok_or_error().await.is_ok());
+        assert!(Response::Skipped.json::<Brightness>().await.is_err());
+    }
+}
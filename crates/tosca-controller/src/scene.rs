@@ -0,0 +1,176 @@
+use tosca::parameters::ParametersValues;
+
+use crate::error::Error;
+use crate::response::Response;
+
+/// A request sent to a device by a [`Scene`].
+#[derive(Debug, PartialEq)]
+pub struct SceneAction<'a> {
+    pub(crate) id: usize,
+    pub(crate) route: String,
+    pub(crate) parameters: Option<ParametersValues<'a>>,
+}
+
+impl<'a> SceneAction<'a> {
+    fn new(id: usize, route: impl Into<String>, parameters: Option<ParametersValues<'a>>) -> Self {
+        Self {
+            id,
+            route: route.into(),
+            parameters,
+        }
+    }
+
+    /// Returns the identifier of the device receiving the request.
+    #[must_use]
+    #[inline]
+    pub const fn id(&self) -> usize {
+        self.id
+    }
+
+    /// Returns the route of the request.
+    #[must_use]
+    #[inline]
+    pub fn route(&self) -> &str {
+        &self.route
+    }
+
+    /// Returns the [`ParametersValues`] of the request, if any.
+    #[must_use]
+    #[inline]
+    pub const fn parameters(&self) -> Option<&ParametersValues<'a>> {
+        self.parameters.as_ref()
+    }
+}
+
+/// A step of a [`Scene`], sending a request to a device.
+///
+/// A step might define a compensating action, which is a request sent to
+/// the same device to undo the step, such as turning a light off after
+/// having turned it on.
+#[derive(Debug, PartialEq)]
+pub struct SceneStep<'a> {
+    pub(crate) action: SceneAction<'a>,
+    pub(crate) compensation: Option<SceneAction<'a>>,
+}
+
+impl<'a> SceneStep<'a> {
+    /// Creates a [`SceneStep`] sending a request with the given route to the
+    /// device with the given identifier.
+    #[must_use]
+    #[inline]
+    pub fn new(id: usize, route: impl Into<String>) -> Self {
+        Self {
+            action: SceneAction::new(id, route, None),
+            compensation: None,
+        }
+    }
+
+    /// Sets the [`ParametersValues`] of the request.
+    #[must_use]
+    #[inline]
+    pub fn parameters(mut self, parameters: ParametersValues<'a>) -> Self {
+        self.action.parameters = Some(parameters);
+        self
+    }
+
+    /// Sets the compensating action of the step, which sends a request with
+    /// the given route to the same device.
+    #[must_use]
+    #[inline]
+    pub fn compensation(mut self, route: impl Into<String>) -> Self {
+        self.compensation = Some(SceneAction::new(self.action.id, route, None));
+        self
+    }
+
+    /// Sets the compensating action of the step, which sends a request with
+    /// the given route and [`ParametersValues`] to the same device.
+    #[must_use]
+    #[inline]
+    pub fn compensation_with_parameters(
+        mut self,
+        route: impl Into<String>,
+        parameters: ParametersValues<'a>,
+    ) -> Self {
+        self.compensation = Some(SceneAction::new(self.action.id, route, Some(parameters)));
+        self
+    }
+
+    /// Returns the [`SceneAction`] of the step.
+    #[must_use]
+    #[inline]
+    pub const fn action(&self) -> &SceneAction<'a> {
+        &self.action
+    }
+
+    /// Returns the compensating [`SceneAction`] of the step, if any.
+    #[must_use]
+    #[inline]
+    pub const fn compensating_action(&self) -> Option<&SceneAction<'a>> {
+        self.compensation.as_ref()
+    }
+}
+
+/// A sequence of requests sent to one or more devices as a single
+/// transaction, such as dimming the lights and closing the blinds for a
+/// movie night.
+///
+/// A scene is run through
+/// [`Controller::run_scene`](crate::controller::Controller::run_scene).
+/// Its steps are run in order, until a step fails. Then, the compensating
+/// actions of the completed steps are run in reverse order, so devices are
+/// brought back to their previous state.
+#[derive(Debug, Default, PartialEq)]
+pub struct Scene<'a> {
+    pub(crate) steps: Vec<SceneStep<'a>>,
+}
+
+impl<'a> Scene<'a> {
+    /// Creates an empty [`Scene`].
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a [`SceneStep`] at the end of the scene.
+    #[must_use]
+    #[inline]
+    pub fn step(mut self, step: SceneStep<'a>) -> Self {
+        self.steps.push(step);
+        self
+    }
+
+    /// Returns the [`SceneStep`]s of the scene, in order.
+    #[must_use]
+    #[inline]
+    pub fn steps(&self) -> &[SceneStep<'a>] {
+        &self.steps
+    }
+}
+
+/// The outcome of a [`Scene`].
+pub struct SceneReport {
+    /// Results of the steps run, in order.
+    ///
+    /// Steps following a failed step are not run, so they have no result.
+    pub steps: Vec<Result<Response, Error>>,
+    /// Results of the compensating actions run after a failed step, in the
+    /// order they have been run, each along with the index of its step.
+    pub compensations: Vec<(usize, Result<Response, Error>)>,
+}
+
+impl SceneReport {
+    /// Checks whether all steps of the scene have succeeded.
+    #[must_use]
+    #[inline]
+    pub fn is_success(&self) -> bool {
+        self.failed_step().is_none()
+    }
+
+    /// Returns the index of the failed step, if any.
+    #[must_use]
+    #[inline]
+    pub fn failed_step(&self) -> Option<usize> {
+        self.steps.iter().position(Result::is_err)
+    }
+}
@@ -0,0 +1,473 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::path::Path;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, ErrorKind, Result};
+
+fn session_error(message: String) -> Error {
+    Error::new(ErrorKind::Session, message)
+}
+
+// Bodies are recorded in base64, so binary data are saved without loss.
+mod base64_body {
+    use base64::{Engine, engine::general_purpose::STANDARD};
+
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    pub(super) fn serialize<S: Serializer>(body: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&STANDARD.encode(body))
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<u8>, D::Error> {
+        STANDARD
+            .decode(String::deserialize(deserializer)?)
+            .map_err(D::Error::custom)
+    }
+}
+
+// Optional bodies are recorded in base64 as well.
+mod optional_base64_body {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    // Serde passes the field by reference.
+    #[allow(clippy::ref_option)]
+    pub(super) fn serialize<S: Serializer>(
+        body: &Option<Vec<u8>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match body {
+            Some(body) => super::base64_body::serialize(body, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Vec<u8>>, D::Error> {
+        #[derive(Deserialize)]
+        struct Body(#[serde(with = "super::base64_body")] Vec<u8>);
+
+        Ok(Option::<Body>::deserialize(deserializer)?.map(|Body(body)| body))
+    }
+}
+
+/// A request sent to a device during a session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    /// `HTTP` method.
+    pub method: String,
+    /// Complete request address, including its query.
+    pub url: String,
+    /// Request body, if any.
+    ///
+    /// It is saved in base64.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(with = "optional_base64_body")]
+    pub body: Option<Vec<u8>>,
+}
+
+impl RecordedRequest {
+    fn new(request: &reqwest::Request) -> Self {
+        Self {
+            method: request.method().to_string(),
+            url: request.url().to_string(),
+            body: request
+                .body()
+                .and_then(reqwest::Body::as_bytes)
+                .map(<[u8]>::to_vec),
+        }
+    }
+}
+
+/// A response received from a device during a session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    /// `HTTP` status.
+    pub status: u16,
+    /// Response headers.
+    ///
+    /// Only the last value of a repeated header is kept.
+    pub headers: BTreeMap<String, String>,
+    /// Response body.
+    ///
+    /// It is saved in base64.
+    #[serde(with = "base64_body")]
+    pub body: Vec<u8>,
+}
+
+impl RecordedResponse {
+    async fn new(response: reqwest::Response) -> Result<Self> {
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| {
+                value
+                    .to_str()
+                    .ok()
+                    .map(|value| (name.to_string(), value.to_string()))
+            })
+            .collect();
+        let body = response.bytes().await?.to_vec();
+
+        Ok(Self {
+            status,
+            headers,
+            body,
+        })
+    }
+
+    fn response(&self) -> Result<reqwest::Response> {
+        let mut builder = http::Response::builder().status(self.status);
+        for (name, value) in &self.headers {
+            builder = builder.header(name, value);
+        }
+
+        builder
+            .body(self.body.clone())
+            .map(Into::into)
+            .map_err(|e| session_error(format!("Invalid recorded response: {e}")))
+    }
+}
+
+/// A request sent to a device, along with its response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Interaction {
+    /// Request.
+    pub request: RecordedRequest,
+    /// Response.
+    pub response: RecordedResponse,
+}
+
+/// A recorder of all requests sent by a
+/// [`Controller`](crate::controller::Controller) and their responses.
+///
+/// A recorder is attached to a controller through
+/// [`Controller::record_session`](crate::controller::Controller::record_session).
+/// Its clones share the same [`Interaction`]s, so a clone can be kept to
+/// save the session once it ends.
+///
+/// Response bodies are entirely read to be recorded, hence a stream is
+/// received only after it ends. Requests blocked by a policy are never
+/// recorded.
+#[derive(Debug, Clone, Default)]
+pub struct SessionRecorder(Arc<Mutex<Vec<Interaction>>>);
+
+impl SessionRecorder {
+    /// Creates an empty [`SessionRecorder`].
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the [`Interaction`]s recorded so far, in order.
+    #[must_use]
+    pub fn interactions(&self) -> Vec<Interaction> {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Saves the [`Interaction`]s recorded so far into a `JSON` file.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned when the file cannot be written.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let session = serde_json::to_vec_pretty(&self.interactions())
+            .map_err(|e| session_error(format!("Unable to serialize the session: {e}")))?;
+
+        std::fs::write(path, session).map_err(|e| {
+            session_error(format!(
+                "Unable to save the session into `{}`: {e}",
+                path.display()
+            ))
+        })
+    }
+
+    async fn record(
+        &self,
+        request: RecordedRequest,
+        response: reqwest::Response,
+    ) -> Result<reqwest::Response> {
+        let response = RecordedResponse::new(response).await?;
+        let replayed = response.response()?;
+
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(Interaction { request, response });
+
+        Ok(replayed)
+    }
+}
+
+/// A player of the [`Interaction`]s recorded by a [`SessionRecorder`].
+///
+/// A player is attached to a controller through
+/// [`Controller::replay_session`](crate::controller::Controller::replay_session).
+/// It answers each request with the response of the first recorded
+/// [`Interaction`] having the same method, address, and body, which has not
+/// been replayed yet. Hence, repeated requests are answered in their
+/// recording order, and devices are never contacted.
+#[derive(Debug, Clone)]
+pub struct SessionPlayer(Arc<Mutex<VecDeque<Interaction>>>);
+
+impl SessionPlayer {
+    /// Creates a [`SessionPlayer`] from a sequence of [`Interaction`]s.
+    #[must_use]
+    #[inline]
+    pub fn new(interactions: Vec<Interaction>) -> Self {
+        Self(Arc::new(Mutex::new(interactions.into())))
+    }
+
+    /// Loads a [`SessionPlayer`] from a `JSON` file saved by
+    /// [`SessionRecorder::save`].
+    ///
+    /// # Errors
+    ///
+    /// An error is returned when the file cannot be read, or when it does
+    /// not contain a valid session.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let session = std::fs::read(path).map_err(|e| {
+            session_error(format!(
+                "Unable to load the session from `{}`: {e}",
+                path.display()
+            ))
+        })?;
+
+        serde_json::from_slice(&session)
+            .map(Self::new)
+            .map_err(|e| session_error(format!("Invalid session: {e}")))
+    }
+
+    /// Returns the number of [`Interaction`]s not replayed yet.
+    #[must_use]
+    pub fn remaining(&self) -> usize {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner).len()
+    }
+
+    fn replay(&self, request: &RecordedRequest) -> Result<reqwest::Response> {
+        let mut interactions = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let position = interactions
+            .iter()
+            .position(|interaction| &interaction.request == request)
+            .ok_or_else(|| {
+                session_error(format!(
+                    "No recorded response for the `{} {}` request.",
+                    request.method, request.url
+                ))
+            })?;
+
+        interactions
+            .remove(position)
+            .expect("The position of a recorded interaction is always valid")
+            .response
+            .response()
+    }
+}
+
+// A session attached to the `HTTP` client.
+#[derive(Debug, Clone)]
+pub(crate) enum Session {
+    Record(SessionRecorder),
+    Replay(SessionPlayer),
+}
+
+impl Session {
+    // Sends a request within the session.
+    //
+    // A recorded request is sent through the given function, while a
+    // replayed request is answered without sending it.
+    pub(crate) async fn send(
+        &self,
+        request: reqwest::Request,
+        send: impl AsyncFnOnce(reqwest::Request) -> Result<reqwest::Response>,
+    ) -> Result<reqwest::Response> {
+        let recorded_request = RecordedRequest::new(&request);
+        match self {
+            Self::Record(recorder) => {
+                recorder
+                    .record(recorded_request, send(request).await?)
+                    .await
+            }
+            Self::Replay(player) => player.replay(&recorded_request),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use tosca::device::DeviceKind;
+    use tosca::response::OkResponse;
+    use tosca::route::Route;
+
+    use crate::controller::Controller;
+    use crate::device::Devices;
+    use crate::discovery::tests::configure_discovery;
+    use crate::error::ErrorKind;
+    use crate::mock::MockDevice;
+    use crate::response::{Response, SerialResponse};
+    use crate::tests::Brightness;
+
+    use super::{Interaction, RecordedRequest, RecordedResponse, SessionPlayer, SessionRecorder};
+
+    fn mock_light() -> MockDevice {
+        MockDevice::new(DeviceKind::Light)
+            .ok_route(Route::put("On", "/on").description("Turn light on."))
+            .serial_route(
+                Route::get("Brightness", "/brightness").description("Retrieve brightness."),
+                Brightness { brightness: 5 },
+            )
+    }
+
+    // Returns a temporary path unique to each test run.
+    fn session_path() -> std::path::PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        std::env::temp_dir().join(format!(
+            "tosca-controller-session-{}-{nanos}.json",
+            std::process::id()
+        ))
+    }
+
+    // Sends the requests of a session, returning their parsed responses.
+    async fn run_session(controller: &Controller) -> (OkResponse, SerialResponse<Brightness>) {
+        let device_sender = controller.device(0).unwrap();
+
+        let Response::OkBody(parser) = device_sender.request("/on").unwrap().send().await.unwrap()
+        else {
+            panic!("An ok response was expected");
+        };
+        let ok = parser.parse_body().await.unwrap();
+
+        let Response::SerialBody(parser) = device_sender
+            .request("/brightness")
+            .unwrap()
+            .send()
+            .await
+            .unwrap()
+        else {
+            panic!("A serial response was expected");
+        };
+        let brightness = parser.parse_body::<Brightness>().await.unwrap();
+
+        (ok, brightness)
+    }
+
+    #[tokio::test]
+    async fn record_and_replay_session() {
+        let mock = mock_light();
+        let path = session_path();
+
+        // Record the session.
+        let recorder = SessionRecorder::new();
+        let controller = Controller::from_devices(
+            configure_discovery(),
+            Devices::from_devices(vec![mock.device()]),
+        )
+        .record_session(recorder.clone());
+
+        let recorded_responses = run_session(&controller).await;
+
+        let interactions = recorder.interactions();
+        assert_eq!(interactions.len(), 2);
+        assert_eq!(interactions[0].request.method, "PUT");
+        assert!(interactions[0].request.url.ends_with("/on"));
+        assert_eq!(interactions[1].request.method, "GET");
+        assert!(interactions[1].request.url.ends_with("/brightness"));
+
+        recorder.save(&path).unwrap();
+
+        // Replay the session.
+        let player = SessionPlayer::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let controller = Controller::from_devices(
+            configure_discovery(),
+            Devices::from_devices(vec![mock.device()]),
+        )
+        .replay_session(player.clone());
+
+        assert_eq!(run_session(&controller).await, recorded_responses);
+        assert_eq!(player.remaining(), 0);
+
+        // Replayed requests never reach the device.
+        assert_eq!(mock.received(), vec!["/on", "/brightness"]);
+
+        // All recorded responses have been replayed.
+        let Err(error) = controller
+            .device(0)
+            .unwrap()
+            .request("/on")
+            .unwrap()
+            .send()
+            .await
+        else {
+            panic!("An unrecorded request must fail");
+        };
+        assert_eq!(error.kind(), ErrorKind::Session);
+    }
+
+    #[tokio::test]
+    async fn binary_bodies() {
+        // Bytes which are not valid UTF-8.
+        let body = vec![0xff, 0x00, 0xfe, 0x80];
+
+        let interaction = Interaction {
+            request: RecordedRequest {
+                method: "POST".into(),
+                url: "http://127.0.0.1/upload".into(),
+                body: Some(body.clone()),
+            },
+            response: RecordedResponse {
+                status: 200,
+                headers: BTreeMap::from([(
+                    "content-type".into(),
+                    "application/octet-stream".into(),
+                )]),
+                body: body.clone(),
+            },
+        };
+
+        // Bodies are saved in base64.
+        let session = serde_json::to_value(&interaction).unwrap();
+        assert_eq!(session["request"]["body"], "/wD+gA==");
+        assert_eq!(session["response"]["body"], "/wD+gA==");
+
+        let interaction = serde_json::from_value::<Interaction>(session).unwrap();
+        assert_eq!(interaction.request.body.as_deref(), Some(body.as_slice()));
+
+        // A replayed response carries the same bytes.
+        let replayed = interaction.response.response().unwrap();
+        assert_eq!(replayed.bytes().await.unwrap().as_ref(), body.as_slice());
+
+        // A request without a body is saved without it.
+        let request = RecordedRequest {
+            method: "GET".into(),
+            url: "http://127.0.0.1/".into(),
+            body: None,
+        };
+        let saved = serde_json::to_value(&request).unwrap();
+        assert!(saved.get("body").is_none());
+        assert_eq!(
+            serde_json::from_value::<RecordedRequest>(saved).unwrap(),
+            request
+        );
+    }
+}
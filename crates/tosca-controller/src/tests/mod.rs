@@ -5,7 +5,7 @@ use tosca::device::{DeviceEnvironment, DeviceKind};
 use tosca::hazards::{Hazard, Hazards};
 use tosca::parameters::{ParameterKind, Parameters, ParametersData};
 use tosca::response::ResponseKind;
-use tosca::route::{LightOffRoute, LightOnRoute, RestKind, Route};
+use tosca::route::{LightOffRoute, LightOnRoute, ParameterStyle, RestKind, Route};
 
 use tosca_os::devices::light::Light;
 use tosca_os::extract::Path;
@@ -17,6 +17,9 @@ use tosca_os::service::ServiceConfig;
 
 use serde::{Deserialize, Serialize};
 
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
 use tracing::info;
 
 use crate::device::Device;
@@ -30,6 +33,8 @@ const SECOND_DEVICE_ROUTE: &str = "/without-toggle";
 
 pub(crate) const DOMAIN: &str = "tosca";
 
+pub(crate) const SUBTYPE: &str = "_light";
+
 async fn turn_light_on() -> Result<OkResponse, ErrorResponse> {
     println!("Light on");
     Ok(OkResponse::ok())
@@ -98,11 +103,16 @@ async fn light(
     );
 
     // Run a discovery service and the device on the server.
-    Server::new(light.build())
+    Server::new(light.build().expect("Error in building the light device."))
         .address(Ipv4Addr::UNSPECIFIED)
         .port(port)
         .well_known_service(id)
-        .discovery_service(ServiceConfig::mdns_sd(id).hostname("tosca").domain(DOMAIN))
+        .discovery_service(
+            ServiceConfig::mdns_sd(id)
+                .hostname("tosca")
+                .domain(DOMAIN)
+                .subtype(SUBTYPE),
+        )
         .with_graceful_shutdown(async move {
             _ = close_rx.await;
         })
@@ -148,7 +158,10 @@ fn check_request(
             description: Some(description.to_string()),
             parameters_data,
             response_kind,
+            parameter_style: ParameterStyle::Path,
             device_environment: DeviceEnvironment::Os,
+            deprecated: None,
+            mock: None,
         })
     );
 }
@@ -160,16 +173,24 @@ pub(crate) fn compare_device_data(device: &Device) {
     assert!(device.network_info().port == PORT_ONE || device.network_info().port == PORT_TWO);
 
     // Check scheme.
-    let scheme = device.network_info().properties.get("scheme");
-    assert!(scheme.is_some_and(|scheme| scheme == "http"));
+    let device_properties = &device.network_info().device_properties;
+    assert_eq!(device_properties.scheme(), "http");
 
     // Check path.
-    let path = device.network_info().properties.get("path");
+    let path = device_properties.path();
     assert!(
         path.is_some_and(|path| path == "/.well-known/light-with-toggle"
             || path == "/.well-known/light-without-toggle")
     );
 
+    // Check advertised device kind and routes number.
+    assert_eq!(device_properties.kind(), Some(&DeviceKind::Light));
+    assert!(
+        device_properties
+            .routes()
+            .is_some_and(|routes| routes == 2 || routes == 3)
+    );
+
     // Check device main route.
     assert!(
         device.description().main_route == FIRST_DEVICE_ROUTE
@@ -294,3 +315,76 @@ where
     // Wait for second device server to gracefully shutdown.
     _ = device2_handle.await;
 }
+
+// Runs a local server on a random port, handling each accepted connection in
+// its own task, and returns the port.
+pub(crate) async fn local_server<F, Fut>(handler: F) -> u16
+where
+    F: Fn(TcpStream) -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(handler(stream));
+        }
+    });
+
+    port
+}
+
+// Reads the next `HTTP` request sent over a connection, returning its
+// lowercased head, or `None` when the connection is closed before the
+// request is complete.
+//
+// The bytes following the request are kept in `buffer`, so the next request
+// sent over a keep-alive connection can be read.
+pub(crate) async fn read_request(stream: &mut TcpStream, buffer: &mut Vec<u8>) -> Option<String> {
+    let mut chunk = [0; 1024];
+    let head_end = loop {
+        if let Some(position) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            break position + 4;
+        }
+        match stream.read(&mut chunk).await {
+            Ok(0) | Err(_) => return None,
+            Ok(n) => buffer.extend_from_slice(&chunk[..n]),
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..head_end]).to_lowercase();
+    let content_length = head
+        .lines()
+        .find_map(|line| line.strip_prefix("content-length:"))
+        .and_then(|length| length.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+
+    let request_end = head_end + content_length;
+    while buffer.len() < request_end {
+        match stream.read(&mut chunk).await {
+            Ok(0) | Err(_) => return None,
+            Ok(n) => buffer.extend_from_slice(&chunk[..n]),
+        }
+    }
+    buffer.drain(..request_end);
+
+    Some(head)
+}
+
+// Writes a `200 OK` response with a `JSON` body, returning whether it has
+// been sent.
+//
+// The connection is closed after the response, unless `keep_alive` is set.
+pub(crate) async fn write_json(stream: &mut TcpStream, body: &str, keep_alive: bool) -> bool {
+    let connection = if keep_alive {
+        ""
+    } else {
+        "connection: close\r\n"
+    };
+    let response = format!(
+        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n{connection}\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await.is_ok()
+}
@@ -0,0 +1,93 @@
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+
+use tosca::device::{DeviceEnvironment, DeviceKind};
+use tosca::route::RouteConfigs;
+
+use serde::{Deserialize, Serialize};
+
+use crate::device::{Description, Device, Devices, NetworkInformation};
+use crate::error::{Error, ErrorKind};
+
+// A snapshot of a device, containing all data needed to rebuild it.
+#[derive(Serialize, Deserialize)]
+struct DeviceSnapshot {
+    // Device identifier.
+    id: usize,
+    // Device kind.
+    kind: DeviceKind,
+    // Device environment.
+    environment: DeviceEnvironment,
+    // Device last reachable address.
+    address: String,
+    // Device location, if advertised.
+    location: Option<String>,
+    // Device complete name.
+    name: String,
+    // Device addresses.
+    addresses: HashSet<IpAddr>,
+    // Device port.
+    port: u16,
+    // Device properties.
+    properties: HashMap<String, String>,
+    // Device main route.
+    main_route: String,
+    // Device routes.
+    routes: RouteConfigs,
+}
+
+// Exports the given devices as a JSON array of device snapshots.
+pub(crate) fn export(devices: &Devices) -> serde_json::Value {
+    let snapshots = devices
+        .iter()
+        .enumerate()
+        .map(|(id, device)| {
+            let network_info = device.network_info();
+            let description = device.description();
+            DeviceSnapshot {
+                id,
+                kind: description.kind.clone(),
+                environment: description.environment,
+                address: network_info.last_reachable_address.clone(),
+                location: network_info.device_properties.location().map(str::to_owned),
+                name: network_info.name.clone(),
+                addresses: network_info.addresses.clone(),
+                port: network_info.port,
+                properties: network_info.properties.clone(),
+                main_route: description.main_route.clone(),
+                routes: device.route_configs().clone(),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    serde_json::to_value(snapshots).unwrap_or_default()
+}
+
+// Rebuilds the devices contained in a JSON array of device snapshots.
+//
+// Devices are ordered by their identifiers.
+pub(crate) fn import(topology: serde_json::Value) -> Result<Devices, Error> {
+    let mut snapshots = serde_json::from_value::<Vec<DeviceSnapshot>>(topology)
+        .map_err(|e| Error::new(ErrorKind::Topology, format!("Invalid topology: {e}")))?;
+
+    snapshots.sort_by_key(|snapshot| snapshot.id);
+
+    Ok(Devices::from_devices(
+        snapshots
+            .into_iter()
+            .map(|snapshot| {
+                Device::new(
+                    NetworkInformation::new(
+                        snapshot.name,
+                        snapshot.addresses,
+                        snapshot.port,
+                        snapshot.properties,
+                        snapshot.address,
+                    ),
+                    Description::new(snapshot.kind, snapshot.environment, snapshot.main_route),
+                    snapshot.routes,
+                )
+            })
+            .collect(),
+    ))
+}
@@ -0,0 +1,64 @@
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use crate::error::Error;
+
+/// A link delivering the requests of a
+/// [`Controller`](crate::controller::Controller) to devices.
+///
+/// By default, a controller sends its requests over `HTTP`. A custom
+/// transport, set through
+/// [`Controller::transport`](crate::controller::Controller::transport),
+/// delivers them through any other link, such as a relay or a Bluetooth
+/// bridge, or answers them in-memory.
+///
+/// Privacy policies, credentials, and concurrency limits are applied before
+/// a request reaches the transport.
+pub trait Transport: Send + Sync + 'static {
+    /// Sends a request to a device, returning the device response.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned when the request cannot be delivered to the
+    /// device, or when its response cannot be received.
+    fn send(
+        &self,
+        request: reqwest::Request,
+    ) -> impl Future<Output = Result<reqwest::Response, Error>> + Send;
+}
+
+// Future returned by a type-erased transport.
+type SendFuture<'a> = Pin<Box<dyn Future<Output = Result<reqwest::Response, Error>> + Send + 'a>>;
+
+// A transport which can be used as a trait object.
+trait ErasedTransport: Send + Sync {
+    fn send(&self, request: reqwest::Request) -> SendFuture<'_>;
+}
+
+impl<T: Transport> ErasedTransport for T {
+    fn send(&self, request: reqwest::Request) -> SendFuture<'_> {
+        Box::pin(Transport::send(self, request))
+    }
+}
+
+// A transport shared by all clones of the `HTTP` client.
+#[derive(Clone)]
+pub(crate) struct SharedTransport(Arc<dyn ErasedTransport>);
+
+impl fmt::Debug for SharedTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SharedTransport").finish_non_exhaustive()
+    }
+}
+
+impl SharedTransport {
+    pub(crate) fn new(transport: impl Transport) -> Self {
+        Self(Arc::new(transport))
+    }
+
+    pub(crate) async fn send(&self, request: reqwest::Request) -> Result<reqwest::Response, Error> {
+        self.0.send(request).await
+    }
+}
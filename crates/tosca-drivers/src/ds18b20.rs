@@ -5,10 +5,16 @@
 //!
 //! The DS18B20 communicates over the 1-Wire bus and provides temperature readings with resolutions up to 12 bits.
 //! It performs temperature conversions internally and exposes the result via its scratchpad memory, which includes a CRC
-//! to ensure data integrity. The driver operates in *single-sensor mode*, using the `Skip ROM` command to address the
+//! to ensure data integrity.
+//!
+//! By default, the driver operates in *single-sensor mode*, using the `Skip ROM` command to address the
 //! device directly without specifying its unique 64-bit ROM code — an approach suitable when only one DS18B20 is connected
 //! to the bus.
 //!
+//! When more sensors share the same 1-Wire bus, [`Ds18b20::search`] enumerates the ROM codes of all connected devices
+//! through the 1-Wire search algorithm, while [`Ds18b20::with_address`] and [`Ds18b20::set_address`] select the sensor
+//! addressed by the `Match ROM` command.
+//!
 //! For detailed information and specifications, see the [datasheet](https://www.alldatasheet.com/datasheet-pdf/pdf/58557/DALLAS/DS18B20.html).
 
 use core::result::Result;
@@ -36,6 +42,8 @@ const READ_RECOVERY_US: u32 = 55;
 const CONVERSION_WAIT_MS: u32 = 750;
 
 // DS18B20 ROM and function commands.
+const CMD_SEARCH_ROM: u8 = 0xF0;
+const CMD_MATCH_ROM: u8 = 0x55;
 const CMD_SKIP_ROM: u8 = 0xCC;
 const CMD_CONVERT_T: u8 = 0x44;
 const CMD_READ_SCRATCHPAD: u8 = 0xBE;
@@ -69,6 +77,7 @@ where
 {
     pin: P,
     delay: D,
+    rom: Option<[u8; 8]>,
 }
 
 impl<P, D> Ds18b20<P, D>
@@ -77,9 +86,52 @@ where
     D: DelayNs,
 {
     /// Creates a new [`Ds18b20`] driver with the given pin and delay provider.
+    ///
+    /// The driver operates in single-sensor mode.
     #[must_use]
     pub fn new(pin: P, delay: D) -> Self {
-        Self { pin, delay }
+        Self {
+            pin,
+            delay,
+            rom: None,
+        }
+    }
+
+    /// Creates a new [`Ds18b20`] driver addressing the sensor identified by
+    /// the given 64-bit ROM code.
+    #[must_use]
+    pub fn with_address(pin: P, delay: D, rom: [u8; 8]) -> Self {
+        Self {
+            pin,
+            delay,
+            rom: Some(rom),
+        }
+    }
+
+    /// Selects the sensor identified by the given 64-bit ROM code.
+    ///
+    /// Passing `None` switches the driver back to single-sensor mode.
+    pub fn set_address(&mut self, rom: Option<[u8; 8]>) {
+        self.rom = rom;
+    }
+
+    /// Returns the ROM code of the selected sensor, if any.
+    #[must_use]
+    pub fn address(&self) -> Option<[u8; 8]> {
+        self.rom
+    }
+
+    /// Returns an iterator over the ROM codes of all devices connected to
+    /// the 1-Wire bus, discovered through the 1-Wire search algorithm.
+    ///
+    /// The iteration stops after the first error.
+    pub fn search(&mut self) -> RomSearch<'_, P, D> {
+        RomSearch {
+            driver: self,
+            rom: [0; 8],
+            last_discrepancy: 0,
+            done: false,
+        }
     }
 
     /// Performs a bus reset and checks for the presence pulse from the sensor.
@@ -119,7 +171,7 @@ where
         }
 
         // 2. Start temperature conversion.
-        self.write_byte(CMD_SKIP_ROM)?;
+        self.select()?;
         self.write_byte(CMD_CONVERT_T)?;
 
         // 3. Wait for conversion completion (poll line or timeout).
@@ -135,7 +187,7 @@ where
             return Err(Ds18b20Error::NoPresence);
         }
 
-        self.write_byte(CMD_SKIP_ROM)?;
+        self.select()?;
         self.write_byte(CMD_READ_SCRATCHPAD)?;
 
         let data = self.read_scratchpad()?;
@@ -153,6 +205,21 @@ where
         Ok(temp)
     }
 
+    fn select(&mut self) -> Result<(), Ds18b20Error<P::Error>> {
+        // Address the selected sensor, or every sensor when none is selected.
+        match self.rom {
+            Some(rom) => {
+                self.write_byte(CMD_MATCH_ROM)?;
+                for byte in rom {
+                    self.write_byte(byte)?;
+                }
+            }
+            None => self.write_byte(CMD_SKIP_ROM)?,
+        }
+
+        Ok(())
+    }
+
     fn write_bit(&mut self, bit: bool) -> Result<(), Ds18b20Error<P::Error>> {
         // Write a single bit to the 1-Wire bus.
         if bit {
@@ -238,6 +305,110 @@ where
     }
 }
 
+/// An iterator over the ROM codes of the devices connected to a 1-Wire bus.
+///
+/// Created by [`Ds18b20::search`].
+pub struct RomSearch<'a, P, D>
+where
+    P: InputPin + OutputPin,
+    D: DelayNs,
+{
+    driver: &'a mut Ds18b20<P, D>,
+    rom: [u8; 8],
+    last_discrepancy: u8,
+    done: bool,
+}
+
+impl<P, D> RomSearch<'_, P, D>
+where
+    P: InputPin + OutputPin,
+    D: DelayNs,
+{
+    fn next_rom(&mut self) -> Result<Option<[u8; 8]>, Ds18b20Error<P::Error>> {
+        if !self.driver.reset()? {
+            return Err(Ds18b20Error::NoPresence);
+        }
+
+        self.driver.write_byte(CMD_SEARCH_ROM)?;
+
+        let mut last_zero = 0;
+        for bit_number in 1..=64u8 {
+            let byte = usize::from((bit_number - 1) / 8);
+            let mask = 1 << ((bit_number - 1) % 8);
+
+            // Each device sends its bit followed by its complement.
+            let id_bit = self.driver.read_bit()?;
+            let cmp_id_bit = self.driver.read_bit()?;
+
+            let direction = match (id_bit, cmp_id_bit) {
+                // No device is participating in the search anymore.
+                (true, true) => return Ok(None),
+                // All participating devices share the same bit.
+                (true, false) => true,
+                (false, true) => false,
+                // Discrepancy: devices with both bit values are present.
+                (false, false) => {
+                    let direction = if bit_number < self.last_discrepancy {
+                        self.rom[byte] & mask != 0
+                    } else {
+                        bit_number == self.last_discrepancy
+                    };
+                    if !direction {
+                        last_zero = bit_number;
+                    }
+                    direction
+                }
+            };
+
+            if direction {
+                self.rom[byte] |= mask;
+            } else {
+                self.rom[byte] &= !mask;
+            }
+
+            // Devices whose bit differs from the direction leave the search.
+            self.driver.write_bit(direction)?;
+        }
+
+        self.last_discrepancy = last_zero;
+        if last_zero == 0 {
+            self.done = true;
+        }
+
+        if Ds18b20::<P, D>::crc8(&self.rom[0..7]) != self.rom[7] {
+            return Err(Ds18b20Error::CrcMismatch);
+        }
+
+        Ok(Some(self.rom))
+    }
+}
+
+impl<P, D> Iterator for RomSearch<'_, P, D>
+where
+    P: InputPin + OutputPin,
+    D: DelayNs,
+{
+    type Item = Result<[u8; 8], Ds18b20Error<P::Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        match self.next_rom() {
+            Ok(Some(rom)) => Some(Ok(rom)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -245,6 +416,9 @@ mod tests {
     use embedded_hal_mock::eh1::delay::NoopDelay;
     use embedded_hal_mock::eh1::digital::{Mock as PinMock, State, Transaction as PinTransaction};
 
+    extern crate std;
+    use std::vec::Vec;
+
     fn raw_to_temp(data: [u8; 9]) -> f32 {
         let raw = (i16::from(data[1]) << 8) | i16::from(data[0]);
         f32::from(raw) * TEMPERATURE_RESOLUTION_C_PER_LSB
@@ -346,4 +520,116 @@ mod tests {
         let temp = raw_to_temp(data);
         assert!((temp + 7.0).abs() < f32::EPSILON);
     }
+
+    fn rom_with_crc(rom: [u8; 7]) -> [u8; 8] {
+        let mut full = [0; 8];
+        full[0..7].copy_from_slice(&rom);
+        full[7] = Ds18b20::<PinMock, NoopDelay>::crc8(&rom);
+        full
+    }
+
+    fn rom_bit(rom: [u8; 8], bit: usize) -> bool {
+        rom[bit / 8] & (1 << (bit % 8)) != 0
+    }
+
+    fn write_bit_transactions(expectations: &mut Vec<PinTransaction>) {
+        expectations.push(PinTransaction::set(State::Low));
+        expectations.push(PinTransaction::set(State::High));
+    }
+
+    fn read_bit_transactions(expectations: &mut Vec<PinTransaction>, bit: bool) {
+        expectations.push(PinTransaction::set(State::Low));
+        expectations.push(PinTransaction::set(State::High));
+        expectations.push(PinTransaction::get(if bit {
+            State::High
+        } else {
+            State::Low
+        }));
+    }
+
+    // Simulates a search pass on a wired-AND bus, where the master
+    // selects the `target` ROM code among the connected `roms`.
+    fn search_pass_transactions(
+        expectations: &mut Vec<PinTransaction>,
+        roms: &[[u8; 8]],
+        target: [u8; 8],
+    ) {
+        // Reset with presence pulse.
+        expectations.push(PinTransaction::set(State::Low));
+        expectations.push(PinTransaction::set(State::High));
+        expectations.push(PinTransaction::get(State::Low));
+
+        // Search ROM command.
+        for _ in 0..8 {
+            write_bit_transactions(expectations);
+        }
+
+        let mut participating: Vec<[u8; 8]> = roms.to_vec();
+        for bit in 0..64 {
+            let id_bit = participating.iter().all(|rom| rom_bit(*rom, bit));
+            let cmp_id_bit = participating.iter().all(|rom| !rom_bit(*rom, bit));
+
+            read_bit_transactions(expectations, id_bit);
+            read_bit_transactions(expectations, cmp_id_bit);
+            write_bit_transactions(expectations);
+
+            participating.retain(|rom| rom_bit(*rom, bit) == rom_bit(target, bit));
+        }
+    }
+
+    #[test]
+    fn test_search_two_devices() {
+        // The two ROM codes first differ on the least significant bit of the
+        // second byte, hence the search algorithm finds `first` before `second`.
+        let first = rom_with_crc([0x28, 0x10, 0xA2, 0x3B, 0x05, 0x00, 0x00]);
+        let second = rom_with_crc([0x28, 0x11, 0x7C, 0x42, 0x06, 0x00, 0x00]);
+        let roms = [first, second];
+
+        let mut expectations = Vec::new();
+        search_pass_transactions(&mut expectations, &roms, first);
+        search_pass_transactions(&mut expectations, &roms, second);
+
+        let pin = PinMock::new(&expectations);
+        let delay = NoopDelay::new();
+        let mut ds18b20 = Ds18b20::new(pin, delay);
+
+        let found = ds18b20.search().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(found, [first, second]);
+
+        ds18b20.pin.done();
+    }
+
+    #[test]
+    fn test_search_no_presence() {
+        let expectations = [
+            PinTransaction::set(State::Low),
+            PinTransaction::set(State::High),
+            PinTransaction::get(State::High),
+        ];
+
+        let pin = PinMock::new(&expectations);
+        let delay = NoopDelay::new();
+        let mut ds18b20 = Ds18b20::new(pin, delay);
+
+        let mut search = ds18b20.search();
+        assert!(matches!(search.next(), Some(Err(Ds18b20Error::NoPresence))));
+        assert!(search.next().is_none());
+
+        ds18b20.pin.done();
+    }
+
+    #[test]
+    fn test_address_selection() {
+        let rom = rom_with_crc([0x28, 0x10, 0xA2, 0x3B, 0x05, 0x00, 0x00]);
+
+        let pin = PinMock::new(&[]);
+        let delay = NoopDelay::new();
+        let mut ds18b20 = Ds18b20::with_address(pin, delay, rom);
+        assert_eq!(ds18b20.address(), Some(rom));
+
+        ds18b20.set_address(None);
+        assert_eq!(ds18b20.address(), None);
+
+        ds18b20.pin.done();
+    }
 }
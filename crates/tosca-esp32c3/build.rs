@@ -0,0 +1,7 @@
+fn main() {
+    // Unit tests are flashed on the board, so they are linked as a firmware.
+    // Make sure `linkall.x` is the last linker script.
+    println!("cargo:rustc-link-arg=-Tembedded-test.x");
+    println!("cargo:rustc-link-arg=-Tlinkall.x");
+    println!("cargo::rustc-check-cfg=cfg(rust_analyzer)");
+}
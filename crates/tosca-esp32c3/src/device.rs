@@ -1,11 +1,9 @@
-use alloc::vec::Vec;
-
 use tosca::device::DeviceData;
 use tosca::events::EventsDescription;
 use tosca::route::RouteConfigs;
 
 use crate::response::Response;
-use crate::server::{FuncIndex, Functions};
+use crate::server::RouteRegistry;
 use crate::state::{State, ValueFromRef};
 
 /// A general device.
@@ -17,8 +15,7 @@ where
     pub(crate) state: State<S>,
     pub(crate) description: DeviceData,
     pub(crate) main_route: &'static str,
-    pub(crate) routes_functions: Functions<S>,
-    pub(crate) index_array: Vec<FuncIndex>,
+    pub(crate) route_registry: RouteRegistry<S>,
 }
 
 impl<S> Device<S>
@@ -31,19 +28,24 @@ where
         state: State<S>,
         description: DeviceData,
         main_route: &'static str,
-        routes_functions: Functions<S>,
-        index_array: Vec<FuncIndex>,
+        route_registry: RouteRegistry<S>,
     ) -> Self {
         Self {
             wifi_mac,
             state,
             description,
             main_route,
-            routes_functions,
-            index_array,
+            route_registry,
         }
     }
 
+    /// Returns the number of actions added to [`Device`].
+    #[must_use]
+    #[inline]
+    pub fn actions_len(&self) -> usize {
+        self.description.route_configs.len()
+    }
+
     #[inline]
     pub(crate) fn events_description(mut self, events_description: EventsDescription) -> Self {
         self.description = self.description.events_description(events_description);
@@ -57,8 +59,7 @@ where
             state: self.state,
             main_route: self.main_route,
             main_route_response: Response::json(&self.description),
-            routes_functions: self.routes_functions,
-            index_array: self.index_array,
+            route_registry: self.route_registry,
             route_configs: self.description.route_configs,
         }
     }
@@ -71,7 +72,6 @@ where
     pub(crate) state: State<S>,
     pub(crate) main_route: &'static str,
     pub(crate) main_route_response: Response,
-    pub(crate) routes_functions: Functions<S>,
-    pub(crate) index_array: Vec<FuncIndex>,
+    pub(crate) route_registry: RouteRegistry<S>,
     pub(crate) route_configs: RouteConfigs,
 }
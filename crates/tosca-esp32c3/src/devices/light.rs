@@ -1,6 +1,5 @@
 use alloc::borrow::Cow;
 use alloc::boxed::Box;
-use alloc::vec::Vec;
 
 use tosca::device::{DeviceData, DeviceEnvironment, DeviceKind};
 use tosca::hazards::Hazard;
@@ -12,10 +11,12 @@ use esp_radio::wifi::WifiDevice;
 use log::error;
 
 use crate::device::Device;
-use crate::parameters::ParametersPayloads;
+use crate::ota::Ota;
+use crate::parameters::{ByteStream, ParametersPayloads};
 use crate::response::{ErrorResponse, InfoResponse, OkResponse, SerialResponse};
 use crate::server::{
-    FuncIndex, FuncType, Functions, InfoFn, InfoStateFn, OkFn, OkStateFn, SerialFn, SerialStateFn,
+    InfoFn, InfoStateFn, OkFn, OkStateFn, OkStreamFn, OkStreamStateFn, RouteHandler, RouteRegistry,
+    SerialFn, SerialStateFn,
 };
 use crate::state::{State, ValueFromRef};
 
@@ -204,9 +205,8 @@ where
     wifi_mac: [u8; 6],
     main_route: &'static str,
     state: State<S>,
-    routes_functions: Functions<S>,
+    route_registry: RouteRegistry<S>,
     device_data: DeviceData,
-    index_array: Vec<FuncIndex>,
 }
 
 impl<S> CompleteLight<S>
@@ -232,11 +232,7 @@ where
     {
         self.route_func_manager(route, ResponseKind::Ok, move |mut func_manager| {
             let func: OkFn = Box::new(move |parameters_values| Box::pin(func(parameters_values)));
-            func_manager.routes_functions.0.push(func);
-            func_manager.index_array.push(FuncIndex::new(
-                FuncType::OkStateless,
-                func_manager.routes_functions.0.len() - 1,
-            ));
+            func_manager.route_registry.register(RouteHandler::Ok(func));
             func_manager
         })
     }
@@ -252,11 +248,9 @@ where
         self.route_func_manager(route, ResponseKind::Ok, move |mut func_manager| {
             let func: OkStateFn<S> =
                 Box::new(move |state, parameters_values| Box::pin(func(state, parameters_values)));
-            func_manager.routes_functions.1.push(func);
-            func_manager.index_array.push(FuncIndex::new(
-                FuncType::OkStateful,
-                func_manager.routes_functions.1.len() - 1,
-            ));
+            func_manager
+                .route_registry
+                .register(RouteHandler::OkStateful(func));
             func_manager
         })
     }
@@ -272,11 +266,9 @@ where
         self.route_func_manager(route, ResponseKind::Serial, move |mut func_manager| {
             let func: SerialFn =
                 Box::new(move |parameters_values| Box::pin(func(parameters_values)));
-            func_manager.routes_functions.2.push(func);
-            func_manager.index_array.push(FuncIndex::new(
-                FuncType::SerialStateless,
-                func_manager.routes_functions.2.len() - 1,
-            ));
+            func_manager
+                .route_registry
+                .register(RouteHandler::Serial(func));
             func_manager
         })
     }
@@ -292,11 +284,9 @@ where
         self.route_func_manager(route, ResponseKind::Serial, move |mut func_manager| {
             let func: SerialStateFn<S> =
                 Box::new(move |state, parameters_values| Box::pin(func(state, parameters_values)));
-            func_manager.routes_functions.3.push(func);
-            func_manager.index_array.push(FuncIndex::new(
-                FuncType::SerialStateful,
-                func_manager.routes_functions.3.len() - 1,
-            ));
+            func_manager
+                .route_registry
+                .register(RouteHandler::SerialStateful(func));
             func_manager
         })
     }
@@ -311,11 +301,9 @@ where
     {
         self.route_func_manager(route, ResponseKind::Info, move |mut func_manager| {
             let func: InfoFn = Box::new(move |parameters_values| Box::pin(func(parameters_values)));
-            func_manager.routes_functions.4.push(func);
-            func_manager.index_array.push(FuncIndex::new(
-                FuncType::InfoStateless,
-                func_manager.routes_functions.4.len() - 1,
-            ));
+            func_manager
+                .route_registry
+                .register(RouteHandler::Info(func));
             func_manager
         })
     }
@@ -331,15 +319,102 @@ where
         self.route_func_manager(route, ResponseKind::Info, move |mut func_manager| {
             let func: InfoStateFn<S> =
                 Box::new(move |state, parameters_values| Box::pin(func(state, parameters_values)));
-            func_manager.routes_functions.5.push(func);
-            func_manager.index_array.push(FuncIndex::new(
-                FuncType::InfoStateful,
-                func_manager.routes_functions.5.len() - 1,
-            ));
+            func_manager
+                .route_registry
+                .register(RouteHandler::InfoStateful(func));
+            func_manager
+        })
+    }
+
+    /// Adds a [`Route`] with a stateless handler that reads the request body
+    /// as a [`ByteStream`] and returns an [`OkResponse`] on success, and an
+    /// [`ErrorResponse`] on failure.
+    ///
+    /// The route must declare a
+    /// [`ParameterKind::ByteStream`](tosca::parameters::ParameterKind::ByteStream)
+    /// parameter, otherwise the handler receives an empty stream.
+    #[must_use]
+    pub fn stateless_stream_route<F>(self, route: Route, func: F) -> Self
+    where
+        F: AsyncFn(ParametersPayloads, ByteStream<'_>) -> Result<OkResponse, ErrorResponse>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.route_func_manager(route, ResponseKind::Ok, move |mut func_manager| {
+            // The handler lives as long as the firmware, so the futures it
+            // returns can borrow it.
+            let func: &'static F = Box::leak(Box::new(func));
+            let func: OkStreamFn = Box::new(move |parameters_values, byte_stream| {
+                Box::pin(func(parameters_values, byte_stream))
+            });
+            func_manager
+                .route_registry
+                .register(RouteHandler::OkStream(func));
+            func_manager
+        })
+    }
+
+    /// Adds a [`Route`] with a stateful handler that reads the request body
+    /// as a [`ByteStream`] and returns an [`OkResponse`] on success, and an
+    /// [`ErrorResponse`] on failure.
+    ///
+    /// The route must declare a
+    /// [`ParameterKind::ByteStream`](tosca::parameters::ParameterKind::ByteStream)
+    /// parameter, otherwise the handler receives an empty stream.
+    #[must_use]
+    pub fn stateful_stream_route<F>(self, route: Route, func: F) -> Self
+    where
+        F: AsyncFn(
+                State<S>,
+                ParametersPayloads,
+                ByteStream<'_>,
+            ) -> Result<OkResponse, ErrorResponse>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.route_func_manager(route, ResponseKind::Ok, move |mut func_manager| {
+            // The handler lives as long as the firmware, so the futures it
+            // returns can borrow it.
+            let func: &'static F = Box::leak(Box::new(func));
+            let func: OkStreamStateFn<S> =
+                Box::new(move |state, parameters_values, byte_stream| {
+                    Box::pin(func(state, parameters_values, byte_stream))
+                });
+            func_manager
+                .route_registry
+                .register(RouteHandler::OkStreamStateful(func));
             func_manager
         })
     }
 
+    /// Adds the over-the-air firmware update route served by [`Ota`].
+    ///
+    /// Unlike the other routes, it keeps its
+    /// [`Hazard::FirmwareUpdate`] hazard, since a light does not allow it
+    /// for its own routes.
+    #[must_use]
+    pub fn ota_route(mut self, ota: Ota) -> Self {
+        let (route, handler, bearer_token) = ota.into_route();
+        let route_config = route
+            .serialize_data()
+            .change_response_kind(ResponseKind::Ok);
+
+        if self.device_data.route_configs.contains(&route_config) {
+            error!(
+                "The route with prefix `{}` already exists!",
+                route_config.data.path
+            );
+            return self;
+        }
+
+        self.device_data.route_configs.add(route_config);
+        self.route_registry.register_guarded(handler, bearer_token);
+
+        self
+    }
+
     /// Builds a [`Device`].
     #[must_use]
     #[inline]
@@ -349,8 +424,7 @@ where
             self.state,
             self.device_data,
             self.main_route,
-            self.routes_functions,
-            self.index_array,
+            self.route_registry,
         )
     }
 
@@ -400,16 +474,8 @@ where
             wifi_mac,
             main_route: MAIN_ROUTE,
             state: State(state),
-            routes_functions: (
-                Vec::new(),
-                Vec::new(),
-                Vec::new(),
-                Vec::new(),
-                Vec::new(),
-                Vec::new(),
-            ),
+            route_registry: RouteRegistry::new(),
             device_data,
-            index_array: Vec::new(),
         }
     }
 }
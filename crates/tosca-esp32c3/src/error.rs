@@ -9,6 +9,8 @@ pub enum ErrorKind {
     MDns,
     /// `MQTT` error.
     Mqtt,
+    /// Network configuration error.
+    Network,
     /// Server error.
     Server,
     /// Socket error.
@@ -30,6 +32,7 @@ impl ErrorKind {
             Self::Dns => "DNS",
             Self::MDns => "mDNS",
             Self::Mqtt => "MQTT",
+            Self::Network => "Network",
             Self::Server => "Server",
             Self::Socket => "Socket",
             Self::SpawningTask => "Spawning task",
@@ -33,10 +33,15 @@
 //! based on established privacy policies.
 
 #![no_std]
+#![cfg_attr(test, no_main)]
 #![deny(missing_docs)]
 
 extern crate alloc;
 
+// Link the scheduler required by the radio driver into the test firmware.
+#[cfg(test)]
+use esp_rtos as _;
+
 /// All device types implementable within firmware.
 pub mod devices;
 
@@ -50,6 +55,8 @@ pub mod events;
 pub mod mdns;
 /// All methods associated with the network stack.
 pub mod net;
+/// Over-the-air firmware updates.
+pub mod ota;
 /// All route parameters.
 pub mod parameters;
 /// All supported response types.
@@ -71,3 +78,15 @@ macro_rules! mk_static {
 }
 
 pub(crate) use mk_static;
+
+// Polls a future until it completes, since the tests run without an executor.
+#[cfg(test)]
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = core::pin::pin!(future);
+    let mut context = core::task::Context::from_waker(core::task::Waker::noop());
+    loop {
+        if let core::task::Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+}
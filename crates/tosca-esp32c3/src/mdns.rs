@@ -1,16 +1,19 @@
 use core::cell::OnceCell;
 use core::net::{Ipv4Addr, Ipv6Addr};
+use core::time::Duration;
 
 use esp_hal::rng::Rng;
 
 use embassy_executor::Spawner;
 
 use embassy_sync::blocking_mutex::CriticalSectionMutex;
-use embassy_sync::blocking_mutex::raw::NoopRawMutex;
+use embassy_sync::blocking_mutex::raw::{CriticalSectionRawMutex, NoopRawMutex};
 use embassy_sync::signal::Signal;
 
 use embassy_net::Stack;
 
+use embassy_time::Timer;
+
 use edge_mdns::HostAnswersMdnsHandler;
 use edge_mdns::buf::VecBufAccess;
 use edge_mdns::domain::base::Ttl;
@@ -34,6 +37,8 @@ const SERVICE_TYPE: &str = "_tosca";
 const TRANSPORT_PROTOCOL: &str = "_udp";
 // Time-to-live for answers in seconds
 const TIME_TO_LIVE: u32 = 60;
+// Shortest interval between two announcements in seconds
+const MIN_ANNOUNCE_INTERVAL: u64 = 1;
 
 // mDNS buffer pool size
 const MDNS_BUFFER_POOL_SIZE: usize = 2;
@@ -44,12 +49,21 @@ const PACKET_METADATA_LENGTH: usize = 2;
 
 static RNG: CriticalSectionMutex<OnceCell<Rng>> = CriticalSectionMutex::new(OnceCell::new());
 
+// Signal that makes the mDNS responder announce the service again.
+static ANNOUNCE: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+
+// Announces the mDNS service again, for example after a network recovery.
+pub(crate) fn announce() {
+    ANNOUNCE.signal(());
+}
+
 /// The `mDNS-SD` service.
 pub struct Mdns {
     hostname: &'static str,
     service: &'static str,
     service_type: &'static str,
     time_to_live: u32,
+    announce_interval: Option<Duration>,
     properties: &'static [(&'static str, &'static str)],
     rng: Rng,
 }
@@ -63,6 +77,7 @@ impl Mdns {
             service: SERVICE,
             service_type: SERVICE_TYPE,
             time_to_live: TIME_TO_LIVE,
+            announce_interval: None,
             properties: &[],
             rng,
         }
@@ -96,6 +111,31 @@ impl Mdns {
         self
     }
 
+    /// Sets the time-to-live for the `mDNS-SD` answers.
+    ///
+    /// The default is 60 seconds, and values are rounded down to whole
+    /// seconds, with a minimum of one second.
+    #[must_use]
+    pub const fn ttl(self, ttl: Duration) -> Self {
+        let seconds = ttl.as_secs();
+        self.time_to_live(if seconds > u32::MAX as u64 {
+            u32::MAX
+        } else {
+            seconds as u32
+        })
+    }
+
+    /// Sets the interval between two `mDNS-SD` announcements.
+    ///
+    /// Announcing the service more often keeps it visible to controllers on
+    /// lossy networks. By default, the service is announced again after
+    /// half of its time-to-live.
+    #[must_use]
+    pub const fn announce_interval(mut self, interval: Duration) -> Self {
+        self.announce_interval = Some(interval);
+        self
+    }
+
     /// Sets the `mDNS-SD` properties.
     #[must_use]
     pub const fn properties(mut self, properties: &'static [(&'static str, &'static str)]) -> Self {
@@ -103,6 +143,20 @@ impl Mdns {
         self
     }
 
+    // Returns the interval between two announcements in seconds.
+    const fn announce_secs(&self) -> u64 {
+        let seconds = match self.announce_interval {
+            Some(interval) => interval.as_secs(),
+            None => self.time_to_live as u64 / 2,
+        };
+
+        if seconds < MIN_ANNOUNCE_INTERVAL {
+            MIN_ANNOUNCE_INTERVAL
+        } else {
+            seconds
+        }
+    }
+
     pub(crate) fn run(
         self,
         stack: Stack<'static>,
@@ -143,12 +197,25 @@ impl Mdns {
             txt_kvs: self.properties,
         };
 
+        let announce_secs = self.announce_secs();
+        info!("The mDNS service is announced every {announce_secs}s.");
+
+        spawner.spawn(run_mdns_task(stack, host, service))?;
         spawner
-            .spawn(run_mdns_task(stack, host, service))
+            .spawn(announce_task(announce_secs))
             .map_err(core::convert::Into::into)
     }
 }
 
+// Announces the service again before it expires from controller caches.
+#[embassy_executor::task]
+async fn announce_task(secs: u64) {
+    loop {
+        Timer::after_secs(secs).await;
+        announce();
+    }
+}
+
 #[embassy_executor::task]
 async fn run_mdns_task(stack: Stack<'static>, host: Host<'static>, service: Service<'static>) {
     let (recv_buf, send_buf) = (
@@ -170,11 +237,9 @@ async fn run_mdns_task(stack: Stack<'static>, host: Host<'static>, service: Serv
 
     let (recv, send) = socket.split();
 
-    // A way to notify the mDNS responder that the data in `Host` has changed.
-    // Not needed for this example, as the data is hard-coded.
-    let signal = Signal::new();
-
-    let mdns = io::Mdns::<NoopRawMutex, _, _, _, _>::new(
+    // A way to notify the mDNS responder that the service must be announced
+    // again, as it happens when the network recovers.
+    let mdns = io::Mdns::<CriticalSectionRawMutex, _, _, _, _>::new(
         Some(Ipv4Addr::UNSPECIFIED),
         // No IPv6 network is up and running
         None,
@@ -185,7 +250,7 @@ async fn run_mdns_task(stack: Stack<'static>, host: Host<'static>, service: Serv
         |buf| {
             RNG.lock(|c| c.get().map(|r| r.clone().read(buf)));
         },
-        &signal,
+        &ANNOUNCE,
     );
 
     mdns.run(HostAnswersMdnsHandler::new(ServiceAnswers::new(
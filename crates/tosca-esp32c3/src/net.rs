@@ -7,18 +7,23 @@ use esp_hal::rng::Rng;
 use esp_radio::wifi::WifiDevice;
 
 use embassy_executor::Spawner;
-use embassy_net::{Config, DhcpConfig, Runner, Stack, StackResources};
+use embassy_net::{Config, DhcpConfig, Ipv4Cidr, Runner, Stack, StackResources, StaticConfigV4};
 use embassy_time::Timer;
 
 use log::info;
 
-use crate::error::Result;
+use crate::error::{Error, ErrorKind, Result};
 
 const MILLISECONDS_TO_WAIT: u64 = 100;
 
 // Retrieves the IPV4 address from the network stack.
 #[inline]
 pub(crate) async fn get_ip(stack: Stack<'static>) -> Ipv4Addr {
+    // A static configuration is immediately available.
+    if let Some(config) = stack.config_v4() {
+        return config.address.address();
+    }
+
     info!("Waiting till the link is up...");
     loop {
         if stack.is_link_up() {
@@ -41,11 +46,70 @@ async fn task(mut runner: Runner<'static, WifiDevice<'static>>) {
     runner.run().await;
 }
 
+/// A static IPv4 configuration.
+///
+/// It is used in networks where no `DHCP` server assigns addresses.
+#[derive(Debug, Clone, Copy)]
+pub struct StaticIpConfig {
+    address: Ipv4Addr,
+    gateway: Ipv4Addr,
+    netmask: Ipv4Addr,
+    dns_server: Option<Ipv4Addr>,
+}
+
+impl StaticIpConfig {
+    /// Creates a [`StaticIpConfig`] from a device address, a gateway,
+    /// and a netmask.
+    #[must_use]
+    pub const fn new(address: Ipv4Addr, gateway: Ipv4Addr, netmask: Ipv4Addr) -> Self {
+        Self {
+            address,
+            gateway,
+            netmask,
+            dns_server: None,
+        }
+    }
+
+    /// Sets a `DNS` server.
+    #[must_use]
+    pub const fn dns_server(mut self, dns_server: Ipv4Addr) -> Self {
+        self.dns_server = Some(dns_server);
+        self
+    }
+
+    fn into_config(self) -> Result<Config> {
+        let netmask = self.netmask.to_bits();
+        let prefix_len = netmask.leading_ones();
+
+        // All netmask ones must precede its zeros.
+        if netmask.count_ones() != prefix_len {
+            return Err(Error::new(ErrorKind::Network, "Invalid netmask"));
+        }
+
+        let mut config = StaticConfigV4 {
+            // A prefix length always fits an `u8` and never exceeds 32.
+            address: Ipv4Cidr::new(self.address, prefix_len as u8),
+            gateway: Some(self.gateway),
+            dns_servers: Default::default(),
+        };
+
+        if let Some(dns_server) = self.dns_server {
+            // A single server always fits the DNS servers list.
+            _ = config.dns_servers.push(dns_server);
+        }
+
+        Ok(Config::ipv4_static(config))
+    }
+}
+
 /// The network stack builder.
+///
+/// Only a single network stack can be built, since its task can only be
+/// spawned once.
 pub struct NetworkStack;
 
 impl NetworkStack {
-    /// Builds a [`NetworkStack`] .
+    /// Builds a [`NetworkStack`] which obtains its address through `DHCP`.
     ///
     /// # Errors
     ///
@@ -56,6 +120,52 @@ impl NetworkStack {
         spawner: Spawner,
     ) -> Result<Stack<'static>> {
         let config = Config::dhcpv4(DhcpConfig::default());
+        Self::init::<SOCKET_STACK_SIZE>(rng, wifi_interface, spawner, config).await
+    }
+
+    /// Builds a [`NetworkStack`] with a [`StaticIpConfig`].
+    ///
+    /// No `DHCP` client runs on the stack, so `SOCKET_STACK_SIZE` can be
+    /// one less than the value required by [`NetworkStack::build`].
+    ///
+    /// ```no_run
+    /// # use core::net::Ipv4Addr;
+    /// # use esp_hal::rng::Rng;
+    /// # use esp_radio::wifi::WifiDevice;
+    /// # use embassy_executor::Spawner;
+    /// # use tosca_esp32c3::net::{NetworkStack, StaticIpConfig};
+    /// # async fn run(rng: Rng, wifi_interface: WifiDevice<'static>, spawner: Spawner) {
+    /// let config = StaticIpConfig::new(
+    ///     Ipv4Addr::new(192, 168, 1, 50),
+    ///     Ipv4Addr::new(192, 168, 1, 1),
+    ///     Ipv4Addr::new(255, 255, 255, 0),
+    /// );
+    ///
+    /// let stack = NetworkStack::build_with_static::<5>(rng, wifi_interface, spawner, config)
+    ///     .await
+    ///     .expect("Failed to create network stack.");
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Failure to spawn the network stack task or an invalid netmask.
+    pub async fn build_with_static<const SOCKET_STACK_SIZE: usize>(
+        rng: Rng,
+        wifi_interface: WifiDevice<'static>,
+        spawner: Spawner,
+        config: StaticIpConfig,
+    ) -> Result<Stack<'static>> {
+        let config = config.into_config()?;
+        Self::init::<SOCKET_STACK_SIZE>(rng, wifi_interface, spawner, config).await
+    }
+
+    async fn init<const SOCKET_STACK_SIZE: usize>(
+        rng: Rng,
+        wifi_interface: WifiDevice<'static>,
+        spawner: Spawner,
+        config: Config,
+    ) -> Result<Stack<'static>> {
         let seed = u64::from(rng.random()) << 32 | u64::from(rng.random());
 
         // FIXME: We need to use `Box::leak` and then `Box::new` because
@@ -0,0 +1,181 @@
+use core::fmt::Debug;
+
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+
+use tosca::ota::{CHECKSUM_PARAMETER, FirmwareChecksum, ota_route};
+use tosca::route::Route;
+
+use embassy_executor::Spawner;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::mutex::Mutex;
+use embassy_sync::signal::Signal;
+use embassy_time::Timer;
+
+use embedded_storage::{ReadStorage, Storage};
+
+use esp_bootloader_esp_idf::ota::OtaImageState;
+use esp_bootloader_esp_idf::ota_updater::OtaUpdater;
+use esp_bootloader_esp_idf::partitions::PARTITION_TABLE_MAX_LEN;
+
+use esp_hal::peripherals::FLASH;
+
+use esp_storage::FlashStorage;
+
+use log::{error, info};
+
+use crate::error::Error;
+use crate::mk_static;
+use crate::parameters::{ByteStream, ParametersPayloads};
+use crate::response::{ErrorResponse, OkResponse};
+use crate::server::{OkStreamFn, RouteHandler};
+
+// Size of the firmware chunks written to flash, equal to a flash sector.
+const CHUNK_SIZE: usize = 4096;
+
+// Time left to the server to send the response before rebooting.
+const REBOOT_DELAY_MS: u64 = 1000;
+
+// Notifies the reboot task that a new firmware has been activated.
+static REBOOT: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+
+type Flash = Mutex<CriticalSectionRawMutex, FlashStorage<'static>>;
+
+/// Over-the-air firmware updates.
+///
+/// It serves the [`tosca::ota::ota_route`] route, writing the received
+/// firmware image into the inactive `OTA` partition. Once the whole image
+/// has been written, its [`FirmwareChecksum`] is verified, the partition is
+/// activated, and the device reboots into it.
+///
+/// An image whose checksum does not match the expected one is never
+/// activated, so the device keeps running its current firmware.
+///
+/// The route is guarded by a bearer token, so requests without an
+/// `Authorization: Bearer <token>` header carrying it are rejected.
+pub struct Ota {
+    flash: &'static Flash,
+    bearer_token: String,
+    max_size: u32,
+}
+
+impl Ota {
+    /// Creates an [`Ota`] accepting firmware images of at most `max_size`
+    /// bytes, sent along with the given bearer token.
+    ///
+    /// It spawns the task rebooting the device once a new firmware has been
+    /// activated.
+    ///
+    /// # Errors
+    ///
+    /// Failure to spawn the reboot task, as it happens when an [`Ota`] has
+    /// already been created.
+    pub fn new(
+        flash: FLASH<'static>,
+        bearer_token: &str,
+        max_size: u32,
+        spawner: Spawner,
+    ) -> Result<Self, Error> {
+        spawner.spawn(reboot_task())?;
+
+        Ok(Self {
+            flash: mk_static!(Flash, Mutex::new(FlashStorage::new(flash))),
+            bearer_token: bearer_token.into(),
+            max_size,
+        })
+    }
+
+    // Returns the route, its handler, and the bearer token guarding it.
+    pub(crate) fn into_route<S>(self) -> (Route, RouteHandler<S>, String) {
+        let flash = self.flash;
+        let func: OkStreamFn = Box::new(move |parameters_values, byte_stream| {
+            Box::pin(update_firmware(flash, parameters_values, byte_stream))
+        });
+
+        (
+            ota_route(self.max_size),
+            RouteHandler::OkStream(func),
+            self.bearer_token,
+        )
+    }
+}
+
+async fn update_firmware(
+    flash: &Flash,
+    mut parameters: ParametersPayloads,
+    mut firmware: ByteStream<'_>,
+) -> Result<OkResponse, ErrorResponse> {
+    let expected = parameters.u32(CHECKSUM_PARAMETER)?.value;
+
+    // A single update at a time.
+    let mut flash = flash.lock().await;
+
+    let mut buffer = [0; PARTITION_TABLE_MAX_LEN];
+    let mut updater = OtaUpdater::new(&mut *flash, &mut buffer)
+        .map_err(|e| ota_error("Unable to read the partition table", e))?;
+
+    let written = {
+        let (mut partition, _) = updater
+            .next_partition()
+            .map_err(|e| ota_error("Unable to find the inactive OTA partition", e))?;
+
+        if firmware.remaining() > partition.capacity() {
+            return Err(ErrorResponse::invalid_data(
+                "The firmware image does not fit the OTA partition",
+            ));
+        }
+
+        let mut checksum = FirmwareChecksum::new();
+        let mut chunk = [0; CHUNK_SIZE];
+        let mut offset = 0;
+        loop {
+            let read = firmware.read_chunk(&mut chunk).await?;
+            if read == 0 {
+                break;
+            }
+
+            checksum.update(&chunk[..read]);
+            partition
+                .write(offset, &chunk[..read])
+                .map_err(|e| ota_error("Unable to write the firmware image", e))?;
+            // A chunk is never larger than `CHUNK_SIZE`.
+            offset += read as u32;
+        }
+
+        // An image with a wrong checksum is never activated.
+        checksum.verify(expected).map_err(|e| {
+            error!("{e}");
+            ErrorResponse::invalid_data_with_error("Corrupted firmware image", &e.to_string())
+        })?;
+
+        offset
+    };
+
+    updater
+        .activate_next_partition()
+        .map_err(|e| ota_error("Unable to activate the OTA partition", e))?;
+    updater
+        .set_current_ota_state(OtaImageState::New)
+        .map_err(|e| ota_error("Unable to mark the new firmware", e))?;
+
+    info!("Firmware image of {written} bytes activated, the device is about to reboot");
+
+    REBOOT.signal(());
+
+    Ok(OkResponse::new())
+}
+
+#[inline]
+fn ota_error<E: Debug>(description: &str, error: E) -> ErrorResponse {
+    error!("{description}: {error:?}");
+    ErrorResponse::internal_with_error(description, &format!("{error:?}"))
+}
+
+// Reboots the device into the new firmware, once its response has been sent.
+#[embassy_executor::task]
+async fn reboot_task() {
+    REBOOT.wait().await;
+    Timer::after_millis(REBOOT_DELAY_MS).await;
+    esp_hal::system::software_reset();
+}
@@ -1,10 +1,16 @@
+use core::fmt::Debug;
+use core::pin::Pin;
+
 use alloc::borrow::Cow;
+use alloc::boxed::Box;
 use alloc::format;
 
 use tosca::parameters::{
     ParameterKind, ParameterPayload, ParameterValue, ParametersPayloads as ToscaParametersPayloads,
 };
 
+use embedded_io_async::Read;
+
 use crate::response::ErrorResponse;
 use crate::server::invalid_data;
 
@@ -114,6 +120,29 @@ impl U64Payload {
     }
 }
 
+/// A [`u128`] payload.
+pub struct U128Payload {
+    /// Value.
+    pub value: u128,
+    /// Default value.
+    pub default: u128,
+    /// Minimum value.
+    pub min: u128,
+    /// Maximum value.
+    pub max: u128,
+}
+
+impl U128Payload {
+    const fn new(value: u128, default: u128, min: u128, max: u128) -> Self {
+        Self {
+            value,
+            default,
+            min,
+            max,
+        }
+    }
+}
+
 /// A [`f32`] payload.
 pub struct F32Payload {
     /// Value.
@@ -292,6 +321,26 @@ impl ParametersPayloads {
         })
     }
 
+    /// Retrieves the [`U128Payload`] associated with the given parameter name.
+    ///
+    /// **It consumes the parameter.**
+    ///
+    /// # Errors
+    ///
+    /// An [`ErrorResponse`] is returned in the following cases:
+    ///
+    /// - When the given parameter is not found
+    /// - When the given parameter has an incorrect type
+    #[inline]
+    pub fn u128(&mut self, name: &'static str) -> Result<U128Payload, ErrorResponse> {
+        self.insert(name, |payload| match (payload.value, payload.kind) {
+            (ParameterValue::U128(v), ParameterKind::U128 { default, min, max }) => {
+                Ok(U128Payload::new(v, default, min, max))
+            }
+            _ => Err(invalid_data(&format!("`{name}` is not a `u128` kind"))),
+        })
+    }
+
     /// Retrieves the [`F32Payload`] associated with the given parameter name.
     ///
     /// **It consumes the parameter.**
@@ -365,9 +414,10 @@ impl ParametersPayloads {
         name: &'static str,
     ) -> Result<CharsSequencePayload<'_>, ErrorResponse> {
         self.insert(name, |payload| match (payload.value, payload.kind) {
-            (ParameterValue::CharsSequence(s), ParameterKind::CharsSequence { default }) => {
-                Ok(CharsSequencePayload::new(s, default))
-            }
+            (
+                ParameterValue::CharsSequence(s) | ParameterValue::SecretCharsSequence(s),
+                ParameterKind::CharsSequence { default, .. },
+            ) => Ok(CharsSequencePayload::new(s, default)),
             _ => Err(invalid_data(&format!(
                 "`{name}` is not a `characters sequence` kind"
             ))),
@@ -387,3 +437,177 @@ impl ParametersPayloads {
         func(value)
     }
 }
+
+// A reader of a request body.
+//
+// It hides the type of the underlying connection, so that route handlers
+// can be stored without being generic over it.
+pub(crate) trait BodyReader {
+    fn read_chunk<'a>(
+        &'a mut self,
+        buffer: &'a mut [u8],
+    ) -> Pin<Box<dyn Future<Output = Result<usize, ErrorResponse>> + 'a>>;
+}
+
+impl<R> BodyReader for R
+where
+    R: Read,
+    R::Error: Debug,
+{
+    fn read_chunk<'a>(
+        &'a mut self,
+        buffer: &'a mut [u8],
+    ) -> Pin<Box<dyn Future<Output = Result<usize, ErrorResponse>> + 'a>> {
+        Box::pin(async move {
+            self.read(buffer).await.map_err(|e| {
+                ErrorResponse::internal_with_error(
+                    "Error reading the byte stream",
+                    &format!("{e:?}"),
+                )
+            })
+        })
+    }
+}
+
+/// A stream of bytes sent as a request body.
+///
+/// It is passed to the handlers of the routes with a
+/// [`ParameterKind::ByteStream`] parameter, which read the body in chunks
+/// rather than buffering it entirely in memory.
+pub struct ByteStream<'a> {
+    reader: &'a mut dyn BodyReader,
+    remaining: usize,
+}
+
+impl<'a> ByteStream<'a> {
+    pub(crate) fn new(reader: &'a mut dyn BodyReader, len: usize) -> Self {
+        Self {
+            reader,
+            remaining: len,
+        }
+    }
+
+    /// Returns the number of bytes still to be read.
+    #[must_use]
+    pub const fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    /// Reads the next chunk of bytes into the given buffer, returning the
+    /// number of read bytes.
+    ///
+    /// A chunk is never larger than the buffer, and a zero length means that
+    /// the stream has been entirely read.
+    ///
+    /// # Errors
+    ///
+    /// An [`ErrorResponse`] is returned in the following cases:
+    ///
+    /// - When the request body cannot be read
+    /// - When the request body is shorter than its declared length
+    pub async fn read_chunk(&mut self, buffer: &mut [u8]) -> Result<usize, ErrorResponse> {
+        if self.remaining == 0 || buffer.is_empty() {
+            return Ok(0);
+        }
+
+        let len = buffer.len().min(self.remaining);
+        let read = self.reader.read_chunk(&mut buffer[..len]).await?;
+        if read == 0 {
+            return Err(invalid_data(&format!(
+                "The byte stream ended with {} bytes still to be read",
+                self.remaining
+            )));
+        }
+
+        self.remaining -= read;
+        Ok(read)
+    }
+}
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    use core::convert::Infallible;
+
+    use alloc::vec::Vec;
+
+    use embedded_io_async::{ErrorType, Read};
+
+    use crate::block_on;
+
+    use super::ByteStream;
+
+    // A request body which delivers at most `chunk_len` bytes per read, as a
+    // connection receiving the body in several packets.
+    struct ChunkedBody<'a> {
+        bytes: &'a [u8],
+        chunk_len: usize,
+    }
+
+    impl ErrorType for ChunkedBody<'_> {
+        type Error = Infallible;
+    }
+
+    impl Read for ChunkedBody<'_> {
+        async fn read(&mut self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+            let len = buffer.len().min(self.chunk_len).min(self.bytes.len());
+            buffer[..len].copy_from_slice(&self.bytes[..len]);
+            self.bytes = &self.bytes[len..];
+            Ok(len)
+        }
+    }
+
+    async fn upload(mut byte_stream: ByteStream<'_>) -> Vec<u8> {
+        let mut received = Vec::new();
+        let mut chunk = [0; 4];
+        loop {
+            let remaining = byte_stream.remaining();
+            let read = byte_stream.read_chunk(&mut chunk).await.ok().unwrap();
+            if read == 0 {
+                return received;
+            }
+            assert!(read <= chunk.len());
+            assert_eq!(byte_stream.remaining(), remaining - read);
+            received.extend_from_slice(&chunk[..read]);
+        }
+    }
+
+    #[init]
+    fn init() {
+        esp_alloc::heap_allocator!(size: 16 * 1024);
+    }
+
+    #[test]
+    fn test_byte_stream_upload() {
+        let upload_data: Vec<u8> = (0..=40).collect();
+
+        // Every byte is received, whatever the size of the read chunks.
+        for chunk_len in [1, 3, 4, 64] {
+            let mut body = ChunkedBody {
+                bytes: &upload_data,
+                chunk_len,
+            };
+            let received = block_on(upload(ByteStream::new(&mut body, upload_data.len())));
+            assert_eq!(received, upload_data);
+        }
+
+        // Bytes beyond the `Content-Length` are not read.
+        let mut body = ChunkedBody {
+            bytes: &upload_data,
+            chunk_len: 3,
+        };
+        let received = block_on(upload(ByteStream::new(&mut body, 10)));
+        assert_eq!(received, &upload_data[..10]);
+        assert_eq!(body.bytes, &upload_data[10..]);
+
+        // A body shorter than its `Content-Length` is an error.
+        let mut body = ChunkedBody {
+            bytes: &upload_data,
+            chunk_len: 64,
+        };
+        let mut byte_stream = ByteStream::new(&mut body, 50);
+        let mut chunk = [0; 64];
+        assert_eq!(block_on(byte_stream.read_chunk(&mut chunk)).ok(), Some(41));
+        assert!(block_on(byte_stream.read_chunk(&mut chunk)).is_err());
+    }
+}
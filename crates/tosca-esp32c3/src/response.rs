@@ -15,6 +15,8 @@ use embedded_io_async::{Read, Write};
 
 use serde::Serialize;
 
+use crate::server::{handled_requests, uptime};
+
 /// A response which transmits a concise JSON message over the network to notify
 /// a controller that an operation completed successfully.
 pub struct OkResponse(Response);
@@ -71,9 +73,18 @@ pub struct InfoResponse(Response);
 impl InfoResponse {
     /// Creates a [`InfoResponse`] containing
     /// a [`tosca::response::InfoResponse`].
+    ///
+    /// The number of requests handled by the server and the server uptime
+    /// are added to the device information.
     #[must_use]
     #[inline]
     pub fn new(device_info: DeviceInfo) -> Self {
+        let device_info = device_info.add_handled_requests(handled_requests());
+        let device_info = match uptime() {
+            Some(uptime) => device_info.add_uptime(uptime),
+            None => device_info,
+        };
+
         Self(json_to_response(
             Headers::json(),
             ToscaInfoResponse::new(device_info),
@@ -161,6 +172,57 @@ impl ErrorResponse {
     pub fn internal_with_error(description: &str, info: &str) -> Self {
         Self::error_with_info(ErrorKind::Internal, description, info)
     }
+
+    /// Generates an [`ErrorResponse`] for an operation which is not allowed
+    /// in the current device state.
+    ///
+    /// Requires specifying a general error description.
+    #[must_use]
+    #[inline]
+    pub fn invalid_state(description: &str) -> Self {
+        Self(json_to_response(
+            Headers::json_error_status(409, "Conflict"),
+            ToscaErrorResponse::invalid_state(description),
+        ))
+    }
+
+    /// Generates an [`ErrorResponse`] for a resource which has not been
+    /// found.
+    ///
+    /// Requires specifying a general error description.
+    #[must_use]
+    #[inline]
+    pub fn not_found(description: &str) -> Self {
+        Self(json_to_response(
+            Headers::json_error_status(404, "Not Found"),
+            ToscaErrorResponse::not_found(description),
+        ))
+    }
+
+    /// Generates an [`ErrorResponse`] for an unauthorized requester.
+    ///
+    /// Requires specifying a general error description.
+    #[must_use]
+    #[inline]
+    pub fn unauthorized(description: &str) -> Self {
+        Self(json_to_response(
+            Headers::json_error_status(401, "Unauthorized"),
+            ToscaErrorResponse::unauthorized(description),
+        ))
+    }
+
+    /// Generates an [`ErrorResponse`] for a requester which has sent too
+    /// many requests.
+    ///
+    /// Requires specifying a general error description.
+    #[must_use]
+    #[inline]
+    pub fn rate_limited(description: &str) -> Self {
+        Self(json_to_response(
+            Headers::json_error_status(429, "Too Many Requests"),
+            ToscaErrorResponse::rate_limited(description),
+        ))
+    }
 }
 
 struct Headers {
@@ -170,22 +232,6 @@ struct Headers {
 }
 
 impl Headers {
-    const fn not_found() -> Self {
-        Self {
-            status: 404,
-            message: "Not Found",
-            content_type: &[],
-        }
-    }
-
-    const fn not_allowed() -> Self {
-        Self {
-            status: 405,
-            message: "Method Not Allowed",
-            content_type: &[],
-        }
-    }
-
     const fn json() -> Self {
         Self {
             status: 200,
@@ -195,9 +241,13 @@ impl Headers {
     }
 
     const fn json_error() -> Self {
+        Self::json_error_status(500, "Error")
+    }
+
+    const fn json_error_status(status: u16, message: &'static str) -> Self {
         Self {
-            status: 500,
-            message: "Error",
+            status,
+            message,
             content_type: &[("Content-Type", "application/json")],
         }
     }
@@ -214,14 +264,6 @@ impl Headers {
 struct Body(Cow<'static, [u8]>);
 
 impl Body {
-    const fn empty() -> Self {
-        Self(Cow::Borrowed(&[]))
-    }
-
-    const fn static_ref(v: &'static [u8]) -> Self {
-        Self(Cow::Borrowed(v))
-    }
-
     const fn owned(v: Vec<u8>) -> Self {
         Self(Cow::Owned(v))
     }
@@ -308,18 +350,28 @@ impl Response {
         conn.write_all(&self.body.0).await
     }
 
-    pub(crate) const fn not_found() -> Self {
-        Response::new(Headers::not_found(), Body::empty())
+    // Answers a request to an unknown route.
+    pub(crate) fn not_found(route: &str) -> Self {
+        json_to_response(
+            Headers::json_error_status(404, "Not Found"),
+            ToscaErrorResponse::not_found("Route not found").with_route(route),
+        )
     }
 
-    pub(crate) const fn not_allowed() -> Self {
-        Response::new(
-            Headers::not_allowed(),
-            Body::static_ref("Method not allowed".as_bytes()),
+    // Answers a request with a method not supported by any route.
+    pub(crate) fn not_allowed(route: &str) -> Self {
+        json_to_response(
+            Headers::json_error_status(405, "Method Not Allowed"),
+            ToscaErrorResponse::invalid_data("Method not allowed").with_route(route),
         )
     }
 
     const fn new(headers: Headers, body: Body) -> Response {
         Self { headers, body }
     }
+
+    #[cfg(test)]
+    pub(crate) fn body(&self) -> &[u8] {
+        &self.body.0
+    }
 }
@@ -1,19 +1,23 @@
+use core::cell::Cell;
 use core::fmt::{Debug, Display};
 use core::net::SocketAddr;
 use core::pin::Pin;
+use core::sync::atomic::{AtomicU32, Ordering};
 
 use alloc::borrow::Cow;
 use alloc::boxed::Box;
 use alloc::format;
 use alloc::str::SplitTerminator;
-use alloc::string::ToString;
+use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 
 use tosca::parameters::{
     ParameterKind, ParameterPayload, ParameterValue, ParametersPayloads as ToscaParametersPayloads,
     ParametersValues,
 };
-use tosca::route::{RestKind, RouteConfig};
+use tosca::route::{
+    ParameterStyle, RestKind, RouteConfig, RouteParameterError, normalize_path, segment_matches,
+};
 
 use edge_http::io::Body;
 use edge_http::io::server::{Connection, Handler, Server as EdgeServer};
@@ -23,6 +27,7 @@ use edge_nal_embassy::{Tcp, TcpBuffers};
 
 use embassy_executor::Spawner;
 use embassy_net::Stack;
+use embassy_time::Instant;
 
 use embedded_io_async::{Read, Write};
 
@@ -32,7 +37,7 @@ use crate::device::{Device, InternalDevice};
 use crate::error::Error;
 use crate::mdns::Mdns;
 use crate::net::get_ip;
-use crate::parameters::ParametersPayloads;
+use crate::parameters::{ByteStream, ParametersPayloads};
 use crate::response::{ErrorResponse, InfoResponse, OkResponse, Response, SerialResponse};
 use crate::state::{State, ValueFromRef};
 
@@ -45,6 +50,54 @@ const SERVER_SOCKETS: usize = 1;
 // Maximum request size in bytes.
 const MAXIMUM_REQUEST_SIZE: usize = 128;
 
+// Number of requests handled by the server.
+static HANDLED_REQUESTS: AtomicU32 = AtomicU32::new(0);
+
+/// Returns the number of requests handled by the server.
+///
+/// Application code can use this function to observe the server load
+/// without maintaining its own counter. The same value is added to every
+/// [`InfoResponse`].
+#[must_use]
+pub fn handled_requests() -> u32 {
+    HANDLED_REQUESTS.load(Ordering::Relaxed)
+}
+
+// Instant at which the server started running.
+static SERVER_START: critical_section::Mutex<Cell<Option<Instant>>> =
+    critical_section::Mutex::new(Cell::new(None));
+
+/// Returns the number of seconds elapsed since the server started running.
+///
+/// The same value is added to every [`InfoResponse`]. The return value is
+/// [`None`] when the server has not been run yet.
+#[must_use]
+pub fn uptime() -> Option<u64> {
+    critical_section::with(|cs| SERVER_START.borrow(cs).get())
+        .map(|start| start.elapsed().as_secs())
+}
+
+// Starts counting the server uptime, if not already started.
+fn start_uptime() {
+    critical_section::with(|cs| {
+        let start = SERVER_START.borrow(cs);
+        if start.get().is_none() {
+            start.set(Some(Instant::now()));
+        }
+    });
+}
+
+// Increments the number of requests handled by the server.
+//
+// The target has no atomic read-modify-write instructions, so the counter
+// is loaded and stored inside a critical section.
+fn count_request() {
+    critical_section::with(|_| {
+        let handled_requests = HANDLED_REQUESTS.load(Ordering::Relaxed);
+        HANDLED_REQUESTS.store(handled_requests.wrapping_add(1), Ordering::Relaxed);
+    });
+}
+
 pub(crate) type OkFn = Box<
     dyn Fn(
             ParametersPayloads,
@@ -108,37 +161,129 @@ pub(crate) type InfoStateFn<S> = Box<
         + 'static,
 >;
 
-pub(crate) type Functions<S> = (
-    Vec<OkFn>,
-    Vec<OkStateFn<S>>,
-    Vec<SerialFn>,
-    Vec<SerialStateFn<S>>,
-    Vec<InfoFn>,
-    Vec<InfoStateFn<S>>,
-);
-
-#[derive(Clone, Copy)]
-pub(crate) enum FuncType {
-    OkStateless,
-    OkStateful,
-    SerialStateless,
-    SerialStateful,
-    InfoStateless,
-    InfoStateful,
+pub(crate) type OkStreamFn = Box<
+    dyn for<'a> Fn(
+            ParametersPayloads,
+            ByteStream<'a>,
+        ) -> Pin<Box<dyn Future<Output = Result<OkResponse, ErrorResponse>> + 'a>>
+        + Send
+        + Sync
+        + 'static,
+>;
+
+pub(crate) type OkStreamStateFn<S> = Box<
+    dyn for<'a> Fn(
+            State<S>,
+            ParametersPayloads,
+            ByteStream<'a>,
+        ) -> Pin<Box<dyn Future<Output = Result<OkResponse, ErrorResponse>> + 'a>>
+        + Send
+        + Sync
+        + 'static,
+>;
+
+// A route handler, for each response kind with and without a state.
+pub(crate) enum RouteHandler<S> {
+    Ok(OkFn),
+    OkStateful(OkStateFn<S>),
+    Serial(SerialFn),
+    SerialStateful(SerialStateFn<S>),
+    Info(InfoFn),
+    InfoStateful(InfoStateFn<S>),
+    OkStream(OkStreamFn),
+    OkStreamStateful(OkStreamStateFn<S>),
 }
 
-#[derive(Clone, Copy)]
-pub(crate) struct FuncIndex {
-    func_type: FuncType,
-    index: usize,
+impl<S: ValueFromRef> RouteHandler<S> {
+    // Runs the handler, converting its result into a response.
+    async fn run(
+        &self,
+        state: &State<S>,
+        parameters_payloads: ParametersPayloads,
+        byte_stream: ByteStream<'_>,
+    ) -> Response {
+        let handler_state = || State(S::value_from_ref(&state.0));
+
+        match self {
+            Self::Ok(func) => func(parameters_payloads).await.into(),
+            Self::OkStateful(func) => func(handler_state(), parameters_payloads).await.into(),
+            Self::Serial(func) => func(parameters_payloads).await.into(),
+            Self::SerialStateful(func) => func(handler_state(), parameters_payloads).await.into(),
+            Self::Info(func) => func(parameters_payloads).await.into(),
+            Self::InfoStateful(func) => func(handler_state(), parameters_payloads).await.into(),
+            Self::OkStream(func) => func(parameters_payloads, byte_stream).await.into(),
+            Self::OkStreamStateful(func) => func(handler_state(), parameters_payloads, byte_stream)
+                .await
+                .into(),
+        }
+    }
+}
+
+// A route handler, along with the bearer token guarding its route, if any.
+struct GuardedHandler<S> {
+    handler: RouteHandler<S>,
+    bearer_token: Option<String>,
 }
 
-impl FuncIndex {
-    pub(crate) const fn new(func_type: FuncType, index: usize) -> Self {
-        Self { func_type, index }
+// The handlers of the device routes.
+//
+// A handler is stored at the same index as its route configuration, so
+// handlers must be registered in the same order as their routes.
+pub(crate) struct RouteRegistry<S>(Vec<GuardedHandler<S>>);
+
+impl<S> RouteRegistry<S> {
+    pub(crate) const fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    pub(crate) fn register(&mut self, handler: RouteHandler<S>) {
+        self.0.push(GuardedHandler {
+            handler,
+            bearer_token: None,
+        });
+    }
+
+    // Registers a handler whose requests must carry the given bearer token.
+    pub(crate) fn register_guarded(&mut self, handler: RouteHandler<S>, bearer_token: String) {
+        self.0.push(GuardedHandler {
+            handler,
+            bearer_token: Some(bearer_token),
+        });
+    }
+
+    fn get(&self, index: usize) -> Option<&RouteHandler<S>> {
+        self.0.get(index).map(|guarded| &guarded.handler)
+    }
+
+    // Checks whether a request to the route at the given index carries the
+    // bearer token guarding it, if any.
+    fn authorize(&self, index: usize, authorization: Option<&str>) -> bool {
+        let Some(bearer_token) = self
+            .0
+            .get(index)
+            .and_then(|guarded| guarded.bearer_token.as_deref())
+        else {
+            return true;
+        };
+
+        authorization
+            .and_then(|authorization| authorization.strip_prefix("Bearer "))
+            .is_some_and(|token| tokens_match(token.as_bytes(), bearer_token.as_bytes()))
     }
 }
 
+// Compares two tokens, taking the same time wherever they differ.
+fn tokens_match(first: &[u8], second: &[u8]) -> bool {
+    first.len() == second.len()
+        && first
+            .iter()
+            .zip(second)
+            .fold(0, |difference, (first, second)| {
+                difference | (first ^ second)
+            })
+            == 0
+}
+
 fn with_timeout<T>(timeout_ms: u32, io: T) -> WithTimeout<T> {
     WithTimeout::new(timeout_ms, io)
 }
@@ -173,6 +318,19 @@ fn with_timeout<T>(timeout_ms: u32, io: T) -> WithTimeout<T> {
 ///   interrupted by timeouts.
 ///   See [`Server::handler_timeout()`].
 ///
+/// - **`lenient_routes`**
+///   Whether request paths are matched leniently against routes, collapsing
+///   repeated slashes and ignoring the case of route segments.
+///   The default value is `false`, meaning that request paths must exactly
+///   match routes.
+///   See [`Server::lenient_routes()`].
+///
+/// - **`strict_parameters`**
+///   Whether a request body containing undeclared parameters is rejected.
+///   The default value is `false`, meaning that undeclared parameters are
+///   logged and ignored.
+///   See [`Server::strict_parameters()`].
+///
 /// ## Known Issue
 ///
 /// In `edge-net`
@@ -250,6 +408,26 @@ where
         self
     }
 
+    /// Matches request paths leniently against routes.
+    ///
+    /// Repeated slashes are collapsed and route segments are compared
+    /// ignoring their case, so `/Light//Route` matches the `/light/route`
+    /// route. Parameter values preserve their case.
+    #[must_use]
+    pub const fn lenient_routes(mut self) -> Self {
+        self.handler.lenient_routes = true;
+        self
+    }
+
+    /// Rejects request bodies containing parameters not declared by a route.
+    ///
+    /// The returned invalid data response names the undeclared parameter.
+    #[must_use]
+    pub const fn strict_parameters(mut self) -> Self {
+        self.handler.strict_parameters = true;
+        self
+    }
+
     /// Sets the scheme to `HTTPS`.
     #[must_use]
     pub const fn https(mut self) -> Self {
@@ -257,6 +435,14 @@ where
         self
     }
 
+    /// Returns the number of requests handled by the [`Server`].
+    ///
+    /// See [`handled_requests()`].
+    #[must_use]
+    pub fn handled_requests(&self) -> u32 {
+        handled_requests()
+    }
+
     /// Runs the [`Server`] and the [`Mdns`] task.
     ///
     /// # Errors
@@ -296,6 +482,8 @@ where
 
         info!("Starting server on address `{address}` and port `{port}`");
 
+        start_uptime();
+
         match (io_timeout_ms, handler_timeout_ms) {
             (Some(ta), Some(th)) => {
                 Self::run_server(
@@ -341,6 +529,7 @@ const fn method_map(ascot_method: RestKind) -> Method {
         RestKind::Put => Method::Put,
         RestKind::Post => Method::Post,
         RestKind::Delete => Method::Delete,
+        RestKind::Patch => Method::Patch,
     }
 }
 
@@ -370,6 +559,8 @@ pub(crate) fn invalid_data(description: &str) -> ErrorResponse {
 struct RouteInfo {
     index: usize,
     parameters_payloads: ParametersPayloads,
+    // Length of the byte stream sent as request body.
+    stream_len: usize,
 }
 
 impl RouteInfo {
@@ -377,8 +568,14 @@ impl RouteInfo {
         Self {
             index,
             parameters_payloads: ParametersPayloads(parameters_payloads),
+            stream_len: 0,
         }
     }
+
+    const fn stream_len(mut self, stream_len: usize) -> Self {
+        self.stream_len = stream_len;
+        self
+    }
 }
 
 struct ServerHandler<S>
@@ -386,6 +583,8 @@ where
     S: ValueFromRef + Send + Sync + 'static,
 {
     device: InternalDevice<S>,
+    lenient_routes: bool,
+    strict_parameters: bool,
 }
 
 impl<S> ServerHandler<S>
@@ -394,7 +593,11 @@ where
 {
     #[inline]
     fn new(device: InternalDevice<S>) -> Self {
-        Self { device }
+        Self {
+            device,
+            lenient_routes: false,
+            strict_parameters: false,
+        }
     }
 
     async fn analyze_route<const N: usize, T: Read>(
@@ -404,6 +607,19 @@ where
         headers: &Headers<'_, N>,
         body: &mut Body<'_, T>,
     ) -> Result<RouteInfo, Response> {
+        // Split the query string, which is only read by the routes encoding
+        // their parameters as a query and by the routes with a byte stream.
+        let (path, query) = path.split_once('?').unwrap_or((path, ""));
+
+        // Collapse repeated slashes when routes are lenient.
+        let normalized_path;
+        let path = if self.lenient_routes {
+            normalized_path = normalize_path(path);
+            normalized_path.as_str()
+        } else {
+            path
+        };
+
         // If the last character of a path ends with '/', remove it.
         let path = path.strip_suffix('/').unwrap_or(path);
 
@@ -421,7 +637,7 @@ where
         // because **each** path begins with a '/'.
         //
         // In case of error, return a not found route.
-        let empty_path = route_iter.nth(0).ok_or_else(Response::not_found)?;
+        let empty_path = route_iter.nth(0).ok_or_else(|| Response::not_found(path))?;
 
         // If the empty path is equal to the route path,
         // the route is not correct. This might happen when a route
@@ -430,17 +646,21 @@ where
         //
         // In case of error, return a not found route.
         if empty_path == path {
-            return Err(Response::not_found());
+            return Err(Response::not_found(path));
         }
 
         // Retrieve the main route.
-        let main_route_path = route_iter.nth(0).ok_or_else(Response::not_found)?;
+        let main_route_path = route_iter.nth(0).ok_or_else(|| Response::not_found(path))?;
 
         // If the subpath is not equal to the main route,
         // the route is not correct. Starts from the 1-index
         // in order to skip the "/" placed before the main route.
-        if main_route_path != &self.device.main_route[1..] {
-            return Err(Response::not_found());
+        if !segment_matches(
+            &self.device.main_route[1..],
+            main_route_path,
+            self.lenient_routes,
+        ) {
+            return Err(Response::not_found(path));
         }
 
         let mut route_index = self.device.route_configs.len();
@@ -464,22 +684,18 @@ where
 
             // Compare route segments with the corresponding path segments.
             // If all segments match in order, this is the correct route.
-            if !route_path_iter.all(|seg| path_iter.next() == Some(seg)) {
+            if !route_path_iter.all(|seg| {
+                path_iter
+                    .next()
+                    .is_some_and(|path_seg| segment_matches(seg, path_seg, self.lenient_routes))
+            }) {
                 continue;
             }
 
             info!("Route path: {route_path}");
 
             for _ in 0..route_path.split_terminator('/').count() {
-                route_iter.nth(0).ok_or_else(Response::not_found)?;
-            }
-
-            // If the route has no parameters, return its index.
-            //
-            // Otherwise, save the index and break the loop,
-            // as there are parameters to analyze.
-            if route.data.parameters.is_empty() {
-                return Ok(RouteInfo::new(index, ToscaParametersPayloads::new()));
+                route_iter.nth(0).ok_or_else(|| Response::not_found(path))?;
             }
 
             route_index = index;
@@ -491,45 +707,104 @@ where
             .device
             .route_configs
             .get_index(route_index)
-            .ok_or_else(Response::not_found)?;
+            .ok_or_else(|| Response::not_found(path))?;
+
+        // A guarded route is authorized before its parameters are parsed, so
+        // an unauthorized request learns nothing about them.
+        if !self
+            .device
+            .route_registry
+            .authorize(route_index, headers.get("Authorization"))
+        {
+            return Err(ErrorResponse::unauthorized("Missing or invalid bearer token").0);
+        }
+
+        // A route without parameters has nothing left to analyze.
+        if route_config.data.parameters.is_empty() {
+            return Ok(RouteInfo::new(route_index, ToscaParametersPayloads::new()));
+        }
 
         match method {
-            Method::Get => Self::parse_get_parameters(route_config, route_iter),
+            // Parameters of a `GET` or `DELETE` route might be encoded as a
+            // query string.
+            Method::Get | Method::Delete
+                if route_config.parameter_style == ParameterStyle::Query =>
+            {
+                Self::parse_query_parameters(route_config, query)
+                    .map(|parameters_payloads| RouteInfo::new(route_index, parameters_payloads))
+            }
+            // Parameters of a `DELETE` route are encoded as path segments,
+            // like the `GET` ones, unless they are sent as a body.
+            Method::Get => Self::parse_get_parameters(route_config, route_iter)
+                .map(|parameters_payloads| RouteInfo::new(route_index, parameters_payloads)),
+            Method::Delete if route_config.parameter_style != ParameterStyle::Body => {
+                Self::parse_get_parameters(route_config, route_iter)
+                    .map(|parameters_payloads| RouteInfo::new(route_index, parameters_payloads))
+            }
+            // The body of a route with a byte stream is read by its handler.
+            _ if route_config
+                .data
+                .parameters
+                .iter()
+                .any(|parameter| matches!(parameter.1, ParameterKind::ByteStream { .. })) =>
+            {
+                Self::parse_stream_parameters(route_config, headers, query).map(
+                    |(parameters_payloads, stream_len)| {
+                        RouteInfo::new(route_index, parameters_payloads).stream_len(stream_len)
+                    },
+                )
+            }
             // NOTE: We include the disallowed methods here as well, since
             // the check has already been performed earlier.
-            _ => Self::parse_headers_parameters(route_config, headers, body).await,
+            _ => {
+                Self::parse_headers_parameters(route_config, headers, body, self.strict_parameters)
+                    .await
+                    .map(|parameters_payloads| RouteInfo::new(route_index, parameters_payloads))
+            }
         }
-        .map(|parameters_payloads| RouteInfo::new(route_index, parameters_payloads))
     }
 
     #[inline]
     fn parse_get_parameters(
         route_config: &RouteConfig,
-        mut route_iter: SplitTerminator<'_, char>,
+        route_iter: SplitTerminator<'_, char>,
     ) -> Result<ToscaParametersPayloads<'static>, Response> {
-        // Create parameters payloads.
-        let mut parameters_payloads = ToscaParametersPayloads::new();
-
-        for (index, parameter) in route_config.data.parameters.iter().enumerate() {
-            let parameter_value = route_iter.nth(0).ok_or_else(|| {
+        let path_parameters = route_config
+            .path_parameters(route_iter)
+            .map_err(|missing| {
                 invalid_data_response(&format!(
-                    "Passed route path is too short, missing parameters: {:?}",
-                    route_config
-                        .data
-                        .parameters
-                        .iter()
-                        .skip(index)
-                        .map(|parameter| parameter.0.as_str())
-                        .collect::<Vec<&str>>()
+                    "Passed route path is too short, missing parameters: {missing:?}"
                 ))
             })?;
 
-            info!("Parameter value as string: {parameter_value}");
-            let parameter_value = Self::parse_parameter_value(parameter_value, parameter.1)?;
+        // Create parameters payloads.
+        let mut parameters_payloads = ToscaParametersPayloads::new();
+
+        for parameter in path_parameters {
+            let parameter_value = match parameter.value {
+                Some(parameter_value) => {
+                    // Secret values are masked once parsed.
+                    let parameter_value = Self::parse_parameter_value(
+                        parameter.name,
+                        parameter_value,
+                        parameter.kind,
+                    )?;
+                    let parameter_value = Self::align_to_step(
+                        route_config,
+                        parameter.name,
+                        parameter_value,
+                        parameter.kind,
+                    )?;
+                    info!("Parameter value: {parameter_value}");
+                    parameter_value
+                }
+                // Omitted parameters assume their default values.
+                None => ParameterValue::from_parameter_kind(parameter.kind),
+            };
 
             parameters_payloads.add(
-                parameter.0.clone().into(),
-                ParameterPayload::new(parameter.1.clone(), parameter_value),
+                parameter.name.to_string().into(),
+                ParameterPayload::new(parameter.kind.clone(), parameter_value),
             );
         }
 
@@ -538,24 +813,128 @@ where
         Ok(parameters_payloads)
     }
 
-    #[inline]
-    async fn parse_headers_parameters<const N: usize, T: Read>(
+    // Parses the parameters of a route encoding them as a query string.
+    //
+    // An omitted parameter assumes its default value.
+    fn parse_query_parameters(
         route_config: &RouteConfig,
-        headers: &Headers<'_, N>,
-        body: &mut Body<'_, T>,
+        query: &str,
     ) -> Result<ToscaParametersPayloads<'static>, Response> {
-        info!("Headers: {headers:?}");
+        let mut parameters_payloads = ToscaParametersPayloads::new();
+        for parameter in &route_config.data.parameters {
+            let parameter_value =
+                Self::query_parameter_value(route_config, query, parameter.0, parameter.1)?;
 
+            parameters_payloads.add(
+                parameter.0.clone().into(),
+                ParameterPayload::new(parameter.1.clone(), parameter_value),
+            );
+        }
+
+        Ok(parameters_payloads)
+    }
+
+    // Retrieves the value of a parameter from a query string, or its default
+    // value when omitted.
+    fn query_parameter_value(
+        route_config: &RouteConfig,
+        query: &str,
+        parameter_name: &str,
+        parameter_kind: &ParameterKind,
+    ) -> Result<ParameterValue, Response> {
+        match query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(name, _)| *name == parameter_name)
+        {
+            Some((_, parameter_value)) => {
+                let parameter_value =
+                    Self::parse_parameter_value(parameter_name, parameter_value, parameter_kind)?;
+                info!("Parameter value: {parameter_value}");
+                Self::align_to_step(
+                    route_config,
+                    parameter_name,
+                    parameter_value,
+                    parameter_kind,
+                )
+            }
+            None => Ok(ParameterValue::from_parameter_kind(parameter_kind)),
+        }
+    }
+
+    fn content_length<const N: usize>(headers: &Headers<'_, N>) -> Result<usize, Response> {
         let content_length = headers
             .get("Content-Length")
             .ok_or_else(|| invalid_data_response("No `Content-Length` found"))?;
 
-        let content_length = content_length.parse::<usize>().map_err(|e| {
+        content_length.parse::<usize>().map_err(|e| {
             error_response_with_error(
                 "Unable to convert the `Content-Length` header into a number",
                 &format!("{e}"),
             )
-        })?;
+        })
+    }
+
+    // Parses the parameters of a route whose request body is a byte stream.
+    //
+    // The body is not read here, but by the route handler. Since the body
+    // only contains the stream, any other parameter is read from the query
+    // string, or assumes its default value when omitted.
+    fn parse_stream_parameters<const N: usize>(
+        route_config: &RouteConfig,
+        headers: &Headers<'_, N>,
+        query: &str,
+    ) -> Result<(ToscaParametersPayloads<'static>, usize), Response> {
+        info!("Headers: {headers:?}");
+
+        let content_length = Self::content_length(headers)?;
+
+        let content_type = headers
+            .content_type()
+            .ok_or_else(|| invalid_data_response("No `Content-Type` found"))?;
+
+        if content_type != "application/octet-stream" {
+            return Err(invalid_data_response(
+                "The request body does not have a byte stream as content type",
+            ));
+        }
+
+        let mut parameters_payloads = ToscaParametersPayloads::new();
+        for parameter in &route_config.data.parameters {
+            let parameter_value = match parameter.1 {
+                ParameterKind::ByteStream { max_size } => {
+                    let parameter_value = ParameterValue::ByteStream(
+                        u32::try_from(content_length).unwrap_or(u32::MAX),
+                    );
+                    if !parameter_value.is_within_limits(parameter.1) {
+                        return Err(error_response(&format!(
+                            "The byte stream exceeds the maximum allowed size of {max_size} and cannot be processed"
+                        )));
+                    }
+                    parameter_value
+                }
+                _ => Self::query_parameter_value(route_config, query, parameter.0, parameter.1)?,
+            };
+
+            parameters_payloads.add(
+                parameter.0.clone().into(),
+                ParameterPayload::new(parameter.1.clone(), parameter_value),
+            );
+        }
+
+        Ok((parameters_payloads, content_length))
+    }
+
+    #[inline]
+    async fn parse_headers_parameters<const N: usize, T: Read>(
+        route_config: &RouteConfig,
+        headers: &Headers<'_, N>,
+        body: &mut Body<'_, T>,
+        strict_parameters: bool,
+    ) -> Result<ToscaParametersPayloads<'static>, Response> {
+        info!("Headers: {headers:?}");
+
+        let content_length = Self::content_length(headers)?;
 
         if content_length > MAXIMUM_REQUEST_SIZE {
             return Err(error_response(&format!(
@@ -588,34 +967,26 @@ where
 
         info!("Route parameters: {route_parameters:?}");
 
-        let mut parameters_payloads = ToscaParametersPayloads::new();
-        for (parameter_name, parameter_value) in route_parameters {
-            let parameter_kind = route_config
-                .data
-                .parameters
-                .get(&parameter_name)
-                .ok_or_else(|| {
-                    invalid_data_response(&format!("Parameter `{parameter_name}` not found"))
-                })?;
-
-            if !parameter_value.match_kind(parameter_kind) {
-                return Err(invalid_data_response(&format!(
-                    "Found type `{}` for `{parameter_name}`, expected type `{}`",
-                    parameter_value.as_type(),
-                    parameter_kind.as_type(),
-                )));
-            }
-
-            parameters_payloads.add(
-                parameter_name,
-                ParameterPayload::new(parameter_kind.clone(), parameter_value),
-            );
-        }
+        route_config
+            .body_parameters(route_parameters, strict_parameters)
+            .map_err(|e| invalid_data_response(&e.to_string()))
+    }
 
-        Ok(parameters_payloads)
+    // Aligns a floating point value to its declared step, rejecting it
+    // when the route does not allow off-step values.
+    fn align_to_step(
+        route_config: &RouteConfig,
+        parameter_name: &str,
+        parameter_value: ParameterValue,
+        parameter_kind: &ParameterKind,
+    ) -> Result<ParameterValue, Response> {
+        route_config
+            .align_value(parameter_name, parameter_value, parameter_kind)
+            .map_err(|e| invalid_data_response(&e.to_string()))
     }
 
     fn parse_parameter_value(
+        parameter_name: &str,
         parameter_value: &str,
         parameter_kind: &ParameterKind,
     ) -> Result<ParameterValue, Response> {
@@ -635,15 +1006,35 @@ where
             ParameterKind::U64 { .. } | ParameterKind::RangeU64 { .. } => {
                 Self::into_value::<u64, _>(parameter_value, "u64", ParameterValue::U64)
             }
+            ParameterKind::U128 { .. } => {
+                Self::into_value::<u128, _>(parameter_value, "u128", ParameterValue::U128)
+            }
             ParameterKind::F32 { .. } => {
                 Self::into_value::<f32, _>(parameter_value, "f32", ParameterValue::F32)
             }
             ParameterKind::F64 { .. } | ParameterKind::RangeF64 { .. } => {
                 Self::into_value::<f64, _>(parameter_value, "f64", ParameterValue::F64)
             }
-            ParameterKind::CharsSequence { .. } => Ok(ParameterValue::CharsSequence(Cow::Owned(
-                parameter_value.to_string(),
-            ))),
+            ParameterKind::CharsSequence { max_length, .. } => {
+                let parameter_value =
+                    ParameterValue::CharsSequence(Cow::Owned(parameter_value.to_string()))
+                        .conceal(parameter_kind);
+                if let Some(max_length) = *max_length
+                    && !parameter_value.is_within_limits(parameter_kind)
+                {
+                    return Err(invalid_data_response(
+                        &RouteParameterError::TooLong {
+                            name: parameter_name.into(),
+                            max_length,
+                        }
+                        .to_string(),
+                    ));
+                }
+                Ok(parameter_value)
+            }
+            ParameterKind::ByteStream { .. } => Err(invalid_data_response(
+                "A byte stream cannot be passed in a route path",
+            )),
         }
     }
 
@@ -674,56 +1065,21 @@ where
         &self,
         index: usize,
         parameters_payloads: ParametersPayloads,
+        byte_stream: ByteStream<'_>,
     ) -> Response {
-        let func_index = self.device.index_array[index];
+        let Some(handler) = self.device.route_registry.get(index) else {
+            return ErrorResponse::internal("No handler registered for the route").0;
+        };
 
-        match func_index.func_type {
-            FuncType::OkStateless => {
-                let func = &self.device.routes_functions.0[func_index.index];
-                func(parameters_payloads).await.into()
-            }
-            FuncType::OkStateful => {
-                let func = &self.device.routes_functions.1[func_index.index];
-                func(
-                    State(S::value_from_ref(&self.device.state.0)),
-                    parameters_payloads,
-                )
-                .await
-                .into()
-            }
-            FuncType::SerialStateless => {
-                let func = &self.device.routes_functions.2[func_index.index];
-                func(parameters_payloads).await.into()
-            }
-            FuncType::SerialStateful => {
-                let func = &self.device.routes_functions.3[func_index.index];
-                func(
-                    State(S::value_from_ref(&self.device.state.0)),
-                    parameters_payloads,
-                )
-                .await
-                .into()
-            }
-            FuncType::InfoStateless => {
-                let func = &self.device.routes_functions.4[func_index.index];
-                func(parameters_payloads).await.into()
-            }
-            FuncType::InfoStateful => {
-                let func = &self.device.routes_functions.5[func_index.index];
-                func(
-                    State(S::value_from_ref(&self.device.state.0)),
-                    parameters_payloads,
-                )
-                .await
-                .into()
-            }
-        }
+        handler
+            .run(&self.device.state, parameters_payloads, byte_stream)
+            .await
     }
 
     const fn is_method_allowed(method: Method) -> bool {
         !matches!(
             method,
-            Method::Get | Method::Post | Method::Put | Method::Delete
+            Method::Get | Method::Post | Method::Put | Method::Delete | Method::Patch
         )
     }
 }
@@ -744,12 +1100,14 @@ impl<S: ValueFromRef + Send + Sync + 'static> Handler for ServerHandler<S> {
     {
         let (headers, body) = conn.split();
 
+        count_request();
+
         if headers.path == "/" {
             return self.device.main_route_response.write_from_ref(conn).await;
         }
 
         if Self::is_method_allowed(headers.method) {
-            return Response::not_allowed().write(conn).await;
+            return Response::not_allowed(headers.path).write(conn).await;
         }
 
         let route_info = match self
@@ -763,9 +1121,167 @@ impl<S: ValueFromRef + Send + Sync + 'static> Handler for ServerHandler<S> {
         let RouteInfo {
             index,
             parameters_payloads,
+            stream_len,
         } = route_info;
 
-        let response = self.run_function(index, parameters_payloads).await;
+        let response = self
+            .run_function(
+                index,
+                parameters_payloads,
+                ByteStream::new(body, stream_len),
+            )
+            .await;
         response.write(conn).await
     }
 }
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    use alloc::boxed::Box;
+
+    use tosca::device::DeviceInfo;
+    use tosca::parameters::ParametersPayloads as ToscaParametersPayloads;
+
+    use crate::block_on;
+    use crate::parameters::{ByteStream, ParametersPayloads};
+    use crate::response::{ErrorResponse, InfoResponse, OkResponse, Response, SerialResponse};
+    use crate::state::{State, ValueFromRef};
+
+    use super::{
+        InfoFn, InfoStateFn, OkFn, OkStateFn, OkStreamFn, OkStreamStateFn, RouteHandler,
+        RouteRegistry, SerialFn, SerialStateFn,
+    };
+
+    // Index of the last handler run by the registry.
+    static DISPATCHED: AtomicUsize = AtomicUsize::new(usize::MAX);
+
+    const BEARER_TOKEN: &str = "secret";
+
+    #[derive(Clone, Copy)]
+    struct Label(&'static str);
+
+    impl ValueFromRef for Label {
+        fn value_from_ref(&self) -> Self {
+            *self
+        }
+    }
+
+    // Registers a handler of each kind, guarding every other route.
+    fn route_registry() -> RouteRegistry<Label> {
+        let ok: OkFn = Box::new(|_| {
+            DISPATCHED.store(0, Ordering::Relaxed);
+            Box::pin(async { Ok(OkResponse::new()) })
+        });
+        let ok_stateful: OkStateFn<Label> = Box::new(|_, _| {
+            DISPATCHED.store(1, Ordering::Relaxed);
+            Box::pin(async { Ok(OkResponse::new()) })
+        });
+        let serial: SerialFn = Box::new(|_| {
+            DISPATCHED.store(2, Ordering::Relaxed);
+            Box::pin(async { Ok(SerialResponse::text("serial")) })
+        });
+        let serial_stateful: SerialStateFn<Label> = Box::new(|State(Label(label)), _| {
+            DISPATCHED.store(3, Ordering::Relaxed);
+            Box::pin(async move { Ok(SerialResponse::text(label)) })
+        });
+        let info: InfoFn = Box::new(|_| {
+            DISPATCHED.store(4, Ordering::Relaxed);
+            Box::pin(async { Ok(InfoResponse::new(DeviceInfo::empty())) })
+        });
+        let info_stateful: InfoStateFn<Label> = Box::new(|_, _| {
+            DISPATCHED.store(5, Ordering::Relaxed);
+            Box::pin(async { Ok(InfoResponse::new(DeviceInfo::empty())) })
+        });
+        let stream: OkStreamFn = Box::new(|_, _| {
+            DISPATCHED.store(6, Ordering::Relaxed);
+            Box::pin(async { Ok(OkResponse::new()) })
+        });
+        let stream_stateful: OkStreamStateFn<Label> =
+            Box::new(|State(Label(label)), _, byte_stream| {
+                DISPATCHED.store(7, Ordering::Relaxed);
+                Box::pin(async move {
+                    if byte_stream.remaining() == label.len() {
+                        Ok(OkResponse::new())
+                    } else {
+                        Err(ErrorResponse::invalid_data("Wrong byte stream"))
+                    }
+                })
+            });
+
+        let mut route_registry = RouteRegistry::new();
+        route_registry.register(RouteHandler::Ok(ok));
+        route_registry.register_guarded(RouteHandler::OkStateful(ok_stateful), BEARER_TOKEN.into());
+        route_registry.register(RouteHandler::Serial(serial));
+        route_registry.register_guarded(
+            RouteHandler::SerialStateful(serial_stateful),
+            BEARER_TOKEN.into(),
+        );
+        route_registry.register(RouteHandler::Info(info));
+        route_registry.register_guarded(
+            RouteHandler::InfoStateful(info_stateful),
+            BEARER_TOKEN.into(),
+        );
+        route_registry.register(RouteHandler::OkStream(stream));
+        route_registry.register_guarded(
+            RouteHandler::OkStreamStateful(stream_stateful),
+            BEARER_TOKEN.into(),
+        );
+        route_registry
+    }
+
+    fn dispatch(route_registry: &RouteRegistry<Label>, index: usize) -> Response {
+        let mut body: &[u8] = b"light";
+        block_on(route_registry.get(index).unwrap().run(
+            &State(Label("light")),
+            ParametersPayloads(ToscaParametersPayloads::new()),
+            ByteStream::new(&mut body, 5),
+        ))
+    }
+
+    #[init]
+    fn init() {
+        esp_alloc::heap_allocator!(size: 16 * 1024);
+    }
+
+    #[test]
+    fn test_route_registry_dispatch() {
+        let route_registry = route_registry();
+
+        // Each index runs the handler registered at it.
+        for index in 0..8 {
+            let response = dispatch(&route_registry, index);
+            assert_eq!(DISPATCHED.load(Ordering::Relaxed), index);
+
+            let expected = match index {
+                0 | 1 | 6 | 7 => Response::from(Ok::<_, ErrorResponse>(OkResponse::new())),
+                2 => Response::from(Ok::<_, ErrorResponse>(SerialResponse::text("serial"))),
+                3 => Response::from(Ok::<_, ErrorResponse>(SerialResponse::text("light"))),
+                // Information responses also contain the number of handled
+                // requests, which changes at each dispatch.
+                _ => continue,
+            };
+            assert_eq!(response.body(), expected.body());
+        }
+
+        // No handler is registered at the given index.
+        assert!(route_registry.get(8).is_none());
+    }
+
+    #[test]
+    fn test_route_registry_authorize() {
+        let route_registry = route_registry();
+
+        for index in 0..8 {
+            let guarded = index % 2 == 1;
+            assert_eq!(route_registry.authorize(index, None), !guarded);
+            assert_eq!(
+                route_registry.authorize(index, Some("Bearer wrong")),
+                !guarded
+            );
+            assert!(route_registry.authorize(index, Some("Bearer secret")));
+        }
+    }
+}
@@ -1,21 +1,87 @@
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use tosca::backoff::Backoff;
+
 use embassy_executor::Spawner;
 
 use esp_hal::peripherals::WIFI;
 
 use esp_radio::Controller;
 use esp_radio::wifi::{
-    ClientConfig, Config, Interfaces, ModeConfig, WifiController, WifiEvent, WifiStaState,
-    sta_state,
+    ClientConfig, Config, EapClientConfig, Interfaces, ModeConfig, ScanConfig, TtlsPhase2Method,
+    WifiController, WifiEvent, WifiStaState, sta_state,
 };
 
 use log::{error, info};
 
 use crate::error::{Error, ErrorKind, Result};
+use crate::mdns::announce;
 use crate::mk_static;
 
 pub(crate) const WIFI_RECONNECT_DELAY: u64 = 2;
 
+// Maximum delay, in seconds, between two reconnection attempts.
+const WIFI_MAX_RECONNECT_DELAY: u64 = 64;
+
+// Whether the device is connected to the access point.
+static CONNECTED: AtomicBool = AtomicBool::new(false);
+
+/// Returns whether the device is connected to a `Wi-Fi` access point.
+///
+/// Application code can use this function to observe the connectivity
+/// while the device reconnects after a link drop.
+#[must_use]
+pub fn is_connected() -> bool {
+    CONNECTED.load(Ordering::Relaxed)
+}
+
+/// The configuration of a `WPA2-Enterprise` network.
+///
+/// The identity and the credentials are the ones used by the `EAP`
+/// authentication, while further `EAP` parameters can be set through the
+/// builder methods.
+pub struct EnterpriseConfig<'a> {
+    identity: &'a str,
+    username: &'a str,
+    password: &'a str,
+    ca_cert: Option<&'static [u8]>,
+    ttls_phase2_method: Option<TtlsPhase2Method>,
+}
+
+impl<'a> EnterpriseConfig<'a> {
+    /// Creates an [`EnterpriseConfig`] from an identity, a username,
+    /// and a password.
+    #[must_use]
+    pub const fn new(identity: &'a str, username: &'a str, password: &'a str) -> Self {
+        Self {
+            identity,
+            username,
+            password,
+            ca_cert: None,
+            ttls_phase2_method: None,
+        }
+    }
+
+    /// Sets the certificate authority used to verify the
+    /// authentication server.
+    #[must_use]
+    pub const fn ca_cert(mut self, ca_cert: &'static [u8]) -> Self {
+        self.ca_cert = Some(ca_cert);
+        self
+    }
+
+    /// Sets the `TTLS` phase 2 authentication method.
+    #[must_use]
+    pub const fn ttls_phase2_method(mut self, ttls_phase2_method: TtlsPhase2Method) -> Self {
+        self.ttls_phase2_method = Some(ttls_phase2_method);
+        self
+    }
+}
+
 /// The `Wi-Fi` controller.
+///
+/// Both `PSK` and `WPA2-Enterprise` networks are supported by the `wifi`
+/// feature of `esp-radio`, which this crate already enables.
 pub struct Wifi {
     _esp_radio_controller: &'static Controller<'static>,
     controller: WifiController<'static>,
@@ -45,6 +111,11 @@ impl Wifi {
 
     /// Connects a device to a `Wi-Fi` access point.
     ///
+    /// Whenever the access point drops, the device tries to reconnect with an
+    /// exponential backoff, announcing the `mDNS` service again on recovery.
+    /// Reconnections are performed by the same task spawned by this method,
+    /// so no additional task must be counted.
+    ///
     /// # Errors
     ///
     /// - Missing `Wi-Fi` SSID
@@ -52,7 +123,7 @@ impl Wifi {
     /// - Failure to set up the `Wi-Fi` configuration
     /// - Failure to spawn the task to connect the device to the access point
     ///   via `Wi-Fi`.
-    pub async fn connect(mut self, ssid: &str, password: &str) -> Result<Interfaces<'static>> {
+    pub async fn connect(self, ssid: &str, password: &str) -> Result<Interfaces<'static>> {
         if ssid.is_empty() {
             return Err(Error::new(ErrorKind::WiFi, "Missing Wi-Fi SSID"));
         }
@@ -67,7 +138,108 @@ impl Wifi {
                 .with_password(password.into()),
         );
 
-        self.controller.set_config(&client_config)?;
+        self.start(&client_config).await
+    }
+
+    /// Connects a device to a `Wi-Fi` access point which does not broadcast
+    /// its SSID.
+    ///
+    /// An active scan looks for the access point before associating with it.
+    ///
+    /// # Errors
+    ///
+    /// - Missing `Wi-Fi` SSID
+    /// - Missing `Wi-Fi` password
+    /// - Failure to start the `Wi-Fi` controller or to scan the networks
+    /// - No access point found with the given SSID
+    /// - Failure to set up the `Wi-Fi` configuration
+    /// - Failure to spawn the task to connect the device to the access point
+    ///   via `Wi-Fi`.
+    pub async fn connect_hidden(
+        mut self,
+        ssid: &str,
+        password: &str,
+    ) -> Result<Interfaces<'static>> {
+        if ssid.is_empty() {
+            return Err(Error::new(ErrorKind::WiFi, "Missing Wi-Fi SSID"));
+        }
+
+        if password.is_empty() {
+            return Err(Error::new(ErrorKind::WiFi, "Missing Wi-Fi password"));
+        }
+
+        // The controller must be started to scan the networks.
+        self.controller.start_async().await?;
+
+        info!("Scanning for hidden SSID...");
+        let access_point = self
+            .controller
+            .scan_with_config_async(ScanConfig::default().with_ssid(ssid).with_show_hidden(true))
+            .await?
+            .into_iter()
+            .max_by_key(|access_point| access_point.signal_strength)
+            .ok_or(Error::new(ErrorKind::WiFi, "Hidden SSID not found"))?;
+
+        let client_config = ModeConfig::Client(
+            ClientConfig::default()
+                .with_ssid(ssid.into())
+                .with_password(password.into())
+                .with_bssid(access_point.bssid)
+                .with_channel(access_point.channel),
+        );
+
+        self.start(&client_config).await
+    }
+
+    /// Connects a device to a `WPA2-Enterprise` access point.
+    ///
+    /// # Errors
+    ///
+    /// - Missing `Wi-Fi` SSID
+    /// - Missing `EAP` identity, username, or password
+    /// - Failure to set up the `Wi-Fi` configuration
+    /// - Failure to spawn the task to connect the device to the access point
+    ///   via `Wi-Fi`.
+    pub async fn connect_enterprise(
+        self,
+        ssid: &str,
+        config: EnterpriseConfig<'_>,
+    ) -> Result<Interfaces<'static>> {
+        if ssid.is_empty() {
+            return Err(Error::new(ErrorKind::WiFi, "Missing Wi-Fi SSID"));
+        }
+
+        if config.identity.is_empty() {
+            return Err(Error::new(ErrorKind::WiFi, "Missing EAP identity"));
+        }
+
+        if config.username.is_empty() {
+            return Err(Error::new(ErrorKind::WiFi, "Missing EAP username"));
+        }
+
+        if config.password.is_empty() {
+            return Err(Error::new(ErrorKind::WiFi, "Missing EAP password"));
+        }
+
+        let mut eap_config = EapClientConfig::default()
+            .with_ssid(ssid.into())
+            .with_identity(config.identity.into())
+            .with_username(config.username.into())
+            .with_password(config.password.into());
+
+        if let Some(ca_cert) = config.ca_cert {
+            eap_config = eap_config.with_ca_cert(ca_cert);
+        }
+
+        if let Some(ttls_phase2_method) = config.ttls_phase2_method {
+            eap_config = eap_config.with_ttls_phase2_method(ttls_phase2_method);
+        }
+
+        self.start(&ModeConfig::EapClient(eap_config)).await
+    }
+
+    async fn start(mut self, mode_config: &ModeConfig) -> Result<Interfaces<'static>> {
+        self.controller.set_config(mode_config)?;
 
         self.spawner.spawn(connect(self.controller))?;
 
@@ -83,11 +255,15 @@ impl Wifi {
 #[embassy_executor::task]
 async fn connect(mut wifi_controller: WifiController<'static>) {
     info!("Wi-Fi connection task started");
+    let mut backoff = Backoff::new(WIFI_RECONNECT_DELAY, WIFI_MAX_RECONNECT_DELAY);
+    let mut reconnecting = false;
     loop {
         if sta_state() == WifiStaState::Connected {
             wifi_controller
                 .wait_for_event(WifiEvent::StaDisconnected)
                 .await;
+            CONNECTED.store(false, Ordering::Relaxed);
+            reconnecting = true;
             embassy_time::Timer::after_secs(WIFI_RECONNECT_DELAY).await;
         }
 
@@ -103,10 +279,17 @@ async fn connect(mut wifi_controller: WifiController<'static>) {
 
         info!("Attempting to connect...");
         if let Err(e) = wifi_controller.connect_async().await {
-            error!("Wi-Fi connect failed: {e:?}");
-            embassy_time::Timer::after_secs(WIFI_RECONNECT_DELAY).await;
+            let delay = backoff.next_delay();
+            error!("Wi-Fi connect failed: {e:?}, retrying in {delay} seconds");
+            embassy_time::Timer::after_secs(delay).await;
         } else {
             info!("Wi-Fi connected!");
+            CONNECTED.store(true, Ordering::Relaxed);
+            backoff.reset();
+            if reconnecting {
+                announce();
+                reconnecting = false;
+            }
         }
     }
 }
@@ -118,7 +118,7 @@ fn camera_format(
     Ok(CameraFormat::new(resolution, fourcc, fps))
 }
 
-fn change_format(device: Device<InternalState>) -> Device<InternalState> {
+fn change_format(device: Device<InternalState>) -> tosca_os::error::Result<Device<InternalState>> {
     // Route to change camera index.
     let change_camera_route = Route::get("Change camera", "/change-camera")
         .description("Change camera.")
@@ -174,30 +174,30 @@ fn change_format(device: Device<InternalState>) -> Device<InternalState> {
                 .characters_sequence("fourcc", "YUYV"),
         );
 
-    device
-        .route(serial_stateful(change_camera_route, change_camera))
-        .route(ok_stateful(change_format_random_route, format_random))
+    Ok(device
+        .route(serial_stateful(change_camera_route, change_camera))?
+        .route(ok_stateful(change_format_random_route, format_random))?
         .route(ok_stateful(
             change_format_absolute_highest_resolution_route,
             format_absolute_resolution,
-        ))
+        ))?
         .route(ok_stateful(
             change_format_absolute_highest_framerate_route,
             format_absolute_framerate,
-        ))
+        ))?
         .route(ok_stateful(
             change_format_highest_resolution_route,
             format_highest_resolution,
-        ))
+        ))?
         .route(ok_stateful(
             change_format_highest_framerate_route,
             format_highest_framerate,
-        ))
-        .route(ok_stateful(change_format_exact_route, format_exact))
-        .route(ok_stateful(change_format_closest_route, format_closest))
+        ))?
+        .route(ok_stateful(change_format_exact_route, format_exact))?
+        .route(ok_stateful(change_format_closest_route, format_closest))?)
 }
 
-fn screenshot(device: Device<InternalState>) -> Device<InternalState> {
+fn screenshot(device: Device<InternalState>) -> tosca_os::error::Result<Device<InternalState>> {
     // Route to take a screenshot with a random format.
     let screenshot_random_route = Route::get("Screenshot random", "/screenshot-random")
         .description("Screenshot with a random camera format.")
@@ -289,29 +289,29 @@ fn screenshot(device: Device<InternalState>) -> Device<InternalState> {
                 .characters_sequence("fourcc", "YUYV"),
         );
 
-    device
-        .route(stream_stateful(screenshot_random_route, screenshot_random))
+    Ok(device
+        .route(stream_stateful(screenshot_random_route, screenshot_random))?
         .route(stream_stateful(
             screenshot_absolute_resolution_route,
             screenshot_absolute_resolution,
-        ))
+        ))?
         .route(stream_stateful(
             screenshot_absolute_framerate_route,
             screenshot_absolute_framerate,
-        ))
+        ))?
         .route(stream_stateful(
             screenshot_highest_resolution_route,
             screenshot_highest_resolution,
-        ))
+        ))?
         .route(stream_stateful(
             screenshot_highest_framerate_route,
             screenshot_highest_framerate,
-        ))
-        .route(stream_stateful(screenshot_exact_route, screenshot_exact))
+        ))?
+        .route(stream_stateful(screenshot_exact_route, screenshot_exact))?
         .route(stream_stateful(
             screenshot_closest_route,
             screenshot_closest,
-        ))
+        ))?)
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -383,11 +383,14 @@ async fn main() -> Result<(), Error> {
     let device = Device::with_state(InternalState::new(camera))
         .main_route("/camera")
         .route(stream_stateful(camera_stream_route, show_camera_stream))
+        .map_err(Error::Tosca)?
         .route(serial_stateless(view_cameras_route, show_available_cameras))
-        .route(serial_stateful(camera_info_route, show_camera_info));
+        .map_err(Error::Tosca)?
+        .route(serial_stateful(camera_info_route, show_camera_info))
+        .map_err(Error::Tosca)?;
 
-    let device = change_format(device);
-    let device = screenshot(device);
+    let device = change_format(device).map_err(Error::Tosca)?;
+    let device = screenshot(device).map_err(Error::Tosca)?;
 
     Server::new(device)
         .address(cli.address)
@@ -3,7 +3,7 @@ use std::io::Cursor;
 use tosca_os::responses::error::ErrorResponse;
 use tosca_os::responses::stream::StreamResponse;
 
-use tosca_os::extract::{State, header};
+use tosca_os::extract::State;
 
 use image::ImageFormat;
 
@@ -16,12 +16,6 @@ use tracing::{error, info};
 
 use crate::{InternalState, thread_error};
 
-const FRAME_BOUNDARY: &str = concat!("\r\n--", "123456789000000000000987654321", "\r\n");
-const CONTENT_TYPE: &str = concat!(
-    "multipart/x-mixed-replace;boundary=",
-    "123456789000000000000987654321"
-);
-
 // To avoid busy resources we need a total time of 200ms.
 pub(crate) async fn show_camera_stream(
     State(state): State<InternalState>,
@@ -100,30 +94,18 @@ pub(crate) async fn show_camera_stream(
 
             info!("Image size {}", bytes.capacity());
 
-            let mut response: Vec<u8> = Vec::new();
-            response.extend(
-                format!(
-                    "Content-Type: image/jpeg\r\nContent-Length: {}\r\n\r\n",
-                    bytes.len()
-                )
-                .as_bytes(),
-            );
-            response.extend(&bytes);
-            response.extend(FRAME_BOUNDARY.as_bytes());
-
             // If we do not add this check, we could send data to a
             // non-existent channel.
             if !tx.is_closed()
-                && let Err(e) = tx.send(Ok(response))
+                && let Err(e) = tx.send(Ok(bytes))
             {
                 error!("Error sending image {e}");
             }
         }
     });
 
-    let headers = [(header::CONTENT_TYPE, CONTENT_TYPE)];
-    Ok(StreamResponse::from_headers_stream(
-        headers,
+    // Each frame is sent as soon as it is captured.
+    Ok(StreamResponse::mjpeg(
         tokio_stream::wrappers::UnboundedReceiverStream::new(rx),
     ))
 }
@@ -11,7 +11,7 @@ use tosca::route::{LightOffRoute, LightOnRoute, Route};
 
 use tosca_os::devices::light::Light;
 use tosca_os::error::Error;
-use tosca_os::extract::{FromRef, Json, State};
+use tosca_os::extract::{FromRef, Params, State};
 use tosca_os::responses::error::ErrorResponse;
 use tosca_os::responses::info::{InfoResponse, info_stateful};
 use tosca_os::responses::ok::{OkResponse, mandatory_ok_stateful, ok_stateful};
@@ -123,7 +123,7 @@ struct Inputs {
 
 async fn turn_light_on(
     State(state): State<InternalState>,
-    Json(inputs): Json<Inputs>,
+    Params(inputs): Params<Inputs>,
 ) -> Result<SerialResponse<LightOnResponse>, ErrorResponse> {
     let mut light = state.lock().await;
     light.turn_light_on(inputs.brightness, inputs.save_energy);
@@ -267,12 +267,12 @@ async fn main() -> Result<(), Error> {
         .turn_light_off(light_off_route, mandatory_ok_stateful(turn_light_off))
         .route(serial_stateful(light_on_post_route, turn_light_on))?
         .route(ok_stateful(toggle_route, toggle))?
-        .info_route(info_stateful(info_route, info))
+        .info_route(info_stateful(info_route, info))?
         .info_route(info_stateful(
             update_energy_efficiency_route,
             update_energy_efficiency,
-        ))
-        .build();
+        ))?
+        .build()?;
 
     // Run a discovery service and the device on the server.
     Server::new(device)
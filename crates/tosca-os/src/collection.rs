@@ -0,0 +1,249 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use tosca::hazards::Hazards;
+use tosca::parameters::Parameters;
+use tosca::route::Route;
+
+use axum::extract::Path;
+
+use serde::Serialize;
+
+use crate::responses::BaseResponse;
+use crate::responses::error::ErrorResponse;
+use crate::responses::ok::{OkResponse, ok_stateless};
+use crate::responses::serial::{SerialResponse, serial_stateless};
+
+// Name of the parameter identifying a resource.
+const RESOURCE_ID: &str = "id";
+
+/// The identifiers of the resources stored in a [`ResourceCollection`].
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ResourceIds {
+    /// Resource identifiers, in ascending order.
+    pub ids: Vec<String>,
+}
+
+/// A collection of resources managed by a device, such as stored
+/// screenshots, each identified by a unique identifier.
+///
+/// A collection can be shared among route handlers as a device state, since
+/// its clones share the same resources. It also provides the routes to list
+/// the identifiers of its resources and to delete a resource.
+#[derive(Debug)]
+pub struct ResourceCollection<T>(Arc<Mutex<BTreeMap<String, T>>>);
+
+impl<T> Clone for ResourceCollection<T> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<T> Default for ResourceCollection<T> {
+    fn default() -> Self {
+        Self(Arc::new(Mutex::new(BTreeMap::new())))
+    }
+}
+
+impl<T> ResourceCollection<T> {
+    /// Creates an empty [`ResourceCollection`].
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts a resource with the given identifier, returning the resource
+    /// previously stored with the same identifier, if any.
+    pub fn insert(&self, id: impl Into<String>, resource: T) -> Option<T> {
+        self.resources().insert(id.into(), resource)
+    }
+
+    /// Removes the resource with the given identifier, returning it.
+    // The removal is the effect of the call, so its result can be ignored.
+    #[allow(clippy::must_use_candidate)]
+    pub fn remove(&self, id: &str) -> Option<T> {
+        self.resources().remove(id)
+    }
+
+    /// Returns a copy of the resource with the given identifier.
+    #[must_use]
+    pub fn get(&self, id: &str) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.resources().get(id).cloned()
+    }
+
+    /// Returns the identifiers of the stored resources, in ascending order.
+    #[must_use]
+    pub fn ids(&self) -> Vec<String> {
+        self.resources().keys().cloned().collect()
+    }
+
+    /// Returns the number of stored resources.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.resources().len()
+    }
+
+    /// Checks whether the collection is empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.resources().is_empty()
+    }
+
+    fn resources(&self) -> MutexGuard<'_, BTreeMap<String, T>> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T: Send + 'static> ResourceCollection<T> {
+    /// Creates a `GET` route at the given path, which lists the
+    /// [`ResourceIds`] of the collection.
+    ///
+    /// The route declares the given [`Hazards`], since identifiers might
+    /// reveal private information, such as the time a screenshot was taken.
+    /// [`Hazards::known_safe`] declares that listing them poses no risks.
+    pub fn list_route<S>(
+        &self,
+        path: &'static str,
+        hazards: Hazards,
+    ) -> impl FnOnce(S) -> BaseResponse + use<T, S>
+    where
+        S: Clone + Send + Sync + 'static,
+    {
+        let collection = self.clone();
+        serial_stateless(
+            Route::get("List resources", path)
+                .description("List the identifiers of the stored resources.")
+                .with_hazards(hazards),
+            move || async move {
+                Ok(SerialResponse::new(ResourceIds {
+                    ids: collection.ids(),
+                }))
+            },
+        )
+    }
+
+    /// Creates a `DELETE` route at the given path, which deletes the
+    /// resource whose identifier is passed as the `id` path parameter.
+    ///
+    /// A missing resource is reported with a not found error. The route
+    /// declares the given [`Hazards`]: deleting a resource destroys data, so
+    /// it is never marked as known safe on behalf of the caller.
+    pub fn delete_route<S>(
+        &self,
+        path: &'static str,
+        hazards: Hazards,
+    ) -> impl FnOnce(S) -> BaseResponse + use<T, S>
+    where
+        S: Clone + Send + Sync + 'static,
+    {
+        let collection = self.clone();
+        ok_stateless(
+            Route::delete("Delete resource", path)
+                .description("Delete a stored resource.")
+                .with_parameters(Parameters::new().characters_sequence(RESOURCE_ID, ""))
+                .with_hazards(hazards),
+            move |Path(id): Path<String>| async move {
+                collection
+                    .remove(&id)
+                    .map(|_| OkResponse::ok())
+                    .ok_or_else(|| ErrorResponse::not_found(&format!("No resource `{id}`")))
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tosca::hazards::{Hazard, Hazards};
+    use tosca::response::ResponseKind;
+    use tosca::route::RestKind;
+
+    use axum::body::{Body, to_bytes};
+    use axum::http::{Request, StatusCode};
+
+    use tower::ServiceExt;
+
+    use crate::device::Device;
+
+    use super::ResourceCollection;
+
+    async fn send(
+        router: &axum::Router,
+        request: Request<Body>,
+    ) -> (StatusCode, serde_json::Value) {
+        let response = router.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    async fn list(router: &axum::Router) -> serde_json::Value {
+        let (status, body) = send(
+            router,
+            Request::get("/screenshots").body(Body::empty()).unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        body["ids"].clone()
+    }
+
+    #[tokio::test]
+    async fn list_and_delete_resources() {
+        let screenshots = ResourceCollection::new();
+        screenshots.insert("first", vec![1u8]);
+        screenshots.insert("second", vec![2u8]);
+
+        let (_, device_data, router) = Device::new()
+            .route(screenshots.list_route("/screenshots", Hazards::known_safe()))
+            .unwrap()
+            .route(screenshots.delete_route(
+                "/screenshots",
+                Hazards::new().insert(Hazard::TakeDeviceScreenshots),
+            ))
+            .unwrap()
+            .finalize();
+
+        for route_config in &device_data.route_configs {
+            let hazards = &route_config.data.hazards;
+            match route_config.rest_kind {
+                RestKind::Get => {
+                    assert_eq!(route_config.response_kind, ResponseKind::Serial);
+                    assert!(hazards.is_known_safe());
+                }
+                RestKind::Delete => {
+                    assert_eq!(route_config.response_kind, ResponseKind::Ok);
+                    assert!(hazards.contains(&Hazard::TakeDeviceScreenshots));
+                }
+                _ => panic!("Unexpected route {route_config:?}"),
+            }
+        }
+
+        assert_eq!(list(&router).await, serde_json::json!(["first", "second"]));
+
+        let (status, _) = send(
+            &router,
+            Request::delete("/screenshots/first")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        assert_eq!(list(&router).await, serde_json::json!(["second"]));
+        assert_eq!(screenshots.get("second"), Some(vec![2]));
+
+        // A missing resource.
+        let (status, _) = send(
+            &router,
+            Request::delete("/screenshots/first")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
@@ -1,16 +1,72 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+
 use tosca::device::{DeviceData, DeviceEnvironment, DeviceKind};
 use tosca::route::{RouteConfig, RouteConfigs};
 
+use tosca::route::RestKind;
+
+#[cfg(feature = "sse")]
+use tosca::events::EVENTS_SSE_ROUTE;
+#[cfg(feature = "sse")]
+use tosca::hazards::Hazards;
+#[cfg(feature = "sse")]
+use tosca::response::ResponseKind;
+#[cfg(feature = "sse")]
+use tosca::route::Route;
+
 use axum::Router;
+use axum::extract::Request;
+use axum::http::{StatusCode, header};
+use axum::middleware::Next;
+use axum::response::IntoResponse;
 
 use tracing::{info, warn};
 
+use crate::error::{Error, ErrorKind, Result};
 use crate::mac::get_mac_addresses;
-use crate::responses::BaseResponse;
+use crate::responses::{BaseResponse, error::ErrorResponse, router_path};
+#[cfg(feature = "sse")]
+use crate::sse::{EventStream, events_sse};
+#[cfg(feature = "webhook")]
+use crate::webhook::EventWebhook;
 
 // Default main route.
 const MAIN_ROUTE: &str = "/device";
 
+/// A guard deciding whether a route can be invoked in the current
+/// device state.
+///
+/// When the guard returns `false`, the route handler is not run and an
+/// [`ErrorResponse`] with a `409 Conflict` status is returned instead.
+pub struct StateGuard<S>(Arc<GuardFn<S>>);
+
+// A function checking whether a route can be invoked in a device state.
+type GuardFn<S> = dyn Fn(&S, &RouteConfig) -> bool + Send + Sync;
+
+impl<S> StateGuard<S> {
+    /// Creates a [`StateGuard`] from a function receiving the current device
+    /// state and the configuration of the invoked route.
+    pub fn new<F>(guard: F) -> Self
+    where
+        F: Fn(&S, &RouteConfig) -> bool + Send + Sync + 'static,
+    {
+        Self(Arc::new(guard))
+    }
+}
+
+impl<S> Clone for StateGuard<S> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<S> std::fmt::Debug for StateGuard<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("StateGuard")
+    }
+}
+
 /// A general `device`.
 #[derive(Debug)]
 pub struct Device<S = ()>
@@ -29,6 +85,16 @@ where
     route_configs: RouteConfigs,
     // Number of mandatory routes.
     num_mandatory_routes: u8,
+    // Device location.
+    location: Option<Cow<'static, str>>,
+    // Guard checking the device state before invoking a route.
+    state_guard: Option<StateGuard<S>>,
+    // Webhook delivering device events.
+    #[cfg(feature = "webhook")]
+    event_webhook: Option<EventWebhook>,
+    // Stream delivering device events through Server-Sent Events.
+    #[cfg(feature = "sse")]
+    event_stream: Option<EventStream>,
 }
 
 impl Default for Device<()> {
@@ -64,21 +130,164 @@ where
         self
     }
 
-    /// Adds a route to [`Device`].
+    /// Sets the device location, such as a room or a zone.
+    ///
+    /// The location is advertised by the discovery service, so controllers
+    /// learn it without contacting the device. An information route can
+    /// also return it through
+    /// [`DeviceInfo::with_location`](tosca::device::DeviceInfo::with_location).
+    #[must_use]
+    #[inline]
+    pub fn location(mut self, location: impl Into<Cow<'static, str>>) -> Self {
+        self.location = Some(location.into());
+        self
+    }
+
+    /// Describes [`Device`] through a [`DeviceKind::Custom`] kind, such as
+    /// `WaterValve`, when it does not belong to any predefined kind.
+    ///
+    /// A custom kind has no mandatory routes, while the name of a predefined
+    /// kind, such as `Light`, describes [`Device`] as that kind.
     #[must_use]
     #[inline]
-    pub fn route(self, route: impl FnOnce(S) -> BaseResponse) -> Self {
+    pub fn custom_kind(mut self, name: impl Into<Cow<'static, str>>) -> Self {
+        self.kind = DeviceKind::custom(name);
+        self
+    }
+
+    /// Sets a [`StateGuard`] for [`Device`].
+    ///
+    /// The guard is only applied to the routes added after this call.
+    #[must_use]
+    pub fn state_guard(mut self, state_guard: StateGuard<S>) -> Self {
+        self.state_guard = Some(state_guard);
+        self
+    }
+
+    /// Delivers device events to the given `URL` through `POST` requests.
+    ///
+    /// Route handlers retrieve the [`EventWebhook`] through the
+    /// [`crate::extract::Extension`] extractor.
+    #[cfg(feature = "webhook")]
+    #[must_use]
+    #[inline]
+    pub fn on_event_post(self, url: &str) -> Self {
+        self.event_webhook(EventWebhook::new(url))
+    }
+
+    /// Sets an [`EventWebhook`] delivering device events.
+    ///
+    /// Route handlers retrieve the [`EventWebhook`] through the
+    /// [`crate::extract::Extension`] extractor.
+    #[cfg(feature = "webhook")]
+    #[must_use]
+    pub fn event_webhook(mut self, event_webhook: EventWebhook) -> Self {
+        self.event_webhook = Some(event_webhook);
+        self
+    }
+
+    /// Sets an [`EventStream`] delivering device events through
+    /// Server-Sent Events, served at the [`EVENTS_SSE_ROUTE`] route.
+    ///
+    /// The route is part of the device description, declaring the given
+    /// [`Hazards`], so controllers apply their privacy policies to it as
+    /// to any other route.
+    ///
+    /// Route handlers retrieve the [`EventStream`] through the
+    /// [`crate::extract::Extension`] extractor.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a route with the same path and
+    /// [`tosca::route::RestKind`] has already been added.
+    #[cfg(feature = "sse")]
+    pub fn event_stream(mut self, event_stream: EventStream, hazards: Hazards) -> Result<Self> {
+        let route_config = Route::get("Events", EVENTS_SSE_ROUTE)
+            .description("Stream device events through Server-Sent Events.")
+            .with_hazards(hazards)
+            .serialize_data()
+            .change_response_kind(ResponseKind::Stream);
+        let router = Router::new().route(EVENTS_SSE_ROUTE, axum::routing::get(events_sse));
+
+        self = self.response_data((route_config, router))?;
+        self.event_stream = Some(event_stream);
+        Ok(self)
+    }
+
+    /// Adds a route to [`Device`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the route path is not valid, or if a route with
+    /// the same path and [`tosca::route::RestKind`] has already been added.
+    #[inline]
+    pub fn route(self, route: impl FnOnce(S) -> BaseResponse) -> Result<Self> {
         let base_response = route(self.state.clone());
         self.response_data(base_response.finalize())
     }
 
     /// Adds an informative route to [`Device`].
-    #[must_use]
-    pub fn info_route(self, device_info_route: impl FnOnce(S, ()) -> BaseResponse) -> Self {
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the route path is not valid, or if a route with
+    /// the same path and [`tosca::route::RestKind`] has already been added.
+    pub fn info_route(self, device_info_route: impl FnOnce(S, ()) -> BaseResponse) -> Result<Self> {
         let base_response = device_info_route(self.state.clone(), ());
         self.response_data(base_response.finalize())
     }
 
+    /// Returns the number of actions added to [`Device`].
+    #[must_use]
+    #[inline]
+    pub fn actions_len(&self) -> usize {
+        self.route_configs.len()
+    }
+
+    /// Validates [`Device`], checking that all mandatory routes of its
+    /// [`DeviceKind`] have been added.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error listing the missing mandatory routes.
+    pub fn validate(&self) -> Result<()> {
+        let missing_routes = self
+            .kind
+            .mandatory_routes()
+            .iter()
+            .filter(|(path, rest_kinds)| {
+                !self.route_configs.iter().any(|route_config| {
+                    route_config.data.path == *path && rest_kinds.contains(&route_config.rest_kind)
+                })
+            })
+            .map(|(path, rest_kinds)| {
+                let rest_kinds = rest_kinds
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>();
+                format!("{path} ({})", rest_kinds.join(" or "))
+            })
+            .collect::<Vec<_>>();
+
+        if missing_routes.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::new(
+                ErrorKind::Route,
+                format!(
+                    "{} device is missing mandatory routes: {}",
+                    self.kind,
+                    missing_routes.join(", ")
+                ),
+            ))
+        }
+    }
+
+    // Returns the device location advertised by the discovery service.
+    pub(crate) fn advertised_location(&self) -> Option<&str> {
+        self.location.as_deref()
+    }
+
     pub(crate) fn init(kind: DeviceKind, state: S) -> Self {
         Self {
             main_route: MAIN_ROUTE,
@@ -87,28 +296,65 @@ where
             route_configs: RouteConfigs::new(),
             state,
             num_mandatory_routes: 0,
+            location: None,
+            state_guard: None,
+            #[cfg(feature = "webhook")]
+            event_webhook: None,
+            #[cfg(feature = "sse")]
+            event_stream: None,
         }
     }
 
-    pub(crate) fn response_data(mut self, data: (RouteConfig, Router)) -> Self {
-        self.router = self.router.merge(data.1);
+    pub(crate) fn response_data(mut self, data: (RouteConfig, Router)) -> Result<Self> {
+        Self::check_route(&self.route_configs, &data.0)?;
+
+        let router = self.guard_router(&data.0, data.1);
+        self.router = self.router.merge(router);
         self.route_configs.add(data.0);
-        self
+        Ok(self)
     }
 
-    pub(crate) fn mandatory_response_data<I>(mut self, responses: I) -> Self
+    pub(crate) fn mandatory_response_data<I>(mut self, responses: I) -> Result<Self>
     where
         I: IntoIterator<Item = (RouteConfig, Router)>,
     {
         let mut mandatory_routes = RouteConfigs::new();
         for response in responses {
-            self.router = self.router.merge(response.1);
+            Self::check_route(&self.route_configs, &response.0)?;
+            Self::check_route(&mandatory_routes, &response.0)?;
+
+            let router = self.guard_router(&response.0, response.1);
+            self.router = self.router.merge(router);
             self.num_mandatory_routes += 1;
             mandatory_routes.add(response.0);
         }
 
         self.route_configs = mandatory_routes.merge(self.route_configs);
-        self
+        Ok(self)
+    }
+
+    // Checks whether a route is valid and has not been added yet.
+    //
+    // The router of an invalid route is empty, so it must never be merged.
+    fn check_route(route_configs: &RouteConfigs, route_config: &RouteConfig) -> Result<()> {
+        if let Err(e) = route_config.validate() {
+            return Err(Error::new(
+                ErrorKind::Route,
+                format!("Route \"{}\" is not valid: {e}", route_config.data.path),
+            ));
+        }
+
+        if route_configs.contains(route_config) {
+            return Err(Error::new(
+                ErrorKind::Route,
+                format!(
+                    "Route [{}, \"{}\"] has already been added",
+                    route_config.rest_kind, route_config.data.path
+                ),
+            ));
+        }
+
+        Ok(())
     }
 
     pub(crate) fn finalize(self) -> (&'static str, DeviceData, Router) {
@@ -124,6 +370,22 @@ where
             );
         }
 
+        let router = Self::options_routes(self.router, &self.route_configs);
+
+        #[cfg(feature = "webhook")]
+        let router = if let Some(event_webhook) = self.event_webhook {
+            router.layer(axum::Extension(event_webhook))
+        } else {
+            router
+        };
+
+        #[cfg(feature = "sse")]
+        let router = if let Some(event_stream) = self.event_stream {
+            router.layer(axum::Extension(event_stream))
+        } else {
+            router
+        };
+
         (
             self.main_route,
             DeviceData::new(
@@ -135,9 +397,69 @@ where
                 self.route_configs,
                 self.num_mandatory_routes,
             ),
-            self.router,
+            router,
         )
     }
+
+    // Rejects the invocation of a route when the state guard forbids it.
+    fn guard_router(&self, route_config: &RouteConfig, router: Router) -> Router {
+        let Some(state_guard) = self.state_guard.clone() else {
+            return router;
+        };
+
+        let state = self.state.clone();
+        let route_config = route_config.clone();
+
+        router.route_layer(axum::middleware::from_fn(
+            move |request: Request, next: Next| {
+                let allowed = (state_guard.0)(&state, &route_config);
+                async move {
+                    if allowed {
+                        next.run(request).await
+                    } else {
+                        ErrorResponse::invalid_state(
+                            "The current device state does not allow this operation",
+                        )
+                        .into_response()
+                    }
+                }
+            },
+        ))
+    }
+
+    // Adds an `OPTIONS` responder for each router path, advertising
+    // the methods allowed on that path.
+    fn options_routes(mut router: Router, route_configs: &RouteConfigs) -> Router {
+        let mut allowed_methods: Vec<(String, Vec<String>)> = Vec::new();
+        for route in route_configs {
+            let path = router_path(route);
+            let methods = if let Some(index) = allowed_methods.iter().position(|(p, _)| *p == path)
+            {
+                &mut allowed_methods[index].1
+            } else {
+                allowed_methods.push((path, Vec::new()));
+                &mut allowed_methods.last_mut().expect("Just inserted").1
+            };
+
+            methods.push(route.rest_kind.to_string());
+            if matches!(route.rest_kind, RestKind::Get) {
+                methods.push("HEAD".into());
+            }
+        }
+
+        for (path, mut methods) in allowed_methods {
+            methods.push("OPTIONS".into());
+            let allow = methods.join(", ");
+            router = router.route(
+                &path,
+                axum::routing::options(move || {
+                    std::future::ready((StatusCode::NO_CONTENT, [(header::ALLOW, allow.clone())]))
+                }),
+            );
+        }
+
+        router
+    }
 }
 
 #[cfg(test)]
@@ -146,21 +468,27 @@ mod tests {
 
     use core::ops::{Deref, DerefMut};
 
-    use tosca::device::DeviceInfo;
+    use tosca::device::{DeviceInfo, DeviceKind};
     use tosca::energy::Energy;
+    use tosca::parameters::Parameters;
     use tosca::route::Route;
 
+    use axum::body::{Body, to_bytes};
     use axum::extract::{FromRef, Json, State};
+    use axum::http::{Method, Request, StatusCode, header};
 
     use serde::{Deserialize, Serialize};
 
     use tokio::sync::Mutex;
 
+    use tower::ServiceExt;
+
     use crate::responses::error::ErrorResponse;
-    use crate::responses::info::{InfoResponse, info_stateful};
+    use crate::responses::info::{InfoResponse, info_stateful, info_stateless};
+    use crate::responses::ok::{OkResponse, ok_stateful, ok_stateless};
     use crate::responses::serial::{SerialResponse, serial_stateful, serial_stateless};
 
-    use super::Device;
+    use super::{Device, StateGuard};
 
     #[derive(Clone)]
     struct DeviceState<S>
@@ -340,10 +668,12 @@ mod tests {
                 routes.with_state_route,
                 serial_response_with_state,
             ))
+            .unwrap()
             .route(serial_stateless(
                 routes.without_state_route,
                 serial_response_without_state,
-            ));
+            ))
+            .unwrap();
     }
 
     #[test]
@@ -357,29 +687,343 @@ mod tests {
                 routes.with_state_route,
                 serial_response_with_substate1,
             ))
+            .unwrap()
             .route(serial_stateful(
                 Route::put("Substate response", "/substate-response")
                     .description("Run a serial response with a substate."),
                 serial_response_with_substate2,
             ))
+            .unwrap()
             .info_route(info_stateful(
                 Route::put("Substate info", "/substate-info")
                     .description("Run an informative response with a substate."),
                 info_response_with_substate3,
             ))
+            .unwrap()
             .route(serial_stateless(
                 routes.without_state_route,
                 serial_response_without_state,
-            ));
+            ))
+            .unwrap();
     }
 
     #[test]
     fn without_state() {
         let routes = create_routes();
 
-        let _ = Device::new().route(serial_stateless(
-            routes.without_state_route,
-            serial_response_without_state,
-        ));
+        let _ = Device::new()
+            .route(serial_stateless(
+                routes.without_state_route,
+                serial_response_without_state,
+            ))
+            .unwrap();
+    }
+
+    #[test]
+    fn mandatory_routes() {
+        let on_route = || {
+            ok_stateless(
+                Route::put("On", "/on").description("Turn light on."),
+                ok_response,
+            )
+        };
+
+        // A light device with all mandatory routes.
+        let device = Device::init(DeviceKind::Light, ())
+            .route(on_route())
+            .unwrap()
+            .route(ok_stateless(
+                Route::put("Off", "/off").description("Turn light off."),
+                ok_response,
+            ))
+            .unwrap();
+
+        assert!(device.validate().is_ok());
+
+        // A light device missing a mandatory route.
+        let device = Device::init(DeviceKind::Light, ())
+            .route(on_route())
+            .unwrap();
+
+        assert!(
+            device
+                .validate()
+                .unwrap_err()
+                .to_string()
+                .contains("Light device is missing mandatory routes: /off (POST or PUT)")
+        );
+
+        // A mandatory route served through a wrong REST kind.
+        let device = Device::init(DeviceKind::Light, ())
+            .route(on_route())
+            .unwrap()
+            .route(ok_stateless(
+                Route::get("Off", "/off").description("Turn light off."),
+                ok_response,
+            ))
+            .unwrap();
+
+        assert!(
+            device
+                .validate()
+                .unwrap_err()
+                .to_string()
+                .contains("Light device is missing mandatory routes: /off (POST or PUT)")
+        );
+
+        // A generic device has no mandatory routes.
+        assert!(Device::new().validate().is_ok());
+    }
+
+    #[test]
+    fn custom_kind() {
+        let device = Device::new().custom_kind("WaterValve");
+        assert!(device.validate().is_ok());
+
+        let (_, device_data, _) = device.finalize();
+        assert_eq!(device_data.kind, DeviceKind::custom("WaterValve"));
+    }
+
+    #[test]
+    fn actions_len() {
+        let device = Device::new();
+        assert_eq!(device.actions_len(), 0);
+
+        let device = device
+            .route(ok_stateless(
+                Route::put("On", "/on").description("Turn light on."),
+                ok_response,
+            ))
+            .unwrap()
+            .info_route(info_stateless(
+                Route::get("Info", "/info").description("Retrieve device information."),
+                info_response,
+            ))
+            .unwrap();
+
+        assert_eq!(device.actions_len(), 2);
+    }
+
+    #[test]
+    fn duplicate_route() {
+        let device = Device::new()
+            .route(ok_stateless(
+                Route::get("Route", "/x").description("A GET route."),
+                ok_response,
+            ))
+            .unwrap();
+
+        let error = device
+            .route(ok_stateless(
+                Route::get("Same route", "/x").description("The same GET route."),
+                ok_response,
+            ))
+            .unwrap_err();
+
+        assert!(error.to_string().contains("[GET, \"/x\"]"));
+    }
+
+    #[test]
+    fn invalid_route_path() {
+        let error = Device::new()
+            .route(ok_stateless(
+                Route::get("Route", "x").description("A GET route."),
+                ok_response,
+            ))
+            .unwrap_err();
+
+        assert!(error.to_string().contains("does not start with `/`"));
+    }
+
+    #[test]
+    fn byte_stream_get_route() {
+        let error = Device::new()
+            .route(ok_stateless(
+                Route::get("Upload", "/upload")
+                    .description("A GET route with a byte stream.")
+                    .with_parameters(Parameters::new().byte_stream("firmware", 1024)),
+                ok_response,
+            ))
+            .unwrap_err();
+
+        assert!(error.to_string().contains("use `POST` instead"));
+
+        let error = Device::new()
+            .route(ok_stateless(
+                Route::delete("Upload", "/upload")
+                    .description("A DELETE route with a byte stream.")
+                    .with_parameters(Parameters::new().byte_stream("firmware", 1024)),
+                ok_response,
+            ))
+            .unwrap_err();
+
+        assert!(error.to_string().contains("use `POST` instead"));
+
+        assert!(
+            Device::new()
+                .route(ok_stateless(
+                    Route::post("Upload", "/upload")
+                        .description("A POST route with a byte stream.")
+                        .with_parameters(Parameters::new().byte_stream("firmware", 1024)),
+                    ok_response,
+                ))
+                .is_ok()
+        );
+    }
+
+    async fn info_response() -> Result<InfoResponse, ErrorResponse> {
+        Ok(InfoResponse::new(DeviceInfo::empty()))
+    }
+
+    async fn ok_response() -> Result<OkResponse, ErrorResponse> {
+        Ok(OkResponse::ok())
+    }
+
+    fn info_router() -> axum::Router {
+        let (_, _, router) = Device::new()
+            .info_route(info_stateless(
+                Route::get("Info", "/info").description("Retrieve device information."),
+                info_response,
+            ))
+            .unwrap()
+            .route(ok_stateless(
+                Route::put("Update info", "/info").description("Update device information."),
+                ok_response,
+            ))
+            .unwrap()
+            .finalize();
+        router
+    }
+
+    async fn send(router: axum::Router, method: Method, uri: &str) -> axum::response::Response {
+        router
+            .oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn head_request() {
+        let response = send(info_router(), Method::HEAD, "/info").await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/json"
+        );
+        assert!(
+            to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn options_request() {
+        let response = send(info_router(), Method::OPTIONS, "/info").await;
+
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(
+            response.headers().get(header::ALLOW).unwrap(),
+            "GET, HEAD, PUT, OPTIONS"
+        );
+    }
+
+    #[tokio::test]
+    async fn deprecated_route() {
+        let (_, device_data, router) = Device::new()
+            .route(ok_stateless(
+                Route::put("Old on", "/old-on")
+                    .description("Turn light on.")
+                    .deprecated("Use `/on` instead"),
+                ok_response,
+            ))
+            .unwrap()
+            .route(ok_stateless(
+                Route::put("On", "/on").description("Turn light on."),
+                ok_response,
+            ))
+            .unwrap()
+            .finalize();
+
+        assert!(
+            device_data
+                .route_configs
+                .iter()
+                .any(|route| route.deprecated.as_deref() == Some("Use `/on` instead"))
+        );
+
+        let response = send(router.clone(), Method::PUT, "/old-on").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers().get("deprecation").unwrap(), "true");
+
+        let response = send(router, Method::PUT, "/on").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().get("deprecation").is_none());
+    }
+
+    #[derive(Clone)]
+    struct LightState {
+        on: Arc<std::sync::Mutex<bool>>,
+        invocations: Arc<std::sync::Mutex<u8>>,
+    }
+
+    async fn turn_light_on(State(state): State<LightState>) -> Result<OkResponse, ErrorResponse> {
+        *state.invocations.lock().unwrap() += 1;
+        *state.on.lock().unwrap() = true;
+        Ok(OkResponse::ok())
+    }
+
+    #[tokio::test]
+    async fn state_guard_rejects_route() {
+        let state = LightState {
+            on: Arc::new(std::sync::Mutex::new(true)),
+            invocations: Arc::new(std::sync::Mutex::new(0)),
+        };
+
+        // A light which is already on cannot be turned on again.
+        let (_, _, router) = Device::with_state(state.clone())
+            .state_guard(StateGuard::new(|state: &LightState, route| {
+                !(route.data.path == "/on" && *state.on.lock().unwrap())
+            }))
+            .route(ok_stateful(
+                Route::put("On", "/on").description("Turn light on."),
+                turn_light_on,
+            ))
+            .unwrap()
+            .finalize();
+
+        let response = send(router, Method::PUT, "/on").await;
+
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert!(*state.on.lock().unwrap());
+        assert_eq!(*state.invocations.lock().unwrap(), 0);
+
+        // The route is invoked once the light is off.
+        *state.on.lock().unwrap() = false;
+
+        let (_, _, router) = Device::with_state(state.clone())
+            .state_guard(StateGuard::new(|state: &LightState, route| {
+                !(route.data.path == "/on" && *state.on.lock().unwrap())
+            }))
+            .route(ok_stateful(
+                Route::put("On", "/on").description("Turn light on."),
+                turn_light_on,
+            ))
+            .unwrap()
+            .finalize();
+
+        let response = send(router, Method::PUT, "/on").await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(*state.on.lock().unwrap());
+        assert_eq!(*state.invocations.lock().unwrap(), 1);
     }
 }
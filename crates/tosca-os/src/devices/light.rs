@@ -120,32 +120,45 @@ where
     ///
     /// # Errors
     ///
-    /// Returns an error if any route hazards are not allowed
-    /// for the [`Light`] device.
+    /// Returns an error if the route path is not valid, or if a route with
+    /// the same path and [`tosca::route::RestKind`] has already been added.
     pub fn route(mut self, light_route: impl FnOnce(S) -> BaseResponse) -> Result<Self> {
         let base_response = light_route(self.device.state.clone());
 
         self.device = self
             .device
-            .response_data(Self::check_allowed_hazards(base_response));
+            .response_data(Self::check_allowed_hazards(base_response))?;
 
         Ok(self)
     }
 
     /// Adds an informative route to [`Light`].
-    #[must_use]
-    pub fn info_route(mut self, light_info_route: impl FnOnce(S, ()) -> BaseResponse) -> Self {
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the route path is not valid, or if a route with
+    /// the same path and [`tosca::route::RestKind`] has already been added.
+    pub fn info_route(
+        mut self,
+        light_info_route: impl FnOnce(S, ()) -> BaseResponse,
+    ) -> Result<Self> {
         let base_response = light_info_route(self.device.state.clone(), ());
 
         self.device = self
             .device
-            .response_data(Self::check_allowed_hazards(base_response));
+            .response_data(Self::check_allowed_hazards(base_response))?;
 
-        self
+        Ok(self)
     }
 
     /// Builds a [`Device`].
-    pub fn build(self) -> Device<S> {
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a mandatory route path is not valid, or if a
+    /// route with the same path and [`tosca::route::RestKind`] of a mandatory
+    /// route has already been added.
+    pub fn build(self) -> Result<Device<S>> {
         self.device.mandatory_response_data([
             Self::check_allowed_hazards(self.turn_light_on.base_response),
             Self::check_allowed_hazards(self.turn_light_off.base_response),
@@ -278,7 +291,8 @@ mod tests {
             .unwrap()
             .route(ok_stateful(routes.toggle, toggle))
             .unwrap()
-            .build();
+            .build()
+            .unwrap();
     }
 
     #[test]
@@ -288,7 +302,8 @@ mod tests {
         Light::with_state(LightState {})
             .turn_light_on(routes.light_on, mandatory_serial_stateful(turn_light_on))
             .turn_light_off(routes.light_off, mandatory_ok_stateful(turn_light_off))
-            .build();
+            .build()
+            .unwrap();
     }
 
     #[test]
@@ -302,7 +317,8 @@ mod tests {
             .unwrap()
             .route(ok_stateless(routes.toggle, toggle_stateless))
             .unwrap()
-            .build();
+            .build()
+            .unwrap();
     }
 
     #[test]
@@ -325,7 +341,32 @@ mod tests {
             .unwrap()
             .route(ok_stateless(routes.toggle, toggle_stateless))
             .unwrap()
-            .build();
+            .build()
+            .unwrap();
+    }
+
+    #[test]
+    fn duplicate_mandatory_route() {
+        let routes = create_routes();
+
+        let error = Light::new()
+            .turn_light_on(
+                routes.light_on,
+                mandatory_serial_stateless(turn_light_on_stateless),
+            )
+            .turn_light_off(
+                routes.light_off,
+                mandatory_ok_stateless(turn_light_off_stateless),
+            )
+            .route(ok_stateless(
+                Route::put("On", "/on").description("Turn light on again."),
+                toggle_stateless,
+            ))
+            .unwrap()
+            .build()
+            .unwrap_err();
+
+        assert!(error.to_string().contains("has already been added"));
     }
 
     #[test]
@@ -341,6 +382,7 @@ mod tests {
                 routes.light_off,
                 mandatory_ok_stateless(turn_light_off_stateless),
             )
-            .build();
+            .build()
+            .unwrap();
     }
 }
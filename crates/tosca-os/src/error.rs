@@ -9,6 +9,10 @@ pub enum ErrorKind {
     NotFoundAddress,
     /// Serialize/Deserialize error.
     Serialization,
+    /// Route error.
+    Route,
+    /// Webhook error.
+    Webhook,
 }
 
 impl ErrorKind {
@@ -17,6 +21,8 @@ impl ErrorKind {
             Self::Service => "Service",
             Self::NotFoundAddress => "Not Found Address",
             Self::Serialization => "Serialization",
+            Self::Route => "Route",
+            Self::Webhook => "Webhook",
         }
     }
 }
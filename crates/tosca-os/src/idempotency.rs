@@ -0,0 +1,246 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use axum::{
+    Router,
+    body::{Body, Bytes, HttpBody},
+    extract::Request,
+    http::{HeaderMap, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use tosca::response::IDEMPOTENCY_KEY;
+
+use tracing::info;
+
+// Maximum number of cached responses.
+//
+// When the cache is full, the least recently used response is evicted.
+//
+// The cache limits are documented on `Server` and on the controller
+// `RequestSender::with_idempotency_key` method, so keep them in sync.
+const CACHE_CAPACITY: usize = 64;
+
+// Time a cached response remains valid.
+const CACHE_TTL: Duration = Duration::from_mins(5);
+
+// Maximum size of a cached response body.
+//
+// Larger bodies and bodies of unknown size, such as streams, are not cached.
+const MAX_BODY_SIZE: usize = 64 * 1024;
+
+// A response cached for an idempotency key.
+struct CachedResponse {
+    // Idempotency key.
+    key: String,
+    // Request method.
+    method: Method,
+    // Request path.
+    path: String,
+    // Cache insertion time.
+    created: Instant,
+    // Response status.
+    status: StatusCode,
+    // Response headers.
+    headers: HeaderMap,
+    // Response body.
+    body: Bytes,
+}
+
+impl CachedResponse {
+    fn matches(&self, key: &str, method: &Method, path: &str) -> bool {
+        self.key == key && self.method == method && self.path == path
+    }
+
+    fn response(&self) -> Response {
+        let mut response = Response::new(Body::from(self.body.clone()));
+        *response.status_mut() = self.status;
+        *response.headers_mut() = self.headers.clone();
+        response
+    }
+}
+
+// A bounded least recently used cache of responses, indexed by the
+// idempotency key, method, and path of a request.
+#[derive(Clone, Default)]
+struct IdempotencyCache(Arc<Mutex<VecDeque<CachedResponse>>>);
+
+impl IdempotencyCache {
+    fn get(&self, key: &str, method: &Method, path: &str) -> Option<Response> {
+        let mut entries = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+
+        // Discard expired responses.
+        entries.retain(|entry| entry.created.elapsed() < CACHE_TTL);
+
+        let index = entries
+            .iter()
+            .position(|entry| entry.matches(key, method, path))?;
+
+        // Move the response to the most recently used position.
+        let entry = entries.remove(index)?;
+        let response = entry.response();
+        entries.push_back(entry);
+
+        Some(response)
+    }
+
+    fn insert(&self, entry: CachedResponse) {
+        let mut entries = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if entries.len() == CACHE_CAPACITY {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    async fn handle(self, request: Request, next: Next) -> Response {
+        // Requests which do not change a device state are never cached.
+        if matches!(*request.method(), Method::GET | Method::HEAD) {
+            return next.run(request).await;
+        }
+
+        let Some(key) = request
+            .headers()
+            .get(IDEMPOTENCY_KEY)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_owned)
+        else {
+            return next.run(request).await;
+        };
+
+        let method = request.method().clone();
+        let path = request.uri().path().to_owned();
+
+        if let Some(response) = self.get(&key, &method, &path) {
+            info!("Returning the cached response for the idempotency key {key}");
+            return response;
+        }
+
+        let response = next.run(request).await;
+
+        // Only successful responses are cached, so a failed operation can be
+        // retried with the same key.
+        if !response.status().is_success()
+            || response
+                .body()
+                .size_hint()
+                .upper()
+                .is_none_or(|size| size > MAX_BODY_SIZE as u64)
+        {
+            return response;
+        }
+
+        let (parts, body) = response.into_parts();
+        let Ok(body) = axum::body::to_bytes(body, MAX_BODY_SIZE).await else {
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        };
+
+        self.insert(CachedResponse {
+            key,
+            method,
+            path,
+            created: Instant::now(),
+            status: parts.status,
+            headers: parts.headers.clone(),
+            body: body.clone(),
+        });
+
+        Response::from_parts(parts, Body::from(body))
+    }
+}
+
+// Adds an idempotency layer to a router.
+//
+// A non-`GET` request containing the `Idempotency-Key` header is run only
+// once. Subsequent requests with the same key, method, and path receive the
+// cached response, as long as it is within the cache time-to-live and it has
+// not been evicted.
+//
+// Concurrent requests with the same key are all run, since a response is
+// cached only once completed.
+pub(crate) fn idempotent_router(router: Router) -> Router {
+    let cache = IdempotencyCache::default();
+    router.layer(axum::middleware::from_fn(
+        move |request: Request, next: Next| cache.clone().handle(request, next),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU8, Ordering};
+
+    use tosca::response::IDEMPOTENCY_KEY;
+    use tosca::route::Route;
+
+    use axum::body::{Body, to_bytes};
+    use axum::extract::State;
+    use axum::http::{Request, StatusCode};
+
+    use serde::{Deserialize, Serialize};
+
+    use tower::ServiceExt;
+
+    use crate::device::Device;
+    use crate::responses::error::ErrorResponse;
+    use crate::responses::serial::{SerialResponse, serial_stateful};
+
+    use super::idempotent_router;
+
+    #[derive(Clone, Default)]
+    struct Counter(Arc<AtomicU8>);
+
+    #[derive(Serialize, Deserialize)]
+    struct Invocations {
+        invocations: u8,
+    }
+
+    async fn increment(
+        State(counter): State<Counter>,
+    ) -> Result<SerialResponse<Invocations>, ErrorResponse> {
+        let invocations = counter.0.fetch_add(1, Ordering::SeqCst) + 1;
+        Ok(SerialResponse::new(Invocations { invocations }))
+    }
+
+    fn request(key: &str) -> Request<Body> {
+        Request::post("/increment")
+            .header(IDEMPOTENCY_KEY, key)
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    async fn invocations(router: &axum::Router, key: &str) -> u8 {
+        let response = router.clone().oneshot(request(key)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice::<Invocations>(&body)
+            .unwrap()
+            .invocations
+    }
+
+    #[tokio::test]
+    async fn same_key_runs_once() {
+        let counter = Counter::default();
+
+        let (_, _, router) = Device::with_state(counter.clone())
+            .route(serial_stateful(
+                Route::post("Increment", "/increment").description("Increment a counter."),
+                increment,
+            ))
+            .unwrap()
+            .finalize();
+        let router = idempotent_router(router);
+
+        // The same key returns the first response.
+        assert_eq!(invocations(&router, "key-1").await, 1);
+        assert_eq!(invocations(&router, "key-1").await, 1);
+
+        // A different key runs the handler again.
+        assert_eq!(invocations(&router, "key-2").await, 2);
+
+        assert_eq!(counter.0.load(Ordering::SeqCst), 2);
+    }
+}
@@ -20,7 +20,8 @@
 //! route, one or more associated operations are performed on the device.
 //!
 //! Each route may have zero or more associated hazards.
-//! If a route has no hazards, it could still pose unknown risks to the device.
+//! If a route has no hazards, it could still pose unknown risks to the device,
+//! unless it has been explicitly marked as known safe.
 //! In such cases, it is the responsibility of the controller to evaluate
 //! whether the request should be blocked based on the potential hazards for the
 //! device.
@@ -39,6 +40,9 @@
 /// All device kinds implementable in a firmware.
 pub mod devices;
 
+/// A collection of resources managed by a device, along with the routes to
+/// list and delete them.
+pub mod collection;
 /// Methods for defining a device and its associated operations.
 pub mod device;
 /// Error handling.
@@ -47,6 +51,12 @@ pub mod error;
 pub mod responses;
 /// Methods to define and run the server which represents the firmware.
 pub mod server;
+/// Device events streamed through Server-Sent Events.
+#[cfg(feature = "sse")]
+pub mod sse;
+/// Outbound webhooks delivering device events.
+#[cfg(feature = "webhook")]
+pub mod webhook;
 /// Methods to define and run the discovery service necessary to detect a
 /// device in a network.
 pub mod service {
@@ -55,9 +65,13 @@ pub mod service {
 
 /// Methods to parse requests and construct responses.
 pub mod extract {
-    pub use axum::extract::{FromRef, Json, Path, State};
+    pub use axum::extract::{Extension, FromRef, Json, Path, Query, State};
     pub use axum::http::header;
+
+    pub use super::params::Params;
 }
 
+mod idempotency;
 mod mac;
+mod params;
 mod services;
@@ -0,0 +1,413 @@
+use std::sync::Arc;
+
+use tosca::parameters::{ParameterKind, ParameterValue, ParametersData};
+use tosca::route::OffStepValues;
+
+use axum::extract::{FromRequest, Json, Request};
+
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Number, Value};
+
+use crate::responses::error::ErrorResponse;
+
+// Parameters declared by a route, made available to the extractors of its
+// handler, along with the handling of their off-step values.
+#[derive(Clone)]
+pub(crate) struct RouteParameters {
+    pub(crate) parameters_data: Arc<ParametersData>,
+    pub(crate) off_step_values: OffStepValues,
+}
+
+/// An extractor validating the `JSON` parameters of a request against the
+/// parameters declared by its route.
+///
+/// Each parameter value must match the declared kind and lie within
+/// the declared limits, while a missing parameter assumes its declared
+/// default value. Floating point values which are not a multiple of their
+/// declared step are either rounded or rejected, according to the route.
+/// Values are then deserialized into `T`.
+///
+/// Whenever a parameter is invalid or undeclared, an invalid data
+/// [`ErrorResponse`] is returned and the handler is not run.
+#[derive(Debug)]
+pub struct Params<T>(pub T);
+
+impl<T, S> FromRequest<S> for Params<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ErrorResponse;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let RouteParameters {
+            parameters_data,
+            off_step_values,
+        } = request
+            .extensions()
+            .get::<RouteParameters>()
+            .cloned()
+            .ok_or_else(|| ErrorResponse::internal("No parameters declared by the route"))?;
+
+        let Json(values) = Json::<Map<String, Value>>::from_request(request, state)
+            .await
+            .map_err(|e| {
+                ErrorResponse::invalid_data_with_error("Invalid parameters", &e.body_text())
+            })?;
+
+        let values = validate(&parameters_data, off_step_values, values)
+            .map_err(|e| ErrorResponse::invalid_data_with_error("Invalid parameters", &e))?;
+
+        serde_json::from_value(Value::Object(values))
+            .map(Self)
+            .map_err(|e| {
+                ErrorResponse::invalid_data_with_error("Invalid parameters", &e.to_string())
+            })
+    }
+}
+
+fn validate(
+    parameters_data: &ParametersData,
+    off_step_values: OffStepValues,
+    mut values: Map<String, Value>,
+) -> Result<Map<String, Value>, String> {
+    if let Some(name) = values
+        .keys()
+        .find(|name| parameters_data.get(name).is_none())
+    {
+        return Err(format!("`{name}` is not a route parameter"));
+    }
+
+    let mut validated = Map::new();
+    for (name, parameter_kind) in parameters_data {
+        let parameter_value = match values.remove(name) {
+            Some(value) => parse_value(name, &value, parameter_kind)?,
+            None => ParameterValue::from_parameter_kind(parameter_kind),
+        };
+
+        let parameter_value = parameter_value
+            .align_to_step(parameter_kind, off_step_values)
+            .ok_or_else(|| format!("`{name}` value is not a multiple of the declared step"))?;
+
+        if !parameter_value.is_within_limits(parameter_kind) {
+            return Err(match parameter_kind {
+                // The value is not written, since it might be a secret.
+                ParameterKind::CharsSequence {
+                    max_length: Some(max_length),
+                    ..
+                } => {
+                    format!("`{name}` value exceeds the maximum length of {max_length} characters")
+                }
+                _ => format!("`{name}` value `{parameter_value}` is out of the declared limits"),
+            });
+        }
+
+        validated.insert(name.clone(), into_json(parameter_value));
+    }
+
+    Ok(validated)
+}
+
+// Parses a value according to its parameter kind.
+//
+// Values are also accepted as strings, which is how the controller sends
+// them.
+fn parse_value(
+    name: &str,
+    value: &Value,
+    parameter_kind: &ParameterKind,
+) -> Result<ParameterValue, String> {
+    let value = match value {
+        Value::String(value) => value.clone(),
+        Value::Bool(_) | Value::Number(_) => value.to_string(),
+        _ => {
+            return Err(type_error(name, parameter_kind));
+        }
+    };
+
+    let parameter_value = match parameter_kind {
+        ParameterKind::Bool { .. } => value.parse().ok().map(ParameterValue::Bool),
+        ParameterKind::U8 { .. } => value.parse().ok().map(ParameterValue::U8),
+        ParameterKind::U16 { .. } => value.parse().ok().map(ParameterValue::U16),
+        ParameterKind::U32 { .. } | ParameterKind::RangeU32 { .. } => {
+            value.parse().ok().map(ParameterValue::U32)
+        }
+        ParameterKind::U64 { .. } | ParameterKind::RangeU64 { .. } => {
+            value.parse().ok().map(ParameterValue::U64)
+        }
+        ParameterKind::U128 { .. } => value.parse().ok().map(ParameterValue::U128),
+        ParameterKind::F32 { .. } => value.parse().ok().map(ParameterValue::F32),
+        ParameterKind::F64 { .. } | ParameterKind::RangeF64 { .. } => {
+            value.parse().ok().map(ParameterValue::F64)
+        }
+        ParameterKind::CharsSequence { .. } => {
+            Some(ParameterValue::CharsSequence(value.into()).conceal(parameter_kind))
+        }
+        // A byte stream is the request body, it is never a JSON value.
+        ParameterKind::ByteStream { .. } => None,
+    };
+
+    parameter_value.ok_or_else(|| type_error(name, parameter_kind))
+}
+
+fn type_error(name: &str, parameter_kind: &ParameterKind) -> String {
+    format!("`{name}` is not a `{}` value", parameter_kind.as_type())
+}
+
+fn into_json(parameter_value: ParameterValue) -> Value {
+    match parameter_value {
+        ParameterValue::Bool(v) => Value::Bool(v),
+        ParameterValue::U8(v) => Value::from(v),
+        ParameterValue::U16(v) => Value::from(v),
+        ParameterValue::U32(v) | ParameterValue::ByteStream(v) => Value::from(v),
+        ParameterValue::U64(v) => Value::from(v),
+        // Kept as a decimal string, as it is serialized by a parameter kind.
+        ParameterValue::U128(v) => Value::String(v.to_string()),
+        ParameterValue::F32(v) => Number::from_f64(f64::from(v)).map_or(Value::Null, Value::Number),
+        ParameterValue::F64(v) => Number::from_f64(v).map_or(Value::Null, Value::Number),
+        ParameterValue::CharsSequence(v) | ParameterValue::SecretCharsSequence(v) => {
+            Value::String(v.into_owned())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use tosca::parameters::Parameters;
+    use tosca::route::{OffStepValues, Route};
+
+    use axum::body::{Body, to_bytes};
+    use axum::extract::State;
+    use axum::http::{Request, StatusCode, header::CONTENT_TYPE};
+
+    use serde::{Deserialize, Serialize};
+
+    use tower::ServiceExt;
+
+    use crate::device::Device;
+    use crate::responses::error::ErrorResponse;
+    use crate::responses::ok::{OkResponse, ok_stateful};
+    use crate::responses::serial::{SerialResponse, serial_stateful, serial_stateless};
+
+    use super::Params;
+
+    #[derive(Serialize, Deserialize)]
+    struct Inputs {
+        brightness: u64,
+        #[serde(rename = "save-energy")]
+        save_energy: bool,
+    }
+
+    async fn brightness(
+        State(run): State<Arc<AtomicBool>>,
+        Params(inputs): Params<Inputs>,
+    ) -> Result<SerialResponse<Inputs>, ErrorResponse> {
+        run.store(true, Ordering::SeqCst);
+        Ok(SerialResponse::new(inputs))
+    }
+
+    fn router(run: Arc<AtomicBool>) -> axum::Router {
+        let (_, _, router) = Device::with_state(run)
+            .route(serial_stateful(
+                Route::put("Brightness", "/brightness")
+                    .description("Change brightness.")
+                    .with_parameters(
+                        Parameters::new()
+                            .u64_with_limits("brightness", 5, 0, 10)
+                            .bool("save-energy", false),
+                    ),
+                brightness,
+            ))
+            .unwrap()
+            .finalize();
+        router
+    }
+
+    fn request(body: &'static str) -> Request<Body> {
+        Request::put("/brightness")
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    async fn send(router: axum::Router, body: &'static str) -> Inputs {
+        let response = router.oneshot(request(body)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn params_out_of_limits() {
+        let run = Arc::new(AtomicBool::new(false));
+
+        let response = router(run.clone())
+            .oneshot(request(r#"{"brightness": 20}"#))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(!run.load(Ordering::SeqCst));
+
+        let response = router(run.clone())
+            .oneshot(request(r#"{"brightness": 5, "color": "red"}"#))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(!run.load(Ordering::SeqCst));
+    }
+
+    #[derive(Deserialize)]
+    struct Credentials {
+        password: String,
+    }
+
+    async fn login(
+        State(run): State<Arc<AtomicBool>>,
+        Params(credentials): Params<Credentials>,
+    ) -> Result<OkResponse, ErrorResponse> {
+        run.store(credentials.password == "p4ssw0rd", Ordering::SeqCst);
+        Ok(OkResponse::ok())
+    }
+
+    fn login_router(run: Arc<AtomicBool>, parameters: Parameters) -> axum::Router {
+        let (_, _, router) = Device::with_state(run)
+            .route(ok_stateful(
+                Route::post("Login", "/login")
+                    .description("Log in.")
+                    .with_parameters(parameters),
+                login,
+            ))
+            .unwrap()
+            .finalize();
+        router
+    }
+
+    fn login_request(body: &'static str) -> Request<Body> {
+        Request::post("/login")
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn params_secret() {
+        let run = Arc::new(AtomicBool::new(false));
+
+        let response = login_router(
+            run.clone(),
+            Parameters::new().secret_characters_sequence("password", ""),
+        )
+        .oneshot(login_request(r#"{"password": "p4ssw0rd"}"#))
+        .await
+        .unwrap();
+
+        // A secret value reaches the handler unmasked.
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(run.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn params_max_length() {
+        let run = Arc::new(AtomicBool::new(false));
+        let parameters =
+            || Parameters::new().secret_characters_sequence_with_max_length("password", "", 8);
+
+        // A value longer than the maximum length is rejected.
+        let response = login_router(run.clone(), parameters())
+            .oneshot(login_request(r#"{"password": "p4ssw0rd!"}"#))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(!run.load(Ordering::SeqCst));
+
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("`password` value exceeds the maximum length of 8 characters"));
+        assert!(!body.contains("p4ssw0rd"));
+
+        // A value exactly at the maximum length is accepted.
+        let response = login_router(run.clone(), parameters())
+            .oneshot(login_request(r#"{"password": "p4ssw0rd"}"#))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(run.load(Ordering::SeqCst));
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Level {
+        level: f64,
+    }
+
+    async fn level(Params(level): Params<Level>) -> Result<SerialResponse<Level>, ErrorResponse> {
+        Ok(SerialResponse::new(level))
+    }
+
+    fn level_router(off_step_values: OffStepValues) -> axum::Router {
+        let (_, _, router) = Device::new()
+            .route(serial_stateless(
+                Route::put("Level", "/level")
+                    .description("Change level.")
+                    .with_parameters(Parameters::new().rangef64("level", (0., 20., 0.1)))
+                    .with_off_step_values(off_step_values),
+                level,
+            ))
+            .unwrap()
+            .finalize();
+        router
+    }
+
+    fn level_request() -> Request<Body> {
+        Request::put("/level")
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(r#"{"level": 7.37}"#))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn params_off_step() {
+        // An off-step value is rounded to the nearest step.
+        let response = level_router(OffStepValues::Snap)
+            .oneshot(level_request())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let level = serde_json::from_slice::<Level>(&body).unwrap().level;
+        assert!((level - 7.4).abs() < 1e-9);
+
+        // An off-step value is rejected.
+        let response = level_router(OffStepValues::Reject)
+            .oneshot(level_request())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[tokio::test]
+    async fn params_valid() {
+        let run = Arc::new(AtomicBool::new(false));
+
+        // Values sent as strings.
+        let inputs = send(
+            router(run.clone()),
+            r#"{"brightness": "7", "save-energy": "true"}"#,
+        )
+        .await;
+        assert_eq!(inputs.brightness, 7);
+        assert!(inputs.save_energy);
+        assert!(run.load(Ordering::SeqCst));
+
+        // Missing values assume the declared defaults.
+        let inputs = send(router(run), "{}").await;
+        assert_eq!(inputs.brightness, 5);
+        assert!(!inputs.save_energy);
+    }
+}
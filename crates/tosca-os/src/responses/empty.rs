@@ -0,0 +1,129 @@
+use core::future::Future;
+
+use tosca::response::ResponseKind;
+use tosca::route::Route;
+
+use axum::{
+    handler::Handler,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+
+use super::{BaseResponse, error::ErrorResponse};
+
+/// A response which does not transmit any body over the network, notifying
+/// a controller that an operation completed successfully.
+///
+/// It is sent with a `204 No Content` status.
+#[derive(Debug, Clone, Copy)]
+pub struct EmptyResponse;
+
+impl EmptyResponse {
+    /// Creates an [`EmptyResponse`].
+    #[must_use]
+    #[inline]
+    pub const fn no_content() -> Self {
+        Self
+    }
+}
+
+impl IntoResponse for EmptyResponse {
+    fn into_response(self) -> Response {
+        StatusCode::NO_CONTENT.into_response()
+    }
+}
+
+mod private {
+    #[doc(hidden)]
+    pub trait EmptyTypeName<Args> {}
+}
+
+impl<F, Fut> private::EmptyTypeName<()> for F
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<EmptyResponse, ErrorResponse>> + Send,
+{
+}
+
+macro_rules! impl_empty_type_name {
+    (
+        [$($ty:ident),*], $($last:ident)?
+    ) => {
+        impl<F, Fut, M, $($ty,)* $($last)?> private::EmptyTypeName<(M, $($ty,)* $($last)?)> for F
+        where
+            F: FnOnce($($ty,)* $($last)?) -> Fut,
+            Fut: Future<Output = Result<EmptyResponse, ErrorResponse>> + Send,
+            {
+            }
+    };
+}
+super::all_the_tuples!(impl_empty_type_name);
+
+/// Creates a stateful [`BaseResponse`] from an [`EmptyResponse`].
+#[inline]
+pub fn empty_stateful<H, T, S>(route: Route, handler: H) -> impl FnOnce(S) -> BaseResponse
+where
+    H: Handler<T, S> + private::EmptyTypeName<T>,
+    T: 'static,
+    S: Clone + Send + Sync + 'static,
+{
+    move |state: S| BaseResponse::stateful(route, ResponseKind::Empty, handler, state)
+}
+
+/// Creates a stateless [`BaseResponse`] from an [`EmptyResponse`].
+#[inline]
+pub fn empty_stateless<H, T, S>(route: Route, handler: H) -> impl FnOnce(S) -> BaseResponse
+where
+    H: Handler<T, ()> + private::EmptyTypeName<T>,
+    T: 'static,
+    S: Clone + Send + Sync + 'static,
+{
+    move |_state: S| BaseResponse::stateless(route, ResponseKind::Empty, handler)
+}
+
+#[cfg(test)]
+mod tests {
+    use tosca::response::ResponseKind;
+    use tosca::route::Route;
+
+    use axum::body::{Body, to_bytes};
+    use axum::http::{Request, StatusCode};
+
+    use tower::ServiceExt;
+
+    use crate::device::Device;
+    use crate::responses::error::ErrorResponse;
+
+    use super::{EmptyResponse, empty_stateless};
+
+    async fn clear() -> Result<EmptyResponse, ErrorResponse> {
+        Ok(EmptyResponse::no_content())
+    }
+
+    #[tokio::test]
+    async fn empty_response() {
+        let (_, device_data, router) = Device::new()
+            .route(empty_stateless(
+                Route::delete("Clear", "/clear").description("Clear the device memory."),
+                clear,
+            ))
+            .unwrap()
+            .finalize();
+
+        let route_config = device_data.route_configs.iter().next().unwrap();
+        assert_eq!(route_config.response_kind, ResponseKind::Empty);
+        assert_eq!(
+            serde_json::to_value(route_config).unwrap()["response kind"],
+            "Empty"
+        );
+
+        let response = router
+            .oneshot(Request::delete("/clear").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert!(body.is_empty());
+    }
+}
@@ -75,6 +75,57 @@ impl ErrorResponse {
     pub fn internal_with_error(description: &str, error: &str) -> Self {
         Self::with_description_error(ErrorKind::Internal, description, error)
     }
+
+    /// Generates an [`ErrorResponse`] for an operation which is not allowed
+    /// in the current device state.
+    ///
+    /// Requires specifying a general error description.
+    #[must_use]
+    #[inline]
+    pub fn invalid_state(description: &str) -> Self {
+        let value = ToscaErrorResponse::invalid_state(description);
+        Self((StatusCode::CONFLICT, Json(value)).into_response())
+    }
+
+    /// Generates an [`ErrorResponse`] for a resource which has not been
+    /// found.
+    ///
+    /// Requires specifying a general error description.
+    #[must_use]
+    #[inline]
+    pub fn not_found(description: &str) -> Self {
+        let value = ToscaErrorResponse::not_found(description);
+        Self((StatusCode::NOT_FOUND, Json(value)).into_response())
+    }
+
+    /// Generates an [`ErrorResponse`] for an unauthorized requester.
+    ///
+    /// Requires specifying a general error description.
+    #[must_use]
+    #[inline]
+    pub fn unauthorized(description: &str) -> Self {
+        let value = ToscaErrorResponse::unauthorized(description);
+        Self((StatusCode::UNAUTHORIZED, Json(value)).into_response())
+    }
+
+    /// Generates an [`ErrorResponse`] for a requester which has sent too
+    /// many requests.
+    ///
+    /// Requires specifying a general error description.
+    #[must_use]
+    #[inline]
+    pub fn rate_limited(description: &str) -> Self {
+        let value = ToscaErrorResponse::rate_limited(description);
+        Self((StatusCode::TOO_MANY_REQUESTS, Json(value)).into_response())
+    }
+
+    // Generates an `ErrorResponse` for a request which does not accept any
+    // of the media types a route can produce.
+    #[cfg(feature = "stream")]
+    pub(super) fn not_acceptable(description: &str) -> Self {
+        let value = ToscaErrorResponse::invalid_data(description);
+        Self((StatusCode::NOT_ACCEPTABLE, Json(value)).into_response())
+    }
 }
 
 impl IntoResponse for ErrorResponse {
@@ -13,6 +13,8 @@ use axum::{
 
 use serde::Serialize;
 
+use crate::server::uptime;
+
 use super::{BaseResponse, error::ErrorResponse};
 
 /// A response which transmits a JSON message over the network containing
@@ -22,9 +24,33 @@ pub struct InfoResponse(ToscaInfoResponse);
 
 impl InfoResponse {
     /// Creates an [`InfoResponse`].
+    ///
+    /// The server uptime is added to the device information. With the
+    /// `time` feature, the response also contains the time at which it has
+    /// been generated.
+    #[must_use]
+    pub fn new(info: DeviceInfo) -> Self {
+        let info = match uptime() {
+            Some(uptime) => info.add_uptime(uptime),
+            None => info,
+        };
+
+        let response = ToscaInfoResponse::new(info);
+
+        #[cfg(feature = "time")]
+        let response = response.with_generated_at(super::now());
+
+        Self(response)
+    }
+
+    /// Merges another [`InfoResponse`] into this one.
+    ///
+    /// A single route can then aggregate energy, economy, and any other
+    /// device information into one response. When both responses define
+    /// the same field, the field of `other` wins.
     #[must_use]
-    pub const fn new(info: DeviceInfo) -> Self {
-        Self(ToscaInfoResponse::new(info))
+    pub fn merge(self, other: Self) -> Self {
+        Self(self.0.merge(other.0))
     }
 }
 
@@ -1,8 +1,14 @@
+/// A response without a body notifying the controller that
+/// an operation completed successfully.
+pub mod empty;
 /// A response providing details about an error encountered during a
 /// device operation.
 pub mod error;
 /// A response containing energy and economy data for a device.
 pub mod info;
+/// A response streaming records as newline-delimited JSON.
+#[cfg(feature = "stream")]
+pub mod ndjson;
 /// A response notifying the controller that
 /// an operation completed successfully.
 pub mod ok;
@@ -11,16 +17,66 @@ pub mod serial;
 /// Response to handle a stream of data as a sequence of bytes.
 #[cfg(feature = "stream")]
 pub mod stream;
+/// A response containing a human-readable text, sent as plain text when
+/// preferred by a request.
+pub mod text;
 
 use tosca::hazards::Hazard;
-use tosca::parameters::Parameters;
+use tosca::parameters::{ParameterKind, ParameterValue, ParametersData};
 use tosca::response::ResponseKind;
-use tosca::route::{RestKind, Route, RouteConfig};
+use tosca::route::{MissingParameters, ParameterStyle, RestKind, Route, RouteConfig};
+
+use std::fmt::Write;
+use std::sync::Arc;
 
-use axum::{Router, handler::Handler};
+use axum::extract::Request;
+use axum::http::{HeaderMap, HeaderName, HeaderValue, StatusCode, header::ACCEPT};
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Router, handler::Handler};
+
+use tower::ServiceExt;
 
 use tracing::info;
 
+use crate::params::RouteParameters;
+
+// Header advertising a deprecated route.
+const DEPRECATION: HeaderName = HeaderName::from_static("deprecation");
+
+// Returns the current time as a timestamp.
+#[cfg(feature = "time")]
+fn now() -> tosca::time::Timestamp {
+    tosca::time::Timestamp::from_unix_secs(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs()),
+    )
+}
+
+// Returns the quality value assigned to the given media ranges by the
+// `Accept` header.
+//
+// A media range without a quality value has the highest quality.
+fn quality(headers: &HeaderMap, media_ranges: &[&str]) -> f32 {
+    let Some(accept) = headers.get(ACCEPT).and_then(|value| value.to_str().ok()) else {
+        return 0.;
+    };
+
+    accept
+        .split(',')
+        .filter_map(|media_range| {
+            let mut parts = media_range.split(';').map(str::trim);
+            let media_type = parts.next().unwrap_or_default();
+            media_ranges.contains(&media_type).then(|| {
+                parts
+                    .find_map(|parameter| parameter.strip_prefix("q="))
+                    .and_then(|quality| quality.parse::<f32>().ok())
+                    .unwrap_or(1.)
+            })
+        })
+        .fold(0., f32::max)
+}
+
 #[rustfmt::skip]
 macro_rules! all_the_tuples {
     ($name:ident) => {
@@ -46,9 +102,9 @@ macro_rules! all_the_tuples {
 
 pub(super) use all_the_tuples;
 
-fn build_get_route(route: &str, parameters: &Parameters) -> String {
+fn build_get_route<'a>(route: &str, names: impl Iterator<Item = &'a str>) -> String {
     let mut route = String::from(route);
-    for name in parameters.names() {
+    for name in names {
         let append_str = format!("/{{{name}}}");
         route.push_str(&append_str);
     }
@@ -56,6 +112,118 @@ fn build_get_route(route: &str, parameters: &Parameters) -> String {
     route
 }
 
+// Adds the paths of a `GET` or `DELETE` route omitting some of its trailing
+// path parameters.
+//
+// A request omitting some parameters is forwarded to a router capturing only
+// the omitted parameters, whose values are their defaults. A parameter with an
+// empty default value cannot be a path segment, so it cannot be omitted.
+fn omitted_parameters_routes(
+    mut router: Router,
+    route: &str,
+    parameters_data: &ParametersData,
+    route_router: impl Fn(&str) -> Router,
+) -> Router {
+    let names: Vec<&str> = parameters_data
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect();
+    let mut defaults = String::new();
+    for (index, (_, parameter_kind)) in parameters_data.iter().enumerate().rev() {
+        let Some(segment) = default_segment(parameter_kind) else {
+            break;
+        };
+        defaults.insert_str(0, &segment);
+
+        let omitted_router = route_router(&build_get_route("", names[index..].iter().copied()));
+        let defaults: Arc<str> = defaults.as_str().into();
+        router = router.route(
+            &build_get_route(route, names[..index].iter().copied()),
+            axum::routing::any(move |request: Request| {
+                forward_with_defaults(omitted_router.clone(), defaults.clone(), request)
+            }),
+        );
+    }
+    router
+}
+
+// Returns the default value of a parameter as a percent-encoded path segment,
+// if it is not empty.
+fn default_segment(parameter_kind: &ParameterKind) -> Option<String> {
+    let value = match ParameterValue::from_parameter_kind(parameter_kind) {
+        ParameterValue::CharsSequence(value) | ParameterValue::SecretCharsSequence(value) => {
+            value.into_owned()
+        }
+        value => value.to_string(),
+    };
+
+    if value.is_empty() {
+        return None;
+    }
+
+    let mut segment = String::from("/");
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            segment.push(char::from(byte));
+        } else {
+            let _ = write!(segment, "%{byte:02X}");
+        }
+    }
+    Some(segment)
+}
+
+// Forwards a request to the router of its omitted parameters.
+//
+// The request path is replaced by the default values of the omitted
+// parameters, which are captured after the ones of the original path.
+async fn forward_with_defaults(
+    router: Router,
+    defaults: Arc<str>,
+    mut request: Request,
+) -> Response {
+    let path_and_query = match request.uri().query() {
+        Some(query) => format!("{defaults}?{query}"),
+        None => defaults.to_string(),
+    };
+
+    let Ok(uri) = path_and_query.parse() else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+    *request.uri_mut() = uri;
+
+    match router.oneshot(request).await {
+        Ok(response) => response,
+        Err(infallible) => match infallible {},
+    }
+}
+
+// Returns the router path associated with a route configuration.
+pub(crate) fn router_path(route_config: &RouteConfig) -> String {
+    if matches!(route_config.rest_kind, RestKind::Get | RestKind::Delete)
+        && matches!(route_config.parameter_style, ParameterStyle::Path)
+        && !route_config.data.parameters.is_empty()
+    {
+        build_get_route(
+            &route_config.data.path,
+            route_config
+                .data
+                .parameters
+                .iter()
+                .map(|(name, _)| name.as_str()),
+        )
+    } else {
+        route_config.data.path.to_string()
+    }
+}
+
+// Marks a response as sent by a deprecated route.
+async fn deprecation_header(mut response: axum::response::Response) -> axum::response::Response {
+    response
+        .headers_mut()
+        .insert(DEPRECATION, HeaderValue::from_static("true"));
+    response
+}
+
 #[derive(Debug)]
 /// A base response for a [`crate::device::Device`].
 ///
@@ -98,24 +266,70 @@ impl BaseResponse {
         T: 'static,
         S: Clone + Send + Sync + 'static,
     {
-        // Create the GET route for the axum architecture.
-        let route_str = if matches!(route.kind(), RestKind::Get) && !route.parameters().is_empty() {
-            &build_get_route(route.route(), route.parameters())
+        // An invalid path makes the router panic, so no router is created.
+        // The route is then rejected when added to a device.
+        if route.validate().is_err() {
+            return Self {
+                router: Router::new(),
+                route,
+                response_kind,
+            };
+        }
+
+        // Create the GET or DELETE route for the axum architecture.
+        //
+        // Parameters encoded as a query string do not change the route path.
+        let path_parameters = matches!(route.kind(), RestKind::Get | RestKind::Delete)
+            && matches!(route.parameter_style(), ParameterStyle::Path)
+            && !route.parameters().is_empty();
+        let route_str = if path_parameters {
+            &build_get_route(route.route(), route.parameters().names().copied())
         } else {
             route.route()
         };
 
-        let router = Router::new()
-            .route(
-                route_str,
-                match route.kind() {
-                    RestKind::Get => axum::routing::get(handler),
-                    RestKind::Put => axum::routing::put(handler),
-                    RestKind::Post => axum::routing::post(handler),
-                    RestKind::Delete => axum::routing::delete(handler),
-                },
+        // NOTE: A `GET` route also answers to `HEAD` requests, sending the
+        // same headers without a body.
+        let method_router = match route.kind() {
+            RestKind::Get => axum::routing::get(handler),
+            RestKind::Put => axum::routing::put(handler),
+            RestKind::Post => axum::routing::post(handler),
+            RestKind::Delete => axum::routing::delete(handler),
+            RestKind::Patch => axum::routing::patch(handler),
+        };
+        let route_parameters = RouteParameters {
+            parameters_data: Arc::new(route.parameters().clone().serialize_data()),
+            off_step_values: route.off_step_values(),
+        };
+        let route_router = |path: &str| {
+            Router::new()
+                .route(path, method_router.clone())
+                .with_state(state.clone())
+                .layer(Extension(route_parameters.clone()))
+        };
+        let router = route_router(route_str);
+
+        // Omitted trailing path parameters assume their default values.
+        let router = if path_parameters
+            && matches!(route.missing_parameters(), MissingParameters::Default)
+        {
+            omitted_parameters_routes(
+                router,
+                route.route(),
+                &route_parameters.parameters_data,
+                route_router,
             )
-            .with_state(state);
+        } else {
+            router
+        };
+
+        // A deprecated route advertises its state through the `Deprecation`
+        // header of each response.
+        let router = if route.deprecation().is_some() {
+            router.layer(axum::middleware::map_response(deprecation_header))
+        } else {
+            router
+        };
 
         Self {
             router,
@@ -183,8 +397,101 @@ impl MandatoryResponse<true> {
 #[cfg(test)]
 mod tests {
     use tosca::parameters::Parameters;
+    use tosca::route::MissingParameters;
+
+    use axum::body::{Body, to_bytes};
+    use axum::extract::Path;
+    use axum::http::{Request, StatusCode};
+
+    use serde::{Deserialize, Serialize};
+
+    use tower::ServiceExt;
+
+    use crate::device::Device;
+    use crate::responses::error::ErrorResponse;
+    use crate::responses::serial::{SerialResponse, serial_stateless};
+
+    use super::{ParameterStyle, Route, build_get_route, router_path};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Light {
+        brightness: u64,
+        name: String,
+    }
+
+    async fn light(
+        Path((brightness, name)): Path<(u64, String)>,
+    ) -> Result<SerialResponse<Light>, ErrorResponse> {
+        Ok(SerialResponse::new(Light { brightness, name }))
+    }
+
+    fn light_router(missing_parameters: MissingParameters) -> axum::Router {
+        let (_, _, router) = Device::new()
+            .route(serial_stateless(
+                Route::get("Light", "/light")
+                    .description("Get light.")
+                    .with_parameters(
+                        Parameters::new()
+                            .rangeu64_with_default("brightness", (0, 20, 1), 5)
+                            .characters_sequence("name", "desk lamp"),
+                    )
+                    .with_missing_parameters(missing_parameters),
+                light,
+            ))
+            .unwrap()
+            .finalize();
+        router
+    }
+
+    async fn get_light(router: axum::Router, uri: &str) -> Option<Light> {
+        let response = router
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        if response.status() != StatusCode::OK {
+            return None;
+        }
+
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        Some(serde_json::from_slice(&body).unwrap())
+    }
+
+    fn expected_light(brightness: u64, name: &str) -> Light {
+        Light {
+            brightness,
+            name: name.into(),
+        }
+    }
 
-    use super::{Route, build_get_route};
+    #[tokio::test]
+    async fn strict_missing_parameters() {
+        let router = light_router(MissingParameters::Strict);
+
+        assert_eq!(
+            get_light(router.clone(), "/light/7/lamp").await,
+            Some(expected_light(7, "lamp"))
+        );
+        assert_eq!(get_light(router.clone(), "/light/7").await, None);
+        assert_eq!(get_light(router, "/light").await, None);
+    }
+
+    #[tokio::test]
+    async fn default_missing_parameters() {
+        let router = light_router(MissingParameters::Default);
+
+        assert_eq!(
+            get_light(router.clone(), "/light/7/lamp").await,
+            Some(expected_light(7, "lamp"))
+        );
+        assert_eq!(
+            get_light(router.clone(), "/light/7").await,
+            Some(expected_light(7, "desk lamp"))
+        );
+        assert_eq!(
+            get_light(router, "/light").await,
+            Some(expected_light(5, "desk lamp"))
+        );
+    }
 
     #[test]
     fn test_build_get_route() {
@@ -197,8 +504,24 @@ mod tests {
             );
 
         assert_eq!(
-            &build_get_route(route.route(), route.parameters()),
+            &build_get_route(route.route(), route.parameters().names().copied()),
             "/route/{rangeu64}/{rangef64}"
         );
     }
+
+    #[test]
+    fn test_router_path() {
+        let route = Route::get("Route", "/route")
+            .description("A GET route.")
+            .with_parameters(Parameters::new().rangeu64("rangeu64", (0, 20, 1)));
+
+        assert_eq!(router_path(&route.serialize_data()), "/route/{rangeu64}");
+
+        let route = Route::get("Route", "/route")
+            .description("A GET route.")
+            .with_parameters(Parameters::new().rangeu64("rangeu64", (0, 20, 1)))
+            .with_parameter_style(ParameterStyle::Query);
+
+        assert_eq!(router_path(&route.serialize_data()), "/route");
+    }
 }
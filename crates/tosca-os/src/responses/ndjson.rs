@@ -0,0 +1,208 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use tosca::response::ResponseKind;
+use tosca::route::Route;
+
+use axum::{
+    body::{Body, Bytes},
+    handler::Handler,
+    http::header::CONTENT_TYPE,
+    response::{IntoResponse, Response},
+};
+
+use futures_core::Stream;
+
+use serde::Serialize;
+
+use super::{BaseResponse, error::ErrorResponse};
+
+// Content type of a newline-delimited JSON stream.
+const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+
+// A stream serializing each record as a JSON line.
+struct NdJsonStream<S>(Pin<Box<S>>);
+
+impl<S> Stream for NdJsonStream<S>
+where
+    S: Stream,
+    S::Item: Serialize,
+{
+    type Item = Result<Bytes, serde_json::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.0.as_mut().poll_next(cx).map(|record| {
+            record.map(|record| {
+                let mut line = serde_json::to_vec(&record)?;
+                line.push(b'\n');
+                Ok(Bytes::from(line))
+            })
+        })
+    }
+}
+
+/// A response which transmits a stream of records as newline-delimited
+/// JSON.
+///
+/// Each record is serialized as a single JSON line and sent as soon as it
+/// is produced, so records, such as the entries of a sensor log, are never
+/// buffered altogether.
+///
+/// A record which cannot be serialized interrupts the stream.
+pub struct NdJsonResponse(Response);
+
+impl NdJsonResponse {
+    /// Creates a [`NdJsonResponse`] from a stream of records.
+    #[inline]
+    pub fn new<S>(records: S) -> Self
+    where
+        S: Stream + Send + 'static,
+        S::Item: Serialize,
+    {
+        Self(
+            (
+                [(CONTENT_TYPE, NDJSON_CONTENT_TYPE)],
+                Body::from_stream(NdJsonStream(Box::pin(records))),
+            )
+                .into_response(),
+        )
+    }
+}
+
+impl IntoResponse for NdJsonResponse {
+    fn into_response(self) -> Response {
+        self.0
+    }
+}
+
+mod private {
+    #[doc(hidden)]
+    pub trait NdJsonTypeName<Args> {}
+}
+
+impl<F, Fut> private::NdJsonTypeName<()> for F
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<NdJsonResponse, ErrorResponse>> + Send,
+{
+}
+
+macro_rules! impl_ndjson_type_name {
+    (
+        [$($ty:ident),*], $($last:ident)?
+    ) => {
+        impl<F, Fut, M, $($ty,)* $($last)?> private::NdJsonTypeName<(M, $($ty,)* $($last)?)> for F
+        where
+            F: FnOnce($($ty,)* $($last)?) -> Fut,
+            Fut: Future<Output = Result<NdJsonResponse, ErrorResponse>> + Send,
+            {
+            }
+    };
+}
+
+super::all_the_tuples!(impl_ndjson_type_name);
+
+/// Creates a stateful [`BaseResponse`] from a [`NdJsonResponse`].
+#[inline]
+pub fn ndjson_stateful<H, T, S>(route: Route, handler: H) -> impl FnOnce(S) -> BaseResponse
+where
+    H: Handler<T, S> + private::NdJsonTypeName<T>,
+    T: 'static,
+    S: Clone + Send + Sync + 'static,
+{
+    move |state: S| BaseResponse::stateful(route, ResponseKind::Stream, handler, state)
+}
+
+/// Creates a stateless [`BaseResponse`] from a [`NdJsonResponse`].
+#[inline]
+pub fn ndjson_stateless<H, T, S>(route: Route, handler: H) -> impl FnOnce(S) -> BaseResponse
+where
+    H: Handler<T, ()> + private::NdJsonTypeName<T>,
+    T: 'static,
+    S: Clone + Send + Sync + 'static,
+{
+    move |_state: S| BaseResponse::stateless(route, ResponseKind::Stream, handler)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use tosca::route::Route;
+
+    use axum::body::Body;
+    use axum::http::{Request, header::CONTENT_TYPE};
+
+    use futures_core::Stream;
+
+    use serde::Serialize;
+
+    use tower::ServiceExt;
+
+    use crate::device::Device;
+
+    use super::{ErrorResponse, NDJSON_CONTENT_TYPE, NdJsonResponse, ndjson_stateless};
+
+    #[derive(Serialize)]
+    struct Record {
+        temperature: f64,
+    }
+
+    // A stream of records.
+    struct Records(VecDeque<f64>);
+
+    impl Stream for Records {
+        type Item = Record;
+
+        fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            Poll::Ready(self.0.pop_front().map(|temperature| Record { temperature }))
+        }
+    }
+
+    async fn log() -> Result<NdJsonResponse, ErrorResponse> {
+        Ok(NdJsonResponse::new(Records(VecDeque::from([
+            20.5, 21., 21.5,
+        ]))))
+    }
+
+    #[tokio::test]
+    async fn ndjson_lines_in_order() {
+        let (_, _, router) = Device::new()
+            .route(ndjson_stateless(
+                Route::get("Log", "/log").description("Stream the sensor log."),
+                log,
+            ))
+            .unwrap()
+            .finalize();
+
+        let response = router
+            .oneshot(Request::get("/log").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.headers()[CONTENT_TYPE], NDJSON_CONTENT_TYPE);
+
+        let mut body = response.into_body().into_data_stream();
+        for line in [
+            "{\"temperature\":20.5}\n",
+            "{\"temperature\":21.0}\n",
+            "{\"temperature\":21.5}\n",
+        ] {
+            let chunk = std::future::poll_fn(|cx| Pin::new(&mut body).poll_next(cx))
+                .await
+                .unwrap()
+                .unwrap();
+
+            assert_eq!(chunk, line);
+        }
+
+        assert!(
+            std::future::poll_fn(|cx| Pin::new(&mut body).poll_next(cx))
+                .await
+                .is_none()
+        );
+    }
+}
@@ -21,9 +21,17 @@ pub struct SerialResponse<T: Serialize>(ToscaSerialResponse<T>);
 
 impl<T: Serialize> SerialResponse<T> {
     /// Creates a [`SerialResponse`].
+    ///
+    /// With the `time` feature, data serialized as a map also contains the
+    /// time at which the response has been generated.
     #[must_use]
-    pub const fn new(data: T) -> Self {
-        Self(ToscaSerialResponse::new(data))
+    pub fn new(data: T) -> Self {
+        let response = ToscaSerialResponse::new(data);
+
+        #[cfg(feature = "time")]
+        let response = response.with_generated_at(super::now());
+
+        Self(response)
     }
 }
 
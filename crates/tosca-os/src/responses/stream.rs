@@ -1,5 +1,7 @@
 use std::error::Error;
 use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
 
 use tosca::response::ResponseKind;
 use tosca::route::Route;
@@ -7,17 +9,55 @@ use tosca::route::Route;
 use axum::{
     body::{Body, Bytes},
     handler::Handler,
-    http::header::HeaderName,
+    http::{
+        HeaderMap,
+        header::{ACCEPT, CONTENT_TYPE, HeaderName},
+    },
     response::{IntoResponse, Response},
 };
 
-use futures_core::TryStream;
+use futures_core::{Stream, TryStream};
 
 use tokio::io::AsyncRead;
 use tokio_util::io::ReaderStream;
 
 use super::{BaseResponse, MandatoryResponse, error::ErrorResponse};
 
+// Boundary separating the frames of a `MJPEG` stream.
+const MJPEG_BOUNDARY: &str = "tosca-frame";
+
+// Content type of a `MJPEG` stream.
+const MJPEG_CONTENT_TYPE: &str = "multipart/x-mixed-replace; boundary=tosca-frame";
+
+// A stream wrapping each `JPEG` frame into a multipart section.
+struct MjpegStream<S>(Pin<Box<S>>);
+
+impl<S> Stream for MjpegStream<S>
+where
+    S: TryStream,
+    S::Ok: Into<Bytes>,
+{
+    type Item = Result<Bytes, S::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.0.as_mut().try_poll_next(cx).map(|frame| {
+            frame.map(|frame| {
+                frame.map(|frame| {
+                    let frame = frame.into();
+                    let mut section = format!(
+                        "--{MJPEG_BOUNDARY}\r\nContent-Type: image/jpeg\r\nContent-Length: {}\r\n\r\n",
+                        frame.len()
+                    )
+                    .into_bytes();
+                    section.extend_from_slice(&frame);
+                    section.extend_from_slice(b"\r\n");
+                    Bytes::from(section)
+                })
+            })
+        })
+    }
+}
+
 /// A response that transmits a stream of data as a sequence of bytes
 /// over the network.
 pub struct StreamResponse(Response);
@@ -48,6 +88,24 @@ impl StreamResponse {
         Self(Body::from_stream(stream).into_response())
     }
 
+    /// Creates a `MJPEG` [`StreamResponse`] from a stream of `JPEG` frames.
+    ///
+    /// Each frame is sent as soon as it is produced, as a section of a
+    /// `multipart/x-mixed-replace` response, so frames are never buffered
+    /// altogether.
+    #[inline]
+    pub fn mjpeg<S>(frames: S) -> Self
+    where
+        S: TryStream + Send + 'static,
+        S::Ok: Into<Bytes>,
+        S::Error: Into<Box<dyn Error + Sync + Send>>,
+    {
+        Self::from_headers_stream(
+            [(CONTENT_TYPE, MJPEG_CONTENT_TYPE)],
+            MjpegStream(Box::pin(frames)),
+        )
+    }
+
     /// Creates a [`StreamResponse`] from headers and
     /// an asynchronous byte reader.
     #[inline]
@@ -73,6 +131,63 @@ impl StreamResponse {
     }
 }
 
+/// Creates a [`StreamResponse`] encoded with the media type preferred by the
+/// `Accept` header of a request, among the given ones.
+///
+/// A handler retrieves the request headers through the
+/// [`HeaderMap`] extractor, and then associates each media type it can
+/// produce with a function encoding the data in that media type. Only the
+/// function of the preferred media type is called, and its media type is
+/// sent as the `Content-Type` of the response.
+///
+/// When the `Accept` header is missing, or when more media types are equally
+/// acceptable, such as with `*/*`, the first one is chosen.
+///
+/// # Errors
+///
+/// A `406 Not Acceptable` error is returned when the request does not accept
+/// any of the given media types.
+// The error is returned as is by handlers, like any other error response.
+#[allow(clippy::result_large_err)]
+pub fn negotiate<B>(
+    headers: &HeaderMap,
+    encodings: &[(&'static str, &dyn Fn() -> B)],
+) -> Result<StreamResponse, ErrorResponse>
+where
+    B: Into<Bytes>,
+{
+    let encoding = if headers.contains_key(ACCEPT) {
+        let mut preferred = None;
+        let mut preferred_quality = 0.;
+        for encoding in encodings {
+            let (media_type, _) = encoding;
+            let any_subtype = media_type
+                .split_once('/')
+                .map(|(kind, _)| format!("{kind}/*"))
+                .unwrap_or_default();
+
+            let quality = super::quality(headers, &[media_type, &any_subtype, "*/*"]);
+            if quality > preferred_quality {
+                preferred = Some(encoding);
+                preferred_quality = quality;
+            }
+        }
+        preferred
+    } else {
+        encodings.first()
+    };
+
+    let Some((media_type, encode)) = encoding else {
+        return Err(ErrorResponse::not_acceptable(
+            "The request does not accept any of the available media types",
+        ));
+    };
+
+    Ok(StreamResponse(
+        ([(CONTENT_TYPE, *media_type)], Body::from(encode().into())).into_response(),
+    ))
+}
+
 impl IntoResponse for StreamResponse {
     fn into_response(self) -> Response {
         self.0
@@ -165,3 +280,168 @@ where
 {
     move |_state: S| BaseResponse::stateless(route, ResponseKind::Stream, handler)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+    use std::convert::Infallible;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use tosca::response::ResponseKind;
+    use tosca::route::Route;
+
+    use axum::body::{Body, Bytes, to_bytes};
+    use axum::http::{
+        HeaderMap, Request, StatusCode,
+        header::{ACCEPT, CONTENT_TYPE},
+    };
+
+    use futures_core::Stream;
+
+    use tower::ServiceExt;
+
+    use crate::device::Device;
+
+    use super::{ErrorResponse, MJPEG_CONTENT_TYPE, StreamResponse, negotiate, stream_stateless};
+
+    // A stream of frames.
+    struct Frames(VecDeque<&'static [u8]>);
+
+    impl Stream for Frames {
+        type Item = Result<Bytes, Infallible>;
+
+        fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            Poll::Ready(
+                self.0
+                    .pop_front()
+                    .map(|frame| Ok(Bytes::from_static(frame))),
+            )
+        }
+    }
+
+    async fn stream() -> Result<StreamResponse, ErrorResponse> {
+        Ok(StreamResponse::from_reader(&b"stream"[..]))
+    }
+
+    async fn mjpeg() -> Result<StreamResponse, ErrorResponse> {
+        Ok(StreamResponse::mjpeg(Frames(VecDeque::from([
+            &b"first"[..],
+            &b"second"[..],
+            &b"third"[..],
+        ]))))
+    }
+
+    #[test]
+    fn test_stream_response_kind() {
+        let route = Route::get("Stream", "/stream").description("Stream bytes.");
+
+        let (route_config, _) = stream_stateless(route, stream)(()).finalize();
+
+        assert_eq!(route_config.response_kind, ResponseKind::Stream);
+        assert_eq!(
+            serde_json::to_value(route_config).unwrap()["response kind"],
+            "Stream"
+        );
+    }
+
+    #[tokio::test]
+    async fn mjpeg_chunks_in_order() {
+        let (_, _, router) = Device::new()
+            .route(stream_stateless(
+                Route::get("Camera", "/camera").description("Camera stream."),
+                mjpeg,
+            ))
+            .unwrap()
+            .finalize();
+
+        let response = router
+            .oneshot(Request::get("/camera").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.headers()[CONTENT_TYPE], MJPEG_CONTENT_TYPE);
+
+        let mut body = response.into_body().into_data_stream();
+        for frame in ["first", "second", "third"] {
+            let chunk = std::future::poll_fn(|cx| Pin::new(&mut body).poll_next(cx))
+                .await
+                .unwrap()
+                .unwrap();
+
+            assert_eq!(
+                chunk,
+                format!(
+                    "--tosca-frame\r\nContent-Type: image/jpeg\r\nContent-Length: {}\r\n\r\n{frame}\r\n",
+                    frame.len()
+                )
+            );
+        }
+
+        assert!(
+            std::future::poll_fn(|cx| Pin::new(&mut body).poll_next(cx))
+                .await
+                .is_none()
+        );
+    }
+
+    fn png() -> &'static [u8] {
+        b"png"
+    }
+
+    fn jpeg() -> &'static [u8] {
+        b"jpeg"
+    }
+
+    async fn screenshot(headers: HeaderMap) -> Result<StreamResponse, ErrorResponse> {
+        negotiate(&headers, &[("image/png", &png), ("image/jpeg", &jpeg)])
+    }
+
+    async fn send_screenshot(accept: Option<&str>) -> (StatusCode, Option<String>, Bytes) {
+        let (_, _, router) = Device::new()
+            .route(stream_stateless(
+                Route::get("Screenshot", "/screenshot").description("Take a screenshot."),
+                screenshot,
+            ))
+            .unwrap()
+            .finalize();
+
+        let mut request = Request::get("/screenshot");
+        if let Some(accept) = accept {
+            request = request.header(ACCEPT, accept);
+        }
+
+        let response = router
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        let status = response.status();
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .map(|value| value.to_str().unwrap().into());
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, content_type, body)
+    }
+
+    #[tokio::test]
+    async fn negotiate_media_type() {
+        for accept in [None, Some("*/*"), Some("image/*"), Some("image/png")] {
+            let (status, content_type, body) = send_screenshot(accept).await;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(content_type.as_deref(), Some("image/png"));
+            assert_eq!(body, "png");
+        }
+
+        for accept in ["image/jpeg", "image/png;q=0.5, image/jpeg"] {
+            let (status, content_type, body) = send_screenshot(Some(accept)).await;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(content_type.as_deref(), Some("image/jpeg"));
+            assert_eq!(body, "jpeg");
+        }
+
+        let (status, _, _) = send_screenshot(Some("text/html")).await;
+        assert_eq!(status, StatusCode::NOT_ACCEPTABLE);
+    }
+}
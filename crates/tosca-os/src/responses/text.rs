@@ -0,0 +1,225 @@
+use core::future::Future;
+
+use tosca::response::{ResponseKind, SerialResponse as ToscaSerialResponse};
+use tosca::route::Route;
+
+use axum::{
+    body::Body,
+    extract::{Json, Request},
+    handler::Handler,
+    http::{
+        HeaderMap, HeaderValue, StatusCode,
+        header::{CONTENT_LENGTH, CONTENT_TYPE},
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use serde::Serialize;
+
+use super::{BaseResponse, error::ErrorResponse};
+
+// Media type of a plain text response.
+const TEXT_MEDIA_TYPE: &str = "text/plain; charset=utf-8";
+
+// Text of a response, kept until the media type is negotiated.
+#[derive(Clone)]
+struct PlainText(String);
+
+// Data of a text response sent as a JSON message.
+#[derive(Serialize)]
+struct TextData<'a> {
+    text: &'a str,
+}
+
+/// A response which transmits a human-readable text over the network.
+///
+/// The text is sent as the `text` field of a JSON message, the same as a
+/// [`SerialResponse`](super::serial::SerialResponse), unless a request
+/// prefers plain text through its `Accept` header.
+pub struct TextResponse(String);
+
+impl TextResponse {
+    /// Creates a [`TextResponse`].
+    #[must_use]
+    #[inline]
+    pub fn new(text: impl Into<String>) -> Self {
+        Self(text.into())
+    }
+}
+
+impl IntoResponse for TextResponse {
+    fn into_response(self) -> Response {
+        let response = ToscaSerialResponse::new(TextData { text: &self.0 });
+
+        #[cfg(feature = "time")]
+        let response = response.with_generated_at(super::now());
+
+        let mut http_response = (StatusCode::OK, Json(response)).into_response();
+        http_response.extensions_mut().insert(PlainText(self.0));
+        http_response
+    }
+}
+
+// Checks whether a request prefers plain text over json.
+//
+// When both media types are equally acceptable, json is preferred.
+fn prefers_text(headers: &HeaderMap) -> bool {
+    super::quality(headers, &["text/plain", "text/*"])
+        > super::quality(headers, &["application/json", "application/*"])
+}
+
+// Replaces a json response body with its plain text, when preferred by the
+// request.
+async fn negotiate_text(request: Request, next: Next) -> Response {
+    let prefers_text = prefers_text(request.headers());
+
+    let mut response = next.run(request).await;
+    let Some(PlainText(text)) = response.extensions_mut().remove::<PlainText>() else {
+        return response;
+    };
+
+    if !prefers_text {
+        return response;
+    }
+
+    let (mut parts, _) = response.into_parts();
+    parts.headers.remove(CONTENT_LENGTH);
+    parts
+        .headers
+        .insert(CONTENT_TYPE, HeaderValue::from_static(TEXT_MEDIA_TYPE));
+    Response::from_parts(parts, Body::from(text))
+}
+
+impl BaseResponse {
+    // Enables a route to answer with plain text.
+    fn with_text_negotiation(mut self) -> Self {
+        self.router = self.router.layer(axum::middleware::from_fn(negotiate_text));
+        self
+    }
+}
+
+mod private {
+    #[doc(hidden)]
+    pub trait TextTypeName<Args> {}
+}
+
+impl<F, Fut> private::TextTypeName<()> for F
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<TextResponse, ErrorResponse>> + Send,
+{
+}
+
+macro_rules! impl_text_type_name {
+    (
+        [$($ty:ident),*], $($last:ident)?
+    ) => {
+        impl<F, Fut, M, $($ty,)* $($last)?> private::TextTypeName<(M, $($ty,)* $($last)?)> for F
+        where
+            F: FnOnce($($ty,)* $($last)?) -> Fut,
+            Fut: Future<Output = Result<TextResponse, ErrorResponse>> + Send,
+            {
+            }
+    };
+}
+
+super::all_the_tuples!(impl_text_type_name);
+
+/// Creates a stateful [`BaseResponse`] from a [`TextResponse`].
+#[inline]
+pub fn text_stateful<H, T, S>(route: Route, handler: H) -> impl FnOnce(S) -> BaseResponse
+where
+    H: Handler<T, S> + private::TextTypeName<T>,
+    T: 'static,
+    S: Clone + Send + Sync + 'static,
+{
+    move |state: S| {
+        BaseResponse::stateful(route, ResponseKind::Serial, handler, state).with_text_negotiation()
+    }
+}
+
+/// Creates a stateless [`BaseResponse`] from a [`TextResponse`].
+#[inline]
+pub fn text_stateless<H, T, S>(route: Route, handler: H) -> impl FnOnce(S) -> BaseResponse
+where
+    H: Handler<T, ()> + private::TextTypeName<T>,
+    T: 'static,
+    S: Clone + Send + Sync + 'static,
+{
+    move |_state: S| {
+        BaseResponse::stateless(route, ResponseKind::Serial, handler).with_text_negotiation()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tosca::route::Route;
+
+    use axum::body::{Body, to_bytes};
+    use axum::http::{
+        Request, StatusCode,
+        header::{ACCEPT, CONTENT_TYPE},
+    };
+
+    use tower::ServiceExt;
+
+    use crate::device::Device;
+    use crate::responses::error::ErrorResponse;
+
+    use super::{TextResponse, text_stateless};
+
+    async fn status() -> Result<TextResponse, ErrorResponse> {
+        Ok(TextResponse::new("The light is on"))
+    }
+
+    async fn send(accept: Option<&str>) -> (String, String) {
+        let (_, _, router) = Device::new()
+            .route(text_stateless(
+                Route::get("Status", "/status").description("Retrieve light status."),
+                status,
+            ))
+            .unwrap()
+            .finalize();
+
+        let mut request = Request::get("/status");
+        if let Some(accept) = accept {
+            request = request.header(ACCEPT, accept);
+        }
+
+        let response = router
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let content_type = response.headers()[CONTENT_TYPE].to_str().unwrap().into();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (content_type, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn text_negotiation() {
+        // Json by default.
+        let (content_type, body) = send(None).await;
+        assert_eq!(content_type, "application/json");
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&body).unwrap()["text"],
+            "The light is on"
+        );
+
+        let (content_type, _) = send(Some("*/*")).await;
+        assert_eq!(content_type, "application/json");
+
+        let (content_type, _) = send(Some("text/plain;q=0.5, application/json")).await;
+        assert_eq!(content_type, "application/json");
+
+        // Plain text when preferred.
+        let (content_type, body) = send(Some("text/plain")).await;
+        assert_eq!(content_type, "text/plain; charset=utf-8");
+        assert_eq!(body, "The light is on");
+
+        let (content_type, _) = send(Some("application/json;q=0.5, text/*")).await;
+        assert_eq!(content_type, "text/plain; charset=utf-8");
+    }
+}
@@ -1,12 +1,37 @@
-use std::future::Future;
+use std::future::{Future, IntoFuture};
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::net::Ipv4Addr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
 
-use axum::{Router, response::Redirect};
+use tosca::device::DeviceKind;
+use tosca::response::{ErrorKind, ErrorResponse as ToscaErrorResponse};
+use tosca::route::RouteConfigs;
 
-use tracing::info;
+use axum::{
+    Router,
+    body::{Body, Bytes, HttpBody, to_bytes},
+    extract::Request,
+    handler::Handler,
+    http::{
+        HeaderMap, HeaderValue, StatusCode, Uri,
+        header::{ACCEPT, CONTENT_LENGTH, CONTENT_TYPE, ETAG, IF_NONE_MATCH},
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+    routing::MethodRouter,
+};
+
+use tokio::sync::{oneshot, watch};
+use tokio::time::Instant;
+
+use tracing::{info, warn};
 
 use crate::device::Device;
 use crate::error::Result;
+use crate::idempotency::idempotent_router;
+use crate::responses::router_path;
 use crate::services::{Service, ServiceConfig};
 
 // Default HTTP address.
@@ -28,6 +53,383 @@ const DEFAULT_SCHEME: &str = "http";
 // at URLs consistent well-known locations across servers.
 const DEFAULT_WELL_KNOWN_SERVICE: &str = "tosca";
 
+// Media type of a device description.
+const JSON_MEDIA_TYPE: &str = "application/json";
+
+// Maximum size of a json response body to be indented.
+//
+// Larger bodies and bodies of unknown size, such as streams, are returned
+// unchanged.
+const MAX_PRETTY_BODY_SIZE: usize = 1024 * 1024;
+
+// Instant at which the first server started running.
+//
+// It is measured by the runtime clock, so tests can advance it.
+static SERVER_START: OnceLock<Instant> = OnceLock::new();
+
+/// Returns the number of seconds elapsed since the server started running.
+///
+/// The same value is added to every
+/// [`InfoResponse`](crate::responses::info::InfoResponse). The return value
+/// is [`None`] when no server has been run yet.
+#[must_use]
+pub fn uptime() -> Option<u64> {
+    SERVER_START.get().map(|start| start.elapsed().as_secs())
+}
+
+// Starts counting the server uptime, if not already started.
+pub(crate) fn start_uptime() {
+    SERVER_START.get_or_init(Instant::now);
+}
+
+// A serialized device description along with its entity tag.
+#[derive(Debug)]
+struct DeviceDescription {
+    // Description in json format.
+    body: Bytes,
+    // Entity tag identifying the description content.
+    etag: HeaderValue,
+}
+
+impl DeviceDescription {
+    fn new(body: Vec<u8>) -> Self {
+        let mut hasher = DefaultHasher::new();
+        body.hash(&mut hasher);
+
+        // A hexadecimal number between quotes is always a valid header value.
+        let etag = HeaderValue::from_str(&format!("\"{:016x}\"", hasher.finish()))
+            .expect("Invalid entity tag");
+
+        Self {
+            body: Bytes::from(body),
+            etag,
+        }
+    }
+
+    fn response(&self, headers: &HeaderMap) -> Response {
+        if !accepts_json(headers) {
+            return StatusCode::NOT_ACCEPTABLE.into_response();
+        }
+
+        if matches_etag(headers, &self.etag) {
+            return (StatusCode::NOT_MODIFIED, [(ETAG, self.etag.clone())]).into_response();
+        }
+
+        (
+            [
+                (CONTENT_TYPE, HeaderValue::from_static(JSON_MEDIA_TYPE)),
+                (ETAG, self.etag.clone()),
+            ],
+            self.body.clone(),
+        )
+            .into_response()
+    }
+}
+
+// Checks whether the `Accept` header allows a json response.
+//
+// A missing header accepts any media type.
+fn accepts_json(headers: &HeaderMap) -> bool {
+    let Some(accept) = headers.get(ACCEPT).and_then(|value| value.to_str().ok()) else {
+        return true;
+    };
+
+    accept.split(',').any(|media_range| {
+        let mut parts = media_range.split(';').map(str::trim);
+        let media_type = parts.next().unwrap_or_default();
+        // A media range with a zero quality value is not acceptable.
+        let rejected = parts.any(|parameter| {
+            parameter
+                .strip_prefix("q=")
+                .and_then(|quality| quality.parse::<f32>().ok())
+                .is_some_and(|quality| quality == 0.)
+        });
+
+        !rejected && matches!(media_type, JSON_MEDIA_TYPE | "application/*" | "*/*")
+    })
+}
+
+// Checks whether the `If-None-Match` header matches the given entity tag.
+fn matches_etag(headers: &HeaderMap, etag: &HeaderValue) -> bool {
+    let Some(if_none_match) = headers
+        .get(IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+    else {
+        return false;
+    };
+
+    let etag = etag.to_str().unwrap_or_default();
+
+    // Weak comparison is used, so the weakness indicator is ignored.
+    if_none_match
+        .split(',')
+        .map(str::trim)
+        .any(|tag| tag == "*" || tag.strip_prefix("W/").unwrap_or(tag) == etag)
+}
+
+// Indents a json response body, keeping the order of its fields.
+//
+// Responses with a different media type, with a body which is not valid
+// json, or with a body larger than `MAX_PRETTY_BODY_SIZE` are returned
+// unchanged.
+async fn pretty_json(response: Response) -> Response {
+    let is_json = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with(JSON_MEDIA_TYPE));
+    if !is_json
+        || response
+            .body()
+            .size_hint()
+            .upper()
+            .is_none_or(|size| size > MAX_PRETTY_BODY_SIZE as u64)
+    {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let Ok(body) = to_bytes(body, MAX_PRETTY_BODY_SIZE).await else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+
+    if serde_json::from_slice::<serde::de::IgnoredAny>(&body).is_err() {
+        return Response::from_parts(parts, Body::from(body));
+    }
+
+    // The length of the body has changed.
+    parts.headers.remove(CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(indent_json(&body)))
+}
+
+// Indents a valid json text with two spaces, as `serde_json` does.
+//
+// The text is indented as it is, so its fields are never reordered.
+fn indent_json(json: &[u8]) -> Vec<u8> {
+    fn new_line(pretty: &mut Vec<u8>, depth: usize) {
+        pretty.push(b'\n');
+        for _ in 0..depth {
+            pretty.extend_from_slice(b"  ");
+        }
+    }
+
+    let mut pretty = Vec::with_capacity(json.len() * 2);
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut index = 0;
+
+    while let Some(&byte) = json.get(index) {
+        index += 1;
+
+        if in_string {
+            pretty.push(byte);
+            if escaped {
+                escaped = false;
+            } else if byte == b'\\' {
+                escaped = true;
+            } else if byte == b'"' {
+                in_string = false;
+            }
+            continue;
+        }
+
+        match byte {
+            b'"' => {
+                in_string = true;
+                pretty.push(byte);
+            }
+            b'{' | b'[' => {
+                pretty.push(byte);
+                while json.get(index).is_some_and(u8::is_ascii_whitespace) {
+                    index += 1;
+                }
+                // Empty objects and arrays are kept on a single line.
+                if let Some(&close @ (b'}' | b']')) = json.get(index) {
+                    pretty.push(close);
+                    index += 1;
+                } else {
+                    depth += 1;
+                    new_line(&mut pretty, depth);
+                }
+            }
+            b'}' | b']' => {
+                depth -= 1;
+                new_line(&mut pretty, depth);
+                pretty.push(byte);
+            }
+            b',' => {
+                pretty.push(byte);
+                new_line(&mut pretty, depth);
+            }
+            b':' => pretty.extend_from_slice(b": "),
+            byte if byte.is_ascii_whitespace() => {}
+            byte => pretty.push(byte),
+        }
+    }
+
+    pretty
+}
+
+// Requests being handled by a server.
+//
+// When a shutdown timeout elapses, pending requests are aborted.
+#[derive(Debug, Clone)]
+struct InFlightRequests {
+    // Number of pending requests.
+    pending: Arc<AtomicUsize>,
+    // Abort signal.
+    abort: watch::Receiver<bool>,
+}
+
+impl InFlightRequests {
+    fn new() -> (Self, watch::Sender<bool>) {
+        let (abort_tx, abort_rx) = watch::channel(false);
+        (
+            Self {
+                pending: Arc::new(AtomicUsize::new(0)),
+                abort: abort_rx,
+            },
+            abort_tx,
+        )
+    }
+
+    fn pending(&self) -> usize {
+        self.pending.load(Ordering::SeqCst)
+    }
+
+    async fn track(mut self, request: Request, next: Next) -> Response {
+        let _guard = PendingRequest::new(Arc::clone(&self.pending));
+
+        tokio::select! {
+            response = next.run(request) => response,
+            _ = self.abort.wait_for(|abort| *abort) => {
+                StatusCode::SERVICE_UNAVAILABLE.into_response()
+            }
+        }
+    }
+}
+
+// Counts a request as pending until it is dropped.
+struct PendingRequest(Arc<AtomicUsize>);
+
+impl PendingRequest {
+    fn new(pending: Arc<AtomicUsize>) -> Self {
+        pending.fetch_add(1, Ordering::SeqCst);
+        Self(pending)
+    }
+}
+
+impl Drop for PendingRequest {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+// Creates a router returning a device description both at the server root
+// and at the well-known URI.
+fn description_router(device_info: &serde_json::Value, well_known_uri: &str) -> Result<Router> {
+    let description = Arc::new(DeviceDescription::new(serde_json::to_vec(device_info)?));
+    let well_known_description = Arc::clone(&description);
+
+    Ok(Router::new()
+        .route(
+            "/",
+            axum::routing::get(
+                move |headers: HeaderMap| async move { description.response(&headers) },
+            ),
+        )
+        .route(
+            well_known_uri,
+            axum::routing::get(move |headers: HeaderMap| async move {
+                well_known_description.response(&headers)
+            }),
+        ))
+}
+
+// Creates a fallback answering requests to unknown paths with a json error
+// which lists the device routes.
+fn routes_not_found(main_route: &str, route_configs: &RouteConfigs) -> Result<MethodRouter> {
+    let routes = serde_json::Value::Array(
+        route_configs
+            .iter()
+            .map(|route_config| {
+                serde_json::json!({
+                    "method": route_config.rest_kind,
+                    "path": format!("{main_route}{}", router_path(route_config)),
+                })
+            })
+            .collect(),
+    );
+
+    // Check that the error is serializable once, before serving requests.
+    serde_json::to_value(ToscaErrorResponse::not_found("Route not found"))?;
+
+    Ok(axum::routing::any(move |uri: Uri| async move {
+        let error = ToscaErrorResponse::not_found("Route not found").with_route(uri.path());
+        let mut error =
+            serde_json::to_value(error).expect("An error response is always serializable");
+        error["routes"] = routes;
+
+        (StatusCode::NOT_FOUND, axum::Json(error))
+    }))
+}
+
+// Maps the kind of a device error from an `HTTP` status.
+fn error_kind(status: StatusCode) -> ErrorKind {
+    match status {
+        StatusCode::NOT_FOUND => ErrorKind::NotFound,
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => ErrorKind::Unauthorized,
+        StatusCode::TOO_MANY_REQUESTS => ErrorKind::RateLimited,
+        StatusCode::CONFLICT => ErrorKind::InvalidState,
+        status if status.is_client_error() => ErrorKind::InvalidData,
+        _ => ErrorKind::Internal,
+    }
+}
+
+// Wraps the errors of requests failed before reaching a device operation
+// into a json error, containing the requested route.
+//
+// Requests might fail because of a wrong method, a malformed parameter, or a
+// layer rejecting them, such as an authentication layer. Json responses,
+// which are produced by device operations, are returned unchanged.
+async fn device_errors(request: Request, next: Next) -> Response {
+    let route = request.uri().path().to_owned();
+    let response = next.run(request).await;
+
+    let status = response.status();
+    let is_json = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with(JSON_MEDIA_TYPE));
+    if !(status.is_client_error() || status.is_server_error()) || is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let body = to_bytes(body, usize::MAX).await.unwrap_or_default();
+
+    // Rejections describe their cause as plain text.
+    let description = match String::from_utf8_lossy(&body).trim() {
+        "" => status.canonical_reason().unwrap_or("Error").to_owned(),
+        description => description.to_owned(),
+    };
+
+    let error =
+        ToscaErrorResponse::with_description(error_kind(status), &description).with_route(&route);
+    let Ok(body) = serde_json::to_vec(&error) else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+
+    // Headers describing the error, such as `Allow`, are kept.
+    parts.headers.remove(CONTENT_LENGTH);
+    parts
+        .headers
+        .insert(CONTENT_TYPE, HeaderValue::from_static(JSON_MEDIA_TYPE));
+    Response::from_parts(parts, Body::from(body))
+}
+
 #[derive(Debug)]
 struct ServerData<'a, S>
 where
@@ -43,11 +445,24 @@ where
     well_known_service: &'a str,
     // Service configurator.
     service_config: Option<ServiceConfig<'a>>,
+    // Maximum time to wait for pending requests on shutdown.
+    shutdown_timeout: Option<Duration>,
+    // Whether json responses are indented.
+    pretty_json: bool,
+    // Handler for requests to unknown paths.
+    fallback: Option<MethodRouter>,
+    // Listener already bound to the server address.
+    listener: Option<tokio::net::TcpListener>,
     // Device.
     device: Device<S>,
 }
 
 /// A [`Device`] server.
+///
+/// The server caches the responses to device requests carrying an
+/// idempotency key, so a repeated request does not run an operation twice.
+/// Up to 64 successful responses of at most 64 KiB are cached, each one for
+/// 5 minutes, and the least recently used response is evicted first.
 #[derive(Debug)]
 pub struct Server<'a, S = ()>
 where
@@ -69,6 +484,10 @@ where
                 scheme: DEFAULT_SCHEME,
                 well_known_service: DEFAULT_WELL_KNOWN_SERVICE,
                 service_config: None,
+                shutdown_timeout: None,
+                pretty_json: false,
+                fallback: None,
+                listener: None,
                 device,
             },
         }
@@ -110,6 +529,57 @@ where
         self
     }
 
+    /// Sets the maximum time to wait for pending requests once a graceful
+    /// shutdown signal is received.
+    ///
+    /// New connections are no longer accepted, while requests still pending
+    /// when the timeout elapses are aborted.
+    #[must_use]
+    pub const fn shutdown_timeout(mut self, timeout: Duration) -> Self {
+        self.data.shutdown_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets whether json responses are indented.
+    ///
+    /// Indented responses are easier to read while developing a device,
+    /// while compact responses, the default, are smaller to transmit.
+    ///
+    /// Indentation keeps the order of the response fields, while responses
+    /// larger than 1 MiB are always sent compact.
+    #[must_use]
+    pub const fn pretty_json(mut self, pretty_json: bool) -> Self {
+        self.data.pretty_json = pretty_json;
+        self
+    }
+
+    /// Sets the handler for requests to unknown paths.
+    ///
+    /// By default, a `404 Not Found` json error listing the device routes
+    /// is returned.
+    #[must_use]
+    #[inline]
+    pub fn fallback<H, T>(mut self, handler: H) -> Self
+    where
+        H: Handler<T, ()>,
+        T: 'static,
+    {
+        self.data.fallback = Some(axum::routing::any(handler));
+        self
+    }
+
+    /// Sets a listener already bound to the server address.
+    ///
+    /// The server accepts connections from this listener, ignoring the
+    /// address and port set on it, so a listener bound to port `0` runs the
+    /// server on a port chosen by the operating system.
+    #[must_use]
+    #[inline]
+    pub fn listener(mut self, listener: tokio::net::TcpListener) -> Self {
+        self.data.listener = Some(listener);
+        self
+    }
+
     /// Enables a server with a graceful shutdown operation being performed
     /// by the [`Future`] passed as input.
     #[must_use]
@@ -159,12 +629,30 @@ where
     ///
     /// It returns an error whenever a server fails to start.
     pub async fn run(self) -> Result<()> {
+        start_uptime();
+
         // Create listener bind.
         let listener_bind = format!("{}:{}", self.data.http_address, self.data.port);
 
+        // Check that all mandatory routes have been added.
+        self.data.device.validate()?;
+
+        // Device location advertised by the discovery service.
+        let location = self.data.device.advertised_location().map(str::to_owned);
+
         // Consume a device returning all server information.
         let (device_main_route, device_info, device_router) = self.data.device.finalize();
 
+        // Device properties advertised by the discovery service.
+        let device_kind = device_info.kind.clone();
+        let routes_count = device_info.route_configs.len();
+
+        // Answer requests to unknown paths.
+        let fallback = match self.data.fallback {
+            Some(fallback) => fallback,
+            None => routes_not_found(device_main_route, &device_info.route_configs)?,
+        };
+
         // Serialize device information returning a json format.
         let device_info = serde_json::to_value(device_info)?;
 
@@ -174,48 +662,647 @@ where
         info!("Server route: [GET, \"/\"]");
         info!("Server route: [GET, \"{}\"]", well_known_uri);
 
-        // Run a discovery service if present.
-        if let Some(service_config) = self.data.service_config {
-            // Add server properties.
-            let service_config = service_config
-                .property(("scheme", self.data.scheme))
-                .property(("path", well_known_uri.clone()));
-
-            // Run service.
-            Service::run(service_config, self.data.http_address, self.data.port)?;
-        }
-
         // Create the main router.
         //
-        //- Save device info as a json format which is returned when a query to
-        //  the server root is requested.
-        //- Redirect well-known URI to server root.
-        let router = Router::new()
-            .route(
-                "/",
-                axum::routing::get(move || async { axum::Json(device_info) }),
-            )
-            .route(
-                &well_known_uri,
-                axum::routing::get(move || async { Redirect::to("/") }),
-            )
-            .nest(device_main_route, device_router);
+        // Save device info as a json format which is returned when a query to
+        // the server root or to the well-known URI is requested. The `Accept`
+        // and `If-None-Match` headers of a request are honored.
+        //
+        // Device responses to requests containing an idempotency key are
+        // cached, so that a repeated request does not run an operation twice.
+        //
+        // Requests failing before reaching a device operation are answered
+        // with a json error.
+        let router = description_router(&device_info, &well_known_uri)?
+            .nest(device_main_route, idempotent_router(device_router))
+            .fallback_service(fallback)
+            .layer(axum::middleware::from_fn(device_errors));
 
-        // Print server Ip and port.
-        info!("Device reachable at this HTTP address: {listener_bind}");
+        // Indent json responses, if requested.
+        let router = if self.data.pretty_json {
+            router.layer(axum::middleware::map_response(pretty_json))
+        } else {
+            router
+        };
 
         // Create a new TCP socket which responds to the specified HTTP address
-        // and port.
-        let listener = tokio::net::TcpListener::bind(listener_bind).await?;
+        // and port, unless a listener has already been bound.
+        let listener = match self.data.listener {
+            Some(listener) => listener,
+            None => tokio::net::TcpListener::bind(listener_bind).await?,
+        };
+        let local_address = listener.local_addr()?;
+
+        // Print server Ip and port.
+        info!("Device reachable at this HTTP address: {local_address}");
+
+        // Announce the address and port the listener is actually bound to.
+        let http_address = match local_address.ip() {
+            std::net::IpAddr::V4(ip) => ip,
+            std::net::IpAddr::V6(_) => self.data.http_address,
+        };
+
+        // Run a discovery service if present.
+        let service = match self.data.service_config {
+            Some(service_config) => {
+                // Add server and device properties.
+                let service_config = service_properties(
+                    service_config,
+                    self.data.scheme,
+                    &well_known_uri,
+                    &device_kind,
+                    routes_count,
+                    location.as_deref(),
+                );
+
+                // Run service.
+                Some(Service::run(
+                    service_config,
+                    http_address,
+                    local_address.port(),
+                )?)
+            }
+            None => None,
+        };
 
         // Print server start message
         info!("Starting server...");
 
+        let result = serve(listener, router, self.signal, self.data.shutdown_timeout).await;
+
+        // The device is no longer reachable, so stop announcing it.
+        if let Some(service) = service {
+            service.stop();
+        }
+
+        result
+    }
+}
+
+// Serves a router until the shutdown signal is received.
+//
+// When a shutdown timeout is given, pending requests are aborted once it
+// elapses.
+async fn serve<F>(
+    listener: tokio::net::TcpListener,
+    router: Router,
+    signal: F,
+    shutdown_timeout: Option<Duration>,
+) -> Result<()>
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    let Some(shutdown_timeout) = shutdown_timeout else {
         // Start the server
         axum::serve(listener, router)
-            .with_graceful_shutdown(self.signal)
+            .with_graceful_shutdown(signal)
             .await?;
 
-        Ok(())
+        return Ok(());
+    };
+
+    // Track pending requests to abort them when the timeout elapses.
+    let (in_flight, abort_tx) = InFlightRequests::new();
+    let router = router.layer(axum::middleware::from_fn({
+        let in_flight = in_flight.clone();
+        move |request: Request, next: Next| in_flight.clone().track(request, next)
+    }));
+
+    // Notify when the shutdown signal is received.
+    let (signal_tx, signal_rx) = oneshot::channel();
+
+    // Start the server
+    let server = axum::serve(listener, router)
+        .with_graceful_shutdown(async move {
+            signal.await;
+            _ = signal_tx.send(());
+        })
+        .into_future();
+    tokio::pin!(server);
+
+    tokio::select! {
+        result = &mut server => return Ok(result?),
+        () = async {
+            if signal_rx.await.is_ok() {
+                tokio::time::sleep(shutdown_timeout).await;
+            } else {
+                std::future::pending::<()>().await;
+            }
+        } => {}
+    }
+
+    warn!(
+        "Shutdown timeout elapsed, aborting {} pending requests",
+        in_flight.pending()
+    );
+    _ = abort_tx.send(true);
+
+    server.await?;
+
+    Ok(())
+}
+
+// Adds the server and device properties to a discovery service.
+fn service_properties<'a>(
+    service_config: ServiceConfig<'a>,
+    scheme: &str,
+    well_known_uri: &str,
+    device_kind: &DeviceKind,
+    routes_count: usize,
+    location: Option<&str>,
+) -> ServiceConfig<'a> {
+    let service_config = service_config
+        .property(("scheme", scheme))
+        .property(("path", well_known_uri))
+        .property(("kind", device_kind.as_str()))
+        .property(("routes", routes_count.to_string()));
+
+    match location {
+        Some(location) => service_config.property(("location", location)),
+        None => service_config,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::{Body, to_bytes};
+    use axum::http::{
+        Request, StatusCode,
+        header::{ACCEPT, CONTENT_TYPE, ETAG, IF_NONE_MATCH},
+    };
+    use axum::middleware::Next;
+    use axum::response::IntoResponse;
+
+    use serde_json::json;
+
+    use tower::ServiceExt;
+
+    use std::time::{Duration, Instant};
+
+    use tosca::device::{DeviceInfo, DeviceKind};
+    use tosca::route::Route;
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+
+    use crate::device::Device;
+    use crate::responses::error::ErrorResponse;
+    use crate::responses::info::{InfoResponse, info_stateless};
+    use crate::responses::ok::{OkResponse, ok_stateless};
+
+    use crate::services::ServiceConfig;
+
+    use crate::responses::serial::{SerialResponse, serial_stateless};
+
+    use super::{
+        JSON_MEDIA_TYPE, MAX_PRETTY_BODY_SIZE, Server, description_router, device_errors,
+        indent_json, pretty_json, routes_not_found, service_properties, start_uptime,
+    };
+
+    const WELL_KNOWN_URI: &str = "/.well-known/light";
+
+    async fn send(headers: &[(&str, &str)]) -> axum::response::Response {
+        let router = description_router(&json!({ "kind": "Light" }), WELL_KNOWN_URI).unwrap();
+
+        let mut request = Request::get(WELL_KNOWN_URI);
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+
+        router
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn json_description() {
+        let response = send(&[]).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CONTENT_TYPE], "application/json");
+        assert!(response.headers().contains_key(ETAG));
+
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], br#"{"kind":"Light"}"#);
+
+        let response = send(&[(ACCEPT.as_str(), "text/html, application/*;q=0.8")]).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn not_acceptable_description() {
+        let response = send(&[(ACCEPT.as_str(), "text/html")]).await;
+        assert_eq!(response.status(), StatusCode::NOT_ACCEPTABLE);
+
+        let response = send(&[(ACCEPT.as_str(), "application/json;q=0")]).await;
+        assert_eq!(response.status(), StatusCode::NOT_ACCEPTABLE);
+    }
+
+    #[tokio::test]
+    async fn not_modified_description() {
+        let response = send(&[]).await;
+        let etag = response.headers()[ETAG].to_str().unwrap().to_string();
+
+        let response = send(&[(IF_NONE_MATCH.as_str(), &etag)]).await;
+
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[ETAG], etag.as_str());
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert!(body.is_empty());
+
+        // A different entity tag returns the whole description.
+        let response = send(&[(IF_NONE_MATCH.as_str(), "\"0\"")]).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    async fn slow_response() -> Result<OkResponse, ErrorResponse> {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        Ok(OkResponse::ok())
+    }
+
+    async fn endless_response() -> Result<OkResponse, ErrorResponse> {
+        std::future::pending::<()>().await;
+        Ok(OkResponse::ok())
+    }
+
+    // Runs a server with the given device, then sends a shutdown signal
+    // while the "/device/slow" route is being handled.
+    //
+    // Returns the route response and the time elapsed from the shutdown
+    // signal to the server stop.
+    async fn shutdown_with_pending_request(
+        device: Device,
+        shutdown_timeout: Duration,
+    ) -> (String, Duration) {
+        let (close_tx, close_rx) = tokio::sync::oneshot::channel();
+
+        // Bind a port chosen by the operating system.
+        let listener = tokio::net::TcpListener::bind(("127.0.0.1", 0))
+            .await
+            .unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let server = tokio::spawn(
+            Server::new(device)
+                .listener(listener)
+                .shutdown_timeout(shutdown_timeout)
+                .with_graceful_shutdown(async move {
+                    _ = close_rx.await;
+                })
+                .run(),
+        );
+
+        // Wait for the server to be configured.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let mut stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+        stream
+            .write_all(b"GET /device/slow HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+
+        // Wait for the request to be handled.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let shutdown = Instant::now();
+        _ = close_tx.send(());
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        server.await.unwrap().unwrap();
+
+        (response, shutdown.elapsed())
+    }
+
+    #[tokio::test]
+    async fn drain_pending_request() {
+        let device = Device::new()
+            .route(ok_stateless(
+                Route::get("Slow", "/slow").description("A slow route."),
+                slow_response,
+            ))
+            .unwrap();
+
+        let (response, elapsed) =
+            shutdown_with_pending_request(device, Duration::from_secs(5)).await;
+
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(elapsed < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn abort_pending_request() {
+        let device = Device::new()
+            .route(ok_stateless(
+                Route::get("Slow", "/slow").description("An endless route."),
+                endless_response,
+            ))
+            .unwrap();
+
+        let (response, elapsed) =
+            shutdown_with_pending_request(device, Duration::from_millis(200)).await;
+
+        assert!(response.starts_with("HTTP/1.1 503 Service Unavailable"));
+        assert!(elapsed >= Duration::from_millis(200));
+        assert!(elapsed < Duration::from_secs(5));
+    }
+
+    async fn info_response() -> Result<InfoResponse, ErrorResponse> {
+        Ok(InfoResponse::new(DeviceInfo::empty()))
+    }
+
+    async fn read_uptime(router: axum::Router) -> u64 {
+        let response = router
+            .oneshot(Request::get("/info").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice::<serde_json::Value>(&body).unwrap()["uptime"]
+            .as_u64()
+            .unwrap()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn info_uptime() {
+        start_uptime();
+
+        let (_, _, router) = Device::new()
+            .info_route(info_stateless(
+                Route::get("Info", "/info").description("Retrieve device information."),
+                info_response,
+            ))
+            .unwrap()
+            .finalize();
+
+        let first = read_uptime(router.clone()).await;
+        tokio::time::advance(Duration::from_secs(10)).await;
+        let second = read_uptime(router).await;
+
+        assert!(second > first);
+    }
+
+    #[derive(serde::Serialize)]
+    struct Level {
+        level: u8,
+    }
+
+    async fn level_response() -> Result<SerialResponse<Level>, ErrorResponse> {
+        Ok(SerialResponse::new(Level { level: 5 }))
+    }
+
+    async fn read_level(router: axum::Router) -> String {
+        let response = router
+            .oneshot(Request::get("/level").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn sequence_serial_response() {
+        async fn levels_response() -> Result<SerialResponse<Vec<u8>>, ErrorResponse> {
+            Ok(SerialResponse::new(vec![3, 5, 8]))
+        }
+
+        let (_, _, router) = Device::new()
+            .route(serial_stateless(
+                Route::get("Levels", "/levels").description("Retrieve levels."),
+                levels_response,
+            ))
+            .unwrap()
+            .finalize();
+
+        let response = router
+            .oneshot(Request::get("/levels").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CONTENT_TYPE], "application/json");
+
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap(),
+            json!([3, 5, 8])
+        );
+    }
+
+    #[tokio::test]
+    async fn pretty_json_response() {
+        let (_, _, router) = Device::new()
+            .route(serial_stateless(
+                Route::get("Level", "/level").description("Retrieve level."),
+                level_response,
+            ))
+            .unwrap()
+            .finalize();
+
+        // Compact response.
+        let compact = read_level(router.clone()).await;
+        assert!(!compact.contains('\n'));
+
+        // Indented response.
+        let pretty = read_level(router.layer(axum::middleware::map_response(pretty_json))).await;
+        assert!(pretty.contains("\n  \"level\": 5"));
+
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&compact).unwrap(),
+            serde_json::from_str::<serde_json::Value>(&pretty).unwrap()
+        );
+
+        let indent = |body: String| async move {
+            let response =
+                pretty_json(([(CONTENT_TYPE, JSON_MEDIA_TYPE)], body).into_response()).await;
+            let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            String::from_utf8(body.to_vec()).unwrap()
+        };
+
+        // Fields keep their order.
+        assert_eq!(
+            indent(r#"{"zone":1,"level":5}"#.into()).await,
+            "{\n  \"zone\": 1,\n  \"level\": 5\n}"
+        );
+
+        // Larger responses are sent compact.
+        let large = format!("[\"{}\"]", "a".repeat(MAX_PRETTY_BODY_SIZE));
+        assert_eq!(indent(large.clone()).await, large);
+    }
+
+    #[test]
+    fn indent_json_text() {
+        let json = br#"{"b":[1,{"a":"x,\"y\":[]"}],"a":{},"c":[ ]}"#;
+        let pretty = String::from_utf8(indent_json(json)).unwrap();
+
+        assert_eq!(
+            pretty,
+            "{\n  \"b\": [\n    1,\n    {\n      \"a\": \"x,\\\"y\\\":[]\"\n    }\n  ],\n  \"a\": {},\n  \"c\": []\n}"
+        );
+    }
+
+    #[tokio::test]
+    async fn unknown_route() {
+        let (main_route, device_info, router) = Device::new()
+            .route(serial_stateless(
+                Route::get("Level", "/level").description("Retrieve level."),
+                level_response,
+            ))
+            .unwrap()
+            .finalize();
+
+        let fallback = routes_not_found(main_route, &device_info.route_configs).unwrap();
+        let router = description_router(&json!({ "kind": "Light" }), WELL_KNOWN_URI)
+            .unwrap()
+            .nest(main_route, router)
+            .fallback_service(fallback);
+
+        for path in ["/unknown", "/device/unknown"] {
+            let response = router
+                .clone()
+                .oneshot(Request::put(path).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+            assert_eq!(response.headers()[CONTENT_TYPE], "application/json");
+
+            let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            let error = serde_json::from_slice::<serde_json::Value>(&body).unwrap();
+            assert_eq!(error["error"], "NotFound");
+            assert_eq!(error["description"], "Route not found");
+            assert_eq!(error["route"], path);
+            assert_eq!(
+                error["routes"],
+                json!([{ "method": "Get", "path": "/device/level" }])
+            );
+        }
+    }
+
+    async fn device_error(router: &axum::Router, request: Request<Body>) -> serde_json::Value {
+        let response = router.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.headers()[CONTENT_TYPE], "application/json");
+
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let mut error = serde_json::from_slice::<serde_json::Value>(&body).unwrap();
+        error["status"] = status.as_u16().into();
+        error
+    }
+
+    #[tokio::test]
+    async fn device_error_responses() {
+        let (main_route, device_info, router) = Device::new()
+            .route(serial_stateless(
+                Route::get("Level", "/level").description("Retrieve level."),
+                level_response,
+            ))
+            .unwrap()
+            .route(serial_stateless(
+                Route::get("Secret", "/secret").description("Retrieve a secret."),
+                level_response,
+            ))
+            .unwrap()
+            .finalize();
+
+        // A layer rejecting unauthenticated requests.
+        let router = router.route_layer(axum::middleware::from_fn(
+            |request: Request<Body>, next: Next| async move {
+                if request.uri().path().ends_with("/secret") {
+                    StatusCode::UNAUTHORIZED.into_response()
+                } else {
+                    next.run(request).await
+                }
+            },
+        ));
+
+        let fallback = routes_not_found(main_route, &device_info.route_configs).unwrap();
+        let router = description_router(&json!({ "kind": "Light" }), WELL_KNOWN_URI)
+            .unwrap()
+            .nest(main_route, router)
+            .fallback_service(fallback)
+            .layer(axum::middleware::from_fn(device_errors));
+
+        // Unknown route.
+        let error = device_error(
+            &router,
+            Request::get("/device/unknown").body(Body::empty()).unwrap(),
+        )
+        .await;
+        assert_eq!(error["status"], 404);
+        assert_eq!(error["error"], "NotFound");
+        assert_eq!(error["route"], "/device/unknown");
+
+        // Rejected requester.
+        let error = device_error(
+            &router,
+            Request::get("/device/secret").body(Body::empty()).unwrap(),
+        )
+        .await;
+        assert_eq!(error["status"], 401);
+        assert_eq!(error["error"], "Unauthorized");
+        assert_eq!(error["description"], "Unauthorized");
+        assert_eq!(error["route"], "/device/secret");
+
+        // Wrong method.
+        let error = device_error(
+            &router,
+            Request::put("/device/level").body(Body::empty()).unwrap(),
+        )
+        .await;
+        assert_eq!(error["status"], 405);
+        assert_eq!(error["error"], "InvalidData");
+        assert_eq!(error["route"], "/device/level");
+
+        // Device operations are answered unchanged.
+        let response = router
+            .oneshot(Request::get("/device/level").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn device_service_properties() {
+        let service_config = service_properties(
+            ServiceConfig::mdns_sd("light"),
+            "http",
+            WELL_KNOWN_URI,
+            &DeviceKind::Light,
+            2,
+            None,
+        );
+
+        let properties = &service_config.properties;
+        assert_eq!(properties["scheme"], "http");
+        assert_eq!(properties["path"], WELL_KNOWN_URI);
+        assert_eq!(properties["kind"], "Light");
+        assert_eq!(properties["routes"], "2");
+        assert!(!properties.contains_key("location"));
+
+        let service_config = service_properties(
+            ServiceConfig::mdns_sd("light"),
+            "http",
+            WELL_KNOWN_URI,
+            &DeviceKind::Light,
+            2,
+            Some("kitchen"),
+        );
+        assert_eq!(service_config.properties["location"], "kitchen");
+
+        // A custom kind is advertised by its name.
+        let service_config = service_properties(
+            ServiceConfig::mdns_sd("valve"),
+            "http",
+            WELL_KNOWN_URI,
+            &DeviceKind::custom("WaterValve"),
+            2,
+            None,
+        );
+        assert_eq!(service_config.properties["kind"], "WaterValve");
     }
 }
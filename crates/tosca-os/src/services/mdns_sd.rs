@@ -2,7 +2,10 @@ use std::net::{IpAddr, Ipv4Addr};
 
 use mdns_sd::{IfKind, ServiceDaemon, ServiceInfo};
 
-use tracing::info;
+use tokio::task::JoinHandle;
+use tokio::time::{Instant, interval_at};
+
+use tracing::{info, warn};
 
 use crate::error::{Error, ErrorKind};
 
@@ -20,11 +23,34 @@ impl From<std::io::Error> for Error {
     }
 }
 
+// A registered `mDNS-SD` service, announced periodically until stopped.
+pub(crate) struct Announcer {
+    mdns: ServiceDaemon,
+    fullname: String,
+    task: JoinHandle<()>,
+}
+
+impl Announcer {
+    // Stops announcing the service, withdrawing it from the network and
+    // shutting down the daemon.
+    pub(crate) fn stop(self) {
+        self.task.abort();
+
+        if let Err(e) = self.mdns.unregister(&self.fullname) {
+            warn!("Impossible to withdraw the service: {e}");
+        }
+
+        if let Err(e) = self.mdns.shutdown() {
+            warn!("Impossible to shut down the service daemon: {e}");
+        }
+    }
+}
+
 pub(crate) fn run(
     service_config: ServiceConfig,
     server_address: Ipv4Addr,
     server_port: u16,
-) -> std::result::Result<(), Error> {
+) -> std::result::Result<Announcer, Error> {
     // Create a new mDNS service daemon
     let mdns = ServiceDaemon::new()?;
 
@@ -69,12 +95,26 @@ pub(crate) fn run(
         service_config.top_level_domain
     );
     info!("Service type: {}", service_type);
+
+    // Add the subtype to the service type.
+    let service_type = match service_config.subtype {
+        Some(subtype) => {
+            let subtype = subtype.trim_start_matches('_');
+            info!("Service subtype: {}", subtype);
+            format!("_{subtype}._sub.{service_type}")
+        }
+        None => service_type,
+    };
     info!(
         "Device reachable at this hostname: {}:{}",
         &hostname[0..hostname.len() - 1],
         server_port
     );
 
+    // Interval between two service announcements.
+    let announce_period = service_config.announce_period();
+    info!("Service announced every {}s", announce_period.as_secs());
+
     let service = ServiceInfo::new(
         // Service type
         &service_type,
@@ -96,7 +136,58 @@ pub(crate) fn run(
     )?
     .enable_addr_auto();
 
-    mdns.register(service)?;
+    mdns.register(service.clone())?;
+
+    // Announce the service again before it expires from controller caches.
+    let fullname = service.get_fullname().to_string();
+    let task = tokio::spawn({
+        let mdns = mdns.clone();
+        async move {
+            let mut interval = interval_at(Instant::now() + announce_period, announce_period);
+            loop {
+                interval.tick().await;
+                if let Err(e) = mdns.register(service.clone()) {
+                    warn!("Impossible to announce the service again: {e}");
+                }
+            }
+        }
+    });
+
+    Ok(Announcer {
+        mdns,
+        fullname,
+        task,
+    })
+}
 
-    Ok(())
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+    use std::time::Duration;
+
+    use super::{ServiceConfig, run};
+
+    #[tokio::test]
+    async fn stop_announcements() {
+        let announcer = run(
+            ServiceConfig::mdns_sd("stop-announcements").announce_interval(Duration::ZERO),
+            Ipv4Addr::LOCALHOST,
+            0,
+        )
+        .unwrap();
+
+        let task = announcer.task.abort_handle();
+        assert!(!task.is_finished());
+
+        announcer.stop();
+
+        // No announcement is sent once the service is stopped.
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !task.is_finished() {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await
+        .unwrap();
+    }
 }
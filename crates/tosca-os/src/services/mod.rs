@@ -2,10 +2,11 @@ mod mdns_sd;
 
 use std::collections::HashMap;
 use std::net::{IpAddr, Ipv4Addr};
+use std::time::Duration;
 
 use crate::error::Result;
 
-use mdns_sd::run;
+use mdns_sd::{Announcer, run};
 
 // Service domain.
 //
@@ -17,6 +18,16 @@ const DOMAIN: &str = "tosca";
 // It defines the default top-level domain for a service.
 const TOP_LEVEL_DOMAIN: &str = "local";
 
+// Default interval between two service announcements.
+//
+// The `mDNS-SD` daemon sends host records with the time-to-live recommended
+// by RFC 6762, 120 seconds, so a service is announced again after half of
+// it.
+const ANNOUNCE_INTERVAL: Duration = Duration::from_mins(1);
+
+// Shortest interval between two service announcements.
+const MIN_ANNOUNCE_INTERVAL: Duration = Duration::from_secs(1);
+
 /// Service transport protocol.
 #[derive(Debug, Clone, Copy)]
 pub enum TransportProtocol {
@@ -52,6 +63,8 @@ pub struct ServiceConfig<'a> {
     pub(crate) hostname: &'a str,
     // Service domain.
     pub(crate) domain: &'a str,
+    // Service subtype.
+    pub(crate) subtype: Option<&'a str>,
     // Service transport protocol.
     pub(crate) transport_protocol: TransportProtocol,
     // Top-level domain.
@@ -64,6 +77,8 @@ pub struct ServiceConfig<'a> {
     pub(crate) disable_ip: Option<IpAddr>,
     // Disable network interface.
     pub(crate) disable_network_interface: Option<&'a str>,
+    // Interval between two service announcements.
+    pub(crate) announce_interval: Option<Duration>,
 }
 
 impl<'a> ServiceConfig<'a> {
@@ -74,12 +89,14 @@ impl<'a> ServiceConfig<'a> {
             instance_name,
             hostname: instance_name,
             domain: DOMAIN,
+            subtype: None,
             transport_protocol: TransportProtocol::TCP,
             top_level_domain: TOP_LEVEL_DOMAIN,
             properties: HashMap::new(),
             disable_ipv6: false,
             disable_ip: None,
             disable_network_interface: None,
+            announce_interval: None,
         }
     }
 
@@ -111,6 +128,17 @@ impl<'a> ServiceConfig<'a> {
         self
     }
 
+    /// Sets the service subtype.
+    ///
+    /// A subtype, such as `light`, allows a controller to discover only
+    /// the devices advertising it. Devices are still discoverable without
+    /// specifying any subtype.
+    #[must_use]
+    pub const fn subtype(mut self, subtype: &'a str) -> Self {
+        self.subtype = Some(subtype);
+        self
+    }
+
     /// Sets the service top-level domain.
     #[must_use]
     pub const fn top_level_domain(mut self, top_level_domain: &'a str) -> Self {
@@ -118,6 +146,22 @@ impl<'a> ServiceConfig<'a> {
         self
     }
 
+    /// Sets the interval between two service announcements.
+    ///
+    /// Announcing a service more often keeps it visible to controllers on
+    /// lossy networks. The `mDNS-SD` daemon sends the service records with
+    /// the time-to-live values recommended by RFC 6762, which cannot be
+    /// changed, so by default a service is announced again every 60
+    /// seconds, half of the time-to-live of its host records. The interval
+    /// is at least one second.
+    ///
+    /// Announcements stop when the server shuts down.
+    #[must_use]
+    pub const fn announce_interval(mut self, interval: Duration) -> Self {
+        self.announce_interval = Some(interval);
+        self
+    }
+
     /// Disables `IPv6` interfaces.
     #[must_use]
     pub const fn disable_ipv6(mut self) -> Self {
@@ -141,8 +185,17 @@ impl<'a> ServiceConfig<'a> {
     }
 }
 
-// A new service.
-pub(crate) struct Service;
+impl ServiceConfig<'_> {
+    // Returns the interval between two service announcements.
+    pub(crate) fn announce_period(&self) -> Duration {
+        self.announce_interval
+            .unwrap_or(ANNOUNCE_INTERVAL)
+            .max(MIN_ANNOUNCE_INTERVAL)
+    }
+}
+
+// A running service.
+pub(crate) struct Service(Announcer);
 
 impl Service {
     // Runs a service.
@@ -151,7 +204,37 @@ impl Service {
         service_config: ServiceConfig,
         server_address: Ipv4Addr,
         port: u16,
-    ) -> Result<()> {
-        run(service_config, server_address, port)
+    ) -> Result<Self> {
+        run(service_config, server_address, port).map(Self)
+    }
+
+    // Stops a service, so it is no longer announced.
+    #[inline]
+    pub(crate) fn stop(self) {
+        self.0.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::ServiceConfig;
+
+    #[test]
+    fn default_announcements() {
+        let service_config = ServiceConfig::mdns_sd("light");
+        assert_eq!(service_config.announce_period(), Duration::from_mins(1));
+    }
+
+    #[test]
+    fn custom_announcements() {
+        let service_config =
+            ServiceConfig::mdns_sd("light").announce_interval(Duration::from_secs(5));
+        assert_eq!(service_config.announce_period(), Duration::from_secs(5));
+
+        // The interval is never zero.
+        let service_config = ServiceConfig::mdns_sd("light").announce_interval(Duration::ZERO);
+        assert_eq!(service_config.announce_period(), Duration::from_secs(1));
     }
 }
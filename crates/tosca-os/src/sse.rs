@@ -0,0 +1,219 @@
+use std::convert::Infallible;
+use std::time::Duration;
+
+use tosca::events::Events;
+
+use axum::Extension;
+use axum::response::sse::{Event, KeepAlive, Sse};
+
+use tokio::sync::broadcast;
+
+use tracing::warn;
+
+// Default maximum number of events buffered for each subscriber.
+const DEFAULT_CAPACITY: usize = 16;
+
+// Interval between two keep-alive messages sent to idle subscribers.
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// A stream delivering device [`Events`] to controllers through
+/// Server-Sent Events.
+///
+/// A stream registered on a [`crate::device::Device`] is served at the
+/// [`tosca::events::EVENTS_SSE_ROUTE`] route, so controllers can receive
+/// events without a broker. The route is described along with its hazards
+/// like any other device route. Route handlers retrieve the stream through the
+/// [`crate::extract::Extension`] extractor to send events.
+#[derive(Debug, Clone)]
+pub struct EventStream(broadcast::Sender<Events>);
+
+impl Default for EventStream {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventStream {
+    /// Creates an [`EventStream`].
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+
+    /// Creates an [`EventStream`] buffering at most `capacity` events for
+    /// each subscriber.
+    ///
+    /// When a subscriber is too slow, its oldest buffered events are
+    /// discarded.
+    ///
+    /// # Panics
+    ///
+    /// The capacity is zero.
+    #[must_use]
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self(broadcast::channel(capacity).0)
+    }
+
+    /// Sends [`Events`] to all subscribers, returning their number.
+    ///
+    /// Events sent when no subscriber is connected are discarded.
+    // Sending is the effect of the call, so its result can be ignored.
+    #[allow(clippy::must_use_candidate)]
+    #[inline]
+    pub fn send(&self, events: Events) -> usize {
+        self.0.send(events).unwrap_or_default()
+    }
+
+    /// Returns the number of connected subscribers.
+    #[must_use]
+    #[inline]
+    pub fn subscribers(&self) -> usize {
+        self.0.receiver_count()
+    }
+}
+
+// Answers a subscriber with a Server-Sent Events stream, each event carrying
+// device events in json format.
+pub(crate) async fn events_sse(
+    Extension(event_stream): Extension<EventStream>,
+) -> Sse<impl futures_util::Stream<Item = Result<Event, Infallible>>> {
+    let stream =
+        futures_util::stream::unfold(event_stream.0.subscribe(), |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(events) => {
+                        let event = Event::default().json_data(events).unwrap_or_else(|e| {
+                            Event::default().event("error").data(e.to_string())
+                        });
+                        return Some((Ok(event), receiver));
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("A slow subscriber has missed {skipped} events");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        });
+
+    Sse::new(stream).keep_alive(KeepAlive::new().interval(KEEP_ALIVE_INTERVAL))
+}
+
+#[cfg(test)]
+mod tests {
+    use tosca::events::{EVENTS_SSE_ROUTE, Event, Events};
+    use tosca::hazards::{Hazard, Hazards};
+    use tosca::response::ResponseKind;
+    use tosca::route::{RestKind, Route};
+
+    use axum::body::Body;
+    use axum::extract::Extension;
+    use axum::http::{Request, StatusCode, header::CONTENT_TYPE};
+
+    use futures_util::StreamExt;
+
+    use tower::ServiceExt;
+
+    use crate::device::Device;
+    use crate::responses::error::ErrorResponse;
+    use crate::responses::ok::{OkResponse, ok_stateless};
+
+    use super::EventStream;
+
+    fn events() -> Events {
+        Events::empty().bool_events(vec![Event::bool("on").description("Light status.")])
+    }
+
+    async fn notify(
+        Extension(event_stream): Extension<EventStream>,
+    ) -> Result<OkResponse, ErrorResponse> {
+        event_stream.send(events());
+        Ok(OkResponse::ok())
+    }
+
+    #[tokio::test]
+    async fn stream_events() {
+        let event_stream = EventStream::new();
+        let (_, device_data, router) = Device::new()
+            .event_stream(
+                event_stream.clone(),
+                Hazards::new().insert(Hazard::LogEnergyConsumption),
+            )
+            .unwrap()
+            .route(ok_stateless(
+                Route::put("Notify", "/notify")
+                    .description("Notify light status.")
+                    .with_hazard(Hazard::LogEnergyConsumption),
+                notify,
+            ))
+            .unwrap()
+            .finalize();
+
+        // The events route is described along with its hazards.
+        let events_route = device_data
+            .route_configs
+            .iter()
+            .find(|route_config| route_config.data.path == EVENTS_SSE_ROUTE)
+            .unwrap();
+        assert_eq!(events_route.rest_kind, RestKind::Get);
+        assert_eq!(events_route.response_kind, ResponseKind::Stream);
+        assert!(
+            events_route
+                .data
+                .hazards
+                .contains(&Hazard::LogEnergyConsumption)
+        );
+
+        // No subscriber is connected.
+        assert_eq!(event_stream.send(events()), 0);
+
+        let subscription = router
+            .clone()
+            .oneshot(Request::get(EVENTS_SSE_ROUTE).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(subscription.status(), StatusCode::OK);
+        assert_eq!(subscription.headers()[CONTENT_TYPE], "text/event-stream");
+        assert_eq!(event_stream.subscribers(), 1);
+
+        let response = router
+            .oneshot(Request::put("/notify").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let message = subscription
+            .into_body()
+            .into_data_stream()
+            .next()
+            .await
+            .unwrap()
+            .unwrap();
+        let message = std::str::from_utf8(&message).unwrap();
+
+        let data = message
+            .strip_prefix("data: ")
+            .and_then(|data| data.strip_suffix("\n\n"))
+            .unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(data).unwrap(),
+            serde_json::to_value(events()).unwrap()
+        );
+    }
+
+    #[test]
+    fn events_route_clash() {
+        let error = Device::new()
+            .route(ok_stateless(
+                Route::get("Events", EVENTS_SSE_ROUTE).description("Another events route."),
+                notify,
+            ))
+            .unwrap()
+            .event_stream(EventStream::new(), Hazards::known_safe())
+            .unwrap_err();
+
+        assert!(error.to_string().contains("has already been added"));
+    }
+}
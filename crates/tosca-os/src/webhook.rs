@@ -0,0 +1,197 @@
+use std::time::Duration;
+
+use tosca::events::Events;
+
+use tracing::warn;
+
+use crate::error::{Error, ErrorKind, Result};
+
+// Default maximum number of delivery retries.
+const DEFAULT_MAX_RETRIES: u8 = 3;
+
+// Default delay between two delivery attempts.
+const DEFAULT_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// An outbound webhook delivering device [`Events`] to an `URL` through
+/// `POST` requests.
+///
+/// A webhook registered on a [`crate::device::Device`] can be retrieved by
+/// route handlers through the [`crate::extract::Extension`] extractor.
+///
+/// Routes delivering events should declare the
+/// [`tosca::hazards::Hazard::NetworkAccess`] hazard, since they open
+/// outbound network connections.
+#[derive(Debug, Clone)]
+pub struct EventWebhook {
+    // HTTP client.
+    client: reqwest::Client,
+    // Webhook URL.
+    url: String,
+    // Maximum number of delivery retries.
+    max_retries: u8,
+    // Delay between two delivery attempts.
+    retry_delay: Duration,
+}
+
+impl EventWebhook {
+    /// Creates an [`EventWebhook`] for the given `URL`.
+    #[must_use]
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.into(),
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_delay: DEFAULT_RETRY_DELAY,
+        }
+    }
+
+    /// Sets the maximum number of delivery retries.
+    #[must_use]
+    pub const fn max_retries(mut self, max_retries: u8) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Sets the delay between two delivery attempts.
+    #[must_use]
+    pub const fn retry_delay(mut self, retry_delay: Duration) -> Self {
+        self.retry_delay = retry_delay;
+        self
+    }
+
+    /// Returns the webhook `URL`.
+    #[must_use]
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Delivers [`Events`] to the webhook `URL`.
+    ///
+    /// A failed delivery is retried until the maximum number of
+    /// retries is reached.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error whenever events cannot be delivered.
+    pub async fn post(&self, events: &Events) -> Result<()> {
+        let mut retries = 0;
+        loop {
+            let error = match self.client.post(&self.url).json(events).send().await {
+                Ok(response) if response.status().is_success() => return Ok(()),
+                Ok(response) => format!("status code {}", response.status()),
+                Err(e) => e.to_string(),
+            };
+
+            if retries == self.max_retries {
+                return Err(Error::new(
+                    ErrorKind::Webhook,
+                    format!("Unable to deliver events to {}: {error}", self.url),
+                ));
+            }
+
+            retries += 1;
+            warn!(
+                "Delivering events to {} failed with {error}, retry {retries} of {}",
+                self.url, self.max_retries
+            );
+
+            tokio::time::sleep(self.retry_delay).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU8, Ordering};
+    use std::time::Duration;
+
+    use tosca::events::{Event, Events};
+    use tosca::hazards::Hazard;
+    use tosca::route::Route;
+
+    use axum::body::Body;
+    use axum::extract::{Extension, Json, State};
+    use axum::http::{Request, StatusCode};
+
+    use tokio::sync::mpsc;
+
+    use tower::ServiceExt;
+
+    use crate::device::Device;
+    use crate::responses::error::ErrorResponse;
+    use crate::responses::ok::{OkResponse, ok_stateless};
+
+    use super::EventWebhook;
+
+    #[derive(Clone)]
+    struct ReceiverState {
+        attempts: Arc<AtomicU8>,
+        events_tx: mpsc::UnboundedSender<serde_json::Value>,
+    }
+
+    // A receiver failing the first delivery attempt.
+    async fn receive(
+        State(state): State<ReceiverState>,
+        Json(events): Json<serde_json::Value>,
+    ) -> StatusCode {
+        if state.attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+            return StatusCode::SERVICE_UNAVAILABLE;
+        }
+        _ = state.events_tx.send(events);
+        StatusCode::OK
+    }
+
+    fn events() -> Events {
+        Events::empty().bool_events(vec![Event::bool("on").description("Light status.")])
+    }
+
+    async fn notify(
+        Extension(webhook): Extension<EventWebhook>,
+    ) -> Result<OkResponse, ErrorResponse> {
+        webhook.post(&events()).await.map_err(|e| {
+            ErrorResponse::internal_with_error("Events not delivered", &e.to_string())
+        })?;
+        Ok(OkResponse::ok())
+    }
+
+    #[tokio::test]
+    async fn deliver_events() {
+        let (events_tx, mut events_rx) = mpsc::unbounded_channel();
+        let receiver = axum::Router::new()
+            .route("/events", axum::routing::post(receive))
+            .with_state(ReceiverState {
+                attempts: Arc::new(AtomicU8::new(0)),
+                events_tx,
+            });
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, receiver).await });
+
+        let (_, _, router) = Device::new()
+            .event_webhook(
+                EventWebhook::new(format!("http://{address}/events"))
+                    .retry_delay(Duration::from_millis(10)),
+            )
+            .route(ok_stateless(
+                Route::put("Notify", "/notify")
+                    .description("Notify light status.")
+                    .with_hazard(Hazard::NetworkAccess),
+                notify,
+            ))
+            .unwrap()
+            .finalize();
+
+        let response = router
+            .oneshot(Request::put("/notify").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            events_rx.recv().await.unwrap(),
+            serde_json::to_value(events()).unwrap()
+        );
+    }
+}
@@ -0,0 +1,63 @@
+/// An exponential backoff between two reconnection attempts.
+///
+/// Each delay doubles the previous one, up to a maximum delay. Delays have
+/// no unit, so the caller decides whether they are seconds or milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Backoff {
+    initial_delay: u64,
+    max_delay: u64,
+    delay: u64,
+}
+
+impl Backoff {
+    /// Creates a [`Backoff`] starting from `initial_delay` and growing
+    /// up to `max_delay`.
+    #[must_use]
+    pub const fn new(initial_delay: u64, max_delay: u64) -> Self {
+        Self {
+            initial_delay,
+            max_delay,
+            delay: initial_delay,
+        }
+    }
+
+    /// Returns the current delay, doubling the next one up to the maximum
+    /// delay.
+    pub const fn next_delay(&mut self) -> u64 {
+        let delay = self.delay;
+        let next_delay = self.delay.saturating_mul(2);
+        self.delay = if next_delay < self.max_delay {
+            next_delay
+        } else {
+            self.max_delay
+        };
+        delay
+    }
+
+    /// Restores the initial delay, as it happens after a successful
+    /// attempt.
+    pub const fn reset(&mut self) {
+        self.delay = self.initial_delay;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Backoff;
+
+    #[test]
+    fn test_backoff() {
+        let mut backoff = Backoff::new(2, 64);
+
+        let delays: [u64; 8] = core::array::from_fn(|_| backoff.next_delay());
+        assert_eq!(delays, [2, 4, 8, 16, 32, 64, 64, 64]);
+
+        backoff.reset();
+        assert_eq!(backoff.next_delay(), 2);
+
+        // A delay never overflows.
+        let mut backoff = Backoff::new(u64::MAX / 2 + 1, u64::MAX);
+        assert_eq!(backoff.next_delay(), u64::MAX / 2 + 1);
+        assert_eq!(backoff.next_delay(), u64::MAX);
+    }
+}
@@ -0,0 +1,600 @@
+//! A compact binary encoding of device descriptions, built on top of the
+//! [`postcard`](https://docs.rs/postcard) format.
+//!
+//! The `JSON` representation of a description is verbose, since it repeats
+//! field names for each route and parameter, and writes numbers as text.
+//! This encoding omits field names altogether and writes numbers as
+//! variable-length integers, hence it is suited for controllers running on
+//! microcontrollers. For example, four light routes with some hazards and
+//! parameters shrink from `926` bytes in `JSON` to `283` bytes, saving about
+//! `70%` of the space.
+//!
+//! The encoding is not self-describing, so the encoder and the decoder must
+//! be built with the same `tosca` version.
+//!
+//! Only [`RouteConfig`](crate::route::RouteConfig),
+//! [`RouteConfigs`](crate::route::RouteConfigs), and
+//! [`DeviceInfo`](crate::device::DeviceInfo) can be encoded.
+
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+
+use serde::Serialize;
+
+use crate::device::DeviceInfo;
+use crate::economy::{Costs, Rois};
+use crate::energy::{CarbonFootprints, EnergyEfficiencies, WaterUseEfficiency};
+use crate::hazards::Hazards;
+use crate::parameters::ParameterKind;
+use crate::response::ResponseKind;
+use crate::route::{
+    MissingParameters, OffStepValues, ParameterStyle, RestKind, RouteConfig, RouteConfigs,
+};
+
+#[cfg(feature = "deserialize")]
+use crate::{economy::Economy, energy::Energy, parameters::ParametersData, route::RouteData};
+
+pub use postcard::Error;
+
+/// Encodes a description into compact binary data.
+///
+/// # Errors
+///
+/// It returns an error whenever the description cannot be encoded.
+#[inline]
+pub fn encode<T: private::Compact>(description: &T) -> Result<Vec<u8>, Error> {
+    description.encode()
+}
+
+/// Decodes a description from compact binary data.
+///
+/// # Errors
+///
+/// It returns an error whenever the data is not a valid encoding of the
+/// description.
+#[cfg(feature = "deserialize")]
+#[inline]
+pub fn decode<T: private::Compact>(bytes: &[u8]) -> Result<T, Error> {
+    T::decode(bytes)
+}
+
+mod private {
+    use alloc::vec::Vec;
+
+    #[doc(hidden)]
+    pub trait Compact: Sized {
+        fn encode(&self) -> Result<Vec<u8>, postcard::Error>;
+
+        #[cfg(feature = "deserialize")]
+        fn decode(bytes: &[u8]) -> Result<Self, postcard::Error>;
+    }
+}
+
+#[cfg(feature = "deserialize")]
+fn owned(value: Cow<'_, str>) -> Cow<'static, str> {
+    Cow::Owned(value.into_owned())
+}
+
+// A parameter kind with all its fields, since omitted fields cannot be
+// detected by a format which is not self-describing.
+#[derive(Serialize)]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+enum CompactParameterKind<'a> {
+    Bool {
+        default: bool,
+    },
+    U8 {
+        default: u8,
+        min: u8,
+        max: u8,
+    },
+    U16 {
+        default: u16,
+        min: u16,
+        max: u16,
+    },
+    U32 {
+        default: u32,
+        min: u32,
+        max: u32,
+    },
+    U64 {
+        default: u64,
+        min: u64,
+        max: u64,
+    },
+    U128 {
+        default: u128,
+        min: u128,
+        max: u128,
+    },
+    F32 {
+        default: f32,
+        min: f32,
+        max: f32,
+        step: f32,
+    },
+    F64 {
+        default: f64,
+        min: f64,
+        max: f64,
+        step: f64,
+    },
+    RangeU32 {
+        min: u32,
+        max: u32,
+        step: u32,
+        default: u32,
+    },
+    RangeU64 {
+        min: u64,
+        max: u64,
+        step: u64,
+        default: u64,
+    },
+    RangeF64 {
+        min: f64,
+        max: f64,
+        step: f64,
+        default: f64,
+    },
+    CharsSequence {
+        default: Cow<'a, str>,
+        secret: bool,
+        max_length: Option<u32>,
+    },
+    ByteStream {
+        max_size: u32,
+    },
+}
+
+impl<'a> From<&'a ParameterKind> for CompactParameterKind<'a> {
+    fn from(kind: &'a ParameterKind) -> Self {
+        match *kind {
+            ParameterKind::Bool { default } => Self::Bool { default },
+            ParameterKind::U8 { default, min, max } => Self::U8 { default, min, max },
+            ParameterKind::U16 { default, min, max } => Self::U16 { default, min, max },
+            ParameterKind::U32 { default, min, max } => Self::U32 { default, min, max },
+            ParameterKind::U64 { default, min, max } => Self::U64 { default, min, max },
+            ParameterKind::U128 { default, min, max } => Self::U128 { default, min, max },
+            ParameterKind::F32 {
+                default,
+                min,
+                max,
+                step,
+            } => Self::F32 {
+                default,
+                min,
+                max,
+                step,
+            },
+            ParameterKind::F64 {
+                default,
+                min,
+                max,
+                step,
+            } => Self::F64 {
+                default,
+                min,
+                max,
+                step,
+            },
+            ParameterKind::RangeU32 {
+                min,
+                max,
+                step,
+                default,
+            } => Self::RangeU32 {
+                min,
+                max,
+                step,
+                default,
+            },
+            ParameterKind::RangeU64 {
+                min,
+                max,
+                step,
+                default,
+            } => Self::RangeU64 {
+                min,
+                max,
+                step,
+                default,
+            },
+            ParameterKind::RangeF64 {
+                min,
+                max,
+                step,
+                default,
+            } => Self::RangeF64 {
+                min,
+                max,
+                step,
+                default,
+            },
+            ParameterKind::CharsSequence {
+                ref default,
+                secret,
+                max_length,
+            } => Self::CharsSequence {
+                default: Cow::Borrowed(default),
+                secret,
+                max_length,
+            },
+            ParameterKind::ByteStream { max_size } => Self::ByteStream { max_size },
+        }
+    }
+}
+
+#[cfg(feature = "deserialize")]
+impl From<CompactParameterKind<'_>> for ParameterKind {
+    fn from(kind: CompactParameterKind<'_>) -> Self {
+        match kind {
+            CompactParameterKind::Bool { default } => Self::Bool { default },
+            CompactParameterKind::U8 { default, min, max } => Self::U8 { default, min, max },
+            CompactParameterKind::U16 { default, min, max } => Self::U16 { default, min, max },
+            CompactParameterKind::U32 { default, min, max } => Self::U32 { default, min, max },
+            CompactParameterKind::U64 { default, min, max } => Self::U64 { default, min, max },
+            CompactParameterKind::U128 { default, min, max } => Self::U128 { default, min, max },
+            CompactParameterKind::F32 {
+                default,
+                min,
+                max,
+                step,
+            } => Self::F32 {
+                default,
+                min,
+                max,
+                step,
+            },
+            CompactParameterKind::F64 {
+                default,
+                min,
+                max,
+                step,
+            } => Self::F64 {
+                default,
+                min,
+                max,
+                step,
+            },
+            CompactParameterKind::RangeU32 {
+                min,
+                max,
+                step,
+                default,
+            } => Self::RangeU32 {
+                min,
+                max,
+                step,
+                default,
+            },
+            CompactParameterKind::RangeU64 {
+                min,
+                max,
+                step,
+                default,
+            } => Self::RangeU64 {
+                min,
+                max,
+                step,
+                default,
+            },
+            CompactParameterKind::RangeF64 {
+                min,
+                max,
+                step,
+                default,
+            } => Self::RangeF64 {
+                min,
+                max,
+                step,
+                default,
+            },
+            CompactParameterKind::CharsSequence {
+                default,
+                secret,
+                max_length,
+            } => Self::CharsSequence {
+                default: owned(default),
+                secret,
+                max_length,
+            },
+            CompactParameterKind::ByteStream { max_size } => Self::ByteStream { max_size },
+        }
+    }
+}
+
+// A route configuration with all its fields.
+//
+// Unspecified hazards are encoded as `None`, to be distinguished from known
+// safe hazards.
+#[derive(Serialize)]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+struct CompactRouteConfig<'a> {
+    name: Cow<'a, str>,
+    path: Cow<'a, str>,
+    description: Option<Cow<'a, str>>,
+    hazards: Option<Cow<'a, Hazards>>,
+    parameters: Vec<(Cow<'a, str>, CompactParameterKind<'a>)>,
+    rest_kind: RestKind,
+    response_kind: ResponseKind,
+    parameter_style: ParameterStyle,
+    missing_parameters: MissingParameters,
+    off_step_values: OffStepValues,
+    deprecated: Option<Cow<'a, str>>,
+}
+
+impl<'a> From<&'a RouteConfig> for CompactRouteConfig<'a> {
+    fn from(route: &'a RouteConfig) -> Self {
+        Self {
+            name: Cow::Borrowed(&route.data.name),
+            path: Cow::Borrowed(&route.data.path),
+            description: route.data.description.as_deref().map(Cow::Borrowed),
+            hazards: (!route.data.hazards.is_unspecified())
+                .then_some(Cow::Borrowed(&route.data.hazards)),
+            parameters: route
+                .data
+                .parameters
+                .iter()
+                .map(|(name, kind)| (Cow::Borrowed(name.as_str()), kind.into()))
+                .collect(),
+            rest_kind: route.rest_kind,
+            response_kind: route.response_kind,
+            parameter_style: route.parameter_style,
+            missing_parameters: route.missing_parameters,
+            off_step_values: route.off_step_values,
+            deprecated: route.deprecated.as_deref().map(Cow::Borrowed),
+        }
+    }
+}
+
+#[cfg(feature = "deserialize")]
+impl From<CompactRouteConfig<'_>> for RouteConfig {
+    fn from(route: CompactRouteConfig<'_>) -> Self {
+        Self {
+            data: RouteData {
+                name: owned(route.name),
+                path: owned(route.path),
+                description: route.description.map(owned),
+                hazards: route.hazards.map(Cow::into_owned).unwrap_or_default(),
+                parameters: route
+                    .parameters
+                    .into_iter()
+                    .fold(ParametersData::new(), |parameters, (name, kind)| {
+                        parameters.insert(name.into_owned(), kind.into())
+                    }),
+            },
+            rest_kind: route.rest_kind,
+            response_kind: route.response_kind,
+            parameter_style: route.parameter_style,
+            missing_parameters: route.missing_parameters,
+            off_step_values: route.off_step_values,
+            deprecated: route.deprecated.map(owned),
+        }
+    }
+}
+
+// Device information with all its fields.
+#[derive(Serialize)]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+struct CompactDeviceInfo<'a> {
+    energy_efficiencies: Option<Cow<'a, EnergyEfficiencies>>,
+    carbon_footprints: Option<Cow<'a, CarbonFootprints>>,
+    water_use_efficiency: Option<WaterUseEfficiency>,
+    costs: Option<Cow<'a, Costs>>,
+    roi: Option<Cow<'a, Rois>>,
+    handled_requests: Option<u32>,
+    uptime: Option<u64>,
+    icon: Option<Cow<'a, str>>,
+    location: Option<Cow<'a, str>>,
+}
+
+impl<'a> From<&'a DeviceInfo> for CompactDeviceInfo<'a> {
+    fn from(info: &'a DeviceInfo) -> Self {
+        Self {
+            energy_efficiencies: info.energy.energy_efficiencies.as_ref().map(Cow::Borrowed),
+            carbon_footprints: info.energy.carbon_footprints.as_ref().map(Cow::Borrowed),
+            water_use_efficiency: info.energy.water_use_efficiency,
+            costs: info.economy.costs.as_ref().map(Cow::Borrowed),
+            roi: info.economy.roi.as_ref().map(Cow::Borrowed),
+            handled_requests: info.handled_requests,
+            uptime: info.uptime,
+            icon: info.icon.as_deref().map(Cow::Borrowed),
+            location: info.location.as_deref().map(Cow::Borrowed),
+        }
+    }
+}
+
+#[cfg(feature = "deserialize")]
+impl From<CompactDeviceInfo<'_>> for DeviceInfo {
+    fn from(info: CompactDeviceInfo<'_>) -> Self {
+        Self {
+            energy: Energy {
+                energy_efficiencies: info.energy_efficiencies.map(Cow::into_owned),
+                carbon_footprints: info.carbon_footprints.map(Cow::into_owned),
+                water_use_efficiency: info.water_use_efficiency,
+            },
+            economy: Economy {
+                costs: info.costs.map(Cow::into_owned),
+                roi: info.roi.map(Cow::into_owned),
+            },
+            handled_requests: info.handled_requests,
+            uptime: info.uptime,
+            icon: info.icon.map(|icon| Cow::Owned(icon.into_owned())),
+            location: info
+                .location
+                .map(|location| Cow::Owned(location.into_owned())),
+        }
+    }
+}
+
+impl private::Compact for RouteConfig {
+    fn encode(&self) -> Result<Vec<u8>, Error> {
+        postcard::to_allocvec(&CompactRouteConfig::from(self))
+    }
+
+    #[cfg(feature = "deserialize")]
+    fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        postcard::from_bytes::<CompactRouteConfig>(bytes).map(Into::into)
+    }
+}
+
+impl private::Compact for RouteConfigs {
+    fn encode(&self) -> Result<Vec<u8>, Error> {
+        postcard::to_allocvec(
+            &self
+                .iter()
+                .map(CompactRouteConfig::from)
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    #[cfg(feature = "deserialize")]
+    fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        postcard::from_bytes::<Vec<CompactRouteConfig>>(bytes).map(|routes| {
+            routes
+                .into_iter()
+                .fold(Self::new(), |routes, route| routes.insert(route.into()))
+        })
+    }
+}
+
+impl private::Compact for DeviceInfo {
+    fn encode(&self) -> Result<Vec<u8>, Error> {
+        postcard::to_allocvec(&CompactDeviceInfo::from(self))
+    }
+
+    #[cfg(feature = "deserialize")]
+    fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        postcard::from_bytes::<CompactDeviceInfo>(bytes).map(Into::into)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "deserialize")]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::device::DeviceInfo;
+    use crate::economy::{Cost, CostTimespan, Costs, Economy};
+    use crate::energy::{Energy, WaterUseEfficiency};
+    use crate::hazards::Hazard;
+    use crate::parameters::{DecimalPrecision, Parameters};
+    use crate::route::{ParameterStyle, Route, RouteConfig, RouteConfigs};
+
+    use super::{decode, encode};
+
+    fn light_routes() -> RouteConfigs {
+        RouteConfigs::new()
+            .insert(
+                Route::put("On", "/on")
+                    .description("Turn light on.")
+                    .with_hazard(Hazard::ElectricEnergyConsumption)
+                    .with_parameters(
+                        Parameters::new()
+                            .rangeu64_with_default("brightness", (0, 20, 1), 4)
+                            .u8("effect", 0)
+                            .f64_with_limits("hue", 0.5, 0., 1., DecimalPrecision::TwoDigits),
+                    )
+                    .serialize_data(),
+            )
+            .insert(
+                Route::put("Off", "/off")
+                    .description("Turn light off.")
+                    .no_hazards_known_safe()
+                    .serialize_data(),
+            )
+            .insert(
+                Route::get("Scene", "/scene")
+                    .with_parameters(
+                        Parameters::new()
+                            .u128("id", u128::MAX)
+                            .characters_sequence("name", "evening"),
+                    )
+                    .with_parameter_style(ParameterStyle::Query)
+                    .serialize_data(),
+            )
+            .insert(
+                Route::post("Pair", "/pair")
+                    .description("Pair the light.")
+                    .with_array_of_hazards([Hazard::NetworkAccess, Hazard::FirmwareUpdate])
+                    .with_parameters(Parameters::new().secret_characters_sequence("pin", "0000"))
+                    .deprecated("Use `/bind` instead")
+                    .serialize_data(),
+            )
+    }
+
+    #[test]
+    fn test_route_configs_round_trip() {
+        let routes = light_routes();
+        let bytes = encode(&routes).unwrap();
+
+        // The binary encoding is far more compact than json.
+        let json = serde_json::to_string(&routes).unwrap();
+        assert!(bytes.len() * 3 < json.len());
+
+        // Each route decodes back to the original one, field by field.
+        let decoded = decode::<RouteConfigs>(&bytes).unwrap();
+        assert_eq!(decoded, routes);
+        assert_eq!(serde_json::to_string(&decoded).unwrap(), json);
+
+        // Parameters keep their order.
+        let on = decoded.iter().next().unwrap();
+        assert_eq!(
+            on.data
+                .parameters
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>(),
+            ["brightness", "effect", "hue"]
+        );
+
+        // Known safe hazards are distinguished from unspecified ones.
+        let hazards = decoded
+            .iter()
+            .map(|route| {
+                (
+                    route.data.hazards.is_known_safe(),
+                    route.data.hazards.is_unspecified(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            hazards,
+            [(false, false), (true, false), (false, true), (false, false)]
+        );
+
+        // A single route.
+        let route = routes.iter().last().unwrap();
+        let decoded = decode::<RouteConfig>(&encode(route).unwrap()).unwrap();
+        assert_eq!(
+            serde_json::to_string(&decoded).unwrap(),
+            serde_json::to_string(route).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_device_info_round_trip() {
+        let info = DeviceInfo::empty()
+            .add_energy(Energy::init_with_water_use_efficiency(
+                WaterUseEfficiency::init_with_gpp(3.5),
+            ))
+            .add_economy(Economy::init_with_costs(
+                Costs::new().insert(Cost::new(100, CostTimespan::Month)),
+            ))
+            .add_uptime(3600)
+            .with_icon("lightbulb")
+            .with_location("kitchen");
+
+        assert_eq!(decode::<DeviceInfo>(&encode(&info).unwrap()).unwrap(), info);
+
+        let info = DeviceInfo::empty();
+        assert_eq!(decode::<DeviceInfo>(&encode(&info).unwrap()).unwrap(), info);
+    }
+
+    #[test]
+    fn test_invalid_data() {
+        assert!(decode::<RouteConfigs>(&[0xff, 0xff]).is_err());
+    }
+}
@@ -1,13 +1,20 @@
-use serde::Serialize;
+use alloc::borrow::Cow;
+use alloc::string::String;
+
+use serde::{Serialize, Serializer};
 
 use crate::economy::Economy;
 use crate::energy::Energy;
 use crate::events::EventsDescription;
-use crate::route::RouteConfigs;
+use crate::route::{LightOffRoute, LightOnRoute, RestKind, RouteConfigs};
 
 /// A device kind.
-#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
-#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+///
+/// A device which does not belong to any predefined kind, such as a water
+/// valve, is described by a [`DeviceKind::Custom`] kind.
+///
+/// A kind is serialized as its name.
+#[derive(Debug, Clone, PartialEq)]
 pub enum DeviceKind {
     /// Unknown.
     Unknown,
@@ -15,21 +22,88 @@ pub enum DeviceKind {
     Light,
     /// Camera.
     Camera,
+    /// A kind identified by a custom name.
+    ///
+    /// A custom name equal to the name of a predefined kind is created and
+    /// received as that kind.
+    Custom(Cow<'static, str>),
 }
 
 impl DeviceKind {
-    const fn description(self) -> &'static str {
+    /// Creates a [`DeviceKind::Custom`] kind from its name.
+    ///
+    /// The name of a predefined kind, such as `Light`, creates that kind.
+    #[must_use]
+    #[inline]
+    pub fn custom(name: impl Into<Cow<'static, str>>) -> Self {
+        let name = name.into();
+        Self::from_name(&name).unwrap_or(Self::Custom(name))
+    }
+
+    /// Returns the name of a [`DeviceKind`], such as `Light`.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
         match self {
             Self::Unknown => "Unknown",
             Self::Light => "Light",
             Self::Camera => "Camera",
+            Self::Custom(name) => name,
+        }
+    }
+
+    /// Returns a predefined [`DeviceKind`] from its name, such as `Light`.
+    ///
+    /// The return value is [`None`] when the name does not belong to a
+    /// predefined kind.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Unknown" => Some(Self::Unknown),
+            "Light" => Some(Self::Light),
+            "Camera" => Some(Self::Camera),
+            _ => None,
+        }
+    }
+
+    /// Returns a [`DeviceKind`] from its name, falling back to a
+    /// [`DeviceKind::Custom`] kind when the name does not belong to a
+    /// predefined kind.
+    #[must_use]
+    pub fn from_name_or_custom(name: &str) -> Self {
+        Self::from_name(name).unwrap_or_else(|| Self::Custom(String::from(name).into()))
+    }
+
+    /// Returns the routes a [`DeviceKind`] must provide, each one as its
+    /// path along with the [`RestKind`]s it can be served through.
+    #[must_use]
+    pub const fn mandatory_routes(&self) -> &'static [(&'static str, &'static [RestKind])] {
+        match self {
+            Self::Light => &[
+                (LightOnRoute::PATH, LightOnRoute::REST_KINDS),
+                (LightOffRoute::PATH, LightOffRoute::REST_KINDS),
+            ],
+            Self::Unknown | Self::Camera | Self::Custom(_) => &[],
         }
     }
 }
 
 impl core::fmt::Display for DeviceKind {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "{}", self.description())
+        self.as_str().fmt(f)
+    }
+}
+
+impl Serialize for DeviceKind {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "deserialize")]
+impl<'de> serde::Deserialize<'de> for DeviceKind {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = <Cow<'de, str>>::deserialize(deserializer)?;
+        Ok(Self::from_name_or_custom(&name))
     }
 }
 
@@ -62,6 +136,23 @@ pub struct DeviceInfo {
     #[serde(skip_serializing_if = "Economy::is_empty")]
     #[serde(default = "Economy::empty")]
     pub economy: Economy,
+    /// Number of requests handled by a device.
+    #[serde(rename = "handled requests")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub handled_requests: Option<u32>,
+    /// Number of seconds elapsed since the device server started.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub uptime: Option<u64>,
+    /// Device icon, either a `URL` or the name of a well-known icon.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub icon: Option<Cow<'static, str>>,
+    /// Device location, such as a room or a zone.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub location: Option<Cow<'static, str>>,
 }
 
 impl DeviceInfo {
@@ -71,6 +162,10 @@ impl DeviceInfo {
         Self {
             energy: Energy::empty(),
             economy: Economy::empty(),
+            handled_requests: None,
+            uptime: None,
+            icon: None,
+            location: None,
         }
     }
 
@@ -87,6 +182,63 @@ impl DeviceInfo {
         self.economy = economy;
         self
     }
+
+    /// Merges another [`DeviceInfo`] into this one.
+    ///
+    /// [`Energy`] and [`Economy`] data are merged field by field. When both
+    /// instances define the same field, the field of `other` wins.
+    #[must_use]
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            energy: self.energy.merge(other.energy),
+            economy: self.economy.merge(other.economy),
+            handled_requests: other.handled_requests.or(self.handled_requests),
+            uptime: other.uptime.or(self.uptime),
+            icon: other.icon.or(self.icon),
+            location: other.location.or(self.location),
+        }
+    }
+
+    /// Adds the number of requests handled by a device.
+    #[must_use]
+    pub const fn add_handled_requests(mut self, handled_requests: u32) -> Self {
+        self.handled_requests = Some(handled_requests);
+        self
+    }
+
+    /// Adds the number of seconds elapsed since the device server started.
+    #[must_use]
+    pub const fn add_uptime(mut self, uptime: u64) -> Self {
+        self.uptime = Some(uptime);
+        self
+    }
+
+    /// Sets the device icon, either a `URL` or the name of a well-known
+    /// icon, which allows a dashboard to display the device.
+    #[must_use]
+    pub fn with_icon(mut self, icon: impl Into<Cow<'static, str>>) -> Self {
+        self.icon = Some(icon.into());
+        self
+    }
+
+    /// Returns the device icon, if any.
+    #[must_use]
+    pub fn icon(&self) -> Option<&str> {
+        self.icon.as_deref()
+    }
+
+    /// Sets the device location, such as a room or a zone.
+    #[must_use]
+    pub fn with_location(mut self, location: impl Into<Cow<'static, str>>) -> Self {
+        self.location = Some(location.into());
+        self
+    }
+
+    /// Returns the device location, if any.
+    #[must_use]
+    pub fn location(&self) -> Option<&str> {
+        self.location.as_deref()
+    }
 }
 
 /// Device data.
@@ -205,7 +357,12 @@ mod tests {
 
     #[test]
     fn test_device_kind() {
-        for device_kind in &[DeviceKind::Unknown, DeviceKind::Light, DeviceKind::Camera] {
+        for device_kind in &[
+            DeviceKind::Unknown,
+            DeviceKind::Light,
+            DeviceKind::Camera,
+            DeviceKind::custom("WaterValve"),
+        ] {
             assert_eq!(
                 deserialize::<DeviceKind>(serialize(device_kind)),
                 *device_kind
@@ -213,6 +370,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_custom_device_kind() {
+        let device_kind = DeviceKind::custom("WaterValve");
+        assert_eq!(device_kind.as_str(), "WaterValve");
+        assert_eq!(serialize(&device_kind), serde_json::json!("WaterValve"));
+        assert!(device_kind.mandatory_routes().is_empty());
+
+        // A custom name of a predefined kind is created and received as
+        // that kind.
+        assert_eq!(DeviceKind::custom("Light"), DeviceKind::Light);
+        assert_eq!(
+            deserialize::<DeviceKind>(serialize(DeviceKind::Custom("Light".into()))),
+            DeviceKind::Light
+        );
+    }
+
     #[test]
     fn test_device_environment() {
         for device_environment in &[DeviceEnvironment::Os, DeviceEnvironment::Esp32] {
@@ -235,6 +408,56 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_device_location() {
+        let device_info = DeviceInfo::empty().with_location("kitchen");
+
+        assert_eq!(
+            serialize(&device_info),
+            serde_json::json!({ "location": "kitchen" })
+        );
+        assert_eq!(
+            deserialize::<DeviceInfo>(serialize(&device_info)).location(),
+            Some("kitchen")
+        );
+
+        // A missing location is not serialized.
+        assert!(serialize(DeviceInfo::empty()).get("location").is_none());
+
+        // The most recent location wins.
+        assert_eq!(
+            device_info
+                .merge(DeviceInfo::empty().with_location("bedroom"))
+                .location(),
+            Some("bedroom")
+        );
+    }
+
+    #[test]
+    fn test_handled_requests() {
+        let device_info = DeviceInfo::empty().add_handled_requests(42);
+
+        assert_eq!(
+            serialize(&device_info),
+            serde_json::json!({ "handled requests": 42 })
+        );
+        assert_eq!(
+            deserialize::<DeviceInfo>(serialize(&device_info)),
+            device_info
+        );
+
+        // An unknown number of handled requests is not serialized.
+        assert_eq!(serialize(DeviceInfo::empty()), serde_json::json!({}));
+
+        // The most recent number of handled requests wins.
+        assert_eq!(
+            device_info
+                .merge(DeviceInfo::empty().add_handled_requests(43))
+                .handled_requests,
+            Some(43)
+        );
+    }
+
     #[test]
     fn test_device_data() {
         let device_data = DeviceData::new(
@@ -253,4 +476,54 @@ mod tests {
             device_data
         );
     }
+
+    #[test]
+    fn test_device_data_stable_serialization() {
+        let device_data = |route_configs| {
+            DeviceData::new(
+                DeviceKind::Light,
+                DeviceEnvironment::Os,
+                None,
+                None,
+                "/light",
+                route_configs,
+                2,
+            )
+        };
+
+        let first = device_data(
+            RouteConfigs::init(Route::put("On", "/on").serialize_data())
+                .insert(Route::get("State", "/state").serialize_data())
+                .insert(Route::put("State", "/state").serialize_data()),
+        );
+        let second = device_data(
+            RouteConfigs::init(Route::put("State", "/state").serialize_data())
+                .insert(Route::put("On", "/on").serialize_data())
+                .insert(Route::get("State", "/state").serialize_data()),
+        );
+
+        // Routes are sorted by path, then by `REST` kind.
+        let routes = serialize(&first)["route_configs"].clone();
+        let routes = routes.as_array().unwrap();
+        assert_eq!(routes[0]["path"], "/on");
+        assert_eq!(routes[1]["REST kind"], "Get");
+        assert_eq!(routes[2]["REST kind"], "Put");
+
+        assert_eq!(
+            serde_json::to_vec(&first).unwrap(),
+            serde_json::to_vec(&second).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_device_kind_from_name() {
+        assert_eq!(DeviceKind::from_name("Unknown"), Some(DeviceKind::Unknown));
+        assert_eq!(DeviceKind::from_name("Light"), Some(DeviceKind::Light));
+        assert_eq!(DeviceKind::from_name("Camera"), Some(DeviceKind::Camera));
+        assert_eq!(DeviceKind::from_name("Fan"), None);
+        assert_eq!(
+            DeviceKind::from_name_or_custom("Fan"),
+            DeviceKind::custom("Fan")
+        );
+    }
 }
@@ -35,6 +35,130 @@ impl core::fmt::Display for CostTimespan {
     }
 }
 
+/// Currency of an amount of money.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize)]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub enum Currency {
+    /// Euro
+    Eur,
+    /// United States dollar
+    Usd,
+    /// Pound sterling
+    Gbp,
+}
+
+impl Currency {
+    /// Returns the [`Currency`] symbol.
+    #[must_use]
+    pub const fn symbol(self) -> &'static str {
+        match self {
+            Self::Eur => "€",
+            Self::Usd => "$",
+            Self::Gbp => "£",
+        }
+    }
+
+    const fn code(self) -> &'static str {
+        match self {
+            Self::Eur => "EUR",
+            Self::Usd => "USD",
+            Self::Gbp => "GBP",
+        }
+    }
+}
+
+impl core::fmt::Display for Currency {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        self.code().fmt(f)
+    }
+}
+
+/// Errors of operations on [`Money`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MoneyError {
+    /// The amounts of money have different currencies.
+    CurrencyMismatch(Currency, Currency),
+    /// The result does not fit into the amount of money.
+    Overflow,
+}
+
+impl core::fmt::Display for MoneyError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Self::CurrencyMismatch(left, right) => {
+                write!(f, "Mismatched currencies: {left} and {right}")
+            }
+            Self::Overflow => "Amount of money overflow".fmt(f),
+        }
+    }
+}
+
+impl core::error::Error for MoneyError {}
+
+/// An amount of money.
+///
+/// The amount is expressed in minor units, such as cents, so that
+/// computations do not suffer from floating-point rounding issues.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize)]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub struct Money {
+    /// Amount in minor units.
+    pub amount: i64,
+    /// Currency.
+    pub currency: Currency,
+}
+
+impl core::fmt::Display for Money {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        let sign = if self.amount < 0 { "-" } else { "" };
+        let amount = self.amount.unsigned_abs();
+        write!(
+            f,
+            "{sign}{}{}.{:02}",
+            self.currency.symbol(),
+            amount / 100,
+            amount % 100
+        )
+    }
+}
+
+impl Money {
+    /// Creates a [`Money`] instance from an amount in minor units.
+    #[must_use]
+    pub const fn new(amount: i64, currency: Currency) -> Self {
+        Self { amount, currency }
+    }
+
+    /// Adds another amount of money.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error when the currencies differ or the result
+    /// overflows.
+    pub fn checked_add(self, other: Self) -> Result<Self, MoneyError> {
+        if self.currency != other.currency {
+            return Err(MoneyError::CurrencyMismatch(self.currency, other.currency));
+        }
+
+        match self.amount.checked_add(other.amount) {
+            Some(amount) => Ok(Self::new(amount, self.currency)),
+            None => Err(MoneyError::Overflow),
+        }
+    }
+
+    /// Multiplies the amount of money by a factor.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error when the result overflows.
+    pub const fn checked_mul(self, factor: i64) -> Result<Self, MoneyError> {
+        match self.amount.checked_mul(factor) {
+            Some(amount) => Ok(Self::new(amount, self.currency)),
+            None => Err(MoneyError::Overflow),
+        }
+    }
+}
+
 /// A device cost in terms of expenses/savings.
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize)]
 #[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
@@ -190,6 +314,17 @@ impl Economy {
         self
     }
 
+    /// Merges another [`Economy`] instance into this one.
+    ///
+    /// When both instances define the same data, the data of `other` wins.
+    #[must_use]
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            costs: other.costs.or(self.costs),
+            roi: other.roi.or(self.roi),
+        }
+    }
+
     /// Checks whether [`Economy`] is **completely** empty.
     #[must_use]
     pub const fn is_empty(&self) -> bool {
@@ -200,12 +335,14 @@ impl Economy {
 #[cfg(test)]
 #[cfg(feature = "deserialize")]
 mod tests {
+    use alloc::string::ToString;
+
     use super::Economy;
 
     use crate::energy::EnergyClass;
     use crate::{deserialize, serialize};
 
-    use super::{Cost, CostTimespan, Costs, Roi, Rois};
+    use super::{Cost, CostTimespan, Costs, Currency, Money, MoneyError, Roi, Rois};
 
     #[test]
     fn test_cost_timespan() {
@@ -253,4 +390,37 @@ mod tests {
 
         assert_eq!(deserialize::<Economy>(serialize(&economy)), economy);
     }
+
+    #[test]
+    fn test_money_format() {
+        assert_eq!(Money::new(1234, Currency::Eur).to_string(), "€12.34");
+        assert_eq!(Money::new(5, Currency::Usd).to_string(), "$0.05");
+        assert_eq!(Money::new(-150, Currency::Gbp).to_string(), "-£1.50");
+        assert_eq!(Money::new(0, Currency::Eur).to_string(), "€0.00");
+    }
+
+    #[test]
+    fn test_money_operations() {
+        assert_eq!(
+            Money::new(150, Currency::Eur).checked_add(Money::new(275, Currency::Eur)),
+            Ok(Money::new(425, Currency::Eur))
+        );
+        assert_eq!(
+            Money::new(150, Currency::Eur).checked_add(Money::new(275, Currency::Usd)),
+            Err(MoneyError::CurrencyMismatch(Currency::Eur, Currency::Usd))
+        );
+        assert_eq!(
+            Money::new(i64::MAX, Currency::Usd).checked_add(Money::new(1, Currency::Usd)),
+            Err(MoneyError::Overflow)
+        );
+
+        assert_eq!(
+            Money::new(150, Currency::Gbp).checked_mul(3),
+            Ok(Money::new(450, Currency::Gbp))
+        );
+        assert_eq!(
+            Money::new(i64::MAX, Currency::Gbp).checked_mul(2),
+            Err(MoneyError::Overflow)
+        );
+    }
 }
@@ -1,3 +1,5 @@
+use core::ops::RangeInclusive;
+
 use hashbrown::DefaultHashBuilder;
 
 use indexmap::set::{IndexSet, IntoIter, Iter};
@@ -50,6 +52,33 @@ impl EnergyClass {
             Self::G => "G",
         }
     }
+
+    /// Returns the range of [`EnergyEfficiency`] percentages allowed for
+    /// the [`EnergyClass`].
+    ///
+    /// Higher classes cover narrower consumption bands, so a device
+    /// belonging to them can deviate less from their reference consumption:
+    ///
+    /// - `A+++`, `A++`, and `A+`: from -10% to 10%
+    /// - `A` and `B`: from -25% to 25%
+    /// - `C`, `D`, and `E`: from -50% to 50%
+    /// - `F` and `G`: from -100% to 100%
+    ///
+    /// The energy labelling framework, Regulation (EU) 2017/1369 for the `A`
+    /// to `G` scale and Directive 2010/30/EU for the `A+` to `A+++` classes,
+    /// defines each class through product-specific Energy Efficiency Index
+    /// thresholds rather than through a percentage. Hence, these ranges are
+    /// a convention of this crate, following the ordering of the classes,
+    /// and not values taken from the regulation.
+    #[must_use]
+    pub const fn percentage_range(self) -> RangeInclusive<i8> {
+        match self {
+            Self::APlusPlusPlus | Self::APlusPlus | Self::APlus => -10..=10,
+            Self::A | Self::B => -25..=25,
+            Self::C | Self::D | Self::E => -50..=50,
+            Self::F | Self::G => -100..=100,
+        }
+    }
 }
 
 impl core::fmt::Display for EnergyClass {
@@ -62,6 +91,32 @@ const fn decimal_percentage(percentage: i8) -> f64 {
     percentage as f64 / 100.
 }
 
+/// An [`EnergyEfficiency`] percentage out of the range allowed for its
+/// [`EnergyClass`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct EnergyEfficiencyError {
+    /// Invalid percentage.
+    pub percentage: i8,
+    /// Energy class.
+    pub energy_class: EnergyClass,
+}
+
+impl core::fmt::Display for EnergyEfficiencyError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        let range = self.energy_class.percentage_range();
+        write!(
+            f,
+            "The {}% energy efficiency is out of the [{}%, {}%] range of the \"{}\" efficiency class",
+            self.percentage,
+            range.start(),
+            range.end(),
+            self.energy_class
+        )
+    }
+}
+
+impl core::error::Error for EnergyEfficiencyError {}
+
 /// Energy efficiency.
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize)]
 #[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
@@ -96,6 +151,10 @@ impl EnergyEfficiency {
     /// is automatically being set.
     /// If the `percentage` parameter is greater than 100, the value of 100 is
     /// automatically being set.
+    ///
+    /// The percentage is not checked against the
+    /// [`EnergyClass::percentage_range`] of the energy class, use
+    /// [`EnergyEfficiency::try_new`] for that.
     #[must_use]
     pub const fn new(percentage: i8, energy_class: EnergyClass) -> Self {
         let percentage = match percentage {
@@ -109,6 +168,30 @@ impl EnergyEfficiency {
         }
     }
 
+    /// Creates an [`EnergyEfficiency`] instance, checking that the
+    /// `percentage` parameter is within the
+    /// [`EnergyClass::percentage_range`] of the energy class.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error when the percentage is out of the range.
+    pub const fn try_new(
+        percentage: i8,
+        energy_class: EnergyClass,
+    ) -> Result<Self, EnergyEfficiencyError> {
+        let range = energy_class.percentage_range();
+        if percentage < *range.start() || percentage > *range.end() {
+            return Err(EnergyEfficiencyError {
+                percentage,
+                energy_class,
+            });
+        }
+        Ok(Self {
+            percentage,
+            energy_class,
+        })
+    }
+
     /// Returns the [`EnergyEfficiency`] percentage as decimal value.
     #[must_use]
     pub const fn decimal_percentage(&self) -> f64 {
@@ -350,6 +433,18 @@ impl Energy {
         self
     }
 
+    /// Merges another [`Energy`] instance into this one.
+    ///
+    /// When both instances define the same data, the data of `other` wins.
+    #[must_use]
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            energy_efficiencies: other.energy_efficiencies.or(self.energy_efficiencies),
+            carbon_footprints: other.carbon_footprints.or(self.carbon_footprints),
+            water_use_efficiency: other.water_use_efficiency.or(self.water_use_efficiency),
+        }
+    }
+
     /// Checks whether [`Energy`] is **completely** empty.
     #[must_use]
     pub const fn is_empty(&self) -> bool {
@@ -368,7 +463,7 @@ mod tests {
 
     use super::{
         CarbonFootprint, CarbonFootprints, EnergyClass, EnergyEfficiencies, EnergyEfficiency,
-        WaterUseEfficiency,
+        EnergyEfficiencyError, WaterUseEfficiency,
     };
 
     fn assert_float_eq(a: f64, b: f64) {
@@ -413,6 +508,29 @@ mod tests {
         assert_eq!(EnergyEfficiency::new(50, EnergyClass::C).percentage, 50);
     }
 
+    #[test]
+    fn test_energy_efficiency_validation() {
+        // In range.
+        assert_eq!(
+            EnergyEfficiency::try_new(-25, EnergyClass::A),
+            Ok(EnergyEfficiency {
+                percentage: -25,
+                energy_class: EnergyClass::A,
+            })
+        );
+        assert!(EnergyEfficiency::try_new(100, EnergyClass::G).is_ok());
+
+        // Out of range.
+        assert_eq!(
+            EnergyEfficiency::try_new(11, EnergyClass::APlus),
+            Err(EnergyEfficiencyError {
+                percentage: 11,
+                energy_class: EnergyClass::APlus,
+            })
+        );
+        assert!(EnergyEfficiency::try_new(-101, EnergyClass::F).is_err());
+    }
+
     #[test]
     fn test_energy_efficiency_decimal_percentage() {
         assert_float_eq(
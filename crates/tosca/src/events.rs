@@ -7,6 +7,12 @@ use core::time::Duration;
 
 use serde::Serialize;
 
+/// Route streaming device events through Server-Sent Events, relative to
+/// the device main route.
+///
+/// It allows a controller to receive device events without a broker.
+pub const EVENTS_SSE_ROUTE: &str = "/events/sse";
+
 /// Broker data.
 #[derive(Debug, PartialEq, Serialize)]
 #[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
@@ -4,8 +4,6 @@ use indexmap::set::{IndexSet, IntoIter, Iter};
 
 use serde::{Deserialize, Serialize};
 
-use crate::macros::set;
-
 /// All [`Hazard`]s.
 pub const ALL_HAZARDS: &[Hazard] = &[
     Hazard::AirPoisoning,
@@ -15,9 +13,11 @@ pub const ALL_HAZARDS: &[Hazard] = &[
     Hazard::ElectricEnergyConsumption,
     Hazard::Explosion,
     Hazard::FireHazard,
+    Hazard::FirmwareUpdate,
     Hazard::GasConsumption,
     Hazard::LogEnergyConsumption,
     Hazard::LogUsageTime,
+    Hazard::NetworkAccess,
     Hazard::PaySubscriptionFee,
     Hazard::PowerOutage,
     Hazard::PowerSurge,
@@ -54,6 +54,9 @@ pub enum Hazard {
     Explosion,
     /// The execution may cause fire.
     FireHazard,
+    /// The execution replaces the device firmware, which may change or stop
+    /// the device behaviour.
+    FirmwareUpdate,
     /// The execution enables a device which consumes gas.
     GasConsumption,
     /// The execution authorises an application to get and save information
@@ -62,6 +65,9 @@ pub enum Hazard {
     /// The execution authorises an application to get and save information
     /// about a device's duration of use.
     LogUsageTime,
+    /// The execution authorises a device to open outbound network connections
+    /// and send data to external services.
+    NetworkAccess,
     /// The execution authorises an application to use payment information and
     /// make a periodic payment.
     PaySubscriptionFee,
@@ -129,9 +135,11 @@ impl Hazard {
             Self::ElectricEnergyConsumption => "Electric Energy Consumption",
             Self::Explosion => "Explosion",
             Self::FireHazard => "Fire Hazard",
+            Self::FirmwareUpdate => "Firmware Update",
             Self::GasConsumption => "Gas Consumption",
             Self::LogEnergyConsumption => "Log Energy Consumption",
             Self::LogUsageTime => "Log Usage Time",
+            Self::NetworkAccess => "Network Access",
             Self::PaySubscriptionFee => "Pay Subscription Fee",
             Self::PowerOutage => "Power Outage",
             Self::PowerSurge => "Power Surge",
@@ -166,6 +174,9 @@ impl Hazard {
             }
             Self::Explosion => "The execution may cause an explosion.",
             Self::FireHazard => "The execution may cause fire.",
+            Self::FirmwareUpdate => {
+                "The execution replaces the device firmware, which may change or stop the device behaviour."
+            }
             Self::GasConsumption => "The execution enables a device which consumes gas.",
             Self::LogEnergyConsumption => {
                 "The execution authorises an application to get and save information about a device's energy impact."
@@ -173,6 +184,9 @@ impl Hazard {
             Self::LogUsageTime => {
                 "The execution authorises an application to get and save information about a device's duration of use."
             }
+            Self::NetworkAccess => {
+                "The execution authorises a device to open outbound network connections and send data to external services."
+            }
             Self::PaySubscriptionFee => {
                 "The execution authorises an application to use payment information and make a periodic payment."
             }
@@ -222,6 +236,7 @@ impl Hazard {
             | Self::Asphyxia
             | Self::Explosion
             | Self::FireHazard
+            | Self::FirmwareUpdate
             | Self::PowerOutage
             | Self::PowerSurge
             | Self::SpoiledFood
@@ -231,6 +246,7 @@ impl Hazard {
             | Self::AudioVideoRecordAndStore
             | Self::LogEnergyConsumption
             | Self::LogUsageTime
+            | Self::NetworkAccess
             | Self::RecordIssuedCommands
             | Self::RecordUserPreferences
             | Self::TakeDeviceScreenshots
@@ -246,6 +262,9 @@ impl Hazard {
     }
 
     /// Returns the identifier associated with an [`Hazard`].
+    ///
+    /// Identifiers are stable, and they also define the bit of an [`Hazard`]
+    /// in the bitset representation of [`Hazards`].
     #[must_use]
     pub const fn id(&self) -> u16 {
         match self {
@@ -273,6 +292,8 @@ impl Hazard {
             Self::VideoRecordAndStore => 21,
             Self::WaterConsumption => 22,
             Self::WaterFlooding => 23,
+            Self::FirmwareUpdate => 24,
+            Self::NetworkAccess => 25,
         }
     }
 
@@ -307,6 +328,8 @@ impl Hazard {
             21 => Some(Self::VideoRecordAndStore),
             22 => Some(Self::WaterConsumption),
             23 => Some(Self::WaterFlooding),
+            24 => Some(Self::FirmwareUpdate),
+            25 => Some(Self::NetworkAccess),
             _ => None,
         }
     }
@@ -324,14 +347,161 @@ impl Hazard {
     }
 }
 
-set! {
-  /// A collection of [`Hazard`]s.
-  #[derive(Debug, Clone, PartialEq, Serialize)]
-  #[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
-  pub struct Hazards(IndexSet<Hazard, DefaultHashBuilder>);
+/// A collection of [`Hazard`]s.
+///
+/// An empty collection describes one of two distinct states:
+///
+/// - _known safe_, when it has been explicitly declared through
+///   [`Self::known_safe`] that no hazards exist
+/// - _unspecified_, when no hazards have been declared at all, so unknown
+///   risks might still be present
+///
+/// A known safe collection is serialized as an empty sequence, while an
+/// unspecified one is usually omitted.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Hazards {
+    hazards: IndexSet<Hazard, DefaultHashBuilder>,
+    known_safe: bool,
+}
+
+impl Serialize for Hazards {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.hazards.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "deserialize")]
+impl<'de> Deserialize<'de> for Hazards {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let hazards = IndexSet::<Hazard, DefaultHashBuilder>::deserialize(deserializer)?;
+        // An empty sequence is only serialized for known safe hazards.
+        let known_safe = hazards.is_empty();
+        Ok(Self {
+            hazards,
+            known_safe,
+        })
+    }
+}
+
+impl IntoIterator for Hazards {
+    type Item = Hazard;
+    type IntoIter = IntoIter<Hazard>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.hazards.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Hazards {
+    type Item = &'a Hazard;
+    type IntoIter = Iter<'a, Hazard>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
 }
 
 impl Hazards {
+    /// Creates an empty [`Hazards`] with unspecified hazards.
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty [`Hazards`] explicitly declared as known safe.
+    #[must_use]
+    #[inline]
+    pub fn known_safe() -> Self {
+        Self {
+            hazards: IndexSet::default(),
+            known_safe: true,
+        }
+    }
+
+    /// Initializes [`Hazards`] with a specific element.
+    #[must_use]
+    #[inline]
+    pub fn init(element: Hazard) -> Self {
+        Self::new().insert(element)
+    }
+
+    /// Inserts a new element into [`Hazards`].
+    ///
+    /// [`Hazards`] are no longer known safe afterwards.
+    #[must_use]
+    #[inline]
+    pub fn insert(mut self, element: Hazard) -> Self {
+        self.add(element);
+        self
+    }
+
+    /// Adds a new element into [`Hazards`].
+    ///
+    /// Unlike [`Self::insert`], this method does not return a modified
+    /// [`Hazards`].
+    ///
+    /// [`Hazards`] are no longer known safe afterwards.
+    #[inline]
+    pub fn add(&mut self, element: Hazard) {
+        self.hazards.insert(element);
+        self.known_safe = false;
+    }
+
+    /// Checks if [`Hazards`] contains the given [`Hazard`].
+    #[inline]
+    #[must_use]
+    pub fn contains(&self, hazard: &Hazard) -> bool {
+        self.hazards.contains(hazard)
+    }
+
+    /// Checks if [`Hazards`] is empty.
+    ///
+    /// Both known safe and unspecified [`Hazards`] are empty.
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.hazards.is_empty()
+    }
+
+    /// Checks if [`Hazards`] have been explicitly declared as known safe.
+    #[must_use]
+    #[inline]
+    pub const fn is_known_safe(&self) -> bool {
+        self.known_safe
+    }
+
+    /// Checks if [`Hazards`] are unspecified, hence empty without having
+    /// been declared as known safe.
+    #[must_use]
+    #[inline]
+    pub fn is_unspecified(&self) -> bool {
+        self.is_empty() && !self.known_safe
+    }
+
+    /// Provides the number of elements in [`Hazards`].
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.hazards.len()
+    }
+
+    /// Gets an element from [`Hazards`] by index.
+    #[must_use]
+    #[inline]
+    pub fn get_index(&self, index: usize) -> Option<&Hazard> {
+        self.hazards.get_index(index)
+    }
+
+    /// Returns an iterator over [`Hazards`].
+    ///
+    /// **Iterates over the elements in the order they were inserted.**
+    #[must_use]
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, Hazard> {
+        self.hazards.iter()
+    }
+
     /// Initializes [`Hazards`] from an array of [`Hazard`]s.
     #[must_use]
     #[inline]
@@ -342,6 +512,76 @@ impl Hazards {
         }
         elements
     }
+
+    /// Encodes [`Hazards`] into a bitmask.
+    ///
+    /// Each [`Hazard`] sets the bit at the position of its identifier, as
+    /// returned by [`Hazard::id`]. For example, [`Hazard::FireHazard`] sets
+    /// the bit `6`.
+    #[cfg(feature = "bitset")]
+    #[must_use]
+    pub fn to_bits(&self) -> u64 {
+        self.iter()
+            .fold(0, |bits, hazard| bits | (1 << hazard.id()))
+    }
+
+    /// Decodes [`Hazards`] from a bitmask.
+    ///
+    /// [`Hazard`]s are inserted in the order of their identifiers.
+    ///
+    /// The return value is [`None`] when a bit does not correspond to any
+    /// [`Hazard`] identifier.
+    #[cfg(feature = "bitset")]
+    #[must_use]
+    pub fn from_bits(bits: u64) -> Option<Self> {
+        let mut hazards = Self::new();
+        for id in 0..u64::BITS {
+            if bits & (1 << id) != 0 {
+                hazards.add(Hazard::from_id(u16::try_from(id).ok()?)?);
+            }
+        }
+        Some(hazards)
+    }
+}
+
+/// Serialization of [`Hazards`] as a bitmask.
+///
+/// This representation is more compact than a sequence of names, hence it
+/// is suited for constrained links. Use it through the
+/// `#[serde(with = "tosca::hazards::bitset")]` attribute.
+///
+/// A bitmask cannot distinguish known safe [`Hazards`] from unspecified
+/// ones, so an empty bitmask is always decoded as unspecified.
+#[cfg(feature = "bitset")]
+pub mod bitset {
+    use serde::{Serialize, Serializer};
+
+    use super::Hazards;
+
+    /// Serializes [`Hazards`] as a bitmask.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error whenever the serializer fails.
+    pub fn serialize<S: Serializer>(hazards: &Hazards, serializer: S) -> Result<S::Ok, S::Error> {
+        hazards.to_bits().serialize(serializer)
+    }
+
+    /// Deserializes [`Hazards`] from a bitmask.
+    ///
+    /// # Errors
+    ///
+    /// It returns an error whenever the bitmask contains an unknown
+    /// [`super::Hazard`] bit.
+    #[cfg(feature = "deserialize")]
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Hazards, D::Error> {
+        let bits = <u64 as serde::Deserialize>::deserialize(deserializer)?;
+        Hazards::from_bits(bits).ok_or_else(|| {
+            serde::de::Error::custom(alloc::format!("unknown hazard bits in `{bits:#x}`"))
+        })
+    }
 }
 
 /// All [`Hazard`] data.
@@ -424,6 +664,7 @@ impl Category {
                 Hazard::AudioVideoRecordAndStore,
                 Hazard::LogEnergyConsumption,
                 Hazard::LogUsageTime,
+                Hazard::NetworkAccess,
                 Hazard::RecordIssuedCommands,
                 Hazard::RecordUserPreferences,
                 Hazard::TakeDeviceScreenshots,
@@ -436,6 +677,7 @@ impl Category {
                 Hazard::Asphyxia,
                 Hazard::Explosion,
                 Hazard::FireHazard,
+                Hazard::FirmwareUpdate,
                 Hazard::PowerOutage,
                 Hazard::PowerSurge,
                 Hazard::SpoiledFood,
@@ -478,6 +720,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_hazard_category() {
+        // Each hazard belongs to the hazards of its category.
+        for hazard in ALL_HAZARDS {
+            assert!(hazard.category().hazards().contains(hazard));
+        }
+
+        assert_eq!(Hazard::from_id(24), Some(Hazard::FirmwareUpdate));
+        assert_eq!(Hazard::FirmwareUpdate.category(), Category::Safety);
+
+        assert_eq!(Hazard::from_id(25), Some(Hazard::NetworkAccess));
+        assert_eq!(Hazard::NetworkAccess.category(), Category::Privacy);
+    }
+
+    #[test]
+    fn test_new_hazards_serialization() {
+        assert_eq!(
+            serialize(Hazard::FirmwareUpdate),
+            serde_json::json!("FirmwareUpdate")
+        );
+        assert_eq!(
+            serialize(Hazard::NetworkAccess),
+            serde_json::json!("NetworkAccess")
+        );
+
+        for hazard in [Hazard::FirmwareUpdate, Hazard::NetworkAccess] {
+            assert_eq!(deserialize::<Hazard>(serialize(hazard)), hazard);
+        }
+    }
+
     #[test]
     fn test_category() {
         // Compare all categories.
@@ -485,4 +757,93 @@ mod tests {
             assert_eq!(deserialize::<Category>(serialize(category)), *category);
         }
     }
+
+    #[test]
+    fn test_hazards_iteration() {
+        use super::Hazards;
+
+        let hazards = Hazards::new()
+            .insert(Hazard::FireHazard)
+            .insert(Hazard::AirPoisoning)
+            .insert(Hazard::NetworkAccess);
+
+        assert_eq!(hazards.len(), 3);
+        assert_eq!(hazards.iter().count(), hazards.len());
+
+        // Hazards are iterated in their insertion order.
+        assert_eq!(
+            hazards.iter().copied().collect::<alloc::vec::Vec<_>>(),
+            [
+                Hazard::FireHazard,
+                Hazard::AirPoisoning,
+                Hazard::NetworkAccess
+            ]
+        );
+        assert!(
+            (&hazards)
+                .into_iter()
+                .all(|hazard| hazards.contains(hazard))
+        );
+        assert!(!hazards.contains(&Hazard::WaterFlooding));
+
+        // An inserted hazard is never duplicated.
+        let hazards = hazards.insert(Hazard::FireHazard);
+        assert_eq!(hazards.len(), 3);
+
+        let owned = hazards.clone().into_iter().collect::<alloc::vec::Vec<_>>();
+        assert_eq!(owned.len(), 3);
+        assert!(owned.iter().all(|hazard| hazards.contains(hazard)));
+    }
+
+    #[cfg(feature = "bitset")]
+    #[test]
+    fn test_hazards_bitset() {
+        use super::Hazards;
+
+        // Round-trip of all hazards.
+        let hazards = Hazards::init_from_hazards([
+            Hazard::AirPoisoning,
+            Hazard::FireHazard,
+            Hazard::NetworkAccess,
+        ]);
+        assert_eq!(hazards.to_bits(), 1 | 1 << 6 | 1 << 25);
+        assert_eq!(Hazards::from_bits(hazards.to_bits()), Some(hazards));
+
+        let all_hazards = ALL_HAZARDS
+            .iter()
+            .fold(Hazards::new(), |hazards, hazard| hazards.insert(*hazard));
+        let decoded = Hazards::from_bits(all_hazards.to_bits()).unwrap();
+        assert_eq!(decoded.len(), ALL_HAZARDS.len());
+        assert!(ALL_HAZARDS.iter().all(|hazard| decoded.contains(hazard)));
+
+        assert_eq!(Hazards::from_bits(0), Some(Hazards::new()));
+    }
+
+    #[cfg(feature = "bitset")]
+    #[test]
+    fn test_hazards_bitset_serde() {
+        use super::Hazards;
+
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct Compact {
+            #[serde(with = "super::bitset")]
+            hazards: Hazards,
+        }
+
+        let compact = Compact {
+            hazards: Hazards::init(Hazard::FireHazard),
+        };
+        assert_eq!(serialize(&compact), serde_json::json!({ "hazards": 64 }));
+        assert_eq!(deserialize::<Compact>(serialize(&compact)), compact);
+    }
+
+    #[cfg(feature = "bitset")]
+    #[test]
+    fn test_hazards_unknown_bit() {
+        use super::Hazards;
+
+        assert_eq!(Hazards::from_bits(1 << 26), None);
+        assert_eq!(Hazards::from_bits(1 | 1 << 63), None);
+        assert!(super::bitset::deserialize(serde_json::json!(1u64 << 40)).is_err());
+    }
 }
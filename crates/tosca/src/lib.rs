@@ -33,6 +33,12 @@ extern crate alloc;
 
 mod macros;
 
+/// Exponential backoff of reconnection attempts.
+#[cfg(feature = "backoff")]
+pub mod backoff;
+/// Compact binary encoding of device descriptions.
+#[cfg(feature = "postcard")]
+pub mod binary;
 /// Description of a device with its routes information.
 pub mod device;
 /// Information about the economy device aspects.
@@ -43,12 +49,18 @@ pub mod energy;
 pub mod events;
 /// Hazards descriptions and methods.
 pub mod hazards;
+/// Over-the-air firmware updates.
+#[cfg(feature = "ota")]
+pub mod ota;
 /// Route input parameters.
 pub mod parameters;
 /// All supported responses returned by a device action.
 pub mod response;
 /// Definition of device routes.
 pub mod route;
+/// Timestamps to check the freshness of device responses.
+#[cfg(feature = "time")]
+pub mod time;
 
 #[cfg(test)]
 #[cfg(feature = "deserialize")]
@@ -11,6 +11,12 @@ macro_rules! mandatory_route {
         }
 
         impl $name {
+            #[doc = "Route path."]
+            pub const PATH: &'static str = $path;
+
+            #[doc = "[`RestKind`]s the route can be served through."]
+            pub const REST_KINDS: &'static [RestKind] = &[$(mandatory_route!(@rest_kind $method)),*];
+
             $(
                 mandatory_route!(@method_fn $method, $name, $path);
             )*
@@ -53,6 +59,14 @@ macro_rules! mandatory_route {
                 self
             }
 
+            #[doc = concat!("Marks a [`", stringify!($name), "`] without hazards as known safe.")]
+            #[must_use]
+            #[inline]
+            pub fn no_hazards_known_safe(mut self) -> Self {
+                self.route = self.route.no_hazards_known_safe();
+                self
+            }
+
             #[doc = concat!("Adds [`Parameters`] to a [`", stringify!($name), "`].")]
             #[must_use]
             #[inline]
@@ -93,6 +107,11 @@ macro_rules! mandatory_route {
         }
     };
 
+    (@rest_kind get) => { RestKind::Get };
+    (@rest_kind put) => { RestKind::Put };
+    (@rest_kind post) => { RestKind::Post };
+    (@rest_kind delete) => { RestKind::Delete };
+
     (@method_fn get, $name:ident, $path:expr) => {
         #[doc = concat!("Creates a new [`", stringify!($name), "`] through a REST `GET` API.")]
         #[must_use]
@@ -0,0 +1,202 @@
+use crate::hazards::Hazard;
+use crate::parameters::Parameters;
+use crate::route::{ParameterStyle, Route};
+
+/// Path of the route updating a device firmware over the air.
+pub const OTA_ROUTE: &str = "/ota";
+
+/// Name of the byte stream parameter carrying a firmware image.
+pub const FIRMWARE_PARAMETER: &str = "firmware";
+
+/// Name of the parameter carrying the [`FirmwareChecksum`] of a firmware
+/// image.
+pub const CHECKSUM_PARAMETER: &str = "checksum";
+
+// Reversed polynomial of the `CRC-32/ISO-HDLC` checksum.
+const CRC32_POLYNOMIAL: u32 = 0xEDB8_8320;
+
+// Checksums of all byte values, computed at compile time.
+const CRC32_TABLE: [u32; 256] = crc32_table();
+
+// `From` conversions cannot be called at compile time, so byte values are
+// widened with lossless casts.
+const fn crc32_table() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut byte: u8 = 0;
+    loop {
+        let mut crc = byte as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ CRC32_POLYNOMIAL
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[byte as usize] = crc;
+        if byte == u8::MAX {
+            break;
+        }
+        byte += 1;
+    }
+    table
+}
+
+/// Creates the `POST` route updating a device firmware over the air.
+///
+/// The firmware image is sent as the request body, up to `max_size` bytes,
+/// while its [`FirmwareChecksum`] is sent as a query parameter, such as
+/// `/ota?checksum=3421780262`.
+///
+/// The route declares the [`Hazard::FirmwareUpdate`] hazard.
+#[must_use]
+pub fn ota_route(max_size: u32) -> Route {
+    Route::post("Firmware update", OTA_ROUTE)
+        .description("Update the device firmware.")
+        .with_hazard(Hazard::FirmwareUpdate)
+        .with_parameters(
+            Parameters::new()
+                .byte_stream(FIRMWARE_PARAMETER, max_size)
+                .u32(CHECKSUM_PARAMETER, 0),
+        )
+        .with_parameter_style(ParameterStyle::Query)
+}
+
+/// A firmware image whose checksum does not match the expected one.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ChecksumMismatch {
+    /// Expected checksum.
+    pub expected: u32,
+    /// Checksum of the received firmware image.
+    pub computed: u32,
+}
+
+impl core::fmt::Display for ChecksumMismatch {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(
+            f,
+            "The firmware checksum {} does not match the expected checksum {}",
+            self.computed, self.expected
+        )
+    }
+}
+
+impl core::error::Error for ChecksumMismatch {}
+
+/// The checksum of a firmware image received in chunks.
+///
+/// It is a `CRC-32/ISO-HDLC` checksum, the same computed by common tools
+/// such as `crc32` or `zlib`, so a controller can easily compute it before
+/// sending an image.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct FirmwareChecksum(u32);
+
+impl Default for FirmwareChecksum {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FirmwareChecksum {
+    /// Creates the [`FirmwareChecksum`] of an empty image.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self(u32::MAX)
+    }
+
+    /// Updates the [`FirmwareChecksum`] with the next chunk of an image.
+    pub fn update(&mut self, chunk: &[u8]) {
+        self.0 = chunk.iter().fold(self.0, |crc, byte| {
+            CRC32_TABLE[((crc ^ u32::from(*byte)) & 0xFF) as usize] ^ (crc >> 8)
+        });
+    }
+
+    /// Returns the checksum value of the chunks received so far.
+    #[must_use]
+    pub const fn value(&self) -> u32 {
+        !self.0
+    }
+
+    /// Checks whether the checksum of the chunks received so far matches
+    /// the `expected` one.
+    ///
+    /// # Errors
+    ///
+    /// A [`ChecksumMismatch`] is returned when the checksums differ.
+    pub const fn verify(&self, expected: u32) -> Result<(), ChecksumMismatch> {
+        let computed = self.value();
+        if computed == expected {
+            Ok(())
+        } else {
+            Err(ChecksumMismatch { expected, computed })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hazards::Hazard;
+    use crate::parameters::ParameterKind;
+    use crate::route::{ParameterStyle, RestKind};
+
+    use super::{
+        CHECKSUM_PARAMETER, ChecksumMismatch, FIRMWARE_PARAMETER, FirmwareChecksum, OTA_ROUTE,
+        ota_route,
+    };
+
+    // The check value of the `CRC-32/ISO-HDLC` checksum.
+    const CHECK: u32 = 0xCBF4_3926;
+
+    #[test]
+    fn test_firmware_checksum() {
+        assert_eq!(FirmwareChecksum::new().value(), 0);
+
+        let mut checksum = FirmwareChecksum::new();
+        checksum.update(b"123456789");
+        assert_eq!(checksum.value(), CHECK);
+
+        // An image received in chunks has the same checksum.
+        let mut chunked = FirmwareChecksum::new();
+        for chunk in b"123456789".chunks(4) {
+            chunked.update(chunk);
+        }
+        assert_eq!(chunked, checksum);
+        assert_eq!(chunked.verify(CHECK), Ok(()));
+    }
+
+    #[test]
+    fn test_checksum_mismatch() {
+        let mut checksum = FirmwareChecksum::new();
+        checksum.update(b"123456789");
+
+        // A single corrupted byte changes the checksum.
+        let mut corrupted = FirmwareChecksum::new();
+        corrupted.update(b"123456780");
+
+        assert_eq!(
+            corrupted.verify(checksum.value()),
+            Err(ChecksumMismatch {
+                expected: CHECK,
+                computed: corrupted.value(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_ota_route() {
+        let route = ota_route(1024);
+        assert_eq!(route.validate(), Ok(()));
+
+        let route = route.serialize_data();
+        assert_eq!(route.rest_kind, RestKind::Post);
+        assert_eq!(route.data.path, OTA_ROUTE);
+        assert_eq!(route.parameter_style, ParameterStyle::Query);
+        assert!(route.data.hazards.contains(&Hazard::FirmwareUpdate));
+        assert_eq!(
+            route.data.parameters.get(FIRMWARE_PARAMETER),
+            Some(&ParameterKind::ByteStream { max_size: 1024 })
+        );
+        assert!(route.data.parameters.get(CHECKSUM_PARAMETER).is_some());
+    }
+}
@@ -1,8 +1,10 @@
 // FIXME: Remove once passing by value will be supported in serde.
 #![allow(clippy::trivially_copy_pass_by_ref)]
 
+use core::cmp::Ordering;
+
 use alloc::borrow::Cow;
-use alloc::string::String;
+use alloc::string::{String, ToString};
 
 use hashbrown::DefaultHashBuilder;
 
@@ -11,6 +13,7 @@ use indexmap::map::{IndexMap, IntoIter, Iter, Keys};
 use serde::{Deserialize, Serialize};
 
 use crate::macros::map;
+use crate::route::OffStepValues;
 
 fn is_u8_max(value: &u8) -> bool {
     *value == u8::MAX
@@ -64,6 +67,46 @@ fn u64_max() -> u64 {
     u64::MAX
 }
 
+fn is_u128_max(value: &u128) -> bool {
+    *value == u128::MAX
+}
+
+fn is_u128_min(value: &u128) -> bool {
+    *value == u128::MIN
+}
+
+#[cfg(feature = "deserialize")]
+fn u128_max() -> u128 {
+    u128::MAX
+}
+
+// Encodes an [`u128`] value as a decimal string.
+//
+// Many JSON consumers represent numbers as 64-bit values, hence a larger
+// number would be truncated.
+mod decimal {
+    use serde::Serializer;
+
+    pub(super) fn serialize<S: Serializer>(value: &u128, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    #[cfg(feature = "deserialize")]
+    pub(super) fn deserialize<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<u128, D::Error> {
+        use serde::Deserialize;
+        use serde::de::Error;
+
+        let value = alloc::borrow::Cow::<str>::deserialize(deserializer)?;
+        value.parse().map_err(D::Error::custom)
+    }
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
 fn is_f32_max(value: &f32) -> bool {
     *value == f32::MAX
 }
@@ -166,6 +209,26 @@ pub enum ParameterKind {
         #[serde(default = "u64_max")]
         max: u64,
     },
+    /// An [`u128`] value, such as a cumulative counter.
+    ///
+    /// Its values are serialized as decimal strings, since many JSON
+    /// consumers cannot represent numbers larger than 64 bits.
+    U128 {
+        /// The initial [`u128`] value, but also the default one
+        /// in case of a missing input parameter.
+        #[serde(with = "decimal")]
+        default: u128,
+        /// The minimum [`u128`] value allowed.
+        #[serde(skip_serializing_if = "is_u128_max")]
+        #[serde(with = "decimal")]
+        #[serde(default)]
+        min: u128,
+        /// The maximum [`u128`] allowed value.
+        #[serde(skip_serializing_if = "is_u128_min")]
+        #[serde(with = "decimal")]
+        #[serde(default = "u128_max")]
+        max: u128,
+    },
     /// A [`f32`] value.
     F32 {
         /// The initial [`f32`] value, but also the default one
@@ -242,6 +305,27 @@ pub enum ParameterKind {
     CharsSequence {
         /// A character sequence representing the default value.
         default: Cow<'static, str>,
+        /// Whether the value is secret, such as a password or a token.
+        ///
+        /// A secret value is never displayed.
+        #[serde(skip_serializing_if = "is_false")]
+        #[serde(default)]
+        secret: bool,
+        /// The maximum number of characters allowed for a value.
+        ///
+        /// It is an upper bound rather than an exact length, so shorter
+        /// values are accepted. If [`None`], values are not limited.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default)]
+        max_length: Option<u32>,
+    },
+    /// A stream of bytes, such as a file, sent as the request body.
+    ///
+    /// The bytes are read in chunks by a route handler, rather than being
+    /// entirely buffered in memory.
+    ByteStream {
+        /// The maximum number of bytes allowed for the stream.
+        max_size: u32,
     },
 }
 
@@ -257,10 +341,12 @@ impl ParameterKind {
             Self::RangeU32 { .. } => "RangeU32",
             Self::U64 { .. } => "U64",
             Self::RangeU64 { .. } => "RangeU64",
+            Self::U128 { .. } => "U128",
             Self::F32 { .. } => "F32",
             Self::F64 { .. } => "F64",
             Self::RangeF64 { .. } => "RangeF64",
             Self::CharsSequence { .. } => "CharsSequence",
+            Self::ByteStream { .. } => "ByteStream",
         }
     }
 
@@ -273,13 +359,227 @@ impl ParameterKind {
             Self::U16 { .. } => "u16",
             Self::U32 { .. } | Self::RangeU32 { .. } => "u32",
             Self::U64 { .. } | Self::RangeU64 { .. } => "u64",
+            Self::U128 { .. } => "u128",
             Self::F32 { .. } => "f32",
             Self::F64 { .. } | Self::RangeF64 { .. } => "f64",
             Self::CharsSequence { .. } => "String",
+            Self::ByteStream { .. } => "bytes",
+        }
+    }
+
+    /// Returns the number of decimal places expressed by the step of a
+    /// [`ParameterKind::F32`], [`ParameterKind::F64`], or
+    /// [`ParameterKind::RangeF64`].
+    ///
+    /// A step of `0.25` results in two decimal places, while an integer
+    /// step results in zero decimal places. The step of a floating point
+    /// value is its [`DecimalPrecision`].
+    ///
+    /// The return value is [`None`] for any other [`ParameterKind`] or when
+    /// the step is not a positive finite value, such as a
+    /// [`DecimalPrecision::Any`] precision.
+    #[must_use]
+    pub fn decimal_places(&self) -> Option<u32> {
+        match *self {
+            Self::F32 { step, .. } if step > 0. && step.is_finite() => {
+                Some(decimal_places(step.into(), F32_DECIMAL_TOLERANCE))
+            }
+            Self::F64 { step, .. } | Self::RangeF64 { step, .. }
+                if step > 0. && step.is_finite() =>
+            {
+                Some(decimal_places(step, DECIMAL_TOLERANCE))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the minimum and maximum values effectively allowed by a
+    /// [`ParameterKind`].
+    ///
+    /// Numeric kinds without limits store sentinel values, such as a minimum
+    /// of [`u8::MAX`] and a maximum of [`u8::MIN`], which are received as
+    /// the whole range of their type. Both forms are considered unbounded.
+    ///
+    /// The return value is [`None`] for an unbounded kind and for any kind
+    /// which is not numeric.
+    #[must_use]
+    pub fn effective_bounds(&self) -> Option<(f64, f64)> {
+        match *self {
+            Self::U8 { min, max, .. } => {
+                bounds(min, max, u8::MIN, u8::MAX).map(|(min, max)| (min.into(), max.into()))
+            }
+            Self::U16 { min, max, .. } => {
+                bounds(min, max, u16::MIN, u16::MAX).map(|(min, max)| (min.into(), max.into()))
+            }
+            Self::U32 { min, max, .. } => {
+                bounds(min, max, u32::MIN, u32::MAX).map(|(min, max)| (min.into(), max.into()))
+            }
+            Self::U64 { min, max, .. } => bounds(min, max, u64::MIN, u64::MAX)
+                .map(|(min, max)| (u64_to_f64(min), u64_to_f64(max))),
+            Self::U128 { min, max, .. } => bounds(min, max, u128::MIN, u128::MAX)
+                .map(|(min, max)| (u128_to_f64(min), u128_to_f64(max))),
+            Self::F32 { min, max, .. } => {
+                bounds(min, max, f32::MIN, f32::MAX).map(|(min, max)| (min.into(), max.into()))
+            }
+            Self::F64 { min, max, .. } => bounds(min, max, f64::MIN, f64::MAX),
+            Self::RangeU32 { min, max, .. } => Some((min.into(), max.into())),
+            Self::RangeU64 { min, max, .. } => Some((u64_to_f64(min), u64_to_f64(max))),
+            Self::RangeF64 { min, max, .. } => Some((min, max)),
+            Self::Bool { .. } | Self::CharsSequence { .. } | Self::ByteStream { .. } => None,
+        }
+    }
+
+    /// Returns an iterator over the values allowed by a range
+    /// [`ParameterKind`], from its minimum to its maximum.
+    ///
+    /// Values are obtained by adding the step to the minimum, so the
+    /// maximum is only yielded when it is a multiple of the step away from
+    /// the minimum. A zero step only yields the minimum.
+    ///
+    /// Floating point values are clamped to the maximum, so rounding errors
+    /// never yield a value beyond it.
+    ///
+    /// The iterator is empty for any kind which is not a range, and for a
+    /// range whose minimum is greater than its maximum.
+    pub fn range_values(&self) -> impl Iterator<Item = ParameterValue> {
+        let (steps, last) = match *self {
+            Self::RangeU32 { min, max, step, .. } => (
+                RangeSteps::U32(min, step),
+                integer_last_step(min.into(), max.into(), step.into()),
+            ),
+            Self::RangeU64 { min, max, step, .. } => (
+                RangeSteps::U64(min, step),
+                integer_last_step(min, max, step),
+            ),
+            Self::RangeF64 { min, max, step, .. } => (
+                RangeSteps::F64(min, step, max),
+                float_last_step(min, max, step),
+            ),
+            _ => (RangeSteps::U64(0, 0), None),
+        };
+
+        last.into_iter()
+            .flat_map(|last| 0..=last)
+            .map(move |index| steps.value(index))
+    }
+}
+
+// Returns the limits of a numeric kind, if any.
+//
+// Inverted limits are the sentinels of a kind without limits, while the whole
+// range of a type is how those sentinels are deserialized.
+fn bounds<T: PartialOrd + Copy>(min: T, max: T, type_min: T, type_max: T) -> Option<(T, T)> {
+    (min <= max && !(min == type_min && max == type_max)).then_some((min, max))
+}
+
+// Converts a `u64` limit to `f64`.
+//
+// Limits beyond 2^53 are rounded to the nearest `f64`, which is accurate
+// enough to report the bounds of a kind.
+#[allow(clippy::cast_precision_loss)]
+fn u64_to_f64(value: u64) -> f64 {
+    value as f64
+}
+
+// Converts a `u128` limit to `f64`.
+//
+// Every `u128` is below `f64::MAX`, so the conversion is finite and only
+// rounds limits beyond 2^53 to the nearest `f64`.
+#[allow(clippy::cast_precision_loss)]
+fn u128_to_f64(value: u128) -> f64 {
+    value as f64
+}
+
+// Minimum and step of a range kind, along with the maximum of a floating
+// point range.
+#[derive(Clone, Copy)]
+enum RangeSteps {
+    U32(u32, u32),
+    U64(u64, u64),
+    F64(f64, f64, f64),
+}
+
+impl RangeSteps {
+    // Returns the value of a range after the given number of steps.
+    //
+    // The number of steps never exceeds the last step of the range, so the
+    // integer values cannot overflow, while the floating point values are
+    // clamped to the maximum to absorb rounding errors.
+    fn value(self, index: u64) -> ParameterValue {
+        match self {
+            Self::U32(min, step) => {
+                // The last step of a `u32` range is at most `u32::MAX`.
+                #[allow(clippy::cast_possible_truncation)]
+                let index = index as u32;
+                ParameterValue::U32(min + index * step)
+            }
+            Self::U64(min, step) => ParameterValue::U64(min + index * step),
+            Self::F64(min, step, max) => {
+                // Indices beyond 2^53 lose precision, which clamping absorbs.
+                #[allow(clippy::cast_precision_loss)]
+                let index = index as f64;
+                ParameterValue::F64((min + index * step).min(max))
+            }
+        }
+    }
+}
+
+// Returns the number of steps needed to reach the last value of an integer
+// range.
+fn integer_last_step(min: u64, max: u64, step: u64) -> Option<u64> {
+    if min > max {
+        None
+    } else {
+        Some((max - min).checked_div(step).unwrap_or_default())
+    }
+}
+
+// Returns the number of steps needed to reach the last value of a floating
+// point range.
+//
+// A tolerance absorbs the rounding errors of a maximum which is a multiple of
+// the step away from the minimum.
+fn float_last_step(min: f64, max: f64, step: f64) -> Option<u64> {
+    match min.partial_cmp(&max) {
+        Some(Ordering::Less | Ordering::Equal) if step > 0. => {
+            // The quotient is non-negative, and the saturating cast floors it.
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let last = ((max - min) / step + DECIMAL_TOLERANCE) as u64;
+            Some(last)
         }
+        Some(Ordering::Less | Ordering::Equal) => Some(0),
+        _ => None,
     }
 }
 
+// Maximum number of decimal places a step is inspected for.
+const MAX_DECIMAL_PLACES: u32 = 15;
+
+// Tolerance used to consider a scaled step as an integer value.
+const DECIMAL_TOLERANCE: f64 = 1e-9;
+
+// Tolerance used to consider a scaled `f32` step as an integer value.
+//
+// An `f32` step is less accurate than an `f64` one, so `0.1` is widened to
+// `0.10000000149011612`.
+const F32_DECIMAL_TOLERANCE: f64 = 1e-6;
+
+// Computes the decimal places of a positive finite step.
+//
+// The remainder of the division by one is the fractional part of a positive
+// value, even beyond the range of the integer types.
+fn decimal_places(step: f64, tolerance: f64) -> u32 {
+    let mut scaled = step;
+    for places in 0..MAX_DECIMAL_PLACES {
+        let fraction = scaled % 1.;
+        if fraction < tolerance || 1. - fraction < tolerance {
+            return places;
+        }
+        scaled *= 10.;
+    }
+    MAX_DECIMAL_PLACES
+}
+
 /// Floating point decimal precision.
 #[derive(Debug, Clone, Copy, PartialEq, Serialize)]
 #[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
@@ -317,6 +617,10 @@ impl DecimalPrecision {
 map! {
   /// A map that associates each parameter name with its
   /// corresponding [`ParameterKind`].
+  ///
+  /// Parameters are kept in insertion order, which is also the order of
+  /// their serialized json object. A receiver must preserve it, since it
+  /// defines the path of a route with path parameters.
   #[derive(Debug, Clone, PartialEq, Serialize)]
   #[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
   pub struct ParametersData(IndexMap<String, ParameterKind, DefaultHashBuilder>);
@@ -330,11 +634,42 @@ impl ParametersData {
     pub fn get(&self, key: &str) -> Option<&ParameterKind> {
         self.0.get(key)
     }
+
+    /// Returns the declared default value of each parameter as
+    /// [`ParametersValues`].
+    ///
+    /// A byte stream is the request body rather than a value, so it is never
+    /// included.
+    #[must_use]
+    pub fn defaults(&self) -> ParametersValues<'_> {
+        let mut parameters_values = ParametersValues::new();
+        for (name, parameter_kind) in self {
+            if !matches!(parameter_kind, ParameterKind::ByteStream { .. }) {
+                parameters_values.parameter_value(
+                    name.as_str(),
+                    ParameterValue::from_parameter_kind(parameter_kind),
+                );
+            }
+        }
+        parameters_values
+    }
 }
 
 /// Route input parameters.
+///
+/// By default, adding a parameter with an already used name replaces the
+/// previous definition. A strict [`Parameters`], created through
+/// [`Parameters::strict`], keeps the first definition instead and records
+/// the duplicate name, so that the route declaring it is rejected.
 #[derive(Debug, Clone)]
-pub struct Parameters(IndexMap<&'static str, ParameterKind, DefaultHashBuilder>);
+pub struct Parameters {
+    // Parameters kinds, indexed by name.
+    entries: IndexMap<&'static str, ParameterKind, DefaultHashBuilder>,
+    // Whether duplicate names are rejected.
+    strict: bool,
+    // First duplicate name found in strict mode.
+    duplicate: Option<&'static str>,
+}
 
 impl Default for Parameters {
     fn default() -> Self {
@@ -347,7 +682,24 @@ impl Parameters {
     #[must_use]
     #[inline]
     pub fn new() -> Self {
-        Self(IndexMap::with_hasher(DefaultHashBuilder::default()))
+        Self {
+            entries: IndexMap::with_hasher(DefaultHashBuilder::default()),
+            strict: false,
+            duplicate: None,
+        }
+    }
+
+    /// Creates a strict [`Parameters`], which rejects duplicate names.
+    ///
+    /// A duplicate name does not replace the previous definition, and it is
+    /// then returned by [`Parameters::duplicate`].
+    #[must_use]
+    #[inline]
+    pub fn strict() -> Self {
+        Self {
+            strict: true,
+            ..Self::new()
+        }
     }
 
     /// Adds a [`bool`] parameter.
@@ -441,6 +793,27 @@ impl Parameters {
         self.create_parameter(name, ParameterKind::U64 { default, min, max })
     }
 
+    /// Adds an [`u128`] parameter.
+    #[must_use]
+    #[inline]
+    pub fn u128(self, name: &'static str, default: u128) -> Self {
+        self.create_parameter(
+            name,
+            ParameterKind::U128 {
+                default,
+                min: u128::MAX,
+                max: u128::MIN,
+            },
+        )
+    }
+
+    /// Adds an [`u128`] parameter with limits.
+    #[must_use]
+    #[inline]
+    pub fn u128_with_limits(self, name: &'static str, default: u128, min: u128, max: u128) -> Self {
+        self.create_parameter(name, ParameterKind::U128 { default, min, max })
+    }
+
     /// Adds a [`f32`] parameter.
     #[must_use]
     #[inline]
@@ -596,7 +969,27 @@ impl Parameters {
         )
     }
 
-    /// Adds a characters sequence with a determined length.
+    /// Adds a [`f64`] range with a default value, whose step is expressed
+    /// as a [`DecimalPrecision`].
+    #[must_use]
+    #[inline]
+    pub fn rangef64_with_precision(
+        self,
+        name: &'static str,
+        range: (f64, f64),
+        decimal_precision: DecimalPrecision,
+        default: f64,
+    ) -> Self {
+        self.rangef64_with_default(
+            name,
+            (range.0, range.1, decimal_precision.to_f64()),
+            default,
+        )
+    }
+
+    /// Adds a characters sequence.
+    ///
+    /// Its values are not limited in length.
     #[must_use]
     #[inline]
     pub fn characters_sequence(
@@ -608,10 +1001,88 @@ impl Parameters {
             name,
             ParameterKind::CharsSequence {
                 default: default.into(),
+                secret: false,
+                max_length: None,
+            },
+        )
+    }
+
+    /// Adds a characters sequence with a maximum length.
+    ///
+    /// Values longer than `max_length` characters are rejected, while
+    /// shorter ones are accepted.
+    #[must_use]
+    #[inline]
+    pub fn characters_sequence_with_max_length(
+        self,
+        name: &'static str,
+        default: impl Into<Cow<'static, str>>,
+        max_length: u32,
+    ) -> Self {
+        self.create_parameter(
+            name,
+            ParameterKind::CharsSequence {
+                default: default.into(),
+                secret: false,
+                max_length: Some(max_length),
+            },
+        )
+    }
+
+    /// Adds a secret characters sequence, such as a password or a token.
+    ///
+    /// Its values are displayed as `***`, so they are never written into
+    /// logs or responses.
+    #[must_use]
+    #[inline]
+    pub fn secret_characters_sequence(
+        self,
+        name: &'static str,
+        default: impl Into<Cow<'static, str>>,
+    ) -> Self {
+        self.create_parameter(
+            name,
+            ParameterKind::CharsSequence {
+                default: default.into(),
+                secret: true,
+                max_length: None,
+            },
+        )
+    }
+
+    /// Adds a secret characters sequence with a maximum length.
+    ///
+    /// Values longer than `max_length` characters are rejected, while
+    /// shorter ones are accepted.
+    #[must_use]
+    #[inline]
+    pub fn secret_characters_sequence_with_max_length(
+        self,
+        name: &'static str,
+        default: impl Into<Cow<'static, str>>,
+        max_length: u32,
+    ) -> Self {
+        self.create_parameter(
+            name,
+            ParameterKind::CharsSequence {
+                default: default.into(),
+                secret: true,
+                max_length: Some(max_length),
             },
         )
     }
 
+    /// Adds a stream of bytes with a maximum size.
+    ///
+    /// The stream is sent as the request body, so it excludes any other
+    /// parameter from the body. Other parameters can be sent as a query
+    /// string through [`ParameterStyle::Query`](crate::route::ParameterStyle::Query).
+    #[must_use]
+    #[inline]
+    pub fn byte_stream(self, name: &'static str, max_size: u32) -> Self {
+        self.create_parameter(name, ParameterKind::ByteStream { max_size })
+    }
+
     /// Serializes [`Parameters`] data.
     ///
     /// It consumes the data.
@@ -619,7 +1090,7 @@ impl Parameters {
     #[inline]
     pub fn serialize_data(self) -> ParametersData {
         let mut data = ParametersData::new();
-        for (key, value) in self.0 {
+        for (key, value) in self.entries {
             data.add(key.into(), value);
         }
         data
@@ -629,25 +1100,45 @@ impl Parameters {
     #[must_use]
     #[inline]
     pub fn is_empty(&self) -> bool {
-        self.0.is_empty()
+        self.entries.is_empty()
+    }
+
+    // Checks whether a byte stream is among the parameters.
+    pub(crate) fn has_byte_stream(&self) -> bool {
+        self.entries
+            .values()
+            .any(|kind| matches!(kind, ParameterKind::ByteStream { .. }))
     }
 
     /// Iterates over all [`Parameters`] names.
     #[must_use]
     #[inline]
     pub fn names(&self) -> Keys<'_, &str, ParameterKind> {
-        self.0.keys()
+        self.entries.keys()
+    }
+
+    /// Returns the first duplicate name added to a strict [`Parameters`].
+    ///
+    /// The return value is always [`None`] for a lenient [`Parameters`].
+    #[must_use]
+    #[inline]
+    pub const fn duplicate(&self) -> Option<&'static str> {
+        self.duplicate
     }
 
     fn create_parameter(mut self, name: &'static str, parameter_kind: ParameterKind) -> Self {
-        self.0.insert(name, parameter_kind);
+        if self.strict && self.entries.contains_key(name) {
+            self.duplicate.get_or_insert(name);
+        } else {
+            self.entries.insert(name, parameter_kind);
+        }
         self
     }
 }
 
 /// All supported parameter values extracted from or
 /// used to construct a request.
-#[derive(Debug, PartialEq, Deserialize)]
+#[derive(PartialEq, Deserialize)]
 #[serde(untagged)]
 pub enum ParameterValue {
     /// A [`bool`] value.
@@ -660,12 +1151,42 @@ pub enum ParameterValue {
     U32(u32),
     /// A [`u64`] value.
     U64(u64),
+    /// A [`u128`] value.
+    ///
+    /// It is never deserialized directly, since it is sent as a decimal
+    /// string. Use [`Self::coerce`] to obtain it from a received value.
+    #[serde(skip_deserializing)]
+    U128(u128),
     /// A [`f32`] value.
     F32(f32),
     /// A [`f64`] value.
     F64(f64),
     /// A characters sequence.
     CharsSequence(Cow<'static, str>),
+    /// A secret characters sequence, displayed as `***`.
+    SecretCharsSequence(Cow<'static, str>),
+    /// The number of bytes of a stream.
+    ByteStream(u32),
+}
+
+impl core::fmt::Debug for ParameterValue {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Bool(v) => f.debug_tuple("Bool").field(v).finish(),
+            Self::U8(v) => f.debug_tuple("U8").field(v).finish(),
+            Self::U16(v) => f.debug_tuple("U16").field(v).finish(),
+            Self::U32(v) => f.debug_tuple("U32").field(v).finish(),
+            Self::U64(v) => f.debug_tuple("U64").field(v).finish(),
+            Self::U128(v) => f.debug_tuple("U128").field(v).finish(),
+            Self::F32(v) => f.debug_tuple("F32").field(v).finish(),
+            Self::F64(v) => f.debug_tuple("F64").field(v).finish(),
+            Self::CharsSequence(v) => f.debug_tuple("CharsSequence").field(v).finish(),
+            Self::SecretCharsSequence(_) => {
+                f.debug_tuple("SecretCharsSequence").field(&"***").finish()
+            }
+            Self::ByteStream(v) => f.debug_tuple("ByteStream").field(v).finish(),
+        }
+    }
 }
 
 impl core::fmt::Display for ParameterValue {
@@ -675,11 +1196,13 @@ impl core::fmt::Display for ParameterValue {
             Self::Bool(v) => v.fmt(f),
             Self::U8(v) => v.fmt(f),
             Self::U16(v) => v.fmt(f),
-            Self::U32(v) => v.fmt(f),
+            Self::U32(v) | Self::ByteStream(v) => v.fmt(f),
             Self::U64(v) => v.fmt(f),
+            Self::U128(v) => v.fmt(f),
             Self::F32(v) => v.fmt(f),
             Self::F64(v) => v.fmt(f),
             Self::CharsSequence(v) => v.fmt(f),
+            Self::SecretCharsSequence(_) => "***".fmt(f),
         }
     }
 }
@@ -698,11 +1221,101 @@ impl ParameterValue {
             ParameterKind::U64 { default, .. } | ParameterKind::RangeU64 { default, .. } => {
                 Self::U64(*default)
             }
+            ParameterKind::U128 { default, .. } => Self::U128(*default),
             ParameterKind::F32 { default, .. } => Self::F32(*default),
             ParameterKind::F64 { default, .. } | ParameterKind::RangeF64 { default, .. } => {
                 Self::F64(*default)
             }
-            ParameterKind::CharsSequence { default, .. } => Self::CharsSequence(default.clone()),
+            ParameterKind::CharsSequence {
+                default,
+                secret: false,
+                ..
+            } => Self::CharsSequence(default.clone()),
+            ParameterKind::CharsSequence {
+                default,
+                secret: true,
+                ..
+            } => Self::SecretCharsSequence(default.clone()),
+            // An omitted stream is empty.
+            ParameterKind::ByteStream { .. } => Self::ByteStream(0),
+        }
+    }
+
+    /// Turns a characters sequence into a secret one when the given
+    /// [`ParameterKind`] is secret.
+    ///
+    /// Any other value is returned unchanged.
+    #[must_use]
+    pub fn conceal(self, parameter_kind: &ParameterKind) -> Self {
+        match (self, parameter_kind) {
+            (Self::CharsSequence(v), ParameterKind::CharsSequence { secret: true, .. }) => {
+                Self::SecretCharsSequence(v)
+            }
+            (value, _) => value,
+        }
+    }
+
+    /// Turns a received value into a value of the given [`ParameterKind`].
+    ///
+    /// A received number is deserialized into the smallest type which can
+    /// hold it, so an unsigned integer is widened to its declared type when
+    /// it fits, and a [`f32`] value is turned into a [`f64`] one through its
+    /// shortest decimal representation. An [`u128`] value is received either
+    /// as a decimal characters sequence or as a smaller unsigned integer.
+    /// Any other value is returned unchanged.
+    #[must_use]
+    pub fn coerce(self, parameter_kind: &ParameterKind) -> Self {
+        let unsigned = match self {
+            Self::U8(v) => u128::from(v),
+            Self::U16(v) => u128::from(v),
+            Self::U32(v) => u128::from(v),
+            Self::U64(v) => u128::from(v),
+            Self::F32(v)
+                if matches!(
+                    parameter_kind,
+                    ParameterKind::F64 { .. } | ParameterKind::RangeF64 { .. }
+                ) =>
+            {
+                return v.to_string().parse().map_or(self, Self::F64);
+            }
+            Self::CharsSequence(v) if matches!(parameter_kind, ParameterKind::U128 { .. }) => {
+                return v.parse().map_or(Self::CharsSequence(v), Self::U128);
+            }
+            value => return value,
+        };
+
+        let coerced = match parameter_kind {
+            ParameterKind::U8 { .. } => u8::try_from(unsigned).ok().map(Self::U8),
+            ParameterKind::U16 { .. } => u16::try_from(unsigned).ok().map(Self::U16),
+            ParameterKind::U32 { .. } | ParameterKind::RangeU32 { .. } => {
+                u32::try_from(unsigned).ok().map(Self::U32)
+            }
+            ParameterKind::U64 { .. } | ParameterKind::RangeU64 { .. } => {
+                u64::try_from(unsigned).ok().map(Self::U64)
+            }
+            ParameterKind::U128 { .. } => Some(Self::U128(unsigned)),
+            // Only integers which are exactly represented are converted.
+            ParameterKind::F32 { .. } => u16::try_from(unsigned)
+                .ok()
+                .map(|v| Self::F32(f32::from(v))),
+            ParameterKind::F64 { .. } | ParameterKind::RangeF64 { .. } => u32::try_from(unsigned)
+                .ok()
+                .map(|v| Self::F64(f64::from(v))),
+            _ => None,
+        };
+
+        coerced.unwrap_or(self)
+    }
+
+    /// Returns the characters sequence of a [`ParameterValue`], whether it
+    /// is secret or not.
+    ///
+    /// If [`None`], the value is not a characters sequence.
+    #[must_use]
+    pub fn chars_sequence(&self) -> Option<&str> {
+        match self {
+            Self::CharsSequence(v) | Self::SecretCharsSequence(v) => Some(v),
+            _ => None,
         }
     }
 
@@ -715,9 +1328,11 @@ impl ParameterValue {
             Self::U16(_) => "u16",
             Self::U32(_) => "u32",
             Self::U64(_) => "u64",
+            Self::U128(_) => "u128",
             Self::F32(_) => "f32",
             Self::F64(_) => "f64",
-            Self::CharsSequence(_) => "String",
+            Self::CharsSequence(_) | Self::SecretCharsSequence(_) => "String",
+            Self::ByteStream(_) => "bytes",
         }
     }
 
@@ -737,14 +1352,119 @@ impl ParameterValue {
                     Self::U64(_),
                     ParameterKind::U64 { .. } | ParameterKind::RangeU64 { .. }
                 )
+                | (Self::U128(_), ParameterKind::U128 { .. })
                 | (Self::F32(_), ParameterKind::F32 { .. })
                 | (
                     Self::F64(_),
                     ParameterKind::F64 { .. } | ParameterKind::RangeF64 { .. }
                 )
-                | (Self::CharsSequence(_), ParameterKind::CharsSequence { .. })
+                | (
+                    Self::CharsSequence(_) | Self::SecretCharsSequence(_),
+                    ParameterKind::CharsSequence { .. }
+                )
+                | (Self::ByteStream(_), ParameterKind::ByteStream { .. })
         )
     }
+
+    /// Checks if the [`ParameterValue`] matches the given [`ParameterKind`]
+    /// and lies within its limits.
+    #[must_use]
+    pub fn is_within_limits(&self, parameter_kind: &ParameterKind) -> bool {
+        match (self, parameter_kind) {
+            (Self::Bool(_), ParameterKind::Bool { .. }) => true,
+            (
+                Self::CharsSequence(v) | Self::SecretCharsSequence(v),
+                ParameterKind::CharsSequence { max_length, .. },
+            ) => max_length.is_none_or(|max_length| {
+                u32::try_from(v.chars().count()).is_ok_and(|length| length <= max_length)
+            }),
+            (Self::ByteStream(v), ParameterKind::ByteStream { max_size }) => v <= max_size,
+            (Self::U8(v), ParameterKind::U8 { min, max, .. }) => (min..=max).contains(&v),
+            (Self::U16(v), ParameterKind::U16 { min, max, .. }) => (min..=max).contains(&v),
+            (
+                Self::U32(v),
+                ParameterKind::U32 { min, max, .. } | ParameterKind::RangeU32 { min, max, .. },
+            ) => (min..=max).contains(&v),
+            (
+                Self::U64(v),
+                ParameterKind::U64 { min, max, .. } | ParameterKind::RangeU64 { min, max, .. },
+            ) => (min..=max).contains(&v),
+            (Self::U128(v), ParameterKind::U128 { min, max, .. }) => (min..=max).contains(&v),
+            (Self::F32(v), ParameterKind::F32 { min, max, .. }) => (min..=max).contains(&v),
+            (
+                Self::F64(v),
+                ParameterKind::F64 { min, max, .. } | ParameterKind::RangeF64 { min, max, .. },
+            ) => (min..=max).contains(&v),
+            _ => false,
+        }
+    }
+
+    /// Aligns a floating point [`ParameterValue`] to the step declared by
+    /// the given [`ParameterKind`].
+    ///
+    /// A [`ParameterKind::RangeF64`] step starts from its minimum, while
+    /// the step of a [`f32`] or [`f64`] parameter starts from zero.
+    ///
+    /// The return value is [`None`] when the value is not a multiple of its
+    /// step and [`OffStepValues::Reject`] is set. Values without a step are
+    /// returned untouched.
+    #[must_use]
+    pub fn align_to_step(
+        self,
+        parameter_kind: &ParameterKind,
+        off_step_values: OffStepValues,
+    ) -> Option<Self> {
+        let (value, base, step) = match (&self, parameter_kind) {
+            (Self::F32(v), ParameterKind::F32 { step, .. }) if *step > 0. => {
+                (f64::from(*v), 0., f64::from(*step))
+            }
+            (Self::F64(v), ParameterKind::F64 { step, .. }) if *step > 0. => (*v, 0., *step),
+            (Self::F64(v), ParameterKind::RangeF64 { min, step, .. }) if *step > 0. => {
+                (*v, *min, *step)
+            }
+            _ => return Some(self),
+        };
+
+        let snapped = base + round((value - base) / step) * step;
+        if (snapped - value).abs() <= DECIMAL_TOLERANCE * step {
+            return Some(self);
+        }
+
+        match (off_step_values, self) {
+            (OffStepValues::Reject, _) => None,
+            (OffStepValues::Snap, Self::F32(_)) => {
+                // The snapped value is a step away from an `f32` value at
+                // most, so the conversion only rounds it.
+                #[allow(clippy::cast_possible_truncation)]
+                let snapped = snapped as f32;
+                Some(Self::F32(snapped))
+            }
+            (OffStepValues::Snap, _) => Some(Self::F64(snapped)),
+        }
+    }
+}
+
+// Smallest magnitude of an `f64` which has no fractional part, that is 2^52.
+const INTEGER_THRESHOLD: f64 = 4_503_599_627_370_496.;
+
+// Rounds a value to the nearest integer, away from zero on ties.
+//
+// `f64::round` is not available in `core`, so a value is truncated through an
+// `i64`, which holds any value below the integer threshold exactly.
+fn round(value: f64) -> f64 {
+    if value.is_nan() || value.abs() >= INTEGER_THRESHOLD {
+        return value;
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    let truncated = value as i64 as f64;
+    if value - truncated >= 0.5 {
+        truncated + 1.
+    } else if truncated - value >= 0.5 {
+        truncated - 1.
+    } else {
+        truncated
+    }
 }
 
 /// Route input parameters values.
@@ -824,6 +1544,12 @@ impl<'a> ParametersValues<'a> {
         self.parameter_value(name, ParameterValue::U64(value))
     }
 
+    /// Adds an [`u128`] parameter.
+    #[inline]
+    pub fn u128(&mut self, name: impl Into<Cow<'a, str>>, value: u128) -> &mut Self {
+        self.parameter_value(name, ParameterValue::U128(value))
+    }
+
     /// Adds a [`f32`] parameter.
     #[inline]
     pub fn f32(&mut self, name: impl Into<Cow<'a, str>>, value: f32) -> &mut Self {
@@ -846,6 +1572,16 @@ impl<'a> ParametersValues<'a> {
         self.parameter_value(name, ParameterValue::CharsSequence(value.into()))
     }
 
+    /// Adds a secret characters sequence, displayed as `***`.
+    #[inline]
+    pub fn secret_characters_sequence(
+        &mut self,
+        name: impl Into<Cow<'a, str>>,
+        value: String,
+    ) -> &mut Self {
+        self.parameter_value(name, ParameterValue::SecretCharsSequence(value.into()))
+    }
+
     /// Retrieves a [`ParameterValue`] by name.
     ///
     /// If [`None`], the parameter does not exist.
@@ -916,10 +1652,24 @@ impl<'a> ParametersPayloads<'a> {
 #[cfg(feature = "deserialize")]
 mod tests {
     use alloc::string::String;
+    use alloc::vec::Vec;
 
     use crate::{deserialize, serialize};
 
-    use super::{ParameterKind, Parameters, ParametersData, ParametersValues};
+    use crate::route::OffStepValues;
+
+    use super::{
+        DecimalPrecision, ParameterKind, ParameterValue, Parameters, ParametersData,
+        ParametersValues,
+    };
+
+    fn chars_sequence_kind(default: &'static str, secret: bool) -> ParameterKind {
+        ParameterKind::CharsSequence {
+            default: default.into(),
+            secret,
+            max_length: None,
+        }
+    }
 
     fn expected_parameters_data() -> ParametersData {
         ParametersData::new()
@@ -1001,17 +1751,12 @@ mod tests {
                     default: 5.,
                 },
             )
+            .insert("greeting".into(), chars_sequence_kind("hello", false))
+            .insert("greeting2".into(), chars_sequence_kind("hello", false))
+            .insert("password".into(), chars_sequence_kind("", true))
             .insert(
-                "greeting".into(),
-                ParameterKind::CharsSequence {
-                    default: "hello".into(),
-                },
-            )
-            .insert(
-                "greeting2".into(),
-                ParameterKind::CharsSequence {
-                    default: "hello".into(),
-                },
+                "firmware".into(),
+                ParameterKind::ByteStream { max_size: 1024 },
             )
     }
 
@@ -1030,6 +1775,8 @@ mod tests {
             .rangef64_with_default("rangef64", (0., 20., 0.1), 5.)
             .characters_sequence("greeting", "hello")
             .characters_sequence("greeting2", String::from("hello"))
+            .secret_characters_sequence("password", "")
+            .byte_stream("firmware", 1024)
             // Adds a duplicate to see whether that value is maintained or
             // removed.
             .u16("u16", 0);
@@ -1040,6 +1787,36 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_strict_parameters() {
+        let parameters = Parameters::strict()
+            .u8("u8", 1)
+            .bool("bool", true)
+            .u8("u8", 2)
+            .bool("bool", false);
+
+        // The first duplicate name is recorded, while the first definition
+        // is maintained.
+        assert_eq!(parameters.duplicate(), Some("u8"));
+        assert_eq!(
+            parameters.serialize_data().get("u8"),
+            Some(&ParameterKind::U8 {
+                default: 1,
+                min: u8::MAX,
+                max: u8::MIN,
+            })
+        );
+
+        assert_eq!(
+            Parameters::strict()
+                .u8("u8", 1)
+                .bool("bool", true)
+                .duplicate(),
+            None
+        );
+        assert_eq!(Parameters::new().u8("u8", 1).u8("u8", 2).duplicate(), None);
+    }
+
     #[test]
     fn test_deserialize_parameters_values() {
         let mut parameters = ParametersValues::new();
@@ -1055,4 +1832,537 @@ mod tests {
 
         assert_eq!(deserialize::<ParametersValues>(json_value), parameters);
     }
+
+    #[test]
+    fn test_u128_parameter() {
+        let counter = u128::from(u64::MAX) + 1;
+        let parameters = Parameters::new()
+            .u128("counter", counter)
+            .u128_with_limits("bounded", 0, 0, counter)
+            .serialize_data();
+
+        // Values are serialized as decimal strings.
+        let json_value = serialize(&parameters);
+        assert_eq!(
+            json_value,
+            serde_json::json!({
+                "counter": { "U128": { "default": "18446744073709551616" } },
+                "bounded": {
+                    "U128": {
+                        "default": "0",
+                        "min": "0",
+                        "max": "18446744073709551616",
+                    }
+                },
+            })
+        );
+
+        let parameters = deserialize::<ParametersData>(json_value);
+        let kind = parameters.get("counter").unwrap();
+        assert_eq!(
+            kind,
+            &ParameterKind::U128 {
+                default: counter,
+                min: u128::MIN,
+                max: u128::MAX,
+            }
+        );
+
+        // A received decimal string is turned into an `u128` value.
+        let value = ParameterValue::CharsSequence("18446744073709551617".into()).coerce(kind);
+        assert_eq!(value, ParameterValue::U128(counter + 1));
+        assert!(value.is_within_limits(kind));
+        assert!(ParameterValue::U64(5).coerce(kind).match_kind(kind));
+        assert!(
+            !ParameterValue::CharsSequence("-1".into())
+                .coerce(kind)
+                .match_kind(kind)
+        );
+    }
+
+    #[test]
+    fn test_coerce_received_values() {
+        let parameters = Parameters::new()
+            .u64("u64", 0)
+            .f64("f64", 0.)
+            .f32("f32", 0.)
+            .u8("u8", 0)
+            .serialize_data();
+
+        // Received numbers are deserialized into the smallest fitting type.
+        let values = deserialize::<ParametersValues>(serde_json::json!({
+            "u64": 7,
+            "f64": 7.37,
+            "f32": 2,
+            "u8": 300,
+        }))
+        .into_iter()
+        .map(|(name, value)| {
+            let kind = parameters.get(&name).unwrap();
+            (name, value.coerce(kind))
+        })
+        .collect::<Vec<_>>();
+
+        let value = |name: &str| &values.iter().find(|(n, _)| n == name).unwrap().1;
+
+        assert_eq!(value("u64"), &ParameterValue::U64(7));
+        // The decimal representation of the value is kept.
+        assert_eq!(value("f64"), &ParameterValue::F64(7.37));
+        assert_eq!(value("f32"), &ParameterValue::F32(2.));
+        // A value which does not fit its declared type is left unchanged.
+        assert!(!value("u8").match_kind(parameters.get("u8").unwrap()));
+    }
+
+    #[test]
+    fn test_parameters_order() {
+        // Names are deliberately not in alphabetical order.
+        let parameters = Parameters::new()
+            .u8("red", 0)
+            .u8("green", 0)
+            .u8("blue", 0)
+            .f32("alpha", 1.)
+            .serialize_data();
+
+        let json = serde_json::to_string(&parameters).unwrap();
+        let received = serde_json::from_str::<ParametersData>(&json).unwrap();
+
+        assert_eq!(
+            received
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>(),
+            ["red", "green", "blue", "alpha"]
+        );
+    }
+
+    #[test]
+    fn test_parameters_defaults() {
+        let parameters_data = Parameters::new()
+            .bool("bool", true)
+            .u8("u8", 3)
+            .rangef64_with_default("rangef64", (0., 20., 0.1), 2.5)
+            .characters_sequence("name", "light")
+            .secret_characters_sequence("password", "admin")
+            .byte_stream("firmware", 1024)
+            .serialize_data();
+
+        let defaults = parameters_data.defaults();
+
+        assert_eq!(defaults.iter().count(), 5);
+        assert_eq!(defaults.get("bool"), Some(&ParameterValue::Bool(true)));
+        assert_eq!(defaults.get("u8"), Some(&ParameterValue::U8(3)));
+        assert_eq!(defaults.get("rangef64"), Some(&ParameterValue::F64(2.5)));
+        assert_eq!(
+            defaults.get("name"),
+            Some(&ParameterValue::CharsSequence("light".into()))
+        );
+        assert_eq!(
+            defaults.get("password"),
+            Some(&ParameterValue::SecretCharsSequence("admin".into()))
+        );
+        assert_eq!(defaults.get("firmware"), None);
+    }
+
+    #[test]
+    fn test_parameter_value_limits() {
+        let kind = ParameterKind::U8 {
+            default: 5,
+            min: 1,
+            max: 10,
+        };
+        assert!(ParameterValue::U8(1).is_within_limits(&kind));
+        assert!(ParameterValue::U8(10).is_within_limits(&kind));
+        assert!(!ParameterValue::U8(11).is_within_limits(&kind));
+        assert!(!ParameterValue::U16(5).is_within_limits(&kind));
+
+        let kind = ParameterKind::RangeF64 {
+            min: 0.,
+            max: 1.,
+            step: 0.1,
+            default: 0.,
+        };
+        assert!(ParameterValue::F64(0.5).is_within_limits(&kind));
+        assert!(!ParameterValue::F64(1.5).is_within_limits(&kind));
+
+        assert!(
+            ParameterValue::Bool(false).is_within_limits(&ParameterKind::Bool { default: true })
+        );
+
+        let kind = ParameterKind::ByteStream { max_size: 1024 };
+        assert!(ParameterValue::ByteStream(1024).is_within_limits(&kind));
+        assert!(!ParameterValue::ByteStream(1025).is_within_limits(&kind));
+    }
+
+    #[test]
+    fn test_secret_parameter_value() {
+        let kind = ParameterKind::CharsSequence {
+            default: "".into(),
+            secret: true,
+            max_length: None,
+        };
+
+        // A secret kind keeps its flag once serialized.
+        assert_eq!(
+            serialize(&kind),
+            serde_json::json!({ "CharsSequence": { "default": "", "secret": true } })
+        );
+        assert_eq!(deserialize::<ParameterKind>(serialize(&kind)), kind);
+
+        let value = ParameterValue::CharsSequence("p4ssw0rd".into()).conceal(&kind);
+
+        // A secret value is masked when displayed, but it is still usable.
+        assert_eq!(alloc::format!("{value}"), "***");
+        assert!(!alloc::format!("{value:?}").contains("p4ssw0rd"));
+        assert_eq!(value.chars_sequence(), Some("p4ssw0rd"));
+        assert!(value.match_kind(&kind));
+        assert!(value.is_within_limits(&kind));
+
+        // A non-secret value is left untouched.
+        let value =
+            ParameterValue::CharsSequence("hello".into()).conceal(&ParameterKind::CharsSequence {
+                default: "".into(),
+                secret: false,
+                max_length: None,
+            });
+        assert_eq!(alloc::format!("{value}"), "hello");
+    }
+
+    #[test]
+    fn test_chars_sequence_max_length() {
+        let parameters = Parameters::new()
+            .characters_sequence_with_max_length("name", "light", 5)
+            .secret_characters_sequence_with_max_length("pin", "", 4)
+            .serialize_data();
+        let name = parameters.get("name").unwrap();
+        let pin = parameters.get("pin").unwrap();
+
+        // The maximum length is serialized only when declared.
+        assert_eq!(
+            serialize(name),
+            serde_json::json!({ "CharsSequence": { "default": "light", "max_length": 5 } })
+        );
+        assert_eq!(deserialize::<ParameterKind>(serialize(pin)), *pin);
+        assert!(
+            serialize(chars_sequence_kind("", false))["CharsSequence"]
+                .get("max_length")
+                .is_none()
+        );
+
+        // A value exactly at the limit is accepted, as well as a shorter one.
+        assert!(ParameterValue::CharsSequence("lamp".into()).is_within_limits(name));
+        assert!(ParameterValue::CharsSequence("light".into()).is_within_limits(name));
+        assert!(ParameterValue::SecretCharsSequence("1234".into()).is_within_limits(pin));
+
+        // A longer value is rejected.
+        assert!(!ParameterValue::CharsSequence("lights".into()).is_within_limits(name));
+        assert!(!ParameterValue::SecretCharsSequence("12345".into()).is_within_limits(pin));
+
+        // Characters are counted rather than bytes.
+        assert!(ParameterValue::CharsSequence("lùcè".into()).is_within_limits(pin));
+
+        // Without a maximum length, values are never limited.
+        assert!(
+            ParameterValue::CharsSequence("lights".repeat(100).into())
+                .is_within_limits(&chars_sequence_kind("", false))
+        );
+    }
+
+    fn range_kind(step: f64) -> ParameterKind {
+        ParameterKind::RangeF64 {
+            min: 0.,
+            max: 10.,
+            step,
+            default: 0.,
+        }
+    }
+
+    #[test]
+    fn test_rangef64_precision() {
+        let parameters = Parameters::new()
+            .rangef64_with_precision("one", (0., 1.), DecimalPrecision::OneDigit, 0.5)
+            .rangef64_with_precision("two", (0., 1.), DecimalPrecision::TwoDigits, 0.5)
+            .rangef64_with_precision("three", (0., 1.), DecimalPrecision::ThreeDigits, 0.5)
+            .serialize_data();
+
+        assert_eq!(
+            parameters.get("one"),
+            Some(&ParameterKind::RangeF64 {
+                min: 0.,
+                max: 1.,
+                step: 0.1,
+                default: 0.5,
+            })
+        );
+        assert_eq!(
+            parameters
+                .get("two")
+                .and_then(ParameterKind::decimal_places),
+            Some(2)
+        );
+        assert_eq!(
+            parameters
+                .get("three")
+                .and_then(ParameterKind::decimal_places),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn test_decimal_places() {
+        assert_eq!(range_kind(1.).decimal_places(), Some(0));
+        assert_eq!(range_kind(5.).decimal_places(), Some(0));
+        assert_eq!(range_kind(0.5).decimal_places(), Some(1));
+        assert_eq!(range_kind(0.1).decimal_places(), Some(1));
+        assert_eq!(range_kind(0.25).decimal_places(), Some(2));
+        assert_eq!(range_kind(0.01).decimal_places(), Some(2));
+        assert_eq!(range_kind(0.001).decimal_places(), Some(3));
+        assert_eq!(range_kind(0.005).decimal_places(), Some(3));
+
+        // The precision of floating point values.
+        let parameters = Parameters::new()
+            .f32_with_limits("f32", 0.5, 0., 1., DecimalPrecision::OneDigit)
+            .f64_with_limits("f64", 0.5, 0., 1., DecimalPrecision::ThreeDigits)
+            .f64_with_limits("any", 0.5, 0., 1., DecimalPrecision::Any)
+            .serialize_data();
+        assert_eq!(
+            parameters
+                .get("f32")
+                .and_then(ParameterKind::decimal_places),
+            Some(1)
+        );
+        assert_eq!(
+            parameters
+                .get("f64")
+                .and_then(ParameterKind::decimal_places),
+            Some(3)
+        );
+
+        // A missing step and a kind different from a float have no decimal
+        // places.
+        assert_eq!(
+            parameters
+                .get("any")
+                .and_then(ParameterKind::decimal_places),
+            None
+        );
+        assert_eq!(range_kind(0.).decimal_places(), None);
+        assert_eq!(range_kind(-0.5).decimal_places(), None);
+        assert_eq!(range_kind(f64::INFINITY).decimal_places(), None);
+        assert_eq!(range_kind(f64::NAN).decimal_places(), None);
+        assert_eq!(range_kind(1e20).decimal_places(), Some(0));
+        assert_eq!(
+            ParameterKind::U8 {
+                default: 0,
+                min: 0,
+                max: 1
+            }
+            .decimal_places(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_effective_bounds() {
+        // Bounded u8.
+        let parameters = Parameters::new()
+            .u8_with_limits("bounded", 5, 1, 10)
+            .u8("unbounded", 5)
+            .rangeu64("range", (0, 20, 1))
+            .serialize_data();
+        assert_eq!(
+            parameters
+                .get("bounded")
+                .and_then(ParameterKind::effective_bounds),
+            Some((1., 10.))
+        );
+
+        // Unbounded u8, both with sentinels and as deserialized.
+        assert_eq!(
+            parameters
+                .get("unbounded")
+                .and_then(ParameterKind::effective_bounds),
+            None
+        );
+        let unbounded = parameters.get("unbounded").unwrap();
+        assert_eq!(
+            deserialize::<ParameterKind>(serialize(unbounded)).effective_bounds(),
+            None
+        );
+
+        // Range.
+        assert_eq!(
+            parameters
+                .get("range")
+                .and_then(ParameterKind::effective_bounds),
+            Some((0., 20.))
+        );
+
+        // Non-numeric kinds.
+        assert_eq!(
+            ParameterKind::Bool { default: true }.effective_bounds(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_range_values() {
+        let values = |kind: ParameterKind| kind.range_values().collect::<Vec<_>>();
+
+        // Integer range whose maximum is a multiple of the step.
+        assert_eq!(
+            values(ParameterKind::RangeU64 {
+                min: 2,
+                max: 8,
+                step: 3,
+                default: 2,
+            }),
+            [2, 5, 8].map(ParameterValue::U64)
+        );
+
+        // Integer range whose maximum cannot be reached.
+        assert_eq!(
+            values(ParameterKind::RangeU32 {
+                min: 0,
+                max: 10,
+                step: 4,
+                default: 0,
+            }),
+            [0, 4, 8].map(ParameterValue::U32)
+        );
+
+        // Float range whose maximum is a multiple of the step.
+        assert_eq!(
+            values(ParameterKind::RangeF64 {
+                min: 0.5,
+                max: 1.5,
+                step: 0.25,
+                default: 0.5,
+            }),
+            [0.5, 0.75, 1., 1.25, 1.5].map(ParameterValue::F64)
+        );
+
+        // Float range whose maximum cannot be reached.
+        assert_eq!(
+            values(ParameterKind::RangeF64 {
+                min: 0.,
+                max: 1.1,
+                step: 0.5,
+                default: 0.,
+            }),
+            [0., 0.5, 1.].map(ParameterValue::F64)
+        );
+
+        // Rounding errors neither drop nor exceed the maximum.
+        assert_eq!(
+            values(ParameterKind::RangeF64 {
+                min: 0.,
+                max: 0.3,
+                step: 0.1,
+                default: 0.,
+            }),
+            [0., 0.1, 0.2, 0.3].map(ParameterValue::F64)
+        );
+
+        // A zero step only yields the minimum.
+        assert_eq!(
+            values(ParameterKind::RangeU64 {
+                min: 3,
+                max: 10,
+                step: 0,
+                default: 3,
+            }),
+            [ParameterValue::U64(3)]
+        );
+        assert_eq!(
+            values(ParameterKind::RangeF64 {
+                min: 1.,
+                max: 2.,
+                step: 0.,
+                default: 1.,
+            }),
+            [ParameterValue::F64(1.)]
+        );
+
+        // Inverted ranges and non-range kinds are empty.
+        assert!(
+            values(ParameterKind::RangeU32 {
+                min: 10,
+                max: 0,
+                step: 1,
+                default: 0,
+            })
+            .is_empty()
+        );
+        assert!(values(ParameterKind::Bool { default: true }).is_empty());
+    }
+
+    #[test]
+    fn test_align_to_step() {
+        let kind = ParameterKind::RangeF64 {
+            min: 0.,
+            max: 20.,
+            step: 0.1,
+            default: 0.,
+        };
+
+        // An off-step value is snapped to the nearest step.
+        let Some(ParameterValue::F64(value)) =
+            ParameterValue::F64(7.37).align_to_step(&kind, OffStepValues::Snap)
+        else {
+            panic!("The value has not been snapped");
+        };
+        assert!((value - 7.4).abs() < 1e-9);
+
+        // An off-step value is rejected.
+        assert_eq!(
+            ParameterValue::F64(7.37).align_to_step(&kind, OffStepValues::Reject),
+            None
+        );
+
+        // An on-step value is always accepted.
+        assert_eq!(
+            ParameterValue::F64(7.4).align_to_step(&kind, OffStepValues::Reject),
+            Some(ParameterValue::F64(7.4))
+        );
+
+        // The step of a range starts from its minimum.
+        let kind = ParameterKind::RangeF64 {
+            min: 0.5,
+            max: 10.,
+            step: 2.,
+            default: 0.5,
+        };
+        assert_eq!(
+            ParameterValue::F64(3.).align_to_step(&kind, OffStepValues::Snap),
+            Some(ParameterValue::F64(2.5))
+        );
+
+        // A value without a step is left untouched.
+        let kind = ParameterKind::F32 {
+            default: 0.,
+            min: 0.,
+            max: 1.,
+            step: 0.,
+        };
+        assert_eq!(
+            ParameterValue::F32(0.123).align_to_step(&kind, OffStepValues::Reject),
+            Some(ParameterValue::F32(0.123))
+        );
+
+        // A value beyond the `i64` range is an integer, hence on-step.
+        let kind = ParameterKind::F64 {
+            default: 0.,
+            min: f64::MIN,
+            max: f64::MAX,
+            step: 1.,
+        };
+        assert_eq!(
+            ParameterValue::F64(1e20).align_to_step(&kind, OffStepValues::Reject),
+            Some(ParameterValue::F64(1e20))
+        );
+        assert_eq!(
+            ParameterValue::F64(-1e20).align_to_step(&kind, OffStepValues::Reject),
+            Some(ParameterValue::F64(-1e20))
+        );
+    }
 }
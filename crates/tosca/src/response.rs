@@ -1,8 +1,10 @@
 use alloc::borrow::Cow;
 
-use serde::Serialize;
+use serde::{Serialize, Serializer};
 
 use crate::device::DeviceInfo;
+#[cfg(feature = "time")]
+use crate::time::Timestamp;
 
 /// The header name associated with a response which failed to serialize its
 /// values.
@@ -11,6 +13,12 @@ use crate::device::DeviceInfo;
 /// on the device, causing the discarding of the invalid response.
 pub const SERIALIZATION_ERROR: &str = "Serialization-Error";
 
+/// The header name associated with a request carrying an idempotency key.
+///
+/// A device receiving the same key more than once returns the response
+/// produced for the first request, without running the operation again.
+pub const IDEMPOTENCY_KEY: &str = "Idempotency-Key";
+
 /// Response kinds.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
 #[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
@@ -25,6 +33,10 @@ pub enum ResponseKind {
     /// This response transmits a JSON message over the network containing
     /// a device's energy and economy information.
     Info,
+    /// This response does not transmit any body, notifying a controller
+    /// that an operation completed successfully with a `204 No Content`
+    /// status.
+    Empty,
     /// This response transmits a stream of data, represented as a
     /// sequence of bytes, over the network.
     #[cfg(feature = "stream")]
@@ -37,6 +49,7 @@ impl core::fmt::Display for ResponseKind {
             Self::Ok => "Ok",
             Self::Serial => "Serial",
             Self::Info => "Info",
+            Self::Empty => "Empty",
             #[cfg(feature = "stream")]
             Self::Stream => "Stream",
         }
@@ -64,15 +77,99 @@ impl OkResponse {
 
 /// A response which transmits a JSON message over the network containing
 /// the data produced during a device operation.
-#[derive(Debug, PartialEq, Serialize)]
-#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
-pub struct SerialResponse<T: Serialize>(T);
+///
+/// Data is serialized as is. With the `time` feature, a generation
+/// `Timestamp` might be set, which is added to data serialized as a map as
+/// the `generated at` field. Data serialized in any other form, such as a
+/// sequence or a scalar, does not carry the timestamp.
+#[derive(Debug, PartialEq)]
+pub struct SerialResponse<T: Serialize> {
+    data: T,
+    #[cfg(feature = "time")]
+    generated_at: Option<Timestamp>,
+}
 
 impl<T: Serialize> SerialResponse<T> {
     /// Generates a [`SerialResponse`].
     #[must_use]
     pub const fn new(data: T) -> Self {
-        Self(data)
+        Self {
+            data,
+            #[cfg(feature = "time")]
+            generated_at: None,
+        }
+    }
+
+    /// Sets the [`Timestamp`] at which the response has been generated.
+    ///
+    /// The timestamp is only serialized when data is serialized as a map.
+    #[cfg(feature = "time")]
+    #[must_use]
+    pub const fn with_generated_at(mut self, generated_at: Timestamp) -> Self {
+        self.generated_at = Some(generated_at);
+        self
+    }
+
+    /// Returns the [`Timestamp`] at which the response has been generated,
+    /// if any.
+    #[cfg(feature = "time")]
+    #[must_use]
+    pub const fn generated_at(&self) -> Option<Timestamp> {
+        self.generated_at
+    }
+}
+
+impl<T: Serialize> Serialize for SerialResponse<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[cfg(feature = "time")]
+        if let Some(generated_at) = self.generated_at {
+            use serde::ser::Error;
+
+            // Only a map can contain the timestamp as a field.
+            let mut value = serde_json::to_value(&self.data).map_err(S::Error::custom)?;
+            if let Some(map) = value.as_object_mut() {
+                map.insert(
+                    "generated at".into(),
+                    serde_json::to_value(generated_at).map_err(S::Error::custom)?,
+                );
+            }
+            return value.serialize(serializer);
+        }
+
+        self.data.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "deserialize")]
+impl<'de, T> serde::Deserialize<'de> for SerialResponse<T>
+where
+    T: Serialize + serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        let mut value = serde_json::Value::deserialize(deserializer)?;
+
+        // The generation time is never part of data.
+        let generated_at = value
+            .as_object_mut()
+            .and_then(|map| map.remove("generated at"));
+
+        #[cfg(feature = "time")]
+        let generated_at = generated_at
+            .map(Timestamp::deserialize)
+            .transpose()
+            .map_err(D::Error::custom)?;
+        #[cfg(not(feature = "time"))]
+        drop(generated_at);
+
+        let data = T::deserialize(value).map_err(D::Error::custom)?;
+
+        Ok(Self {
+            data,
+            #[cfg(feature = "time")]
+            generated_at,
+        })
     }
 }
 
@@ -80,18 +177,78 @@ impl<T: Serialize> SerialResponse<T> {
 /// a device's energy and economy information.
 #[derive(Debug, PartialEq, Serialize)]
 #[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
-pub struct InfoResponse(DeviceInfo);
+pub struct InfoResponse {
+    #[serde(flatten)]
+    info: DeviceInfo,
+    #[cfg(feature = "time")]
+    #[serde(rename = "generated at")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    generated_at: Option<Timestamp>,
+}
 
 impl InfoResponse {
     /// Generates an [`InfoResponse`].
     #[must_use]
     pub const fn new(info: DeviceInfo) -> Self {
-        Self(info)
+        Self {
+            info,
+            #[cfg(feature = "time")]
+            generated_at: None,
+        }
+    }
+
+    /// Returns the [`DeviceInfo`] contained in the response.
+    #[must_use]
+    pub const fn info(&self) -> &DeviceInfo {
+        &self.info
+    }
+
+    /// Sets the [`Timestamp`] at which the response has been generated.
+    #[cfg(feature = "time")]
+    #[must_use]
+    pub const fn with_generated_at(mut self, generated_at: Timestamp) -> Self {
+        self.generated_at = Some(generated_at);
+        self
+    }
+
+    /// Returns the [`Timestamp`] at which the response has been generated,
+    /// if any.
+    #[cfg(feature = "time")]
+    #[must_use]
+    pub const fn generated_at(&self) -> Option<Timestamp> {
+        self.generated_at
+    }
+
+    /// Merges another [`InfoResponse`] into this one.
+    ///
+    /// This allows to aggregate the information of several providers into
+    /// a single response. When both responses define the same field, the
+    /// field of `other` wins. See [`DeviceInfo::merge`].
+    ///
+    /// With the `time` feature, the generation timestamp is the oldest of
+    /// the two, since a response is only as fresh as its oldest information.
+    #[must_use]
+    pub fn merge(self, other: Self) -> Self {
+        #[cfg(feature = "time")]
+        let generated_at = match (self.generated_at, other.generated_at) {
+            (Some(first), Some(second)) => Some(first.min(second)),
+            (first, second) => first.or(second),
+        };
+
+        Self {
+            info: self.info.merge(other.info),
+            #[cfg(feature = "time")]
+            generated_at,
+        }
     }
 }
 
 /// All possible errors that may cause a device operation to fail.
-#[derive(Debug, PartialEq, Serialize)]
+///
+/// An error kind is serialized alongside an error description, so a
+/// controller can programmatically react to a specific error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 #[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
 pub enum ErrorKind {
     /// Some data encountered during a device operation is invalid or malformed.
@@ -99,6 +256,16 @@ pub enum ErrorKind {
     /// An internal error has occurred during the execution of a device
     /// operation.
     Internal,
+    /// The current device state does not allow the execution of a device
+    /// operation.
+    InvalidState,
+    /// A resource required by a device operation has not been found.
+    NotFound,
+    /// The requester is not authorized to execute a device operation.
+    Unauthorized,
+    /// Too many requests have been sent to a device in a given amount
+    /// of time.
+    RateLimited,
 }
 
 /// A response providing details about an error encountered during a
@@ -106,6 +273,11 @@ pub enum ErrorKind {
 ///
 /// Contains the [`ErrorKind`], a general error description,
 /// and optional information about the encountered error.
+///
+/// The same response is returned when a request fails before reaching a
+/// device operation, such as a request to an unknown route, so a controller
+/// always receives the same error shape. In that case, it also contains the
+/// requested route.
 #[derive(Debug, PartialEq, Serialize)]
 #[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
 pub struct ErrorResponse<'a> {
@@ -115,6 +287,10 @@ pub struct ErrorResponse<'a> {
     pub description: Cow<'a, str>,
     /// Information describing the encountered error.
     pub info: Option<Cow<'a, str>>,
+    /// Route of the failed request, if known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub route: Option<Cow<'a, str>>,
 }
 
 impl<'a> ErrorResponse<'a> {
@@ -128,6 +304,7 @@ impl<'a> ErrorResponse<'a> {
             error,
             description: Cow::Borrowed(description),
             info: None,
+            route: None,
         }
     }
 
@@ -142,9 +319,18 @@ impl<'a> ErrorResponse<'a> {
             error,
             description: Cow::Borrowed(description),
             info: Some(Cow::Borrowed(info)),
+            route: None,
         }
     }
 
+    /// Sets the route of the failed request.
+    #[must_use]
+    #[inline]
+    pub fn with_route(mut self, route: &'a str) -> Self {
+        self.route = Some(Cow::Borrowed(route));
+        self
+    }
+
     /// Generates an [`ErrorResponse`] for invalid data.
     ///
     /// Requires specifying a general error description.
@@ -184,6 +370,44 @@ impl<'a> ErrorResponse<'a> {
     pub fn internal_with_error(description: &'a str, info: &'a str) -> Self {
         Self::with_description_error(ErrorKind::Internal, description, info)
     }
+
+    /// Generates an [`ErrorResponse`] for an invalid device state.
+    ///
+    /// Requires specifying a general error description.
+    #[must_use]
+    #[inline]
+    pub fn invalid_state(description: &'a str) -> Self {
+        Self::with_description(ErrorKind::InvalidState, description)
+    }
+
+    /// Generates an [`ErrorResponse`] for a resource which has not been
+    /// found.
+    ///
+    /// Requires specifying a general error description.
+    #[must_use]
+    #[inline]
+    pub fn not_found(description: &'a str) -> Self {
+        Self::with_description(ErrorKind::NotFound, description)
+    }
+
+    /// Generates an [`ErrorResponse`] for an unauthorized requester.
+    ///
+    /// Requires specifying a general error description.
+    #[must_use]
+    #[inline]
+    pub fn unauthorized(description: &'a str) -> Self {
+        Self::with_description(ErrorKind::Unauthorized, description)
+    }
+
+    /// Generates an [`ErrorResponse`] for a requester which has sent too
+    /// many requests.
+    ///
+    /// Requires specifying a general error description.
+    #[must_use]
+    #[inline]
+    pub fn rate_limited(description: &'a str) -> Self {
+        Self::with_description(ErrorKind::RateLimited, description)
+    }
 }
 
 #[cfg(test)]
@@ -193,7 +417,7 @@ mod tests {
 
     use crate::{deserialize, serialize};
 
-    use super::{OkResponse, SerialResponse, Serialize};
+    use super::{OkResponse, ResponseKind, SerialResponse, Serialize};
 
     use super::{Cow, DeviceInfo, ErrorKind, ErrorResponse, InfoResponse};
 
@@ -207,6 +431,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_empty_response_kind() {
+        assert_eq!(serialize(ResponseKind::Empty), serde_json::json!("Empty"));
+        assert_eq!(
+            deserialize::<ResponseKind>(serialize(ResponseKind::Empty)),
+            ResponseKind::Empty
+        );
+    }
+
     #[test]
     fn test_serial_value_response() {
         #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -258,8 +491,144 @@ mod tests {
                     }),
                 },
                 economy: crate::economy::Economy::empty(),
+                handled_requests: None,
+                uptime: None,
+                icon: None,
+                location: None,
             }
         );
+
+        assert_eq!(
+            deserialize::<DeviceInfo>(serialize(InfoResponse::new(
+                DeviceInfo::empty().add_handled_requests(2)
+            ))),
+            DeviceInfo {
+                energy: crate::energy::Energy::empty(),
+                economy: crate::economy::Economy::empty(),
+                handled_requests: Some(2),
+                uptime: None,
+                icon: None,
+                location: None,
+            }
+        );
+
+        let info = serialize(InfoResponse::new(DeviceInfo::empty().add_uptime(3600)));
+        assert_eq!(info, serde_json::json!({ "uptime": 3600 }));
+        assert_eq!(deserialize::<InfoResponse>(info).info().uptime, Some(3600));
+    }
+
+    #[test]
+    fn test_info_icon() {
+        let info = serialize(InfoResponse::new(
+            DeviceInfo::empty().with_icon("https://example.com/light.svg"),
+        ));
+        assert_eq!(
+            info,
+            serde_json::json!({ "icon": "https://example.com/light.svg" })
+        );
+        assert_eq!(
+            deserialize::<InfoResponse>(info).info().icon(),
+            Some("https://example.com/light.svg")
+        );
+
+        // A missing icon is not serialized.
+        let info = serialize(InfoResponse::new(DeviceInfo::empty()));
+        assert_eq!(info, serde_json::json!({}));
+        assert_eq!(deserialize::<InfoResponse>(info).info().icon(), None);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_generated_at() {
+        use crate::time::Timestamp;
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct SerialValue {
+            value: u32,
+        }
+
+        let generated_at = Timestamp::from_unix_secs(1_741_944_413);
+
+        let serial = serialize(
+            SerialResponse::new(SerialValue { value: 42 }).with_generated_at(generated_at),
+        );
+        assert_eq!(
+            serial,
+            serde_json::json!({ "value": 42, "generated at": "2025-03-14T09:26:53Z" })
+        );
+        assert_eq!(
+            deserialize::<SerialResponse<SerialValue>>(serial).generated_at(),
+            Some(generated_at)
+        );
+
+        // Without a timestamp, data is serialized as is.
+        assert_eq!(serialize(SerialResponse::new(42)), serde_json::json!(42));
+
+        // Data which is not a map does not carry the timestamp.
+        let sequence =
+            serialize(SerialResponse::new(alloc::vec![1, 2, 3]).with_generated_at(generated_at));
+        assert_eq!(sequence, serde_json::json!([1, 2, 3]));
+        assert_eq!(
+            deserialize::<SerialResponse<alloc::vec::Vec<u8>>>(sequence).generated_at(),
+            None
+        );
+        assert_eq!(
+            serialize(SerialResponse::new(42).with_generated_at(generated_at)),
+            serde_json::json!(42)
+        );
+
+        let info =
+            serialize(InfoResponse::new(DeviceInfo::empty()).with_generated_at(generated_at));
+        assert_eq!(
+            info,
+            serde_json::json!({ "generated at": "2025-03-14T09:26:53Z" })
+        );
+        assert_eq!(
+            deserialize::<InfoResponse>(info).generated_at(),
+            Some(generated_at)
+        );
+    }
+
+    #[test]
+    fn test_merge_info_responses() {
+        let energy = crate::energy::Energy::init_with_water_use_efficiency(
+            crate::energy::WaterUseEfficiency::init_with_gpp(42.0),
+        );
+        let economy = crate::economy::Economy::init_with_roi(crate::economy::Rois::init(
+            crate::economy::Roi::new(5, crate::energy::EnergyClass::A),
+        ));
+
+        let info = InfoResponse::new(DeviceInfo::empty().add_energy(energy.clone())).merge(
+            InfoResponse::new(DeviceInfo::empty().add_economy(economy.clone())),
+        );
+
+        assert_eq!(
+            deserialize::<DeviceInfo>(serialize(info)),
+            DeviceInfo {
+                energy: energy.clone(),
+                economy,
+                handled_requests: None,
+                uptime: None,
+                icon: None,
+                location: None,
+            }
+        );
+
+        // When both responses define the same field, the last one wins.
+        let info = InfoResponse::new(DeviceInfo::empty().add_energy(energy)).merge(
+            InfoResponse::new(DeviceInfo::empty().add_energy(
+                crate::energy::Energy::init_with_water_use_efficiency(
+                    crate::energy::WaterUseEfficiency::init_with_wer(1.5),
+                ),
+            )),
+        );
+
+        assert_eq!(
+            deserialize::<DeviceInfo>(serialize(info))
+                .energy
+                .water_use_efficiency,
+            Some(crate::energy::WaterUseEfficiency::init_with_wer(1.5))
+        );
     }
 
     #[test]
@@ -275,7 +644,80 @@ mod tests {
                 error: ErrorKind::InvalidData,
                 description: Cow::Borrowed("Invalid data error description"),
                 info: None,
+                route: None,
             }
         );
+
+        let error = ErrorResponse::invalid_state("Invalid state error description");
+
+        assert_eq!(
+            deserialize::<ErrorResponse>(serialize(error)),
+            ErrorResponse {
+                error: ErrorKind::InvalidState,
+                description: Cow::Borrowed("Invalid state error description"),
+                info: None,
+                route: None,
+            }
+        );
+
+        // The route of a failed request is serialized only when known.
+        assert!(
+            serialize(ErrorResponse::not_found("Route not found"))
+                .get("route")
+                .is_none()
+        );
+
+        let error = ErrorResponse::not_found("Route not found").with_route("/light/dim");
+
+        assert_eq!(
+            deserialize::<ErrorResponse>(serialize(error)),
+            ErrorResponse {
+                error: ErrorKind::NotFound,
+                description: Cow::Borrowed("Route not found"),
+                info: None,
+                route: Some(Cow::Borrowed("/light/dim")),
+            }
+        );
+    }
+
+    #[test]
+    fn test_error_kinds() {
+        for error in [
+            ErrorKind::InvalidData,
+            ErrorKind::Internal,
+            ErrorKind::InvalidState,
+            ErrorKind::NotFound,
+            ErrorKind::Unauthorized,
+            ErrorKind::RateLimited,
+        ] {
+            assert_eq!(
+                deserialize::<ErrorResponse>(serialize(ErrorResponse::with_description_error(
+                    error,
+                    "Error description",
+                    "Error information"
+                ))),
+                ErrorResponse {
+                    error,
+                    description: Cow::Borrowed("Error description"),
+                    info: Some(Cow::Borrowed("Error information")),
+                    route: None,
+                }
+            );
+        }
+
+        assert_eq!(
+            deserialize::<ErrorResponse>(serialize(ErrorResponse::not_found("Not found"))).error,
+            ErrorKind::NotFound
+        );
+        assert_eq!(
+            deserialize::<ErrorResponse>(serialize(ErrorResponse::unauthorized("Unauthorized")))
+                .error,
+            ErrorKind::Unauthorized
+        );
+        assert_eq!(
+            deserialize::<ErrorResponse>(serialize(ErrorResponse::rate_limited("Rate limited")))
+                .error,
+            ErrorKind::RateLimited
+        );
     }
 }
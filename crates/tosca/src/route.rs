@@ -1,21 +1,28 @@
 use alloc::borrow::Cow;
+#[cfg(feature = "routing")]
+use alloc::string::String;
+use alloc::vec::Vec;
 
 use hashbrown::DefaultHashBuilder;
 
 use indexmap::set::{IndexSet, IntoIter, Iter};
 
 use log::error;
+#[cfg(feature = "routing")]
+use log::warn;
 
-use serde::Serialize;
+use serde::{Serialize, Serializer};
 
 use crate::hazards::{Hazard, Hazards};
-use crate::parameters::{Parameters, ParametersData};
+use crate::parameters::{ParameterKind, Parameters, ParametersData};
+#[cfg(feature = "routing")]
+use crate::parameters::{ParameterPayload, ParameterValue, ParametersPayloads, ParametersValues};
 use crate::response::ResponseKind;
 
 use crate::macros::{mandatory_route, set};
 
 /// `REST` requests kind.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
 #[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
 pub enum RestKind {
     /// `GET` request.
@@ -26,6 +33,8 @@ pub enum RestKind {
     Post,
     /// `DELETE` request.
     Delete,
+    /// `PATCH` request.
+    Patch,
 }
 
 impl core::fmt::Display for RestKind {
@@ -35,11 +44,218 @@ impl core::fmt::Display for RestKind {
             Self::Put => "PUT",
             Self::Post => "POST",
             Self::Delete => "DELETE",
+            Self::Patch => "PATCH",
         }
         .fmt(f)
     }
 }
 
+/// Encoding of the parameters of a `GET` or `DELETE` route.
+///
+/// Parameters of a `DELETE` route are sent as a request body only when
+/// explicitly requested, since many `HTTP` stacks reject it.
+///
+/// A route with a byte stream parameter can send its other parameters as a
+/// query string, since its request body only contains the stream.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize)]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub enum ParameterStyle {
+    /// Parameters are encoded as path segments, e.g. `/route/5/0.1`.
+    #[default]
+    Path,
+    /// Parameters are encoded as a query string, e.g. `/route?first=5&second=0.1`.
+    Query,
+    /// Parameters are sent as a json request body.
+    ///
+    /// Only a `DELETE` route can use it, since a `GET` request never carries
+    /// a body.
+    Body,
+}
+
+/// Handling of the trailing path parameters omitted from a `GET` request.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize)]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub enum MissingParameters {
+    /// A request omitting some parameters is rejected.
+    #[default]
+    Strict,
+    /// Omitted parameters assume their declared default values, e.g.
+    /// `/route` is equivalent to `/route/5/0.1` when `5` and `0.1` are
+    /// the parameters default values.
+    Default,
+}
+
+#[cfg(feature = "routing")]
+impl MissingParameters {
+    const fn is_strict(self) -> bool {
+        matches!(self, Self::Strict)
+    }
+}
+
+/// Handling of the floating point parameter values which are not a multiple
+/// of their declared step.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize)]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub enum OffStepValues {
+    /// A value is rounded to the nearest multiple of its step, e.g. `7.37`
+    /// becomes `7.4` when the step is `0.1`.
+    #[default]
+    Snap,
+    /// A request containing a value which is not a multiple of its step is
+    /// rejected.
+    Reject,
+}
+
+/// Errors occurring when a route path is malformed, or when the route
+/// cannot receive its parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoutePathError {
+    /// The path does not start with `/`.
+    MissingLeadingSlash,
+    /// The path contains a whitespace character.
+    Whitespace,
+    /// The path contains a character reserved by the URL syntax.
+    ReservedCharacter(char),
+    /// A `GET` or `DELETE` route declares a byte stream parameter, which can
+    /// only be sent as a request body.
+    ByteStreamWithoutBody,
+    /// A `GET` route sends its parameters as a request body, which a `GET`
+    /// request cannot carry.
+    BodyOnGet,
+    /// A strict [`Parameters`] declares the same name more than once.
+    DuplicateParameter(&'static str),
+}
+
+impl core::fmt::Display for RoutePathError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Self::MissingLeadingSlash => write!(f, "the path does not start with `/`"),
+            Self::Whitespace => write!(f, "the path contains a whitespace"),
+            Self::ReservedCharacter(c) => {
+                write!(f, "the path contains the reserved character `{c}`")
+            }
+            Self::ByteStreamWithoutBody => write!(
+                f,
+                "a `GET` or `DELETE` route cannot declare a byte stream parameter, use `POST` instead"
+            ),
+            Self::BodyOnGet => write!(
+                f,
+                "a `GET` route cannot send its parameters as a body, use a path or a query instead"
+            ),
+            Self::DuplicateParameter(name) => {
+                write!(f, "the parameter `{name}` is declared more than once")
+            }
+        }
+    }
+}
+
+/// Errors occurring when the parameters of a request do not match the route
+/// ones.
+#[cfg(feature = "routing")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RouteParameterError {
+    /// The request contains a parameter which the route does not declare.
+    Undeclared(String),
+    /// A parameter value does not match its declared kind.
+    WrongKind {
+        /// Parameter name.
+        name: String,
+        /// Type of the received value.
+        found: &'static str,
+        /// Declared type.
+        expected: &'static str,
+    },
+    /// A floating point value is not a multiple of its declared step.
+    OffStep(String),
+    /// A characters sequence is longer than its declared maximum length.
+    TooLong {
+        /// Parameter name.
+        name: String,
+        /// Declared maximum number of characters.
+        max_length: u32,
+    },
+}
+
+#[cfg(feature = "routing")]
+impl core::fmt::Display for RouteParameterError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Self::Undeclared(name) => write!(f, "Parameter `{name}` not found"),
+            Self::WrongKind {
+                name,
+                found,
+                expected,
+            } => write!(
+                f,
+                "Found type `{found}` for `{name}`, expected type `{expected}`"
+            ),
+            Self::OffStep(name) => write!(
+                f,
+                "The `{name}` value is not a multiple of the declared step"
+            ),
+            Self::TooLong { name, max_length } => write!(
+                f,
+                "The `{name}` value exceeds the maximum length of {max_length} characters"
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "routing")]
+impl core::error::Error for RouteParameterError {}
+
+// Checks whether a route setting has its default value, which is omitted
+// from the serialized route.
+fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+// Characters reserved for queries, fragments, and path parameters.
+const RESERVED_PATH_CHARACTERS: &[char] = &['?', '#', '{', '}'];
+
+fn validate_path(path: &str) -> Result<(), RoutePathError> {
+    if !path.starts_with('/') {
+        return Err(RoutePathError::MissingLeadingSlash);
+    }
+
+    for c in path.chars() {
+        if c.is_whitespace() {
+            return Err(RoutePathError::Whitespace);
+        }
+        if RESERVED_PATH_CHARACTERS.contains(&c) {
+            return Err(RoutePathError::ReservedCharacter(c));
+        }
+    }
+
+    Ok(())
+}
+
+/// Normalizes a request path, collapsing its repeated slashes and removing
+/// the trailing one, e.g. `//light/route/` becomes `/light/route`.
+#[cfg(feature = "routing")]
+#[must_use]
+pub fn normalize_path(path: &str) -> String {
+    let mut normalized = String::with_capacity(path.len());
+    for segment in path.split('/').filter(|segment| !segment.is_empty()) {
+        normalized.push('/');
+        normalized.push_str(segment);
+    }
+    normalized
+}
+
+/// Compares a route path segment with a request path segment.
+///
+/// When `lenient` is set, the comparison is `ASCII` case-insensitive.
+#[cfg(feature = "routing")]
+#[must_use]
+pub fn segment_matches(route_segment: &str, path_segment: &str, lenient: bool) -> bool {
+    if lenient {
+        route_segment.eq_ignore_ascii_case(path_segment)
+    } else {
+        route_segment == path_segment
+    }
+}
+
 /// Route data.
 #[derive(Debug, Clone, Serialize)]
 #[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
@@ -51,7 +267,10 @@ pub struct RouteData {
     /// Description.
     pub description: Option<Cow<'static, str>>,
     /// Hazards data.
-    #[serde(skip_serializing_if = "Hazards::is_empty")]
+    ///
+    /// Known safe hazards are serialized as an empty sequence, while
+    /// unspecified hazards are omitted.
+    #[serde(skip_serializing_if = "Hazards::is_unspecified")]
     #[serde(default = "Hazards::new")]
     pub hazards: Hazards,
     /// Input parameters associated with a route.
@@ -78,6 +297,21 @@ impl RouteData {
     }
 }
 
+/// A route parameter paired with its value in a request path.
+#[cfg(feature = "routing")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathParameter<'a, 'p> {
+    /// Parameter name.
+    pub name: &'a str,
+    /// Parameter kind.
+    pub kind: &'a ParameterKind,
+    /// Parameter value, as found in the request path.
+    ///
+    /// If [`None`], the value has been omitted and the parameter assumes its
+    /// default value.
+    pub value: Option<&'p str>,
+}
+
 /// A server route configuration.
 #[derive(Debug, Clone, Serialize)]
 #[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
@@ -91,6 +325,28 @@ pub struct RouteConfig {
     /// Response kind.
     #[serde(rename = "response kind")]
     pub response_kind: ResponseKind,
+    /// Encoding of `GET` and `DELETE` parameters.
+    #[serde(rename = "parameter style")]
+    #[serde(skip_serializing_if = "is_default")]
+    #[serde(default)]
+    pub parameter_style: ParameterStyle,
+    /// Handling of omitted `GET` path parameters.
+    #[serde(rename = "missing parameters")]
+    #[serde(skip_serializing_if = "is_default")]
+    #[serde(default)]
+    pub missing_parameters: MissingParameters,
+    /// Handling of floating point values which are not a multiple of their
+    /// step.
+    #[serde(rename = "off-step values")]
+    #[serde(skip_serializing_if = "is_default")]
+    #[serde(default)]
+    pub off_step_values: OffStepValues,
+    /// Deprecation reason.
+    ///
+    /// If [`None`], the route is not deprecated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub deprecated: Option<Cow<'static, str>>,
 }
 
 impl PartialEq for RouteConfig {
@@ -116,22 +372,225 @@ impl RouteConfig {
         self
     }
 
+    /// Validates the route path and parameters.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`RoutePathError`] when the path does not start with `/`,
+    /// when it contains whitespaces or reserved characters, or when a `GET`
+    /// or `DELETE` route declares a byte stream parameter, or when a `GET`
+    /// route sends its parameters as a body.
+    #[inline]
+    pub fn validate(&self) -> Result<(), RoutePathError> {
+        validate_path(&self.data.path)?;
+
+        if self.rest_kind == RestKind::Get && self.parameter_style == ParameterStyle::Body {
+            return Err(RoutePathError::BodyOnGet);
+        }
+
+        if matches!(self.rest_kind, RestKind::Get | RestKind::Delete)
+            && self
+                .data
+                .parameters
+                .iter()
+                .any(|(_, kind)| matches!(kind, ParameterKind::ByteStream { .. }))
+        {
+            return Err(RoutePathError::ByteStreamWithoutBody);
+        }
+
+        Ok(())
+    }
+
+    /// Pairs the route parameters with the values of a request path, in
+    /// their declaration order.
+    ///
+    /// Values beyond the declared parameters are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns the names of the omitted parameters when the route
+    /// [`MissingParameters`] handling rejects them.
+    #[cfg(feature = "routing")]
+    pub fn path_parameters<'a, 'p>(
+        &'a self,
+        values: impl IntoIterator<Item = &'p str>,
+    ) -> Result<Vec<PathParameter<'a, 'p>>, Vec<&'a str>> {
+        let mut values = values.into_iter();
+        let mut path_parameters = Vec::with_capacity(self.data.parameters.len());
+
+        for (index, (name, kind)) in self.data.parameters.iter().enumerate() {
+            let value = values.next();
+            if value.is_none() && self.missing_parameters.is_strict() {
+                return Err(self
+                    .data
+                    .parameters
+                    .iter()
+                    .skip(index)
+                    .map(|(name, _)| name.as_str())
+                    .collect());
+            }
+            path_parameters.push(PathParameter { name, kind, value });
+        }
+
+        Ok(path_parameters)
+    }
+
+    /// Checks the parameters of a request body against the route ones,
+    /// converting them into [`ParametersPayloads`].
+    ///
+    /// Values are coerced to their declared kinds and aligned to their
+    /// declared steps, while secret values are concealed. Undeclared
+    /// parameters are rejected when `strict` is set, otherwise they are
+    /// ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`RouteParameterError`] when a parameter is undeclared in
+    /// strict mode, when a value does not match its declared kind, when
+    /// an off-step value is rejected, or when a characters sequence exceeds
+    /// its maximum length.
+    #[cfg(feature = "routing")]
+    pub fn body_parameters<'a>(
+        &self,
+        values: ParametersValues<'a>,
+        strict: bool,
+    ) -> Result<ParametersPayloads<'a>, RouteParameterError> {
+        let mut parameters_payloads = ParametersPayloads::new();
+        for (name, value) in values {
+            let Some(kind) = self.data.parameters.get(&name) else {
+                if strict {
+                    return Err(RouteParameterError::Undeclared(name.into_owned()));
+                }
+                warn!("Ignoring the undeclared parameter `{name}`");
+                continue;
+            };
+
+            let value = value.coerce(kind);
+            if !value.match_kind(kind) {
+                return Err(RouteParameterError::WrongKind {
+                    name: name.into_owned(),
+                    found: value.as_type(),
+                    expected: kind.as_type(),
+                });
+            }
+
+            let value = self.align_value(&name, value, kind)?;
+            if let ParameterKind::CharsSequence {
+                max_length: Some(max_length),
+                ..
+            } = *kind
+                && !value.is_within_limits(kind)
+            {
+                return Err(RouteParameterError::TooLong {
+                    name: name.into_owned(),
+                    max_length,
+                });
+            }
+            parameters_payloads.add(
+                name,
+                ParameterPayload::new(kind.clone(), value.conceal(kind)),
+            );
+        }
+
+        Ok(parameters_payloads)
+    }
+
+    /// Aligns a floating point value to its declared step, as required by
+    /// the route [`OffStepValues`] handling.
+    ///
+    /// Any other value is returned unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RouteParameterError::OffStep`] when the route rejects
+    /// off-step values and the value is not a multiple of its step.
+    #[cfg(feature = "routing")]
+    pub fn align_value(
+        &self,
+        name: &str,
+        value: ParameterValue,
+        kind: &ParameterKind,
+    ) -> Result<ParameterValue, RouteParameterError> {
+        value
+            .align_to_step(kind, self.off_step_values)
+            .ok_or_else(|| RouteParameterError::OffStep(name.into()))
+    }
+
+    // Checks whether two route configurations with the same path and
+    // REST kind share the same definition.
+    fn same_definition(&self, other: &Self) -> bool {
+        self.response_kind == other.response_kind
+            && self.parameter_style == other.parameter_style
+            && self.missing_parameters == other.missing_parameters
+            && self.off_step_values == other.off_step_values
+            && self.deprecated == other.deprecated
+            && self.data.hazards == other.data.hazards
+            && self.data.parameters == other.data.parameters
+    }
+
     fn new(route: Route) -> Self {
         Self {
             rest_kind: route.rest_kind,
             response_kind: ResponseKind::default(),
+            parameter_style: route.parameter_style,
+            missing_parameters: route.missing_parameters,
+            off_step_values: route.off_step_values,
+            deprecated: route.deprecated.map(Cow::Borrowed),
             data: RouteData::new(route),
         }
     }
 }
 
+impl core::fmt::Display for RouteConfig {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "{} {}", self.rest_kind, self.data.path)?;
+
+        if !self.data.hazards.is_empty() {
+            f.write_str(" (hazards: ")?;
+            for (index, hazard) in self.data.hazards.iter().enumerate() {
+                if index > 0 {
+                    f.write_str(", ")?;
+                }
+                hazard.fmt(f)?;
+            }
+            f.write_str(")")?;
+        }
+
+        if !self.data.parameters.is_empty() {
+            f.write_str(" [params: ")?;
+            for (index, (name, kind)) in self.data.parameters.iter().enumerate() {
+                if index > 0 {
+                    f.write_str(", ")?;
+                }
+                write!(f, "{name}:{}", kind.as_type())?;
+            }
+            f.write_str("]")?;
+        }
+
+        write!(f, " -> {}", self.response_kind)
+    }
+}
+
 set! {
   /// A collection of [`RouteConfig`]s.
-  #[derive(Debug, Clone, PartialEq, Serialize)]
+  ///
+  /// Routes are serialized sorted by path and [`RestKind`], so the same
+  /// routes always produce the same bytes, whatever their insertion order.
+  #[derive(Debug, Clone, PartialEq)]
   #[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
   pub struct RouteConfigs(IndexSet<RouteConfig, DefaultHashBuilder>);
 }
 
+impl Serialize for RouteConfigs {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut route_configs: Vec<&RouteConfig> = self.0.iter().collect();
+        route_configs.sort_by(|first, second| {
+            (&first.data.path, first.rest_kind).cmp(&(&second.data.path, second.rest_kind))
+        });
+        serializer.collect_seq(route_configs)
+    }
+}
+
 impl RouteConfigs {
     /// Merges the provided [`RouteConfigs`] with the current one.
     #[must_use]
@@ -140,6 +599,80 @@ impl RouteConfigs {
         self.0.extend(other);
         self
     }
+
+    /// Compares the current [`RouteConfigs`] with a newer one, returning
+    /// the [`RouteDiff`] between them.
+    ///
+    /// Routes are matched by path and [`RestKind`].
+    #[must_use]
+    pub fn diff(&self, other: &Self) -> RouteDiff {
+        let mut diff = RouteDiff {
+            added: Self::new(),
+            removed: Self::new(),
+            changed: Vec::new(),
+        };
+
+        for new in other {
+            match self.0.get(new) {
+                Some(old) if !old.same_definition(new) => diff.changed.push(RouteChange {
+                    old: old.clone(),
+                    new: new.clone(),
+                }),
+                Some(_) => {}
+                None => diff.added.add(new.clone()),
+            }
+        }
+
+        for old in self {
+            if !other.contains(old) {
+                diff.removed.add(old.clone());
+            }
+        }
+
+        diff
+    }
+}
+
+/// A route whose definition changed between two [`RouteConfigs`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RouteChange {
+    /// Old route configuration.
+    pub old: RouteConfig,
+    /// New route configuration.
+    pub new: RouteConfig,
+}
+
+impl RouteChange {
+    /// Checks whether route hazards changed.
+    #[must_use]
+    pub fn hazards_changed(&self) -> bool {
+        self.old.data.hazards != self.new.data.hazards
+    }
+
+    /// Checks whether route parameters changed.
+    #[must_use]
+    pub fn parameters_changed(&self) -> bool {
+        self.old.data.parameters != self.new.data.parameters
+    }
+}
+
+/// Differences between two [`RouteConfigs`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RouteDiff {
+    /// Routes only present in the newer [`RouteConfigs`].
+    pub added: RouteConfigs,
+    /// Routes only present in the older [`RouteConfigs`].
+    pub removed: RouteConfigs,
+    /// Routes present in both [`RouteConfigs`] with a different definition.
+    pub changed: Vec<RouteChange>,
+}
+
+impl RouteDiff {
+    /// Checks whether there are no differences.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
 }
 
 /// A server route.
@@ -158,6 +691,15 @@ pub struct Route {
     description: Option<&'static str>,
     // Input route parameters.
     parameters: Parameters,
+    // Encoding of `GET` and `DELETE` parameters.
+    parameter_style: ParameterStyle,
+    // Handling of omitted `GET` path parameters.
+    missing_parameters: MissingParameters,
+    // Handling of floating point values which are not a multiple of their
+    // step.
+    off_step_values: OffStepValues,
+    // Deprecation reason.
+    deprecated: Option<&'static str>,
     // Hazards.
     hazards: Hazards,
 }
@@ -206,6 +748,13 @@ impl Route {
         Self::init(RestKind::Delete, name, path)
     }
 
+    /// Creates a new [`Route`] through a REST `PATCH` API.
+    #[must_use]
+    #[inline]
+    pub fn patch(name: &'static str, path: &'static str) -> Self {
+        Self::init(RestKind::Patch, name, path)
+    }
+
     /// Sets the route description.
     #[must_use]
     pub const fn description(mut self, description: &'static str) -> Self {
@@ -251,6 +800,18 @@ impl Route {
         self
     }
 
+    /// Marks a [`Route`] without hazards as known safe.
+    ///
+    /// Unlike a [`Route`] whose hazards are unspecified, a known safe
+    /// [`Route`] explicitly declares that it poses no risks, so a controller
+    /// can trust it. It replaces any previously added [`Hazard`].
+    #[must_use]
+    #[inline]
+    pub fn no_hazards_known_safe(mut self) -> Self {
+        self.hazards = Hazards::known_safe();
+        self
+    }
+
     /// Adds [`Parameters`] to a [`Route`].
     #[must_use]
     #[inline]
@@ -259,6 +820,47 @@ impl Route {
         self
     }
 
+    /// Sets the encoding of `GET` and `DELETE` parameters.
+    ///
+    /// Parameters are encoded as path segments by default. Only a `DELETE`
+    /// route can send its parameters as a body through
+    /// [`ParameterStyle::Body`].
+    #[must_use]
+    pub const fn with_parameter_style(mut self, parameter_style: ParameterStyle) -> Self {
+        self.parameter_style = parameter_style;
+        self
+    }
+
+    /// Sets the handling of the trailing path parameters omitted from a
+    /// `GET` request.
+    ///
+    /// Requests omitting some parameters are rejected by default.
+    #[must_use]
+    pub const fn with_missing_parameters(mut self, missing_parameters: MissingParameters) -> Self {
+        self.missing_parameters = missing_parameters;
+        self
+    }
+
+    /// Sets the handling of the floating point parameter values which are
+    /// not a multiple of their declared step.
+    ///
+    /// Values are rounded to the nearest multiple of their step by default.
+    #[must_use]
+    pub const fn with_off_step_values(mut self, off_step_values: OffStepValues) -> Self {
+        self.off_step_values = off_step_values;
+        self
+    }
+
+    /// Marks a [`Route`] as deprecated, explaining the reason.
+    ///
+    /// A deprecated route still works, but controllers should warn users
+    /// that it might be removed in future firmware versions.
+    #[must_use]
+    pub const fn deprecated(mut self, reason: &'static str) -> Self {
+        self.deprecated = Some(reason);
+        self
+    }
+
     /// Returns the route path.
     #[must_use]
     pub const fn route(&self) -> &str {
@@ -283,11 +885,71 @@ impl Route {
         &self.parameters
     }
 
+    /// Returns [`ParameterStyle`].
+    #[must_use]
+    pub const fn parameter_style(&self) -> ParameterStyle {
+        self.parameter_style
+    }
+
+    /// Returns [`MissingParameters`].
+    #[must_use]
+    pub const fn missing_parameters(&self) -> MissingParameters {
+        self.missing_parameters
+    }
+
+    /// Returns [`OffStepValues`].
+    #[must_use]
+    pub const fn off_step_values(&self) -> OffStepValues {
+        self.off_step_values
+    }
+
+    /// Returns the deprecation reason.
+    ///
+    /// If [`None`], the route is not deprecated.
+    #[must_use]
+    pub const fn deprecation(&self) -> Option<&'static str> {
+        self.deprecated
+    }
+
+    /// Validates the route path and parameters.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`RoutePathError`] when the path does not start with `/`,
+    /// when it contains whitespaces or reserved characters, when a `GET` or
+    /// `DELETE` route declares a byte stream parameter, when a `GET` route
+    /// sends its parameters as a body, or when strict parameters declare a
+    /// duplicate name.
+    #[inline]
+    pub fn validate(&self) -> Result<(), RoutePathError> {
+        validate_path(self.path)?;
+
+        if self.rest_kind == RestKind::Get && self.parameter_style == ParameterStyle::Body {
+            return Err(RoutePathError::BodyOnGet);
+        }
+
+        if let Some(name) = self.parameters.duplicate() {
+            return Err(RoutePathError::DuplicateParameter(name));
+        }
+
+        if matches!(self.rest_kind, RestKind::Get | RestKind::Delete)
+            && self.parameters.has_byte_stream()
+        {
+            return Err(RoutePathError::ByteStreamWithoutBody);
+        }
+
+        Ok(())
+    }
+
     /// Removes any prohibited [`Hazard`]s and returns an updated version of
     /// the [`Route`].
     #[must_use]
     #[inline]
     pub fn remove_prohibited_hazards(mut self, allowed_hazards: &[Hazard]) -> Self {
+        if self.hazards.is_known_safe() {
+            return self;
+        }
+
         let mut hazards = Hazards::new();
         for hazard in self.hazards {
             if allowed_hazards.contains(&hazard) {
@@ -317,6 +979,10 @@ impl Route {
             description: None,
             hazards: Hazards::new(),
             parameters: Parameters::new(),
+            parameter_style: ParameterStyle::Path,
+            missing_parameters: MissingParameters::Strict,
+            off_step_values: OffStepValues::Snap,
+            deprecated: None,
         }
     }
 }
@@ -338,7 +1004,19 @@ mod tests {
     use crate::response::ResponseKind;
     use crate::{deserialize, serialize};
 
-    use super::{RestKind, Route, RouteConfig, RouteData};
+    use super::{
+        MissingParameters, OffStepValues, ParameterStyle, RestKind, Route, RouteConfig,
+        RouteConfigs, RouteData, RoutePathError,
+    };
+
+    #[cfg(feature = "routing")]
+    use alloc::{vec, vec::Vec};
+
+    #[cfg(feature = "routing")]
+    use crate::parameters::{ParameterValue, ParametersValues};
+
+    #[cfg(feature = "routing")]
+    use super::{PathParameter, RouteParameterError, normalize_path, segment_matches};
 
     fn route_config_empty(rest_kind: RestKind, desc: &'static str) -> RouteConfig {
         route_config_hazards(rest_kind, Hazards::new(), desc)
@@ -361,6 +1039,10 @@ mod tests {
         RouteConfig {
             rest_kind,
             response_kind: ResponseKind::default(),
+            parameter_style: ParameterStyle::Path,
+            missing_parameters: MissingParameters::Strict,
+            off_step_values: OffStepValues::Snap,
+            deprecated: None,
             data: RouteData {
                 name: "Route".into(),
                 path: "/route".into(),
@@ -408,6 +1090,154 @@ mod tests {
             )),
             route_config_empty(RestKind::Delete, "A DELETE route",)
         );
+
+        assert_eq!(
+            deserialize::<RouteConfig>(serialize(
+                Route::patch("Route", "/route")
+                    .description("A PATCH route")
+                    .serialize_data()
+            )),
+            route_config_empty(RestKind::Patch, "A PATCH route",)
+        );
+    }
+
+    #[test]
+    fn test_route_configs_diff() {
+        let old = RouteConfigs::new()
+            .insert(Route::put("On", "/on").description("On").serialize_data())
+            .insert(
+                Route::put("Off", "/off")
+                    .description("Off")
+                    .with_hazard(Hazard::LogEnergyConsumption)
+                    .serialize_data(),
+            )
+            .insert(
+                Route::get("Toggle", "/toggle")
+                    .description("Toggle")
+                    .serialize_data(),
+            );
+
+        // Same routes produce no differences.
+        assert!(old.diff(&old).is_empty());
+
+        let new = RouteConfigs::new()
+            .insert(Route::put("On", "/on").description("On").serialize_data())
+            .insert(
+                Route::put("Off", "/off")
+                    .description("Off")
+                    .with_hazard(Hazard::ElectricEnergyConsumption)
+                    .serialize_data(),
+            )
+            .insert(
+                Route::post("Brightness", "/brightness")
+                    .description("Brightness")
+                    .serialize_data(),
+            );
+
+        let diff = old.diff(&new);
+
+        // Added route.
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added.iter().next().unwrap().data.path, "/brightness");
+
+        // Removed route.
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed.iter().next().unwrap().data.path, "/toggle");
+
+        // Route with changed hazards.
+        assert_eq!(diff.changed.len(), 1);
+        let change = &diff.changed[0];
+        assert_eq!(change.new.data.path, "/off");
+        assert!(change.hazards_changed());
+        assert!(!change.parameters_changed());
+        assert_eq!(
+            change.old.data.hazards,
+            Hazards::init(Hazard::LogEnergyConsumption)
+        );
+        assert_eq!(
+            change.new.data.hazards,
+            Hazards::init(Hazard::ElectricEnergyConsumption)
+        );
+
+        // A route with the same path but a different REST kind is a
+        // different route.
+        let get_on =
+            RouteConfigs::new().insert(Route::get("On", "/on").description("On").serialize_data());
+        let diff = get_on.diff(
+            &RouteConfigs::new().insert(Route::put("On", "/on").description("On").serialize_data()),
+        );
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.removed.len(), 1);
+        assert!(diff.changed.is_empty());
+    }
+
+    #[test]
+    fn test_path_validation() {
+        assert_eq!(Route::get("Route", "/route/subroute").validate(), Ok(()));
+
+        assert_eq!(
+            Route::get("Route", "route").validate(),
+            Err(RoutePathError::MissingLeadingSlash)
+        );
+
+        assert_eq!(
+            Route::get("Route", "/a route").validate(),
+            Err(RoutePathError::Whitespace)
+        );
+
+        assert_eq!(
+            Route::get("Route", "/route?value").validate(),
+            Err(RoutePathError::ReservedCharacter('?'))
+        );
+
+        assert_eq!(
+            Route::get("Route", "route").serialize_data().validate(),
+            Err(RoutePathError::MissingLeadingSlash)
+        );
+    }
+
+    #[test]
+    fn test_byte_stream_validation() {
+        let parameters = || Parameters::new().byte_stream("firmware", 1024);
+
+        let get_route = Route::get("Upload", "/upload").with_parameters(parameters());
+        assert_eq!(
+            get_route.validate(),
+            Err(RoutePathError::ByteStreamWithoutBody)
+        );
+        assert_eq!(
+            get_route.serialize_data().validate(),
+            Err(RoutePathError::ByteStreamWithoutBody)
+        );
+
+        let delete_route = Route::delete("Upload", "/upload").with_parameters(parameters());
+        assert_eq!(
+            delete_route.validate(),
+            Err(RoutePathError::ByteStreamWithoutBody)
+        );
+        assert_eq!(
+            delete_route.serialize_data().validate(),
+            Err(RoutePathError::ByteStreamWithoutBody)
+        );
+
+        let post_route = Route::post("Upload", "/upload").with_parameters(parameters());
+        assert_eq!(post_route.validate(), Ok(()));
+        assert_eq!(post_route.serialize_data().validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_duplicate_parameter_validation() {
+        let route = Route::put("Light", "/light")
+            .with_parameters(Parameters::strict().u8("brightness", 0).u8("brightness", 5));
+        assert_eq!(
+            route.validate(),
+            Err(RoutePathError::DuplicateParameter("brightness"))
+        );
+
+        // A duplicate name replaces the previous definition by default.
+        let route = Route::put("Light", "/light")
+            .with_parameters(Parameters::new().u8("brightness", 0).u8("brightness", 5));
+        assert_eq!(route.validate(), Ok(()));
     }
 
     #[test]
@@ -494,6 +1324,356 @@ mod tests {
             expected
         );
     }
+
+    #[test]
+    fn test_parameter_style() {
+        let route = Route::get("Route", "/route")
+            .description("A GET route")
+            .with_parameters(Parameters::new().u64("u64", 5))
+            .with_parameter_style(ParameterStyle::Query)
+            .serialize_data();
+
+        assert_eq!(route.parameter_style, ParameterStyle::Query);
+        assert_eq!(
+            deserialize::<RouteConfig>(serialize(&route)).parameter_style,
+            ParameterStyle::Query
+        );
+
+        // The default path style is not serialized.
+        let route = Route::get("Route", "/route")
+            .description("A GET route")
+            .serialize_data();
+        assert!(serialize(&route).get("parameter style").is_none());
+        assert_eq!(
+            deserialize::<RouteConfig>(serialize(&route)).parameter_style,
+            ParameterStyle::Path
+        );
+
+        // Only a `DELETE` route sends its parameters as a body.
+        let route = Route::delete("Route", "/route")
+            .description("A DELETE route")
+            .with_parameters(Parameters::new().u64("u64", 5))
+            .with_parameter_style(ParameterStyle::Body);
+        assert_eq!(route.validate(), Ok(()));
+        assert_eq!(
+            deserialize::<RouteConfig>(serialize(route.serialize_data())).parameter_style,
+            ParameterStyle::Body
+        );
+
+        let route = Route::get("Route", "/route")
+            .description("A GET route")
+            .with_parameter_style(ParameterStyle::Body);
+        assert_eq!(route.validate(), Err(RoutePathError::BodyOnGet));
+        assert_eq!(
+            route.serialize_data().validate(),
+            Err(RoutePathError::BodyOnGet)
+        );
+    }
+
+    #[test]
+    fn test_missing_parameters() {
+        let route = Route::get("Route", "/route")
+            .description("A GET route")
+            .with_parameters(Parameters::new().u64("u64", 5))
+            .with_missing_parameters(MissingParameters::Default)
+            .serialize_data();
+
+        assert_eq!(route.missing_parameters, MissingParameters::Default);
+        assert_eq!(
+            deserialize::<RouteConfig>(serialize(&route)).missing_parameters,
+            MissingParameters::Default
+        );
+
+        // The default strict handling is not serialized.
+        let route = Route::get("Route", "/route")
+            .description("A GET route")
+            .serialize_data();
+        assert!(serialize(&route).get("missing parameters").is_none());
+        assert_eq!(
+            deserialize::<RouteConfig>(serialize(&route)).missing_parameters,
+            MissingParameters::Strict
+        );
+    }
+
+    #[cfg(feature = "routing")]
+    fn body_parameters(
+        strict: bool,
+        values: serde_json::Value,
+    ) -> Result<Option<ParameterValue>, RouteParameterError> {
+        path_route(MissingParameters::Strict)
+            .body_parameters(deserialize::<ParametersValues>(values), strict)
+            .map(|mut payloads| {
+                assert!(payloads.get("stale").is_none());
+                payloads.extract("u64").map(|payload| payload.value)
+            })
+    }
+
+    #[cfg(feature = "routing")]
+    #[test]
+    fn test_body_parameters() {
+        let values = serde_json::json!({ "u64": 7, "stale": true });
+
+        // Undeclared parameters are ignored.
+        assert_eq!(
+            body_parameters(false, values.clone()),
+            Ok(Some(ParameterValue::U64(7)))
+        );
+
+        // Undeclared parameters are rejected in strict mode.
+        assert_eq!(
+            body_parameters(true, values),
+            Err(RouteParameterError::Undeclared("stale".into()))
+        );
+
+        // A value must match its declared kind.
+        assert_eq!(
+            body_parameters(true, serde_json::json!({ "u64": "7" })),
+            Err(RouteParameterError::WrongKind {
+                name: "u64".into(),
+                found: "String",
+                expected: "u64",
+            })
+        );
+    }
+
+    #[cfg(feature = "routing")]
+    #[test]
+    fn test_body_parameters_max_length() {
+        let route = Route::put("Name", "/name")
+            .description("A PUT route")
+            .with_parameters(Parameters::new().characters_sequence_with_max_length("name", "", 5))
+            .serialize_data();
+        let body_parameters = |name: &str| {
+            route.body_parameters(
+                deserialize::<ParametersValues>(serde_json::json!({ "name": name })),
+                true,
+            )
+        };
+
+        // A value exactly at the limit is accepted.
+        assert_eq!(
+            body_parameters("light")
+                .unwrap()
+                .extract("name")
+                .map(|payload| payload.value),
+            Some(ParameterValue::CharsSequence("light".into()))
+        );
+
+        // A longer value is rejected.
+        assert_eq!(
+            body_parameters("lights").map(|_| ()),
+            Err(RouteParameterError::TooLong {
+                name: "name".into(),
+                max_length: 5,
+            })
+        );
+    }
+
+    #[cfg(feature = "routing")]
+    fn level_route(off_step_values: OffStepValues) -> RouteConfig {
+        Route::get("Level", "/level")
+            .description("A GET route")
+            .with_parameters(Parameters::new().rangef64("level", (0., 20., 0.1)))
+            .with_off_step_values(off_step_values)
+            .serialize_data()
+    }
+
+    #[cfg(feature = "routing")]
+    fn body_level(route: &RouteConfig) -> Result<ParameterValue, RouteParameterError> {
+        route
+            .body_parameters(
+                deserialize::<ParametersValues>(serde_json::json!({ "level": 7.37 })),
+                true,
+            )
+            .map(|mut payloads| payloads.extract("level").unwrap().value)
+    }
+
+    #[cfg(feature = "routing")]
+    #[test]
+    fn test_snap_off_step_values() {
+        let route = level_route(OffStepValues::Snap);
+        let kind = route.data.parameters.get("level").unwrap();
+
+        let Ok(ParameterValue::F64(level)) =
+            route.align_value("level", ParameterValue::F64(7.37), kind)
+        else {
+            panic!("The path value has not been snapped");
+        };
+        assert!((level - 7.4).abs() < 1e-9);
+
+        let Ok(ParameterValue::F64(level)) = body_level(&route) else {
+            panic!("The body value has not been snapped");
+        };
+        assert!((level - 7.4).abs() < 1e-9);
+    }
+
+    #[cfg(feature = "routing")]
+    #[test]
+    fn test_reject_off_step_values() {
+        let route = level_route(OffStepValues::Reject);
+        let kind = route.data.parameters.get("level").unwrap();
+
+        assert_eq!(
+            route.align_value("level", ParameterValue::F64(7.37), kind),
+            Err(RouteParameterError::OffStep("level".into()))
+        );
+        assert_eq!(
+            route.align_value("level", ParameterValue::F64(7.4), kind),
+            Ok(ParameterValue::F64(7.4))
+        );
+        assert_eq!(
+            body_level(&route),
+            Err(RouteParameterError::OffStep("level".into()))
+        );
+    }
+
+    #[cfg(feature = "routing")]
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(normalize_path("/light//route"), "/light/route");
+        assert_eq!(normalize_path("//light/route///5/"), "/light/route/5");
+        assert_eq!(normalize_path("/light/route"), "/light/route");
+        assert_eq!(normalize_path("/"), "");
+    }
+
+    #[cfg(feature = "routing")]
+    #[test]
+    fn test_segment_matches() {
+        // Strict matching.
+        assert!(segment_matches("route", "route", false));
+        assert!(!segment_matches("route", "Route", false));
+
+        // Lenient matching.
+        assert!(segment_matches("route", "Route", true));
+        assert!(segment_matches("light", "LIGHT", true));
+        assert!(!segment_matches("route", "routes", true));
+    }
+
+    #[cfg(feature = "routing")]
+    fn path_route(missing_parameters: MissingParameters) -> RouteConfig {
+        Route::get("Route", "/route")
+            .description("A GET route")
+            .with_parameters(Parameters::new().u64("u64", 5).f64("f64", 0.1))
+            .with_missing_parameters(missing_parameters)
+            .serialize_data()
+    }
+
+    #[cfg(feature = "routing")]
+    #[test]
+    fn test_strict_path_parameters() {
+        let route = path_route(MissingParameters::Strict);
+        let u64_kind = route.data.parameters.get("u64").unwrap();
+        let f64_kind = route.data.parameters.get("f64").unwrap();
+
+        assert_eq!(
+            route.path_parameters(["7", "0.5", "ignored"]),
+            Ok(vec![
+                PathParameter {
+                    name: "u64",
+                    kind: u64_kind,
+                    value: Some("7"),
+                },
+                PathParameter {
+                    name: "f64",
+                    kind: f64_kind,
+                    value: Some("0.5"),
+                },
+            ])
+        );
+
+        // Omitted parameters are rejected.
+        assert_eq!(route.path_parameters(["7"]), Err(vec!["f64"]));
+        assert_eq!(route.path_parameters([]), Err(vec!["u64", "f64"]));
+    }
+
+    #[cfg(feature = "routing")]
+    #[test]
+    fn test_default_path_parameters() {
+        let route = path_route(MissingParameters::Default);
+
+        let values = |path: &'static str| {
+            route
+                .path_parameters(path.split_terminator('/'))
+                .unwrap()
+                .iter()
+                .map(|parameter| parameter.value)
+                .collect::<Vec<_>>()
+        };
+
+        // Omitted parameters assume their default values.
+        assert_eq!(values("7/0.5"), [Some("7"), Some("0.5")]);
+        assert_eq!(values("7"), [Some("7"), None]);
+        assert_eq!(values(""), [None, None]);
+    }
+
+    #[test]
+    fn test_deprecated_route() {
+        let route = Route::put("Route", "/route")
+            .description("A PUT route")
+            .deprecated("Use `/new-route` instead")
+            .serialize_data();
+
+        assert_eq!(
+            route.deprecated.as_deref(),
+            Some("Use `/new-route` instead")
+        );
+        assert_eq!(
+            serialize(&route)["deprecated"],
+            serde_json::json!("Use `/new-route` instead")
+        );
+        assert_eq!(
+            deserialize::<RouteConfig>(serialize(&route)).deprecated,
+            route.deprecated
+        );
+
+        // A route which is not deprecated does not serialize the field.
+        let route = Route::put("Route", "/route")
+            .description("A PUT route")
+            .serialize_data();
+        assert!(serialize(&route).get("deprecated").is_none());
+        assert_eq!(
+            deserialize::<RouteConfig>(serialize(&route)).deprecated,
+            None
+        );
+    }
+
+    #[test]
+    fn test_known_safe_hazards() {
+        // Route with hazards.
+        let route = Route::put("Route", "/route")
+            .with_hazard(Hazard::FireHazard)
+            .serialize_data();
+        assert_eq!(
+            serialize(&route)["hazards"],
+            serde_json::json!(["FireHazard"])
+        );
+        let hazards = deserialize::<RouteConfig>(serialize(&route)).data.hazards;
+        assert_eq!(hazards, Hazards::init(Hazard::FireHazard));
+        assert!(!hazards.is_known_safe() && !hazards.is_unspecified());
+
+        // Route explicitly marked as known safe.
+        let route = Route::put("Route", "/route")
+            .no_hazards_known_safe()
+            .serialize_data();
+        assert_eq!(serialize(&route)["hazards"], serde_json::json!([]));
+        let hazards = deserialize::<RouteConfig>(serialize(&route)).data.hazards;
+        assert_eq!(hazards, Hazards::known_safe());
+        assert!(hazards.is_known_safe() && !hazards.is_unspecified());
+
+        // Route with unspecified hazards.
+        let route = Route::put("Route", "/route").serialize_data();
+        assert!(serialize(&route).get("hazards").is_none());
+        let hazards = deserialize::<RouteConfig>(serialize(&route)).data.hazards;
+        assert_eq!(hazards, Hazards::new());
+        assert!(!hazards.is_known_safe() && hazards.is_unspecified());
+
+        // Adding an hazard revokes the known safe declaration.
+        assert_eq!(
+            Hazards::known_safe().insert(Hazard::FireHazard),
+            Hazards::init(Hazard::FireHazard)
+        );
+        assert_ne!(Hazards::known_safe(), Hazards::new());
+    }
 }
 
 #[cfg(test)]
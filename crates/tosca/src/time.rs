@@ -0,0 +1,276 @@
+use core::time::Duration;
+
+use serde::{Serialize, Serializer};
+
+// Seconds in a day.
+const SECONDS_PER_DAY: u64 = 86_400;
+
+// Days from the `0000-03-01` date to the Unix epoch.
+const EPOCH_DAYS_OFFSET: i64 = 719_468;
+
+// Days in a 400-years era.
+const DAYS_PER_ERA: i64 = 146_097;
+
+/// A point in time expressed as the number of seconds elapsed since the
+/// Unix epoch.
+///
+/// A [`Timestamp`] is serialized as a `RFC 3339` string in the `UTC` time
+/// zone, such as `2025-03-14T09:26:53Z`.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct Timestamp(u64);
+
+impl Timestamp {
+    /// Creates a [`Timestamp`] from the number of seconds elapsed since the
+    /// Unix epoch.
+    #[must_use]
+    pub const fn from_unix_secs(secs: u64) -> Self {
+        Self(secs)
+    }
+
+    /// Returns the number of seconds elapsed since the Unix epoch.
+    #[must_use]
+    pub const fn unix_secs(self) -> u64 {
+        self.0
+    }
+
+    /// Parses a [`Timestamp`] from a `RFC 3339` string.
+    ///
+    /// Fractional seconds are discarded, while a time zone offset is
+    /// converted into `UTC`.
+    ///
+    /// The return value is [`None`] when the string is not a valid `RFC 3339`
+    /// date-time or it precedes the Unix epoch.
+    #[must_use]
+    pub fn parse_rfc3339(value: &str) -> Option<Self> {
+        let bytes = value.as_bytes();
+        if !value.is_ascii()
+            || bytes.len() < 20
+            || bytes[4] != b'-'
+            || bytes[7] != b'-'
+            || !matches!(bytes[10], b'T' | b't')
+            || bytes[13] != b':'
+            || bytes[16] != b':'
+        {
+            return None;
+        }
+
+        let year = number(&value[0..4])?;
+        let month = number(&value[5..7])?;
+        let day = number(&value[8..10])?;
+        let hours = number(&value[11..13])?;
+        let minutes = number(&value[14..16])?;
+        let seconds = number(&value[17..19])?;
+
+        if !(1..=12).contains(&month)
+            || day == 0
+            || day > days_in_month(year, month)
+            || hours > 23
+            || minutes > 59
+            || seconds > 59
+        {
+            return None;
+        }
+
+        // Skip fractional seconds.
+        let mut zone = &value[19..];
+        if let Some(fraction) = zone.strip_prefix('.') {
+            let digits = fraction.bytes().take_while(u8::is_ascii_digit).count();
+            if digits == 0 {
+                return None;
+            }
+            zone = &fraction[digits..];
+        }
+
+        let offset = match zone.as_bytes() {
+            [b'Z' | b'z'] => 0,
+            [sign @ (b'+' | b'-'), _, _, b':', _, _] => {
+                let offset_hours = number(&zone[1..3])?;
+                let offset_minutes = number(&zone[4..6])?;
+                if offset_hours > 23 || offset_minutes > 59 {
+                    return None;
+                }
+                let offset = offset_hours * 3600 + offset_minutes * 60;
+                if *sign == b'+' { offset } else { -offset }
+            }
+            _ => return None,
+        };
+
+        let secs = days_from_civil(year, month, day) * SECONDS_PER_DAY.cast_signed()
+            + hours * 3600
+            + minutes * 60
+            + seconds
+            - offset;
+
+        u64::try_from(secs).ok().map(Self)
+    }
+
+    /// Returns the time elapsed from this [`Timestamp`] to `now`.
+    ///
+    /// When `now` precedes this [`Timestamp`], the elapsed time is zero.
+    #[must_use]
+    pub const fn elapsed(self, now: Self) -> Duration {
+        Duration::from_secs(now.0.saturating_sub(self.0))
+    }
+
+    /// Checks whether more than `threshold` time has elapsed from this
+    /// [`Timestamp`] to `now`.
+    #[must_use]
+    pub fn is_stale(self, now: Self, threshold: Duration) -> bool {
+        self.elapsed(now) > threshold
+    }
+}
+
+impl core::fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        let secs = self.0 % SECONDS_PER_DAY;
+        let (year, month, day) = civil_from_days((self.0 / SECONDS_PER_DAY).cast_signed());
+        write!(
+            f,
+            "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+            secs / 3600,
+            secs % 3600 / 60,
+            secs % 60
+        )
+    }
+}
+
+impl Serialize for Timestamp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "deserialize")]
+impl<'de> serde::Deserialize<'de> for Timestamp {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = <alloc::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        Self::parse_rfc3339(&value).ok_or_else(|| {
+            serde::de::Error::custom(alloc::format!("invalid RFC 3339 timestamp `{value}`"))
+        })
+    }
+}
+
+// Parses a fixed-length sequence of ASCII digits.
+fn number(value: &str) -> Option<i64> {
+    if value.bytes().all(|byte| byte.is_ascii_digit()) {
+        value.parse().ok()
+    } else {
+        None
+    }
+}
+
+const fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+const fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// Converts a proleptic Gregorian date into the number of days since the Unix
+// epoch.
+const fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * DAYS_PER_ERA + day_of_era - EPOCH_DAYS_OFFSET
+}
+
+// Converts the number of days since the Unix epoch into a proleptic
+// Gregorian date.
+const fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + EPOCH_DAYS_OFFSET;
+    let era = days.div_euclid(DAYS_PER_ERA);
+    let day_of_era = days - era * DAYS_PER_ERA;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+#[cfg(feature = "deserialize")]
+mod tests {
+    use core::time::Duration;
+
+    use crate::{deserialize, serialize};
+
+    use super::Timestamp;
+
+    #[test]
+    fn test_timestamp_serialization() {
+        let timestamp = Timestamp::from_unix_secs(1_741_944_413);
+
+        assert_eq!(
+            serialize(timestamp),
+            serde_json::json!("2025-03-14T09:26:53Z")
+        );
+        assert_eq!(deserialize::<Timestamp>(serialize(timestamp)), timestamp);
+
+        assert_eq!(
+            serialize(Timestamp::from_unix_secs(0)),
+            serde_json::json!("1970-01-01T00:00:00Z")
+        );
+        assert_eq!(
+            serialize(Timestamp::from_unix_secs(951_782_400)),
+            serde_json::json!("2000-02-29T00:00:00Z")
+        );
+    }
+
+    #[test]
+    fn test_timestamp_parsing() {
+        let timestamp = Some(Timestamp::from_unix_secs(1_741_944_413));
+
+        assert_eq!(Timestamp::parse_rfc3339("2025-03-14T09:26:53Z"), timestamp);
+        assert_eq!(
+            Timestamp::parse_rfc3339("2025-03-14T09:26:53.250Z"),
+            timestamp
+        );
+        assert_eq!(
+            Timestamp::parse_rfc3339("2025-03-14T11:26:53+02:00"),
+            timestamp
+        );
+        assert_eq!(
+            Timestamp::parse_rfc3339("2025-03-14T08:56:53-00:30"),
+            timestamp
+        );
+
+        assert_eq!(Timestamp::parse_rfc3339("2025-02-29T00:00:00Z"), None);
+        assert_eq!(Timestamp::parse_rfc3339("2025-03-14 09:26:53Z"), None);
+        assert_eq!(Timestamp::parse_rfc3339("2025-03-14T09:26:53"), None);
+        assert_eq!(Timestamp::parse_rfc3339("1969-12-31T23:59:59Z"), None);
+    }
+
+    #[test]
+    fn test_timestamp_staleness() {
+        let timestamp = Timestamp::from_unix_secs(100);
+
+        assert_eq!(
+            timestamp.elapsed(Timestamp::from_unix_secs(130)),
+            Duration::from_secs(30)
+        );
+        assert_eq!(
+            timestamp.elapsed(Timestamp::from_unix_secs(50)),
+            Duration::ZERO
+        );
+
+        assert!(timestamp.is_stale(Timestamp::from_unix_secs(161), Duration::from_mins(1)));
+        assert!(!timestamp.is_stale(Timestamp::from_unix_secs(160), Duration::from_mins(1)));
+    }
+}